@@ -0,0 +1,567 @@
+//! Questions about how a pattern behaves over time, such as whether it is a still life, an oscillator or a spaceship.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife, Region};
+
+/// Hash of the set of live cells in the world, independent of how it is stored.
+pub fn state_hash(world: &GameOfLife) -> u64 {
+    hash_cells(&sorted_cells(world))
+}
+
+/// Step the world until it returns to the state it started in, for at most `max_generations`,
+/// returning the number of generations that took: 1 for a still life, or the period of an oscillator.
+/// Returns [`None`] if the pattern didn't repeat in time, such as for spaceships or patterns still evolving.
+/// The world is left at the generation it was last stepped to.
+pub fn detect_period(world: &mut GameOfLife, max_generations: u64) -> Option<u64> {
+    detect_recurrence(world, max_generations)
+        .filter(|recurrence| recurrence.displacement == Position::ORIGIN)
+        .map(|recurrence| recurrence.period)
+}
+
+/// Step the world until its live cells return to the shape they started in, wherever they are,
+/// for at most `max_generations`. Returns how long that took and how far the shape moved,
+/// or [`None`] if it didn't recur in time. The world is left at the generation it was last stepped to.
+pub fn detect_recurrence(world: &mut GameOfLife, max_generations: u64) -> Option<Recurrence> {
+    let (initial_corner, initial) = shape(world);
+    let initial_hash = hash_cells(&initial);
+    for generation in 1..=max_generations {
+        world.step();
+        let (corner, cells) = shape(world);
+        // Only compare every cell when the hashes match, which is almost always a true repeat
+        if hash_cells(&cells) == initial_hash && cells == initial {
+            return Some(Recurrence { period: generation, displacement: corner - initial_corner })
+        }
+    }
+    None
+}
+
+/// A pattern returning to the same shape, possibly somewhere else.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Recurrence {
+    /// Generations for the shape to come back.
+    pub period: u64,
+    /// How far the shape moved in that time, or [`Position::ORIGIN`] if it stayed put.
+    pub displacement: Position
+}
+
+impl Recurrence {
+    pub fn is_spaceship(&self) -> bool {
+        self.displacement != Position::ORIGIN
+    }
+
+    /// Speed of a spaceship in the usual notation, as a fraction of the speed of light `c` with its direction,
+    /// such as `c/4 diagonal` for a glider or `(2,1)c/6 oblique` for a knightship. Returns [`None`] for patterns that don't move.
+    pub fn speed(&self) -> Option<String> {
+        if !self.is_spaceship() { return None }
+        let (dx, dy) = (self.displacement.x.unsigned_abs(), self.displacement.y.unsigned_abs());
+        let (far, near) = (dx.max(dy), dx.min(dy));
+        let common = gcd(gcd(far, near), self.period);
+        let period = self.period / common;
+        Some(match (far / common, near / common) {
+            (far, 0) => format!("{}/{} orthogonal", speed_of_light(far), period),
+            (far, near) if far == near => format!("{}/{} diagonal", speed_of_light(far), period),
+            (far, near) => format!("({},{})c/{} oblique", far, near, period)
+        })
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.speed() {
+            Some(speed) => write!(f, "spaceship with period {}, moving {} at {}", self.period, self.displacement, speed),
+            None if self.period == 1 => write!(f, "still life"),
+            None => write!(f, "oscillator with period {}", self.period)
+        }
+    }
+}
+
+/// Multiple of `c`, leaving out a multiple of 1.
+fn speed_of_light(multiple: u64) -> String {
+    match multiple {
+        1 => "c".to_string(),
+        _ => format!("{}c", multiple)
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Symmetry group of a pattern: the rotations and reflections that leave it the same shape, wherever its centre is.
+/// `C` groups only have rotations, by a half turn for C2 or a quarter turn for C4, and `D` groups add reflections,
+/// one mirror line for D2, two for D4 and four for D8. Patterns with no symmetry are C1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry {
+    C1,
+    C2,
+    C4,
+    D2,
+    D4,
+    D8
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2 => "D2",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+const QUARTER_TURN: fn(Position) -> Position = |cell| Position::new(-cell.y, cell.x);
+const HALF_TURN: fn(Position) -> Position = |cell| Position::new(-cell.x, -cell.y);
+/// Reflections in the x and y axes and both diagonals.
+const REFLECTIONS: [fn(Position) -> Position; 4] = [
+    |cell| Position::new(-cell.x, cell.y),
+    |cell| Position::new(cell.x, -cell.y),
+    |cell| Position::new(cell.y, cell.x),
+    |cell| Position::new(-cell.y, -cell.x)
+];
+
+/// Work out the symmetry group of the world's live cells. An empty world counts as fully symmetric.
+pub fn symmetry(world: &GameOfLife) -> Symmetry {
+    let (_, cells) = shape(world);
+    let keeps_shape = |transform: &fn(Position) -> Position| {
+        let (_, transformed) = normalise(cells.iter().map(|&cell| transform(cell)).collect());
+        transformed == cells
+    };
+    let quarter_turn = keeps_shape(&QUARTER_TURN);
+    let half_turn = keeps_shape(&HALF_TURN);
+    let mirrors = REFLECTIONS.iter().filter(|reflection| keeps_shape(reflection)).count();
+    match (quarter_turn, half_turn, mirrors) {
+        (true, _, 0) => Symmetry::C4,
+        (true, _, _) => Symmetry::D8,
+        (false, true, 0) => Symmetry::C2,
+        (false, true, _) => Symmetry::D4,
+        (false, false, 0) => Symmetry::C1,
+        (false, false, _) => Symmetry::D2
+    }
+}
+
+/// Corner of the live cells' bounding box, and the cells relative to it in sorted order, so the same shape
+/// anywhere has the same cells.
+fn shape(world: &GameOfLife) -> (Position, Vec<Position>) {
+    normalise(sorted_cells(world))
+}
+
+/// Move cells so the corner of their bounding box is at the origin and sort them, returning where the corner was.
+fn normalise(mut cells: Vec<Position>) -> (Position, Vec<Position>) {
+    let corner = Rect::around(cells.iter().copied()).map_or(Position::ORIGIN, |bounds| bounds.min());
+    for cell in &mut cells {
+        *cell -= corner;
+    }
+    cells.sort_unstable();
+    (corner, cells)
+}
+
+/// Spots a world settling into a still life or a cycle, from the hashes of the states it goes through.
+/// A repeat is only judged by the hashes, so a collision could be mistaken for one, but that is vanishingly unlikely.
+#[derive(Debug, Clone)]
+pub struct CycleDetector {
+    search: Search
+}
+
+#[derive(Debug, Clone)]
+enum Search {
+    /// Hashes of the most recent states, as many as the longest period looked for.
+    Recent { max_period: usize, recent: VecDeque<u64> },
+    /// Brent's algorithm: the hash of a state saved at each power of two steps, the steps since it was saved,
+    /// and the power of two. A cycle is found when a state matches the saved one, with the steps since as its period.
+    Brent { saved: Option<u64>, steps: u64, power: u64 }
+}
+
+impl CycleDetector {
+    /// Detect cycles of at most the given period as soon as they repeat, by remembering that many recent states.
+    pub fn new(max_period: usize) -> CycleDetector {
+        CycleDetector { search: Search::Recent { max_period, recent: VecDeque::with_capacity(max_period + 1) } }
+    }
+
+    /// Detect cycles of any period while remembering only one state, so even long-period oscillators are found
+    /// without storing every generation. A cycle is noticed some time after it starts, within twice the generations
+    /// taken to reach it or twice its period, whichever is longer, plus its period.
+    pub fn any_period() -> CycleDetector {
+        CycleDetector { search: Search::Brent { saved: None, steps: 0, power: 1 } }
+    }
+
+    /// Record the world's state after a step, returning the period of the cycle it has entered
+    /// if one has been found: 1 for a still life.
+    /// Record the starting state too, so a pattern that is already stable is caught after one step.
+    pub fn observe(&mut self, world: &GameOfLife) -> Option<u64> {
+        let hash = state_hash(world);
+        match &mut self.search {
+            Search::Recent { max_period, recent } => {
+                if let Some(age) = recent.iter().rev().position(|&recent| recent == hash) { return Some(age as u64 + 1) }
+                recent.push_back(hash);
+                if recent.len() > *max_period { recent.pop_front(); }
+            }
+            Search::Brent { saved, steps, power } => {
+                if saved.is_some() { *steps += 1 }
+                if *saved == Some(hash) { return Some(*steps) }
+                // Saving a later state at ever longer gaps means it is eventually inside the cycle, with time to go all the way round
+                if saved.is_none() || steps == power {
+                    *saved = Some(hash);
+                    if *steps > 0 { *power *= 2 }
+                    *steps = 0;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Step the world until the detector finds it has settled into a still life or a cycle, for at most `max_generations`,
+/// returning the generation that was noticed at and the period. Returns [`None`] if it was still changing,
+/// such as with gliders escaping. The world is left at the generation it was last stepped to.
+pub fn settle(world: &mut GameOfLife, mut cycles: CycleDetector, max_generations: u64) -> Option<(u64, u64)> {
+    cycles.observe(world);
+    (1..=max_generations).find_map(|generation| {
+        world.step();
+        cycles.observe(world).map(|period| (generation, period))
+    })
+}
+
+/// Spots a world that has roughly settled, from its population alone: once the population has repeated with some period,
+/// to within a tolerance, for a number of generations in a row. Unlike [`CycleDetector`] this doesn't need the exact
+/// state to repeat, so it also fires for ash with a glider flying off or a puffer's slowly growing trail.
+/// A constant population counts as a period of 1.
+#[derive(Debug, Clone)]
+pub struct StabilityDetector {
+    tolerance: usize,
+    generations: usize,
+    /// Most recent populations, as many as the longest period looked for.
+    recent: VecDeque<usize>,
+    /// Generations in a row each period has held for, indexed by period - 1.
+    streaks: Vec<usize>
+}
+
+impl StabilityDetector {
+    /// Look for periods of at most `max_period`, with each population differing from the one a period before
+    /// by at most `tolerance` cells, holding for `generations` generations in a row.
+    pub fn new(max_period: usize, tolerance: usize, generations: usize) -> StabilityDetector {
+        let max_period = max_period.max(1);
+        StabilityDetector { tolerance, generations: generations.max(1), recent: VecDeque::with_capacity(max_period), streaks: vec![0; max_period] }
+    }
+
+    /// Record the world's population after a step, returning the shortest period that has held for long enough, if any.
+    pub fn observe(&mut self, world: &GameOfLife) -> Option<u64> {
+        self.observe_population(world.population())
+    }
+
+    /// Record a population, such as one from [`GameOfLife::population_history`], as for [`observe`](Self::observe).
+    pub fn observe_population(&mut self, population: usize) -> Option<u64> {
+        for (index, streak) in self.streaks.iter_mut().enumerate() {
+            let Some(&before) = self.recent.iter().rev().nth(index) else { break };
+            *streak = if population.abs_diff(before) <= self.tolerance { *streak + 1 } else { 0 };
+        }
+        if self.recent.len() == self.streaks.len() { self.recent.pop_front(); }
+        self.recent.push_back(population);
+        self.streaks.iter().position(|&streak| streak >= self.generations).map(|index| index as u64 + 1)
+    }
+
+    /// Forget every population seen, to start looking again.
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.streaks.fill(0);
+    }
+}
+
+/// Split cells into clusters where every cell is at most `reach` cells from another in the cluster
+/// along both axes, so a reach of 1 groups cells that touch, including diagonally.
+pub fn clusters(cells: &[Position], reach: Coord) -> Vec<Vec<Position>> {
+    let mut remaining: HashSet<Position> = cells.iter().copied().collect();
+    let mut clusters = vec![];
+
+    while let Some(&start) = remaining.iter().next() {
+        remaining.remove(&start);
+        let mut cluster = vec![start];
+        let mut index = 0;
+        while index < cluster.len() {
+            let cell = cluster[index];
+            for x_off in -reach..=reach {
+                for y_off in -reach..=reach {
+                    let neighbour = cell + Position::new(x_off, y_off);
+                    if remaining.remove(&neighbour) {
+                        cluster.push(neighbour);
+                    }
+                }
+            }
+            index += 1;
+        }
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// Split the world's live cells into separate patterns, one for each group of cells touching each other,
+/// including diagonally. Each region covers the bounding box of its cells at their place in the world,
+/// and they are in reading order of their top left corners.
+pub fn components(world: &GameOfLife) -> Vec<Region> {
+    let mut components: Vec<Region> = clusters(&world.live_cells(), 1).into_iter().map(|cells| {
+        let bounds = Rect::around(cells.iter().copied()).expect("Clusters have cells");
+        let mut region = Region::from_rect(bounds).expect("Connected cells fit in a region");
+        for cell in cells {
+            region.set_cell(cell.x, cell.y, Cell::Alive);
+        }
+        region
+    }).collect();
+    components.sort_by_key(|region| (region.y(), region.x()));
+    components
+}
+
+/// Fraction of the cells in the bounding box of the world's live cells that are alive, from 0 for an empty world to 1.
+pub fn density(world: &GameOfLife) -> f64 {
+    let Some(bounds) = world.live_bounds() else { return 0.0 };
+    world.population() as f64 / bounds.area() as f64
+}
+
+/// Estimate of how disordered the world is, in bits per cell from 0 to 1. The bounding box of the live cells is cut
+/// into square blocks of the given size, and this is the Shannon entropy of how often each arrangement of cells appears
+/// in a block, divided by the cells in a block. Ash of a few kinds of still life scores low, and a chaotic soup high.
+/// Blocks hang off the right and bottom edges where the box doesn't divide evenly, with the cells outside it dead.
+///
+/// # Panics
+/// If the block size is 0 or more than 8, as each arrangement is stored in 64 bits.
+pub fn block_entropy(world: &GameOfLife, block_size: usize) -> f64 {
+    assert!((1..=8).contains(&block_size), "Block size must be from 1 to 8");
+    let Some(bounds) = world.live_bounds() else { return 0.0 };
+    let size = block_size as Coord;
+
+    let mut blocks: HashMap<Position, u64> = HashMap::new();
+    for cell in world.live_cells() {
+        let offset = cell - bounds.min();
+        let bit = (offset.y % size) * size + offset.x % size;
+        *blocks.entry(Position::new(offset.x / size, offset.y / size)).or_insert(0) |= 1 << bit;
+    }
+    let total = bounds.width.div_ceil(block_size) * bounds.height.div_ceil(block_size);
+    let mut frequencies: HashMap<u64, usize> = HashMap::new();
+    for arrangement in blocks.into_values() {
+        *frequencies.entry(arrangement).or_insert(0) += 1;
+    }
+    let empty = total - frequencies.values().sum::<usize>();
+
+    let entropy: f64 = frequencies.into_values().chain([empty]).filter(|&count| count > 0).map(|count| {
+        let probability = count as f64 / total as f64;
+        -probability * probability.log2()
+    }).sum();
+    entropy / (block_size * block_size) as f64
+}
+
+fn sorted_cells(world: &GameOfLife) -> Vec<Position> {
+    let mut cells = world.live_cells();
+    cells.sort_unstable();
+    cells
+}
+
+fn hash_cells(cells: &[Position]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cells.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use super::*;
+    use crate::io::rle::read_rle;
+
+    fn world(rle: &str) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle(rle).unwrap());
+        world
+    }
+
+    #[test]
+    fn periods() {
+        // Block, blinker, pulsar
+        assert_eq!(Some(1), detect_period(&mut world("2o$2o!"), 10));
+        assert_eq!(Some(2), detect_period(&mut world("3o!"), 10));
+        let pulsar = "2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!";
+        assert_eq!(Some(3), detect_period(&mut world(pulsar), 10));
+        assert_eq!(None, detect_period(&mut world(pulsar), 2));
+
+        // A glider returns to its shape, but not its position, and the R-pentomino takes over a thousand generations to settle
+        assert_eq!(None, detect_period(&mut world("bo$2bo$3o!"), 20));
+        assert_eq!(None, detect_period(&mut world("b2o$2o$bo!"), 50));
+    }
+
+    #[test]
+    fn spaceships() {
+        let glider = detect_recurrence(&mut world("bo$2bo$3o!"), 10).unwrap();
+        assert_eq!(Recurrence { period: 4, displacement: Position::new(1, 1) }, glider);
+        assert_eq!("spaceship with period 4, moving 1 1 at c/4 diagonal", glider.to_string());
+
+        // The lightweight spaceship moves 2 cells every 4 generations, written as c/2
+        let lwss = detect_recurrence(&mut world("bo2bo$o4b$o3bo$4o!"), 10).unwrap();
+        assert_eq!(Recurrence { period: 4, displacement: Position::new(-2, 0) }, lwss);
+        assert_eq!(Some("c/2 orthogonal".to_string()), lwss.speed());
+
+        let knightship = Recurrence { period: 6, displacement: Position::new(-1, 2) };
+        assert_eq!(Some("(2,1)c/6 oblique".to_string()), knightship.speed());
+        assert_eq!(Some("2c/5 orthogonal".to_string()), Recurrence { period: 5, displacement: Position::new(0, 2) }.speed());
+
+        assert_eq!("still life", detect_recurrence(&mut world("2o$2o!"), 10).unwrap().to_string());
+        assert_eq!("oscillator with period 2", detect_recurrence(&mut world("3o!"), 10).unwrap().to_string());
+        assert!(!detect_recurrence(&mut world("3o!"), 10).unwrap().is_spaceship());
+        assert_eq!(None, detect_recurrence(&mut world("bo$2bo$3o!"), 3));
+    }
+
+    #[test]
+    fn cycles() {
+        // This tetromino becomes a beehive at generation 3, which is seen to repeat a generation later
+        let mut world = world("3o$o!");
+        assert_eq!(Some((4, 1)), settle(&mut world, CycleDetector::new(2), 100));
+        assert_eq!(None, settle(&mut self::world("3o$o!"), CycleDetector::new(2), 3));
+
+        let mut blinker = self::world("3o!");
+        let mut cycles = CycleDetector::new(2);
+        cycles.observe(&blinker);
+        blinker.step();
+        assert_eq!(None, cycles.observe(&blinker));
+        blinker.step();
+        assert_eq!(Some(2), cycles.observe(&blinker));
+
+        // Periods longer than the detector remembers go unnoticed
+        let mut cycles = CycleDetector::new(1);
+        for _ in 0..4 {
+            blinker.step();
+            assert_eq!(None, cycles.observe(&blinker));
+        }
+    }
+
+    #[test]
+    fn long_cycles() {
+        // A blinker, and the pulsar's period of 3 after the pre-pulsar takes 20 generations to become one
+        let mut blinker = world("3o!");
+        let mut cycles = CycleDetector::any_period();
+        cycles.observe(&blinker);
+        let period = (1..10).find_map(|_| {
+            blinker.step();
+            cycles.observe(&blinker)
+        });
+        assert_eq!(Some(2), period);
+
+        let mut pre_pulsar = world("3o3b3o$obo3bobo$3o3b3o!");
+        let mut cycles = CycleDetector::any_period();
+        cycles.observe(&pre_pulsar);
+        let found = (1..200).find_map(|generation| {
+            pre_pulsar.step();
+            cycles.observe(&pre_pulsar).map(|period| (generation, period))
+        });
+        let (generation, period) = found.unwrap();
+        assert_eq!(3, period);
+        assert!(generation >= 23);
+
+        // A glider never repeats exactly
+        let mut glider = world("bo$2bo$3o!");
+        let mut cycles = CycleDetector::any_period();
+        cycles.observe(&glider);
+        for _ in 0..100 {
+            glider.step();
+            assert_eq!(None, cycles.observe(&glider));
+        }
+    }
+
+    #[test]
+    fn clusters_and_components() {
+        let cells: Vec<Position> = [(0, 0), (1, 1), (5, 5), (5, 6), (9, 9)].into_iter().map(Position::from).collect();
+        let mut sizes: Vec<usize> = clusters(&cells, 1).iter().map(Vec::len).collect();
+        sizes.sort();
+        assert_eq!(vec![1, 2, 2], sizes);
+        assert_eq!(1, clusters(&cells, 4).len());
+
+        // A glider below a blinker, each extracted where it is
+        let mut world = world("3o5$bo$2bo$3o!");
+        world.set_cell(10, 10, Cell::Alive);
+        let components = components(&world);
+        assert_eq!(3, components.len());
+        assert_eq!(Rect::new(0, 0, 3, 1), components[0].bounds());
+        assert_eq!(Rect::new(0, 5, 3, 3), components[1].bounds());
+        assert_eq!(Some(Cell::Alive), components[1].get_cell(1, 5));
+        assert_eq!(Some(Cell::Dead), components[1].get_cell(0, 5));
+        assert_eq!(Rect::new(10, 10, 1, 1), components[2].bounds());
+        assert!(super::components(&GameOfLife::new()).is_empty());
+    }
+
+    #[test]
+    fn symmetries() {
+        let symmetry = |rle: &str| symmetry(&world(rle));
+        // Block, glider, blinker, a pre-block, boat, ship and the R-pentomino
+        assert_eq!(Symmetry::D8, symmetry("2o$2o!"));
+        assert_eq!(Symmetry::C1, symmetry("bo$2bo$3o!"));
+        assert_eq!(Symmetry::D4, symmetry("3o!"));
+        assert_eq!(Symmetry::D2, symmetry("2o$o!"));
+        assert_eq!(Symmetry::D2, symmetry("2o$obo$bo!"));
+        assert_eq!(Symmetry::D4, symmetry("2o$obo$b2o!"));
+        assert_eq!(Symmetry::C1, symmetry("b2o$2o$bo!"));
+
+        // A half turn only, and the pinwheel-like quarter turn of four L shapes
+        assert_eq!(Symmetry::C2, symmetry("2o$bo$bo$b2o!"));
+        assert_eq!(Symmetry::C4, symmetry("bo$b3o$3o$2bo!"));
+        assert_eq!(Symmetry::D8, symmetry(""));
+        assert_eq!("D4", Symmetry::D4.to_string());
+    }
+
+    #[test]
+    fn stability() {
+        // Populations wobbling by one cell around a period of 2
+        let mut detector = StabilityDetector::new(3, 1, 4);
+        let settled: Vec<Option<u64>> = [50, 20, 30, 21, 30, 20, 31, 20].into_iter()
+            .map(|population| detector.observe_population(population))
+            .collect();
+        assert_eq!(vec![None, None, None, None, None, None, Some(2), Some(2)], settled);
+
+        // A change breaks the streak, and a constant population is period 1
+        assert_eq!(None, detector.observe_population(100));
+        detector.reset();
+        assert_eq!(None, detector.observe_population(40));
+        for _ in 0..3 {
+            assert_eq!(None, detector.observe_population(41));
+        }
+        assert_eq!(Some(1), detector.observe_population(40));
+
+        // A glider never repeats its state, but its population is always 5
+        let mut glider = world("bo$2bo$3o!");
+        let mut detector = StabilityDetector::new(1, 0, 10);
+        assert_eq!(None, detector.observe(&glider));
+        let settled = (1..=20).find(|_| {
+            glider.step();
+            detector.observe(&glider).is_some()
+        });
+        assert_eq!(Some(10), settled);
+    }
+
+    #[test]
+    fn density_and_entropy() {
+        assert_eq!(1.0, density(&world("2o$2o!")));
+        assert_eq!(5.0 / 9.0, density(&world("bo$2bo$3o!")));
+        assert_eq!(0.0, density(&GameOfLife::new()));
+
+        // Every block of a block is the same, and a beacon is half full blocks and half empty
+        assert_eq!(0.0, block_entropy(&world("2o$2o!"), 2));
+        assert_eq!(0.25, block_entropy(&world("2o$2o$2b2o$2b2o!"), 2));
+        assert_eq!(0.0, block_entropy(&GameOfLife::new(), 3));
+        // With single cells it is the entropy of a cell being alive
+        let alive: f64 = 5.0 / 9.0;
+        let expected = -alive * alive.log2() - (1.0 - alive) * (1.0 - alive).log2();
+        assert!((block_entropy(&world("bo$2bo$3o!"), 1) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hash_ignores_storage() {
+        let mut split = GameOfLife::new();
+        split.set_region(&read_rle("o!").unwrap());
+        split.set_cell(1, 0, Cell::Alive);
+        assert_eq!(state_hash(&world("2o!")), state_hash(&split));
+        assert_ne!(state_hash(&world("2o!")), state_hash(&world("o$o!")));
+    }
+}
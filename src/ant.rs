@@ -0,0 +1,280 @@
+//! Langton's Ant and its many-coloured relatives, where ants walk over the grid turning by the colour of each cell
+//! they land on and recolouring it as they leave. Like [`generations`](crate::generations), cells are kept in
+//! a sparse map of those that aren't colour 0, as the ants only ever touch a thin trail of them.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife};
+use crate::rule::RuleError;
+
+/// Which way an ant turns on a cell of a given colour.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+    /// Carry straight on.
+    None,
+    /// Turn around to face back the way it came.
+    UTurn
+}
+
+impl Turn {
+    fn letter(self) -> char {
+        match self {
+            Turn::Left => 'L',
+            Turn::Right => 'R',
+            Turn::None => 'N',
+            Turn::UTurn => 'U'
+        }
+    }
+}
+
+/// Direction an ant is facing, with up being towards -y.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Heading {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left
+}
+
+impl Heading {
+    /// The heading after making the turn.
+    pub fn turn(self, turn: Turn) -> Heading {
+        let quarter_turns = match turn {
+            Turn::Left => 3,
+            Turn::Right => 1,
+            Turn::None => 0,
+            Turn::UTurn => 2
+        };
+        [Heading::Up, Heading::Right, Heading::Down, Heading::Left][(self as usize + quarter_turns) % 4]
+    }
+
+    /// Offset to the next cell in this direction.
+    pub fn offset(self) -> Position {
+        match self {
+            Heading::Up => Position::new(0, -1),
+            Heading::Right => Position::new(1, 0),
+            Heading::Down => Position::new(0, 1),
+            Heading::Left => Position::new(-1, 0)
+        }
+    }
+}
+
+/// Turn for each colour of cell, in order, such as `RL` for Langton's Ant. Each cell an ant leaves
+/// is recoloured to the next colour, wrapping back to 0 after the last.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AntRule {
+    turns: Vec<Turn>
+}
+
+impl AntRule {
+    /// Most colours a rule can have.
+    pub const MAX_COLOURS: usize = 256;
+
+    /// Langton's Ant, `RL`.
+    pub fn langton() -> AntRule {
+        AntRule { turns: vec![Turn::Right, Turn::Left] }
+    }
+
+    /// Create a rule from the turn for each colour. Rules need between 2 and [`AntRule::MAX_COLOURS`] colours.
+    pub fn new(turns: &[Turn]) -> Result<AntRule, RuleError> {
+        if turns.len() < 2 { return Err(RuleError::TooFewStates(turns.len() as u8)) }
+        if turns.len() > AntRule::MAX_COLOURS {
+            return Err(RuleError::InvalidRulestring(turns.iter().map(|turn| turn.letter()).collect()))
+        }
+        Ok(AntRule { turns: turns.to_vec() })
+    }
+
+    /// The turn made on a cell of the colour, and the colour it is left as.
+    /// Colours past the rule's last, painted by ants with more colours, are taken modulo the rule's.
+    pub fn apply(&self, colour: u8) -> (Turn, u8) {
+        let colour = colour as usize % self.turns.len();
+        (self.turns[colour], ((colour + 1) % self.turns.len()) as u8)
+    }
+
+    // GETTERS
+    pub fn turns(&self) -> &[Turn] { &self.turns }
+}
+
+impl Display for AntRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.turns.iter().try_for_each(|turn| write!(f, "{}", turn.letter()))
+    }
+}
+
+impl FromStr for AntRule {
+    type Err = RuleError;
+
+    /// Parse a rule as a letter per colour, `L`, `R`, `N` or `U`, ignoring case.
+    fn from_str(s: &str) -> Result<AntRule, RuleError> {
+        let turns = s.trim().chars().map(|c| match c.to_ascii_uppercase() {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            'N' => Ok(Turn::None),
+            'U' => Ok(Turn::UTurn),
+            _ => Err(RuleError::InvalidRulestring(s.to_string()))
+        }).collect::<Result<Vec<Turn>, RuleError>>()?;
+        AntRule::new(&turns)
+    }
+}
+
+/// An ant on the grid, following its own rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ant {
+    pub position: Position,
+    pub heading: Heading,
+    pub rule: AntRule
+}
+
+impl Ant {
+    /// Create an ant at the position facing up.
+    pub fn new(position: Position, rule: AntRule) -> Ant {
+        Ant { position, heading: Heading::Up, rule }
+    }
+}
+
+/// Infinite grid of coloured cells with ants walking over it.
+#[derive(Debug, Clone, Default)]
+pub struct AntWorld {
+    /// Colour of every cell that isn't colour 0.
+    cells: HashMap<Position, u8>,
+    ants: Vec<Ant>,
+    generation: u64
+}
+
+impl AntWorld {
+    /// Create a new empty world with no ants.
+    pub fn new() -> AntWorld {
+        AntWorld::default()
+    }
+
+    /// Create a world with the live cells of a two-state world as colour 1, and no ants.
+    pub fn from_world(world: &GameOfLife) -> AntWorld {
+        AntWorld { cells: world.live_cells().into_iter().map(|cell| (cell, 1)).collect(), ..AntWorld::default() }
+    }
+
+    /// Add an ant, which moves after every ant already added.
+    pub fn add_ant(&mut self, ant: Ant) {
+        self.ants.push(ant);
+    }
+
+    /// Move every ant one cell. Each ant turns by the colour of its cell, recolours it and steps forward,
+    /// one after another in the order they were added, so an ant sees the colours left by those before it.
+    pub fn step(&mut self) {
+        for ant in &mut self.ants {
+            let colour = self.cells.get(&ant.position).copied().unwrap_or(0);
+            let (turn, next) = ant.rule.apply(colour);
+            match next {
+                0 => self.cells.remove(&ant.position),
+                next => self.cells.insert(ant.position, next)
+            };
+            ant.heading = ant.heading.turn(turn);
+            ant.position = ant.position.saturating_add(ant.heading.offset());
+        }
+        self.generation += 1;
+    }
+
+    /// Returns the colour of the cell at the position.
+    pub fn colour(&self, position: Position) -> u8 {
+        self.cells.get(&position).copied().unwrap_or(0)
+    }
+
+    /// Set the colour of the cell at the position.
+    pub fn set_colour(&mut self, position: Position, colour: u8) {
+        match colour {
+            0 => self.cells.remove(&position),
+            colour => self.cells.insert(position, colour)
+        };
+    }
+
+    /// Every cell that isn't colour 0, with its colour, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, u8)> + '_ {
+        self.cells.iter().map(|(&position, &colour)| (position, colour))
+    }
+
+    /// Number of cells that aren't colour 0.
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the bounding box of every cell that isn't colour 0, or [`None`] if they all are.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::around(self.cells.keys().copied())
+    }
+
+    /// Copy the cells into a two-state world, with every cell that isn't colour 0 alive.
+    pub fn to_world(&self) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for &position in self.cells.keys() {
+            world.set_cell(position.x, position.y, Cell::Alive);
+        }
+        world
+    }
+
+    // GETTERS
+    pub fn ants(&self) -> &[Ant] { &self.ants }
+    pub fn generation(&self) -> u64 { self.generation }
+}
+
+#[cfg(test)]
+mod ant_tests {
+    use super::*;
+
+    #[test]
+    fn rules() {
+        assert_eq!(Ok(AntRule::langton()), "rl".parse());
+        let rule: AntRule = "LRRL".parse().unwrap();
+        assert_eq!("LRRL", rule.to_string());
+        assert_eq!((Turn::Left, 0), rule.apply(3));
+        assert_eq!((Turn::Right, 2), rule.apply(5));
+        assert_eq!(Err(RuleError::TooFewStates(1)), "L".parse::<AntRule>());
+        assert_eq!(Err(RuleError::InvalidRulestring("LX".to_string())), "LX".parse::<AntRule>());
+        assert_eq!(Heading::Left, Heading::Up.turn(Turn::Left));
+        assert_eq!(Heading::Up, Heading::Down.turn(Turn::UTurn));
+    }
+
+    #[test]
+    fn langtons_ant() {
+        // Four right turns walk a square, then the ant turns left off the cell it started on, clearing it
+        let mut world = AntWorld::new();
+        world.add_ant(Ant::new(Position::ORIGIN, AntRule::langton()));
+        for _ in 0..4 {
+            world.step();
+        }
+        assert_eq!((4, Position::ORIGIN), (world.population(), world.ants()[0].position));
+        world.step();
+        assert_eq!((3, 0), (world.population(), world.colour(Position::ORIGIN)));
+        assert_eq!((Position::new(-1, 0), Heading::Left), (world.ants()[0].position, world.ants()[0].heading));
+        assert_eq!(5, world.generation());
+    }
+
+    #[test]
+    fn mirrored_ants() {
+        // Swapping every turn mirrors the path, so LR draws Langton's Ant reflected left to right
+        let mut ants = [AntRule::langton(), "LR".parse().unwrap()].map(|rule| {
+            let mut world = AntWorld::new();
+            world.add_ant(Ant::new(Position::ORIGIN, rule));
+            world
+        });
+        for _ in 0..500 {
+            ants.iter_mut().for_each(AntWorld::step);
+        }
+        let [right, left] = &ants;
+        assert_eq!(right.population(), left.population());
+        assert!(right.cells().all(|(position, colour)| left.colour(Position::new(-position.x, position.y)) == colour));
+
+        // Ants share the grid, so a second ant on the same cell turns by the colour the first left it
+        let mut world = AntWorld::new();
+        for rule in ["RL", "RLR"] {
+            world.add_ant(Ant::new(Position::ORIGIN, rule.parse().unwrap()));
+        }
+        world.step();
+        assert_eq!(2, world.colour(Position::ORIGIN));
+        assert_eq!([Position::new(1, 0), Position::new(-1, 0)], [world.ants()[0].position, world.ants()[1].position]);
+    }
+}
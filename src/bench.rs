@@ -0,0 +1,195 @@
+//! Standard workloads for measuring how fast worlds step, so versions and backends can be compared on equal terms.
+//! Each workload always starts from the same cells and runs the same number of generations,
+//! and times only the stepping, not building the world.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::geometry::Coord;
+use crate::gol::GameOfLife;
+use crate::io::rle::read_rle;
+use crate::rule::Rule;
+use crate::runner::Runner;
+use crate::soup::{random_soup, Rng};
+
+/// A methuselah that settles after 1103 generations, leaving ash and gliders flying off.
+const R_PENTOMINO: &str = "b2o$2o$bo!";
+/// Gosper glider gun, which keeps growing by a glider every 30 generations.
+const GLIDER_GUN: &str = "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!";
+/// Width and height of the random soup.
+const SOUP_SIZE: usize = 1024;
+
+/// A pattern and how many generations to step it for.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Workload {
+    /// R-pentomino for 10000 generations, a small pattern that spreads out over time.
+    RPentomino,
+    /// 1024 by 1024 random soup at 35% density for 100 generations, dense activity over a large area.
+    Soup,
+    /// Gosper glider gun for 2000 generations, steady growth in a long thin stream.
+    GliderGun
+}
+
+impl Workload {
+    pub const ALL: [Workload; 3] = [Workload::RPentomino, Workload::Soup, Workload::GliderGun];
+
+    /// Create the starting world for the workload, using the given rule.
+    pub fn world(&self, rule: Rule) -> GameOfLife {
+        let region = match self {
+            Workload::RPentomino => read_rle(R_PENTOMINO).expect("Valid R-pentomino"),
+            Workload::GliderGun => read_rle(GLIDER_GUN).expect("Valid glider gun"),
+            Workload::Soup => {
+                let half = (SOUP_SIZE / 2) as Coord;
+                random_soup(&mut Rng::new(1), -half, -half, SOUP_SIZE, SOUP_SIZE, 0.35).expect("Soup fits in a region")
+            }
+        };
+        let mut world = GameOfLife::with_rule(rule);
+        world.set_region(&region);
+        world
+    }
+
+    /// Number of generations the workload is stepped for.
+    pub fn generations(&self) -> u64 {
+        match self {
+            Workload::RPentomino => 10_000,
+            Workload::Soup => 100,
+            Workload::GliderGun => 2000
+        }
+    }
+}
+
+impl Display for Workload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Workload::RPentomino => write!(f, "r-pentomino"),
+            Workload::Soup => write!(f, "soup-{}", SOUP_SIZE),
+            Workload::GliderGun => write!(f, "glider-gun")
+        }
+    }
+}
+
+/// A way of stepping a world.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Backend {
+    /// Step the world directly on the calling thread.
+    Direct,
+    /// Step the world on a background [`Runner`], which also publishes a snapshot after every generation.
+    Runner
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Direct, Backend::Runner];
+
+    /// Step the world the given number of generations, returning it as it ends up and how long the stepping took.
+    pub fn run(&self, world: GameOfLife, generations: u64) -> (GameOfLife, Duration) {
+        let start = Instant::now();
+        match self {
+            Backend::Direct => {
+                let mut world = world;
+                for _ in 0..generations {
+                    world.step();
+                }
+                (world, start.elapsed())
+            }
+            Backend::Runner => {
+                let runner = Runner::start(world, Some(generations));
+                while !runner.is_finished() {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                let elapsed = start.elapsed();
+                (runner.stop().to_world(), elapsed)
+            }
+        }
+    }
+}
+
+impl Display for Backend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Direct => write!(f, "direct"),
+            Backend::Runner => write!(f, "runner")
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = BackendError;
+
+    fn from_str(s: &str) -> Result<Backend, BackendError> {
+        Backend::ALL.into_iter()
+            .find(|backend| backend.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| BackendError(s.to_string()))
+    }
+}
+
+/// Error from parsing the name of a backend that doesn't exist.
+#[derive(Debug, PartialEq)]
+pub struct BackendError(pub String);
+
+impl Display for BackendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown backend '{}', expected direct or runner", self.0)
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+/// Timing of one run of a world on a backend.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub backend: Backend,
+    pub generations: u64,
+    pub elapsed: Duration,
+    pub final_population: usize
+}
+
+impl Measurement {
+    /// Step a copy of the world on the backend and time it.
+    pub fn take(world: &GameOfLife, backend: Backend, generations: u64) -> Measurement {
+        let (world, elapsed) = backend.run(world.clone(), generations);
+        Measurement { backend, generations, elapsed, final_population: world.population() }
+    }
+
+    pub fn generations_per_second(&self) -> f64 {
+        self.generations as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} generations in {:.3}s ({:.1} generations/s), final population {}",
+            self.generations,
+            self.elapsed.as_secs_f64(),
+            self.generations_per_second(),
+            self.final_population
+        )
+    }
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use super::*;
+
+    #[test]
+    fn workloads() {
+        assert_eq!(5, Workload::RPentomino.world(Rule::LIFE).population());
+        assert_eq!(36, Workload::GliderGun.world(Rule::LIFE).population());
+        assert_eq!("soup-1024", Workload::Soup.to_string());
+    }
+
+    #[test]
+    fn backends_agree() {
+        let world = Workload::GliderGun.world(Rule::LIFE);
+        let measurements: Vec<Measurement> = Backend::ALL.into_iter().map(|backend| Measurement::take(&world, backend, 60)).collect();
+        // Two new gliders, of 5 cells each
+        assert!(measurements.iter().all(|measurement| measurement.final_population == 46));
+        assert!(measurements[0].to_string().starts_with("60 generations in "));
+
+        assert_eq!(Ok(Backend::Runner), "Runner".parse());
+        assert_eq!(Err(BackendError("gpu".to_string())), "gpu".parse::<Backend>());
+    }
+}
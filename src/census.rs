@@ -0,0 +1,204 @@
+//! Census of the objects a world has settled into, the ash left behind once a soup stops changing.
+//! Live cells are split into separate objects, and each is named from a dictionary of the common ones
+//! in Conway's Life, in the style of soup searches such as apgsearch.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+use crate::analysis::{clusters, detect_recurrence};
+use crate::geometry::{Coord, Position};
+use crate::gol::{Cell, GameOfLife, Region};
+use crate::io::rle::{read_rle, write_rle};
+use crate::rule::Rule;
+
+/// Objects named in a census, as their name and an RLE of any one phase.
+const KNOWN_OBJECTS: [(&str, &str); 22] = [
+    ("block", "2o$2o!"),
+    ("beehive", "b2o$o2bo$b2o!"),
+    ("loaf", "b2o$o2bo$bobo$2bo!"),
+    ("boat", "2o$obo$bo!"),
+    ("ship", "2o$obo$b2o!"),
+    ("tub", "bo$obo$bo!"),
+    ("pond", "b2o$o2bo$o2bo$b2o!"),
+    ("long boat", "2o$obo$bobo$2bo!"),
+    ("barge", "bo$obo$bobo$2bo!"),
+    ("mango", "b2o$o2bo$bo2bo$2b2o!"),
+    ("eater", "2o$obo$2bo$2b2o!"),
+    ("ship-tie", "2o$obo$b2o$3b2o$3bobo$4b2o!"),
+    ("bi-block", "2ob2o$2ob2o!"),
+    ("blinker", "3o!"),
+    ("toad", "b3o$3o!"),
+    ("beacon", "2o$2o$2b2o$2b2o!"),
+    ("traffic light", "4bo$4bo$4bo2$3o3b3o2$4bo$4bo$4bo!"),
+    ("pulsar", "2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    ("glider", "bo$2bo$3o!"),
+    ("lightweight spaceship", "bo2bo$o4b$o3bo$4o!"),
+    ("middleweight spaceship", "2bo3b$o3bob$5bo$o4bo$b5o!"),
+    ("heavyweight spaceship", "2b2o3b$o4bob$6bo$o5bo$b6o!")
+];
+
+/// Longest period of the known objects, so every phase of each is seen.
+/// The pentadecathlon is left out, as it falls into two pieces for some of its phases.
+const MAX_KNOWN_PERIOD: u64 = 4;
+
+/// Cells at most this far apart, along either axis, share neighbours so can affect each other,
+/// and are counted as part of the same object.
+const OBJECT_REACH: Coord = 2;
+
+/// Number of each kind of object in a world.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Census {
+    /// Name and count of each kind of object, most common first. Objects outside the dictionary
+    /// are named by their shape, as the RLE body of [`canonical_shape`].
+    pub objects: Vec<(String, usize)>
+}
+
+impl Census {
+    /// Split the world's live cells into objects and count each kind.
+    /// Objects are only named under Conway's Life, as the same shapes behave differently under other rules.
+    /// Objects close enough to interact, such as a pair of blocks one cell apart, count as a single unnamed object.
+    pub fn take(world: &GameOfLife) -> Census {
+        let names = (world.rule() == Rule::LIFE).then(known_shapes);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for object in clusters(&world.live_cells(), OBJECT_REACH) {
+            let shape = canonical_shape(&object);
+            let name = names.and_then(|names| names.get(&shape)).map_or(shape, |name| name.to_string());
+            *counts.entry(name).or_insert(0) += 1;
+        }
+        let mut objects: Vec<(String, usize)> = counts.into_iter().collect();
+        objects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Census { objects }
+    }
+
+    /// Returns how many of the named kind of object there are.
+    pub fn count(&self, name: &str) -> usize {
+        self.objects.iter().find(|(object, _)| object == name).map_or(0, |(_, count)| *count)
+    }
+
+    /// Total number of objects of every kind.
+    pub fn total(&self) -> usize {
+        self.objects.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl Display for Census {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, count) in &self.objects {
+            writeln!(f, "{:>6} {}", count, name)?;
+        }
+        writeln!(f, "{:>6} total", self.total())
+    }
+}
+
+/// Canonical shape of every phase of every known object, with the object's name.
+fn known_shapes() -> &'static HashMap<String, &'static str> {
+    static SHAPES: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    SHAPES.get_or_init(|| {
+        let mut shapes = HashMap::new();
+        for (name, rle) in KNOWN_OBJECTS {
+            let mut world = GameOfLife::new();
+            world.set_region(&read_rle(rle).expect("Known objects are valid RLE"));
+            let period = detect_recurrence(&mut world.clone(), MAX_KNOWN_PERIOD).map_or(1, |recurrence| recurrence.period);
+            for _ in 0..period {
+                shapes.insert(canonical_shape(&world.live_cells()), name);
+                world.step();
+            }
+        }
+        shapes
+    })
+}
+
+/// Name for the shape of a cluster of cells that is the same for every position, rotation and reflection,
+/// which is the RLE body of whichever orientation sorts first.
+pub fn canonical_shape(cells: &[Position]) -> String {
+    type Transform = fn((Coord, Coord)) -> (Coord, Coord);
+    let transforms: [Transform; 8] = [
+        |(x, y)| (x, y), |(x, y)| (-x, y), |(x, y)| (x, -y), |(x, y)| (-x, -y),
+        |(x, y)| (y, x), |(x, y)| (-y, x), |(x, y)| (y, -x), |(x, y)| (-y, -x),
+    ];
+
+    let orientation = transforms.iter().map(|transform| {
+        let mut transformed: Vec<(Coord, Coord)> = cells.iter().map(|&cell| transform(cell.into())).collect();
+        let min_x = transformed.iter().map(|cell| cell.0).min().unwrap_or(0);
+        let min_y = transformed.iter().map(|cell| cell.1).min().unwrap_or(0);
+        for cell in &mut transformed {
+            *cell = (cell.0 - min_x, cell.1 - min_y);
+        }
+        // Sort by row first, to match the order cells appear in RLE
+        transformed.sort_unstable_by_key(|&(x, y)| (y, x));
+        transformed
+    }).min_by_key(|orientation| orientation.iter().map(|&(x, y)| (y, x)).collect::<Vec<_>>()).unwrap_or_default();
+
+    let width = orientation.iter().map(|cell| cell.0 + 1).max().unwrap_or(0) as usize;
+    let height = orientation.iter().map(|cell| cell.1 + 1).max().unwrap_or(0) as usize;
+    let mut region = Region::new(0, 0, width, height).expect("Clusters fit in a region");
+    for (x, y) in orientation {
+        region.set_cell(x, y, Cell::Alive);
+    }
+
+    // Drop the header line and end marker
+    let rle = write_rle(&region);
+    rle.lines().skip(1).collect::<String>().trim_end_matches('!').to_string()
+}
+
+#[cfg(test)]
+mod census_tests {
+    use super::*;
+
+    fn positions(cells: &[(Coord, Coord)]) -> Vec<Position> {
+        cells.iter().copied().map(Position::from).collect()
+    }
+
+    fn world(objects: &[(&str, Coord, Coord)]) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for &(rle, x, y) in objects {
+            let mut region = read_rle(rle).unwrap();
+            region.set_position(x, y).unwrap();
+            world.set_region(&region);
+        }
+        world
+    }
+
+    #[test]
+    fn canonical_shapes() {
+        // Both blinker phases are the same shape
+        assert_eq!("3o", canonical_shape(&positions(&[(0, 0), (1, 0), (2, 0)])));
+        assert_eq!("3o", canonical_shape(&positions(&[(5, -1), (5, 0), (5, 1)])));
+
+        let glider = canonical_shape(&positions(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]));
+        let mirrored = canonical_shape(&positions(&[(1, 0), (0, 1), (2, 2), (1, 2), (0, 2)]));
+        assert_eq!(glider, mirrored);
+    }
+
+    #[test]
+    fn known_objects() {
+        // Every phase of every known object is named, however it is turned
+        for (name, rle) in KNOWN_OBJECTS {
+            let mut world = world(&[(rle, 0, 0)]);
+            for _ in 0..MAX_KNOWN_PERIOD {
+                assert_eq!(vec![(name.to_string(), 1)], Census::take(&world).objects, "{}", name);
+                world.step();
+            }
+        }
+    }
+
+    #[test]
+    fn ash() {
+        let mut world = world(&[("2o$2o!", 0, 0), ("o$o$o!", 10, 0), ("bo$obo$bo!", 0, 10), ("2o$2o!", 20, 20), ("3o$o!", 30, 0)]);
+        let census = Census::take(&world);
+        let expected = [("block", 2), ("3o$o", 1), ("blinker", 1), ("tub", 1)];
+        assert_eq!(expected.map(|(name, count)| (name.to_string(), count)).to_vec(), census.objects);
+        assert_eq!(2, census.count("block"));
+        assert_eq!(0, census.count("glider"));
+        assert_eq!("     2 block\n     1 3o$o\n     1 blinker\n     1 tub\n     5 total\n", census.to_string());
+
+        // The tetromino settles into a beehive, and nothing is named under other rules
+        for _ in 0..3 {
+            world.step();
+        }
+        assert_eq!(1, Census::take(&world).count("beehive"));
+        world.set_rule("B36/S23".parse().unwrap());
+        assert_eq!(0, Census::take(&world).count("block"));
+    }
+}
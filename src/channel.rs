@@ -0,0 +1,196 @@
+//! Two-state worlds carrying a value alongside every cell, updated as the world steps, such as how old each cell is
+//! or which player owns it. Cells are kept as [`SparseCells`] of [`Tagged`] states, so the value travels with the cell
+//! through births and deaths rather than being tracked from outside by comparing generations.
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+use crate::rule::{Rule, RuleError};
+use crate::state::{CellState, SparseCells};
+
+/// Most different owners a cell being born tells apart among its neighbours. The 8 Moore neighbours never have more,
+/// and past it in larger neighbourhoods the owners of further neighbours are left out of the vote.
+const MAX_PARENT_OWNERS: usize = 8;
+
+/// What the value carried by each cell means, and so how stepping changes it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Generations the cell has been alive for, from 0 when it is born.
+    Age,
+    /// Id of the player owning the cell. Cells born take the owner with the most weight among their live
+    /// neighbours, ties going to the lowest id, and keep it while they survive.
+    Owner,
+    /// Generations the cell has ever been alive for in total, kept after it dies, so every cell a pattern
+    /// has touched stays marked as in LifeHistory.
+    History
+}
+
+/// A cell's state together with the value it carries. Dead cells carrying 0 are the default, and aren't stored.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Tagged {
+    pub state: Cell,
+    pub data: u16
+}
+
+impl CellState for Tagged {
+    fn is_alive(self) -> bool { self.state == Cell::Alive }
+}
+
+/// Live neighbours of a cell, with the weight each owner among them has.
+#[derive(Debug, Copy, Clone, Default)]
+struct Parents {
+    count: u8,
+    owners: [(u16, u8); MAX_PARENT_OWNERS]
+}
+
+impl Parents {
+    fn add(&mut self, owner: u16, weight: u8) {
+        self.count += weight;
+        let owners = &mut self.owners[..];
+        if let Some(entry) = owners.iter_mut().find(|(id, total)| *total > 0 && *id == owner) {
+            entry.1 += weight;
+        }
+        else if let Some(entry) = owners.iter_mut().find(|(_, total)| *total == 0) {
+            *entry = (owner, weight);
+        }
+    }
+
+    /// Owner with the most weight, the lowest id among those tied.
+    fn majority(&self) -> u16 {
+        self.owners.iter()
+            .filter(|(_, total)| *total > 0)
+            .max_by(|(a, a_total), (b, b_total)| a_total.cmp(b_total).then(b.cmp(a)))
+            .map_or(0, |&(owner, _)| owner)
+    }
+}
+
+/// Infinite world of two-state cells following a rule, each carrying a value.
+#[derive(Debug, Clone)]
+pub struct ChannelWorld {
+    cells: SparseCells<Tagged>,
+    rule: Rule,
+    channel: Channel
+}
+
+impl ChannelWorld {
+    /// Create a new empty world using the given rule, with cells carrying values of the channel.
+    /// Fails for rules with B0, as every cell of the background would need a value.
+    pub fn new(rule: Rule, channel: Channel) -> Result<ChannelWorld, RuleError> {
+        if rule.has_b0() { return Err(RuleError::BirthOnZero) }
+        Ok(ChannelWorld { cells: SparseCells::new(), rule, channel })
+    }
+
+    /// Create a world with the live cells of a two-state world under its rule, each carrying the given value.
+    pub fn from_world(world: &GameOfLife, channel: Channel, data: u16) -> Result<ChannelWorld, RuleError> {
+        let mut channel_world = ChannelWorld::new(world.rule(), channel)?;
+        channel_world.cells = SparseCells::from_world(world, Tagged { state: Cell::Alive, data });
+        Ok(channel_world)
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    pub fn step(&mut self) -> StepStats {
+        let (rule, channel) = (self.rule, self.channel);
+        self.cells.step(
+            rule.neighbourhood(),
+            |parents: &mut Parents, cell, weight| parents.add(cell.data, weight),
+            |_, current, parents| {
+                let state = rule.next_state(current.state, parents.count);
+                let data = match (channel, current.state, state) {
+                    (Channel::Age, Cell::Alive, Cell::Alive) => current.data.saturating_add(1),
+                    (Channel::Owner, Cell::Alive, Cell::Alive) => current.data,
+                    (Channel::Owner, Cell::Dead, Cell::Alive) => parents.majority(),
+                    (Channel::History, _, Cell::Alive) => current.data.saturating_add(1),
+                    (Channel::History, _, Cell::Dead) => current.data,
+                    _ => 0
+                };
+                Tagged { state, data }
+            }
+        )
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get_cell(&self, position: Position) -> Cell {
+        self.cells.get(position).state
+    }
+
+    /// Returns the value the cell at the position carries, 0 for cells never touched.
+    pub fn data(&self, position: Position) -> u16 {
+        self.cells.get(position).data
+    }
+
+    /// Set the state of the cell at the position and the value it carries.
+    pub fn set_cell(&mut self, position: Position, state: Cell, data: u16) {
+        self.cells.set(position, Tagged { state, data });
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.population()
+    }
+
+    /// Positions of the live cells, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.live_cells()
+    }
+
+    /// Every cell that is alive or carries a value, with its state and value, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, Tagged)> + '_ {
+        self.cells.iter()
+    }
+
+    /// Returns the bounding box of every cell that is alive or carries a value, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.cells.bounds()
+    }
+
+    /// Copy the live cells into a two-state world under the same rule, dropping the values they carry.
+    pub fn to_world(&self) -> GameOfLife {
+        self.cells.to_world(self.rule)
+    }
+
+    // GETTERS
+    pub fn rule(&self) -> Rule { self.rule }
+    pub fn channel(&self) -> Channel { self.channel }
+}
+
+#[cfg(test)]
+mod channel_tests {
+    use super::*;
+    use crate::io::rle::read_rle;
+
+    fn world(pattern: &str, channel: Channel) -> ChannelWorld {
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle(pattern).unwrap());
+        ChannelWorld::from_world(&world, channel, 1).unwrap()
+    }
+
+    #[test]
+    fn ages_and_history() {
+        // A blinker's centre survives every generation while its ends are born and die in turn
+        let mut ages = world("3o!", Channel::Age);
+        let mut history = world("3o!", Channel::History);
+        for _ in 0..4 {
+            assert_eq!(ages.step(), history.step());
+        }
+        assert_eq!((5, 0), (ages.data(Position::new(1, 0)), ages.data(Position::new(1, -1))));
+
+        // History keeps every cell the blinker has used, alive or not
+        assert_eq!((5, 5), (history.cells().count(), history.data(Position::new(1, 0))));
+        assert_eq!((Cell::Dead, 2), (history.get_cell(Position::new(1, -1)), history.data(Position::new(1, -1))));
+    }
+
+    #[test]
+    fn owners() {
+        // Two players' cells next to each other give birth to cells owned by whichever has more parents
+        let mut world = ChannelWorld::new(Rule::LIFE, Channel::Owner).unwrap();
+        world.set_cell(Position::new(0, 0), Cell::Alive, 2);
+        world.set_cell(Position::new(1, 0), Cell::Alive, 2);
+        world.set_cell(Position::new(2, 0), Cell::Alive, 7);
+        world.step();
+        assert_eq!((2, 2), (world.data(Position::new(1, -1)), world.data(Position::new(1, 0))));
+        assert_eq!(0, world.data(Position::new(0, 0)));
+
+        let mut parents = Parents::default();
+        [(5, 1), (3, 1), (5, 1), (3, 1)].into_iter().for_each(|(owner, weight)| parents.add(owner, weight));
+        assert_eq!((4, 3), (parents.count, parents.majority()));
+    }
+}
@@ -0,0 +1,90 @@
+//! Coordinate math for dividing the world into square chunks of a fixed size.
+//! Plain integer division rounds towards zero, which puts the cells either side of 0 in the same chunk,
+//! so everything here rounds towards negative infinity instead.
+
+use core::ops::Range;
+
+use crate::geometry::Coord;
+
+/// Integer division rounding towards negative infinity.
+/// Panics if the divisor is 0.
+pub fn floor_div(value: Coord, divisor: Coord) -> Coord {
+    value.div_euclid(divisor)
+}
+
+/// Remainder that is always in the range `0..divisor`, even for negative values.
+/// Panics if the divisor is 0 or negative.
+pub fn positive_mod(value: Coord, divisor: Coord) -> usize {
+    assert!(divisor > 0, "Divisor must be positive");
+    value.rem_euclid(divisor) as usize
+}
+
+/// Returns the coordinates of the chunk containing a world position.
+pub fn world_to_chunk(x: Coord, y: Coord, size: usize) -> (Coord, Coord) {
+    let size = size as Coord;
+    (floor_div(x, size), floor_div(y, size))
+}
+
+/// Returns the position of a world position within its chunk, from 0 0 at the -x -y corner.
+pub fn world_to_local(x: Coord, y: Coord, size: usize) -> (usize, usize) {
+    let size = size as Coord;
+    (positive_mod(x, size), positive_mod(y, size))
+}
+
+/// Returns the world position of the -x -y corner of a chunk.
+pub fn chunk_origin(chunk_x: Coord, chunk_y: Coord, size: usize) -> (Coord, Coord) {
+    let size = size as Coord;
+    (chunk_x * size, chunk_y * size)
+}
+
+/// Returns the world position of a position within a chunk.
+pub fn chunk_to_world(chunk_x: Coord, chunk_y: Coord, local_x: usize, local_y: usize, size: usize) -> (Coord, Coord) {
+    let (origin_x, origin_y) = chunk_origin(chunk_x, chunk_y, size);
+    (origin_x + local_x as Coord, origin_y + local_y as Coord)
+}
+
+/// Returns the range of chunk coordinates along one axis touched by `length` cells starting at `start`.
+/// An empty span touches no chunks.
+pub fn chunk_span(start: Coord, length: usize, size: usize) -> Range<Coord> {
+    let size = size as Coord;
+    let first = floor_div(start, size);
+    if length == 0 { return first..first }
+    first..floor_div(start.saturating_add_unsigned(length as u64) - 1, size) + 1
+}
+
+#[cfg(test)]
+mod chunk_tests {
+    use super::*;
+
+    #[test]
+    fn negative_coordinates() {
+        assert_eq!(-1, floor_div(-1, 16));
+        assert_eq!(-1, floor_div(-16, 16));
+        assert_eq!(-2, floor_div(-17, 16));
+        assert_eq!(15, positive_mod(-1, 16));
+        assert_eq!(0, positive_mod(-16, 16));
+
+        assert_eq!((-1, 0), world_to_chunk(-1, 0, 16));
+        assert_eq!((15, 0), world_to_local(-1, 0, 16));
+        assert_eq!((-16, 0), chunk_origin(-1, 0, 16));
+    }
+
+    #[test]
+    fn round_trip() {
+        for x in -40..40 {
+            for y in [-33, -1, 0, 31] {
+                let (chunk_x, chunk_y) = world_to_chunk(x, y, 8);
+                let (local_x, local_y) = world_to_local(x, y, 8);
+                assert!(local_x < 8 && local_y < 8);
+                assert_eq!((x, y), chunk_to_world(chunk_x, chunk_y, local_x, local_y, 8));
+            }
+        }
+    }
+
+    #[test]
+    fn span() {
+        assert_eq!(-1..1, chunk_span(-3, 6, 4));
+        assert_eq!(-1..0, chunk_span(-4, 4, 4));
+        assert_eq!(2..2, chunk_span(8, 0, 4));
+    }
+}
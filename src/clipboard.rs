@@ -0,0 +1,100 @@
+//! Copying and pasting patterns as RLE, matching the clipboard workflow of Golly.
+//! Access to the system clipboard itself needs the `clipboard` feature.
+
+#[cfg(feature = "clipboard")]
+use std::fmt::{Display, Formatter};
+
+use crate::error::GolError;
+use crate::geometry::Coord;
+use crate::gol::{GameOfLife, Region, RegionError};
+use crate::io::rle;
+
+/// Encode the area of the world from x y with the given width and height as RLE.
+pub fn selection_to_rle(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize) -> Result<String, RegionError> {
+    let mut selection = Region::new(x, y, width, height)?;
+    world.populate_region(&mut selection);
+    Ok(rle::write_rle(&selection))
+}
+
+/// Place an RLE pattern in the world with its -x -y corner at the given position,
+/// replacing every cell underneath it. Returns the placed pattern.
+pub fn paste_rle(world: &mut GameOfLife, text: &str, x: Coord, y: Coord) -> Result<Region, GolError> {
+    let mut pattern = rle::read_rle(text)?;
+    pattern.set_position(x, y)?;
+    world.set_region(&pattern);
+    Ok(pattern)
+}
+
+/// Errors from copying to or pasting from the system clipboard.
+#[cfg(feature = "clipboard")]
+#[derive(Debug)]
+pub enum ClipboardError {
+    /// The system clipboard couldn't be accessed, or didn't hold text.
+    Unavailable(arboard::Error),
+    /// The clipboard text wasn't a valid RLE pattern.
+    InvalidPattern(GolError),
+    /// The selection to copy was too large, or the place to paste at too far out, to fit in a region.
+    InvalidSelection(RegionError)
+}
+
+#[cfg(feature = "clipboard")]
+impl Display for ClipboardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardError::Unavailable(error) => write!(f, "clipboard unavailable: {}", error),
+            ClipboardError::InvalidPattern(error) => write!(f, "clipboard pattern: {}", error),
+            ClipboardError::InvalidSelection(error) => write!(f, "clipboard selection: {}", error)
+        }
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl std::error::Error for ClipboardError {}
+
+/// Copy the area of the world from x y with the given width and height to the system clipboard as RLE.
+#[cfg(feature = "clipboard")]
+pub fn copy_selection(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize) -> Result<(), ClipboardError> {
+    let text = selection_to_rle(world, x, y, width, height).map_err(ClipboardError::InvalidSelection)?;
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(ClipboardError::Unavailable)
+}
+
+/// Read an RLE pattern from the system clipboard with its -x -y corner at the given position,
+/// leaving the caller to place it so the paste can be journalled and undone like other edits.
+#[cfg(feature = "clipboard")]
+pub fn paste_at(x: Coord, y: Coord) -> Result<Region, ClipboardError> {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(ClipboardError::Unavailable)?;
+    let mut pattern = rle::read_rle(&text).map_err(ClipboardError::InvalidPattern)?;
+    pattern.set_position(x, y).map_err(ClipboardError::InvalidSelection)?;
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod clipboard_tests {
+    use super::*;
+    use crate::gol::Cell;
+
+    #[test]
+    fn copy_then_paste() {
+        let mut world = GameOfLife::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            world.set_cell(x - 20, y - 20, Cell::Alive);
+        }
+
+        let text = selection_to_rle(&world, -22, -22, 8, 8).unwrap();
+        assert_eq!("x = 3, y = 3\nbo$2bo$3o!\n", text);
+
+        // Pasting over a live cell clears it, as the whole pattern area is replaced
+        world.set_cell(50, 50, Cell::Alive);
+        let pasted = paste_rle(&mut world, &text, 50, 50).unwrap();
+        assert_eq!((50, 50), (pasted.x(), pasted.y()));
+        assert_eq!(Cell::Dead, world.get_cell(50, 50));
+        assert_eq!(Cell::Alive, world.get_cell(51, 50));
+        assert_eq!(Cell::Alive, world.get_cell(52, 52));
+
+        assert!(paste_rle(&mut world, "x = 1, y = 1\nq!", 0, 0).is_err());
+    }
+}
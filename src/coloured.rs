@@ -0,0 +1,205 @@
+//! Coloured variants of two-state rules, where live cells belong to competing populations. Cells are born into
+//! the colour most of their parents have and keep it while they survive, so the populations can be told apart
+//! as they grow and clash. Like [`generations`](crate::generations), cells are kept as [`SparseCells`] of those alive.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+use crate::render::Palette;
+use crate::rule::{Rule, RuleError};
+use crate::state::{CellState, SparseCells};
+
+/// How many colours live cells can be. States are numbered as in Golly, with 0 dead and each colour from 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// Two colours.
+    Immigration,
+    /// Four colours, where a cell born from three parents of different colours takes the fourth.
+    QuadLife
+}
+
+impl Variant {
+    /// Number of colours live cells can be.
+    pub fn colours(self) -> u8 {
+        match self {
+            Variant::Immigration => 2,
+            Variant::QuadLife => 4
+        }
+    }
+
+    /// Colours to draw each state with, from black for dead cells.
+    pub fn palette(self) -> Palette {
+        let colours = [[0, 0, 0], [240, 70, 60], [60, 140, 240], [90, 200, 80], [240, 210, 60]];
+        Palette::new(colours[..=self.colours() as usize].to_vec())
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Variant::Immigration => write!(f, "Immigration"),
+            Variant::QuadLife => write!(f, "QuadLife")
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = RuleError;
+
+    /// Parse the variant's name, ignoring case.
+    fn from_str(s: &str) -> Result<Variant, RuleError> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "immigration" => Ok(Variant::Immigration),
+            "quadlife" => Ok(Variant::QuadLife),
+            _ => Err(RuleError::InvalidRulestring(s.to_string()))
+        }
+    }
+}
+
+/// State of a cell, 0 if it is dead or its colour from 1 if it is alive.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct Colour(u8);
+
+impl CellState for Colour {
+    fn is_alive(self) -> bool { self.0 != 0 }
+}
+
+/// Infinite world of coloured cells, following a two-state rule for which cells are alive.
+#[derive(Debug, Clone)]
+pub struct ColouredWorld {
+    cells: SparseCells<Colour>,
+    rule: Rule,
+    variant: Variant
+}
+
+impl ColouredWorld {
+    /// Create a new empty world using the given rule and variant.
+    /// Fails for rules with B0, as there is no colour for the background to come alive as.
+    pub fn new(rule: Rule, variant: Variant) -> Result<ColouredWorld, RuleError> {
+        if rule.has_b0() { return Err(RuleError::BirthOnZero) }
+        Ok(ColouredWorld { cells: SparseCells::new(), rule, variant })
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    /// Each cell born takes the colour with the most weight among its live neighbours. Ties go to the lowest colour
+    /// not among those tied, as QuadLife's three different parents give the fourth, or else the lowest tied.
+    pub fn step(&mut self) -> StepStats {
+        let (rule, variant) = (self.rule, self.variant);
+        self.cells.step(
+            rule.neighbourhood(),
+            |tally: &mut [u8; 5], colour, weight| tally[colour.0 as usize] += weight,
+            |_, current, tally| {
+                let alive = if current.is_alive() { Cell::Alive } else { Cell::Dead };
+                match (alive, rule.next_state(alive, tally.iter().sum())) {
+                    (Cell::Alive, Cell::Alive) => current,
+                    (Cell::Dead, Cell::Alive) => Colour(newborn(variant, &tally)),
+                    (_, Cell::Dead) => Colour::default()
+                }
+            }
+        )
+    }
+
+    /// Returns the state of the cell at the position, 0 if it is dead or its colour from 1 if it is alive.
+    pub fn state(&self, position: Position) -> u8 {
+        self.cells.get(position).0
+    }
+
+    /// Set the state of a cell, wrapping states past the last colour back round from dead.
+    pub fn set_state(&mut self, position: Position, state: u8) {
+        self.cells.set(position, Colour(state % (self.variant.colours() + 1)));
+    }
+
+    /// Number of live cells of every colour.
+    pub fn population(&self) -> usize {
+        self.cells.population()
+    }
+
+    /// Number of live cells of each colour, from colour 1.
+    pub fn populations(&self) -> Vec<usize> {
+        let mut populations = vec![0; self.variant.colours() as usize];
+        for (_, colour) in self.cells.iter() {
+            populations[colour.0 as usize - 1] += 1;
+        }
+        populations
+    }
+
+    /// Every live cell, with its state, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, u8)> + '_ {
+        self.cells.iter().map(|(position, colour)| (position, colour.0))
+    }
+
+    /// Returns the bounding box of every live cell, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.cells.bounds()
+    }
+
+    /// Copy the live cells into a two-state world under the same rule, dropping their colours.
+    pub fn to_world(&self) -> GameOfLife {
+        self.cells.to_world(self.rule)
+    }
+
+    pub fn rule(&self) -> Rule { self.rule }
+    pub fn variant(&self) -> Variant { self.variant }
+}
+
+/// Colour of a cell born with the given weight of live neighbours of each state.
+fn newborn(variant: Variant, tally: &[u8; 5]) -> u8 {
+    let colours = 1..=variant.colours();
+    let most = colours.clone().map(|colour| tally[colour as usize]).max().unwrap_or(0);
+    let tied: Vec<u8> = colours.clone().filter(|&colour| tally[colour as usize] == most).collect();
+    if tied.len() == 1 { return tied[0] }
+    colours.clone().find(|colour| !tied.contains(colour)).unwrap_or(tied[0])
+}
+
+#[cfg(test)]
+mod coloured_tests {
+    use super::*;
+    use crate::geometry::Coord;
+    use crate::io::rle::read_rle;
+
+    fn world(variant: Variant, cells: &[((Coord, Coord), u8)]) -> ColouredWorld {
+        let mut world = ColouredWorld::new(Rule::LIFE, variant).unwrap();
+        for &(position, state) in cells {
+            world.set_state(position.into(), state);
+        }
+        world
+    }
+
+    #[test]
+    fn newborn_colours() {
+        // A blinker's new ends are born from its three cells, so take the colour two of them have
+        let mut immigration = world(Variant::Immigration, &[((0, 0), 2), ((1, 0), 1), ((2, 0), 2)]);
+        assert_eq!(StepStats { births: 2, deaths: 2, population: 3 }, immigration.step());
+        assert_eq!([2, 1, 2], [(1, -1), (1, 0), (1, 1)].map(|position| immigration.state(position.into())));
+        assert_eq!(vec![1, 2], immigration.populations());
+
+        // Three different parents give the colour none of them have
+        let mut quadlife = world(Variant::QuadLife, &[((0, 0), 1), ((1, 0), 2), ((2, 0), 4)]);
+        quadlife.step();
+        assert_eq!((3, 2), (quadlife.state(Position::new(1, -1)), quadlife.state(Position::new(1, 0))));
+        quadlife.set_state(Position::new(5, 5), 6);
+        assert_eq!(1, quadlife.state(Position::new(5, 5)));
+    }
+
+    #[test]
+    fn matches_two_states() {
+        // Colours never change which cells are alive
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("b2o$2o$bo!").unwrap());
+        let mut coloured = ColouredWorld::new(Rule::LIFE, Variant::QuadLife).unwrap();
+        for (index, cell) in world.live_cells().into_iter().enumerate() {
+            coloured.set_state(cell, index as u8 % 4 + 1);
+        }
+        for _ in 0..20 {
+            assert_eq!(world.step(), coloured.step());
+        }
+        let (mut expected, mut actual) = (world.live_cells(), coloured.to_world().live_cells());
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+        assert_eq!(coloured.population(), coloured.populations().iter().sum());
+        assert_eq!(Ok(Variant::QuadLife), "quadlife".parse());
+    }
+}
@@ -0,0 +1,145 @@
+//! User settings, read from a TOML file. Without one given, `rust-gol/config.toml` in the user's config directory
+//! is read if it exists, so settings apply to every run without repeating them on the command line.
+//!
+//! ```toml
+//! [theme]
+//! alive_glyph = "█"
+//! dead_glyph = " "
+//! alive = "#e0e0a0"
+//! background = [16, 16, 32]
+//! grid = "#303040"
+//! ```
+
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use crate::error::GolError;
+use crate::render::theme::Theme;
+
+/// Every setting, with the defaults for any the file leaves out.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Config {
+    pub theme: Theme
+}
+
+impl Config {
+    /// Parse the text of a config file.
+    pub fn parse(text: &str) -> Result<Config, GolError> {
+        let table: Table = text.parse().map_err(|error: toml::de::Error| ConfigError::Syntax(error.to_string()))?;
+        let mut config = Config::default();
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("theme", Value::Table(theme)) => config.theme = parse_theme(theme)?,
+                _ => return Err(ConfigError::InvalidSetting(key.clone()).into())
+            }
+        }
+        Ok(config)
+    }
+
+    /// Read a config file.
+    pub fn load(path: &Path) -> Result<Config, GolError> {
+        Config::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Read the config file in the user's config directory, or use the defaults if there isn't one.
+    pub fn load_default() -> Result<Config, GolError> {
+        match default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default())
+        }
+    }
+}
+
+/// Where the config file is looked for when none is given: under `$XDG_CONFIG_HOME`, `~/.config` or `%APPDATA%`.
+pub fn default_path() -> Option<PathBuf> {
+    let directory = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(directory.join("rust-gol").join("config.toml"))
+}
+
+fn parse_theme(table: &Table) -> Result<Theme, ConfigError> {
+    let mut theme = Theme::default();
+    for (key, value) in table {
+        let invalid = || ConfigError::InvalidSetting(format!("theme.{}", key));
+        match key.as_str() {
+            "alive_glyph" => theme.alive_glyph = parse_glyph(value).ok_or_else(invalid)?,
+            "dead_glyph" => theme.dead_glyph = parse_glyph(value).ok_or_else(invalid)?,
+            "alive" => theme.alive = parse_colour(value).ok_or_else(invalid)?,
+            "background" => theme.background = parse_colour(value).ok_or_else(invalid)?,
+            "grid" => theme.grid = Some(parse_colour(value).ok_or_else(invalid)?),
+            _ => return Err(invalid())
+        }
+    }
+    Ok(theme)
+}
+
+/// A string of exactly one character.
+fn parse_glyph(value: &Value) -> Option<char> {
+    let mut chars = value.as_str()?.chars();
+    let glyph = chars.next()?;
+    chars.next().is_none().then_some(glyph)
+}
+
+/// A colour as `"#rrggbb"` or an array of red, green and blue from 0 to 255.
+fn parse_colour(value: &Value) -> Option<[u8; 3]> {
+    match value {
+        Value::String(text) => {
+            let hex = text.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+            let channel = |index: usize| u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok();
+            Some([channel(0)?, channel(1)?, channel(2)?])
+        }
+        Value::Array(channels) if channels.len() == 3 => {
+            let channel = |index: usize| channels[index].as_integer().and_then(|value| u8::try_from(value).ok());
+            Some([channel(0)?, channel(1)?, channel(2)?])
+        }
+        _ => None
+    }
+}
+
+/// Errors from understanding a config file.
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    /// The file isn't valid TOML.
+    Syntax(String),
+    /// A setting that doesn't exist or has a value of the wrong kind, named by its dotted key.
+    InvalidSetting(String)
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Syntax(error) => write!(f, "invalid config file: {}", error),
+            ConfigError::InvalidSetting(key) => write!(f, "unknown or invalid setting in config file: {}", key)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn theme() {
+        let config = Config::parse("[theme]\nalive_glyph = \"█\"\ndead_glyph = \" \"\nalive = \"#E0e0a0\"\nbackground = [16, 16, 32]\ngrid = \"#303040\"\n").unwrap();
+        let expected = Theme { alive_glyph: '█', dead_glyph: ' ', alive: [224, 224, 160], background: [16, 16, 32], grid: Some([48, 48, 64]) };
+        assert_eq!(expected, config.theme);
+        assert_eq!(Config::default(), Config::parse("").unwrap());
+
+        let invalid = |text: &str| match Config::parse(text) {
+            Err(GolError::Config(ConfigError::InvalidSetting(key))) => key,
+            _ => panic!("Expected an invalid setting in {}", text)
+        };
+        assert_eq!("theme.alive_glyph", invalid("[theme]\nalive_glyph = \"ab\""));
+        assert_eq!("theme.grid", invalid("[theme]\ngrid = [1, 2, 300]"));
+        assert_eq!("theme.colour", invalid("[theme]\ncolour = \"#000000\""));
+        assert_eq!("speed", invalid("speed = 3"));
+        assert!(matches!(Config::parse("[theme"), Err(GolError::Config(ConfigError::Syntax(_)))));
+    }
+}
@@ -0,0 +1,101 @@
+//! Wolfram's elementary cellular automata, single rows of cells where each cell's next state depends only on
+//! itself and the cells either side of it. Successive generations are stacked as the rows of a region,
+//! from the first at the top, so they can be drawn and saved like any other pattern.
+
+use crate::geometry::Coord;
+use crate::gol::{Cell, Region, RegionError};
+use crate::rule::ElementaryRule;
+
+/// Infinite row of cells, stored from the first to the last cell that can differ from the background.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// Position of the first stored cell.
+    start: Coord,
+    cells: Vec<Cell>,
+    /// State of every cell outside the stored ones, which is only ever alive under rules that flip it.
+    background: Cell
+}
+
+impl Row {
+    /// Create a row of the cells from the start, with every other cell dead.
+    pub fn new(start: Coord, cells: Vec<Cell>) -> Row {
+        Row { start, cells, background: Cell::Dead }
+    }
+
+    /// Create a row with only the cell at 0 alive, the usual start for exploring a rule.
+    pub fn single_cell() -> Row {
+        Row::new(0, vec![Cell::Alive])
+    }
+
+    /// The row after one generation under the rule. Changes spread at most a cell a generation,
+    /// so the stored cells grow by one at each end.
+    pub fn step(&self, rule: ElementaryRule) -> Row {
+        let cells = (self.start - 1..self.end() + 1)
+            .map(|x| rule.next_state(self.get_cell(x - 1), self.get_cell(x), self.get_cell(x + 1)))
+            .collect();
+        let background = rule.next_state(self.background, self.background, self.background);
+        Row { start: self.start - 1, cells, background }
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get_cell(&self, x: Coord) -> Cell {
+        let Some(index) = x.checked_sub(self.start).and_then(|index| usize::try_from(index).ok()) else { return self.background };
+        self.cells.get(index).copied().unwrap_or(self.background)
+    }
+
+    /// Position just past the last stored cell.
+    fn end(&self) -> Coord {
+        self.start + self.cells.len() as Coord
+    }
+
+    // GETTERS
+    pub fn start(&self) -> Coord { self.start }
+    pub fn cells(&self) -> &[Cell] { &self.cells }
+    pub fn background(&self) -> Cell { self.background }
+}
+
+/// Step the row the given number of generations, returning a region with each generation as a row, from the first
+/// at y 0 to the last at y `generations`. The region is wide enough to hold every cell the last generation stores,
+/// so it covers everything the first row could have reached.
+pub fn history(rule: ElementaryRule, first: &Row, generations: usize) -> Result<Region, RegionError> {
+    let x = first.start.saturating_sub_unsigned(generations as u64);
+    let width = first.cells.len().saturating_add(generations.saturating_mul(2));
+    let mut region = Region::new(x, 0, width, generations.saturating_add(1))?;
+
+    let mut row = first.clone();
+    for y in 0..=generations as Coord {
+        for column in x..x + width as Coord {
+            region.set_cell(column, y, row.get_cell(column));
+        }
+        row = row.step(rule);
+    }
+    Ok(region)
+}
+
+#[cfg(test)]
+mod elementary_tests {
+    use super::*;
+    use crate::io::rle::write_rle;
+
+    #[test]
+    fn step() {
+        // Rule 90 draws Sierpinski's triangle, each cell the exclusive or of its neighbours
+        let rule = ElementaryRule::new(90);
+        let row = Row::single_cell().step(rule).step(rule);
+        assert_eq!((-2, 5), (row.start(), row.cells().len()));
+        let alive: Vec<Coord> = (-3..=3).filter(|&x| row.get_cell(x) == Cell::Alive).collect();
+        assert_eq!(vec![-2, 2], alive);
+
+        // Rule 1 turns three dead cells live, so the background flips every generation
+        let row = Row::single_cell().step(ElementaryRule::new(1));
+        assert_eq!((Cell::Alive, Cell::Dead), (row.background(), row.get_cell(0)));
+        assert_eq!(Cell::Alive, row.get_cell(1000));
+    }
+
+    #[test]
+    fn stacked_history() {
+        let region = history(ElementaryRule::RULE_30, &Row::single_cell(), 3).unwrap();
+        assert_eq!((-3, 0, 7, 4), (region.x(), region.y(), region.width(), region.height()));
+        assert_eq!("x = 7, y = 4\n3bo$2b3o$b2o2bo$2ob4o!\n", write_rle(&region));
+    }
+}
@@ -0,0 +1,181 @@
+//! One error type covering everything the library can fail at, returned by the cell API, the pattern parsers
+//! and reading journals, recipes and config files, so code using several of them passes a single error up.
+//! Each variant holds its module's own error type, saying exactly what went wrong, which converts into
+//! [`GolError`] with `?`.
+//! Without the `std` feature only the engine's errors are left, as the modules for the others aren't built.
+
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "std")]
+use crate::config::ConfigError;
+use crate::gol::RegionError;
+#[cfg(feature = "std")]
+use crate::io::PatternError;
+#[cfg(feature = "std")]
+use crate::io::colors::ColorsError;
+#[cfg(feature = "std")]
+use crate::io::plaintext::PlaintextError;
+#[cfg(feature = "std")]
+use crate::io::rle::RleError;
+#[cfg(feature = "std")]
+use crate::journal::JournalError;
+#[cfg(feature = "std")]
+use crate::recipe::RecipeError;
+use crate::rule::RuleError;
+#[cfg(feature = "std")]
+use crate::wireworld::WireworldError;
+
+/// Any error from the library.
+#[derive(Debug)]
+pub enum GolError {
+    /// A region couldn't be created or changed, including setting a cell outside it.
+    Region(RegionError),
+    /// A rulestring couldn't be understood, or the rule can't be used where it was given.
+    Rule(RuleError),
+    /// A pattern couldn't be understood.
+    #[cfg(feature = "std")]
+    Pattern(PatternError),
+    /// A journal couldn't be understood.
+    #[cfg(feature = "std")]
+    Journal(JournalError),
+    /// A recipe couldn't be understood.
+    #[cfg(feature = "std")]
+    Recipe(RecipeError),
+    /// A config file couldn't be understood.
+    #[cfg(feature = "std")]
+    Config(ConfigError),
+    /// A `.colors` file couldn't be understood.
+    #[cfg(feature = "std")]
+    Colors(ColorsError),
+    /// A Wireworld pattern had cells in states Wireworld doesn't have.
+    #[cfg(feature = "std")]
+    Wireworld(WireworldError),
+    /// A file or stream couldn't be read or written.
+    #[cfg(feature = "std")]
+    Io(io::Error)
+}
+
+impl Display for GolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GolError::Region(error) => write!(f, "{}", error),
+            GolError::Rule(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Pattern(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Journal(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Recipe(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Config(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Colors(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Wireworld(error) => write!(f, "{}", error),
+            #[cfg(feature = "std")]
+            GolError::Io(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl core::error::Error for GolError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            GolError::Region(error) => Some(error),
+            GolError::Rule(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Pattern(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Journal(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Recipe(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Config(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Colors(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Wireworld(error) => Some(error),
+            #[cfg(feature = "std")]
+            GolError::Io(error) => Some(error)
+        }
+    }
+}
+
+impl From<RegionError> for GolError {
+    fn from(error: RegionError) -> GolError { GolError::Region(error) }
+}
+
+impl From<RuleError> for GolError {
+    fn from(error: RuleError) -> GolError { GolError::Rule(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<PatternError> for GolError {
+    fn from(error: PatternError) -> GolError { GolError::Pattern(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<RleError> for GolError {
+    fn from(error: RleError) -> GolError { GolError::Pattern(PatternError::Rle(error)) }
+}
+
+#[cfg(feature = "std")]
+impl From<PlaintextError> for GolError {
+    fn from(error: PlaintextError) -> GolError { GolError::Pattern(PatternError::Plaintext(error)) }
+}
+
+#[cfg(feature = "std")]
+impl From<JournalError> for GolError {
+    fn from(error: JournalError) -> GolError { GolError::Journal(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<RecipeError> for GolError {
+    fn from(error: RecipeError) -> GolError { GolError::Recipe(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<ConfigError> for GolError {
+    fn from(error: ConfigError) -> GolError { GolError::Config(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<ColorsError> for GolError {
+    fn from(error: ColorsError) -> GolError { GolError::Colors(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<WireworldError> for GolError {
+    fn from(error: WireworldError) -> GolError { GolError::Wireworld(error) }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for GolError {
+    fn from(error: io::Error) -> GolError { GolError::Io(error) }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use super::*;
+    use crate::gol::{Cell, Region};
+    use crate::io::rle::read_rle;
+    use crate::rule::Rule;
+
+    #[test]
+    fn conversions() {
+        // Errors from different modules pass up through one function with `?`, keeping their messages
+        let load = |pattern: &str, rule: &str| -> Result<Region, GolError> {
+            let _: Rule = rule.parse()?;
+            let mut region = read_rle(pattern)?;
+            region.try_set_cell(5, 5, Cell::Alive)?;
+            Ok(region)
+        };
+        assert!(matches!(load("o!", "B3/S9"), Err(GolError::Rule(RuleError::InvalidCount { count: 9, max: 8 }))));
+        assert!(matches!(load("o?", "B3/S23"), Err(GolError::Pattern(PatternError::Rle(RleError::UnexpectedCharacter('?'))))));
+        let error = load("o!", "B3/S23").err().unwrap();
+        assert_eq!("cell at 5 5 is outside the region", error.to_string());
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
@@ -0,0 +1,437 @@
+//! Tools for discovering interesting rules, by running a standard soup under many of them and scoring the results.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Write};
+use std::ops::RangeInclusive;
+
+use crate::analysis::{clusters, CycleDetector};
+use crate::census::canonical_shape;
+use crate::geometry::Position;
+use crate::gol::GameOfLife;
+use crate::rule::Rule;
+use crate::soup::{random_soup, Rng};
+
+/// Family of outer-totalistic rules, made up of every combination of a set of allowed birth counts
+/// with a set of allowed survival counts.
+pub struct RuleFamily {
+    birth: u16,
+    survival: u16
+}
+
+impl RuleFamily {
+    /// Family of every rule born only on counts in `birth` and surviving only on counts in `survival`.
+    /// Birth on 0 is always left out, as those rules flash the whole background rather than grow patterns worth exploring.
+    pub fn new(birth: RangeInclusive<u8>, survival: RangeInclusive<u8>) -> RuleFamily {
+        let mask = |counts: RangeInclusive<u8>| counts.filter(|&count| count <= 8).fold(0u16, |mask, count| mask | 1 << count);
+        RuleFamily {
+            birth: mask(birth) & !1,
+            survival: mask(survival)
+        }
+    }
+
+    /// Number of rules in the family.
+    pub fn size(&self) -> u64 {
+        1 << (self.birth.count_ones() + self.survival.count_ones())
+    }
+
+    /// Every rule in the family.
+    pub fn rules(&self) -> Vec<Rule> {
+        let mut rules = Vec::with_capacity(self.size() as usize);
+        for birth in submasks(self.birth) {
+            for survival in submasks(self.survival) {
+                rules.push(Rule::from_masks(birth, survival));
+            }
+        }
+        rules
+    }
+
+    /// Pick up to `count` different rules from the family at random.
+    /// If the family has no more than `count` rules, all of them are returned.
+    pub fn sample(&self, count: usize, rng: &mut Rng) -> Vec<Rule> {
+        if count as u64 >= self.size() { return self.rules() }
+
+        let mut seen = HashSet::new();
+        let mut rules = Vec::with_capacity(count);
+        while rules.len() < count {
+            let rule = Rule::from_masks(random_submask(self.birth, rng), random_submask(self.survival, rng));
+            if seen.insert(rule) {
+                rules.push(rule);
+            }
+        }
+        rules
+    }
+}
+
+/// Every rule one change away from the given one, adding or removing a single birth or survival count,
+/// in order of the counts changed with births first. Birth on 0 is never added, as with [`RuleFamily`].
+/// Changes work on the rule's counts, so the rules near a non-totalistic rule are all totalistic.
+pub fn mutations(rule: Rule) -> Vec<Rule> {
+    let max = rule.neighbourhood().max_count();
+    let (birth, survival) = (rule.birth_counts(), rule.survival_counts());
+    let toggle = |counts: &[u8], count: u8| -> Vec<u8> {
+        if counts.contains(&count) { counts.iter().copied().filter(|&other| other != count).collect() }
+        else { counts.iter().copied().chain([count]).collect() }
+    };
+    let births = (1..=max).map(|count| (toggle(&birth, count), survival.clone()));
+    let survivals = (0..=max).map(|count| (birth.clone(), toggle(&survival, count)));
+    births.chain(survivals)
+        .map(|(birth, survival)| Rule::with_counts(*rule.neighbourhood(), &birth, &survival))
+        .collect()
+}
+
+/// One of the rules one change away from the given one, picked at random.
+pub fn mutate(rule: Rule, rng: &mut Rng) -> Rule {
+    let mutations = mutations(rule);
+    mutations[rng.below(mutations.len() as u64) as usize]
+}
+
+/// Run the standard soup under every rule one change away from the given one,
+/// returning their scores with the most interesting first, for following the rules around a known good one.
+pub fn explore_mutations(rule: Rule, settings: &SampleSettings) -> Vec<RuleScore> {
+    let mut scores: Vec<RuleScore> = mutations(rule).into_iter().map(|rule| score_rule(rule, settings)).collect();
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scores
+}
+
+/// Settings for the soup each sampled rule is run on.
+#[derive(Debug, Clone)]
+pub struct SampleSettings {
+    /// Side length of the square soup.
+    pub soup_size: usize,
+    /// Chance of each soup cell starting alive.
+    pub density: f64,
+    /// Number of generations to run each rule for.
+    pub generations: usize,
+    /// Seed for the soup, which is the same for every rule so they are compared fairly.
+    pub seed: u64
+}
+
+impl Default for SampleSettings {
+    fn default() -> SampleSettings {
+        SampleSettings { soup_size: 16, density: 0.5, generations: 100, seed: 1 }
+    }
+}
+
+/// Results of running the standard soup under a single rule.
+#[derive(Debug, Clone)]
+pub struct RuleScore {
+    pub rule: Rule,
+    /// Live cells at the end of the run.
+    pub population: usize,
+    /// Final population as a multiple of the soup's population.
+    pub growth: f64,
+    /// Average fraction of live cells that changed each generation over the second half of the run.
+    pub activity: f64,
+    /// Set if the population grew so large the run was stopped early.
+    pub exploded: bool,
+    /// Overall interest of the rule, rewarding sustained activity and penalising explosive growth.
+    /// Rules that die out or explode score 0.
+    pub score: f64
+}
+
+/// Run the standard soup under a sample of rules from the family,
+/// returning their scores with the most interesting first.
+pub fn explore(family: &RuleFamily, samples: usize, settings: &SampleSettings) -> Vec<RuleScore> {
+    let mut rng = Rng::new(settings.seed);
+    let mut scores: Vec<RuleScore> = family.sample(samples, &mut rng)
+        .into_iter()
+        .map(|rule| score_rule(rule, settings))
+        .collect();
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scores
+}
+
+/// Run the standard soup under a rule and score how interesting the result is.
+pub fn score_rule(rule: Rule, settings: &SampleSettings) -> RuleScore {
+    let mut rng = Rng::new(settings.seed);
+    let soup = random_soup(&mut rng, 0, 0, settings.soup_size, settings.soup_size, settings.density)
+        .expect("Soup size is too large for a region");
+    let mut world = GameOfLife::with_rule(rule);
+    world.set_region(&soup);
+
+    // Stepping explosive rules gets slower every generation and they are rarely interesting
+    let population_limit = (settings.soup_size * settings.soup_size * 16).max(256);
+    let initial_population = world.population().max(1);
+
+    let mut previous: HashSet<Position> = world.live_cells().into_iter().collect();
+    let (mut changed, mut alive) = (0, 0);
+    let mut exploded = false;
+    for generation in 1..=settings.generations {
+        world.step();
+        let current: HashSet<Position> = world.live_cells().into_iter().collect();
+        if generation > settings.generations / 2 {
+            changed += current.symmetric_difference(&previous).count();
+            alive += current.len();
+        }
+        previous = current;
+
+        if previous.is_empty() { break }
+        if previous.len() > population_limit {
+            exploded = true;
+            break;
+        }
+    }
+
+    let population = previous.len();
+    let growth = population as f64 / initial_population as f64;
+    let activity = changed as f64 / alive.max(1) as f64;
+    let score = if population == 0 || exploded { 0.0 } else { activity / growth.max(1.0) };
+
+    RuleScore { rule, population, growth, activity, exploded, score }
+}
+
+/// Format scores as a table, one rule per line.
+pub fn report(scores: &[RuleScore]) -> String {
+    let mut text = format!("{:<20} {:>8} {:>10} {:>10} {:>8}\n", "rule", "score", "activity", "growth", "final");
+    for score in scores {
+        let rule = score.rule.to_string();
+        let growth = if score.exploded { "exploded".to_string() } else { format!("{:.2}", score.growth) };
+        writeln!(text, "{:<20} {:>8.4} {:>10.4} {:>10} {:>8}", rule, score.score, score.activity, growth, score.population)
+            .expect("Writing to a string can't fail");
+    }
+    text
+}
+
+/// Mean of a set of measurements, with the half-width of its 95% confidence interval.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Statistic {
+    pub mean: f64,
+    pub margin: f64
+}
+
+impl Statistic {
+    /// Summarise a set of measurements, using the normal approximation for the confidence interval.
+    /// With fewer than two measurements the margin is 0.
+    pub fn from_samples(samples: &[f64]) -> Statistic {
+        if samples.is_empty() { return Statistic { mean: 0.0, margin: 0.0 } }
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        if samples.len() < 2 { return Statistic { mean, margin: 0.0 } }
+
+        let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Statistic { mean, margin: 1.96 * (variance / n).sqrt() }
+    }
+}
+
+impl Display for Statistic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(4);
+        write!(f, "{:.*} ± {:.*}", precision, self.mean, precision, self.margin)
+    }
+}
+
+/// Settings for the soups a rule's fingerprint is measured over.
+#[derive(Debug, Clone)]
+pub struct FingerprintSettings {
+    /// Number of soups to run.
+    pub soups: usize,
+    /// Side length of each square soup.
+    pub soup_size: usize,
+    /// Chance of each soup cell starting alive.
+    pub density: f64,
+    /// Soups still changing after this many generations count as never stabilising.
+    pub max_generations: usize,
+    /// Longest cycle that still counts as stable.
+    pub max_period: usize,
+    /// Seed for the first soup, with the rest following on from it.
+    pub seed: u64
+}
+
+impl Default for FingerprintSettings {
+    fn default() -> FingerprintSettings {
+        FingerprintSettings { soups: 32, soup_size: 16, density: 0.5, max_generations: 2000, max_period: 6, seed: 1 }
+    }
+}
+
+/// Statistical summary of how random soups behave under a rule, for comparing rules quantitatively.
+#[derive(Debug, Clone)]
+pub struct RuleFingerprint {
+    pub rule: Rule,
+    /// Number of soups measured.
+    pub soups: usize,
+    /// Live cells per cell of the live bounding box at the end of each soup.
+    pub density: Statistic,
+    /// Number of soups that became stable or cycled within the generation limit.
+    pub stabilised: usize,
+    /// Generations taken to stabilise, over the soups that did.
+    pub stabilisation_time: Statistic,
+    /// Objects left at the end of each soup, as shape and mean count per soup, most common first.
+    /// Shapes are RLE bodies in a canonical orientation, so rotations and reflections count together.
+    pub objects: Vec<(String, Statistic)>
+}
+
+impl Display for RuleFingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Rule: {}", self.rule)?;
+        writeln!(f, "Soups: {}", self.soups)?;
+        writeln!(f, "Equilibrium density: {}", self.density)?;
+        writeln!(f, "Stabilised: {}/{}", self.stabilised, self.soups)?;
+        writeln!(f, "Stabilisation time: {:.1}", self.stabilisation_time)?;
+        writeln!(f, "Common objects (per soup):")?;
+        for (shape, count) in self.objects.iter().take(10) {
+            writeln!(f, "  {:<24} {:.3}", shape, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Measure the fingerprint of a rule by running many random soups until they stabilise.
+pub fn fingerprint(rule: Rule, settings: &FingerprintSettings) -> RuleFingerprint {
+    let mut rng = Rng::new(settings.seed);
+    let mut densities = vec![];
+    let mut times = vec![];
+    let mut object_counts: Vec<HashMap<String, usize>> = vec![];
+
+    for _ in 0..settings.soups {
+        let soup = random_soup(&mut rng, 0, 0, settings.soup_size, settings.soup_size, settings.density)
+        .expect("Soup size is too large for a region");
+        let mut world = GameOfLife::with_rule(rule);
+        world.set_region(&soup);
+
+        let mut cycles = CycleDetector::new(settings.max_period);
+        cycles.observe(&world);
+        for generation in 1..=settings.max_generations {
+            world.step();
+            if cycles.observe(&world).is_some() {
+                times.push(generation as f64);
+                break;
+            }
+        }
+
+        let cells = world.live_cells();
+        densities.push(match world.live_bounds() {
+            Some(bounds) => cells.len() as f64 / bounds.area() as f64,
+            None => 0.0
+        });
+
+        let mut counts = HashMap::new();
+        for cluster in clusters(&cells, 1) {
+            *counts.entry(canonical_shape(&cluster)).or_insert(0) += 1;
+        }
+        object_counts.push(counts);
+    }
+
+    // Every soup contributes to every object's statistic, counting 0 where it didn't appear
+    let shapes: HashSet<&String> = object_counts.iter().flat_map(|counts| counts.keys()).collect();
+    let mut objects: Vec<(String, Statistic)> = shapes.into_iter().map(|shape| {
+        let samples: Vec<f64> = object_counts.iter()
+            .map(|counts| counts.get(shape).copied().unwrap_or(0) as f64)
+            .collect();
+        (shape.clone(), Statistic::from_samples(&samples))
+    }).collect();
+    objects.sort_by(|a, b| b.1.mean.total_cmp(&a.1.mean).then_with(|| a.0.cmp(&b.0)));
+
+    RuleFingerprint {
+        rule,
+        soups: settings.soups,
+        density: Statistic::from_samples(&densities),
+        stabilised: times.len(),
+        stabilisation_time: Statistic::from_samples(&times),
+        objects
+    }
+}
+
+/// Every subset of the bits in a mask, including the empty set and the mask itself.
+fn submasks(mask: u16) -> Vec<u16> {
+    let mut masks = vec![];
+    let mut sub = mask;
+    loop {
+        masks.push(sub);
+        if sub == 0 { break }
+        sub = (sub - 1) & mask;
+    }
+    masks
+}
+
+/// Random subset of the bits in a mask, with every subset equally likely.
+fn random_submask(mask: u16, rng: &mut Rng) -> u16 {
+    (0..16)
+        .filter(|bit| mask & (1 << bit) != 0 && rng.chance(0.5))
+        .fold(0, |sub, bit| sub | 1 << bit)
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn family() {
+        let family = RuleFamily::new(0..=3, 2..=3);
+        // B0 is left out, leaving 3 birth and 2 survival counts
+        assert_eq!(32, family.size());
+        let rules = family.rules();
+        assert_eq!(32, rules.len());
+        assert!(rules.contains(&Rule::LIFE));
+        assert!(rules.iter().all(|rule| !rule.birth_counts().contains(&0)));
+
+        let sample = family.sample(10, &mut Rng::new(3));
+        assert_eq!(10, sample.len());
+        assert_eq!(10, sample.iter().collect::<HashSet<_>>().len());
+        assert!(sample.iter().all(|rule| rules.contains(rule)));
+
+        assert_eq!(32, family.sample(100, &mut Rng::new(3)).len());
+    }
+
+    #[test]
+    fn ranking() {
+        let settings = SampleSettings { generations: 40, ..SampleSettings::default() };
+
+        // Filling every cell explodes, B8 rules die out, and Life keeps going
+        let explosive = score_rule("B12345678/S012345678".parse().unwrap(), &settings);
+        assert!(explosive.exploded);
+        assert_eq!(0.0, explosive.score);
+
+        let dies = score_rule("B8/S".parse().unwrap(), &settings);
+        assert_eq!(0, dies.population);
+        assert_eq!(0.0, dies.score);
+
+        let life = score_rule(Rule::LIFE, &settings);
+        assert!(life.score > 0.0);
+
+        let family = RuleFamily::new(3..=3, 2..=3);
+        let scores = explore(&family, 4, &settings);
+        assert_eq!(4, scores.len());
+        assert!(scores.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(5, report(&scores).lines().count());
+    }
+
+    #[test]
+    fn rule_mutations() {
+        // 8 birth counts and 9 survival counts to toggle, so Life has 17 neighbours, HighLife among them
+        let neighbours = mutations(Rule::LIFE);
+        assert_eq!(17, neighbours.len());
+        assert!(neighbours.contains(&"B36/S23".parse().unwrap()) && neighbours.contains(&"B3/S2".parse().unwrap()));
+        assert!(!neighbours.contains(&Rule::LIFE) && neighbours.iter().all(|rule| !rule.has_b0()));
+        assert!(neighbours.contains(&mutate(Rule::LIFE, &mut Rng::new(4))));
+
+        let settings = SampleSettings { generations: 20, ..SampleSettings::default() };
+        let scores = explore_mutations("B2/SV".parse().unwrap(), &settings);
+        assert_eq!(9, scores.len());
+        assert!(scores.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn statistic() {
+        assert_eq!(Statistic { mean: 0.0, margin: 0.0 }, Statistic::from_samples(&[]));
+        assert_eq!(Statistic { mean: 3.0, margin: 0.0 }, Statistic::from_samples(&[3.0]));
+
+        let statistic = Statistic::from_samples(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(2.5, statistic.mean);
+        assert!((statistic.margin - 1.96 * (5.0f64 / 12.0).sqrt()).abs() < 1e-9);
+        assert_eq!("2.50 ± 1.27", format!("{:.2}", statistic));
+    }
+
+    #[test]
+    fn life_fingerprint() {
+        let settings = FingerprintSettings { soups: 4, soup_size: 8, max_generations: 300, ..FingerprintSettings::default() };
+        let fingerprint = fingerprint(Rule::LIFE, &settings);
+        assert_eq!(4, fingerprint.soups);
+        assert!(fingerprint.stabilised > 0);
+        assert!(fingerprint.stabilisation_time.mean > 0.0);
+        assert!((0.0..=1.0).contains(&fingerprint.density.mean));
+
+        // Mean counts are sorted, and the report lists them
+        assert!(fingerprint.objects.windows(2).all(|pair| pair[0].1.mean >= pair[1].1.mean));
+        assert!(fingerprint.to_string().starts_with("Rule: B3/S23\nSoups: 4\n"));
+    }
+}
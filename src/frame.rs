@@ -0,0 +1,203 @@
+//! Frames of reference that move with a pattern, so cells can be read and written relative to a spaceship
+//! without re-offsetting every generation.
+//! A frame moves by a fixed displacement every period, so its coordinates line up exactly with the pattern
+//! once per period, and advance a cell at a time in between.
+
+use std::collections::HashMap;
+
+use crate::chunk;
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife};
+
+/// Frame of reference moving at a constant velocity of `dx` `dy` cells every `period` generations.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Frame {
+    /// World position of the frame's 0 0 at the start generation.
+    x: Coord,
+    y: Coord,
+    dx: Coord,
+    dy: Coord,
+    period: u64,
+    start: u64
+}
+
+impl Frame {
+    /// Create a frame with its 0 0 at world x y on the start generation, moving `dx` `dy` every `period` generations.
+    /// A period of 0 is treated as 1.
+    pub fn new(x: Coord, y: Coord, dx: Coord, dy: Coord, period: u64, start: u64) -> Frame {
+        Frame { x, y, dx, dy, period: period.max(1), start }
+    }
+
+    /// Create a frame that never moves, with its 0 0 at world x y.
+    pub fn stationary(x: Coord, y: Coord) -> Frame {
+        Frame::new(x, y, 0, 0, 1, 0)
+    }
+
+    /// Create a frame moving with the live cells of the world at the given generation,
+    /// by running a copy of it for up to `max_period` generations until they reappear translated.
+    /// The frame's 0 0 is the -x -y corner of the live cells' bounding box at the start generation.
+    /// Returns [`None`] if the world is empty, or isn't a single spaceship or oscillator of at most that period.
+    pub fn track(world: &GameOfLife, generation: u64, max_period: u64) -> Option<Frame> {
+        let corner = world.live_bounds()?.min();
+        let start = normalised_cells(world, corner);
+
+        let mut future = world.clone();
+        for period in 1..=max_period {
+            future.step();
+            let moved = future.live_bounds()?.min();
+            if normalised_cells(&future, moved) == start {
+                let velocity = moved - corner;
+                return Some(Frame::new(corner.x, corner.y, velocity.x, velocity.y, period, generation));
+            }
+        }
+        None
+    }
+
+    /// Returns the world position of the frame's 0 0 at the given generation.
+    pub fn origin(&self, generation: u64) -> Position {
+        let elapsed = (generation as i128 - self.start as i128) as Coord;
+        let period = self.period as Coord;
+        let offset = |velocity: Coord| chunk::floor_div(velocity.saturating_mul(elapsed), period);
+        Position::new(self.x.saturating_add(offset(self.dx)), self.y.saturating_add(offset(self.dy)))
+    }
+
+    /// Convert a position in the frame to world coordinates at the given generation.
+    pub fn world_position(&self, position: Position, generation: u64) -> Position {
+        self.origin(generation) + position
+    }
+
+    /// Convert a world position to coordinates in the frame at the given generation.
+    pub fn local_position(&self, position: Position, generation: u64) -> Position {
+        position - self.origin(generation)
+    }
+
+    /// Get the state of a cell given in frame coordinates.
+    pub fn get_cell(&self, world: &GameOfLife, generation: u64, x: Coord, y: Coord) -> Cell {
+        let position = self.world_position(Position::new(x, y), generation);
+        world.get_cell(position.x, position.y)
+    }
+
+    /// Set the state of a cell given in frame coordinates.
+    pub fn set_cell(&self, world: &mut GameOfLife, generation: u64, x: Coord, y: Coord, state: Cell) {
+        let position = self.world_position(Position::new(x, y), generation);
+        world.set_cell(position.x, position.y, state);
+    }
+
+    /// Positions of every live cell in the world inside the bounds given in frame coordinates, in frame coordinates.
+    pub fn live_cells_within(&self, world: &GameOfLife, generation: u64, bounds: Rect) -> Vec<Position> {
+        world.live_cells().into_iter()
+            .map(|position| self.local_position(position, generation))
+            .filter(|&position| bounds.contains(position))
+            .collect()
+    }
+
+    // GETTERS
+    /// Cells moved every period.
+    pub fn velocity(&self) -> Position { Position::new(self.dx, self.dy) }
+    pub fn period(&self) -> u64 { self.period }
+    pub fn start(&self) -> u64 { self.start }
+}
+
+/// Named frames for a world, so scripts can register references once and look them up by name.
+#[derive(Debug, Clone, Default)]
+pub struct Frames {
+    frames: HashMap<String, Frame>
+}
+
+impl Frames {
+    pub fn new() -> Frames {
+        Frames::default()
+    }
+
+    /// Register a frame under a name, returning any frame it replaces.
+    pub fn register(&mut self, name: &str, frame: Frame) -> Option<Frame> {
+        self.frames.insert(name.to_string(), frame)
+    }
+
+    /// Forget a frame, returning it if it was registered.
+    pub fn remove(&mut self, name: &str) -> Option<Frame> {
+        self.frames.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Frame> {
+        self.frames.get(name)
+    }
+
+    /// Names of every registered frame, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.frames.keys().map(String::as_str)
+    }
+}
+
+/// Live cells of the world relative to a corner, sorted so they can be compared.
+fn normalised_cells(world: &GameOfLife, corner: Position) -> Vec<Position> {
+    let mut cells: Vec<Position> = world.live_cells().into_iter()
+        .map(|position| position - corner)
+        .collect();
+    cells.sort_unstable();
+    cells
+}
+
+#[cfg(test)]
+mod frame_tests {
+    use super::*;
+
+    fn glider() -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            world.set_cell(x + 10, y - 5, Cell::Alive);
+        }
+        world
+    }
+
+    #[test]
+    fn track_glider() {
+        let mut world = glider();
+        let frame = Frame::track(&world, 3, 8).unwrap();
+        assert_eq!((Position::new(1, 1), 4, 3), (frame.velocity(), frame.period(), frame.start()));
+        assert_eq!(Position::new(10, -5), frame.origin(3));
+
+        // Same local cells every period, wherever the glider has got to
+        let start = frame.live_cells_within(&world, 3, Rect::new(0, 0, 3, 3));
+        for generation in 4..=43 {
+            world.step();
+            if (generation - 3) % 4 == 0 {
+                assert_eq!(start.len(), frame.live_cells_within(&world, generation, Rect::new(0, 0, 3, 3)).len());
+                assert_eq!(Cell::Alive, frame.get_cell(&world, generation, 1, 0));
+                assert_eq!(Cell::Dead, frame.get_cell(&world, generation, 0, 0));
+            }
+        }
+        assert_eq!(Position::new(20, 5), frame.origin(43));
+
+        assert!(Frame::track(&GameOfLife::new(), 0, 8).is_none());
+        assert!(Frame::track(&glider(), 0, 3).is_none());
+    }
+
+    #[test]
+    fn coordinates() {
+        let frame = Frame::new(0, 0, -1, 2, 4, 8);
+        assert_eq!(Position::new(0, 0), frame.origin(8));
+        assert_eq!(Position::new(-1, 2), frame.origin(12));
+        // Moves a cell at a time between periods, rounding down, even before the start generation
+        assert_eq!(Position::new(-1, 1), frame.origin(10));
+        assert_eq!(Position::new(1, -2), frame.origin(4));
+        assert_eq!(Position::new(4, 7), frame.world_position(Position::new(5, 5), 12));
+        assert_eq!(Position::new(5, 5), frame.local_position(Position::new(4, 7), 12));
+
+        let mut world = GameOfLife::new();
+        frame.set_cell(&mut world, 12, 1, 1, Cell::Alive);
+        assert_eq!(Cell::Alive, world.get_cell(0, 3));
+        assert_eq!(Cell::Alive, Frame::stationary(0, 3).get_cell(&world, 100, 0, 0));
+    }
+
+    #[test]
+    fn registry() {
+        let mut frames = Frames::new();
+        assert!(frames.register("ship", Frame::stationary(1, 2)).is_none());
+        assert_eq!(Some(Frame::stationary(1, 2)), frames.register("ship", Frame::stationary(3, 4)));
+        assert_eq!(Some(&Frame::stationary(3, 4)), frames.get("ship"));
+        assert_eq!(vec!["ship"], frames.names().collect::<Vec<_>>());
+        frames.remove("ship");
+        assert!(frames.get("ship").is_none());
+    }
+}
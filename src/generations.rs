@@ -0,0 +1,144 @@
+//! Worlds under Generations rules, where cells can be in more states than alive and dead.
+//! The main engine stores two-state [`Cell`](crate::gol::Cell)s densely in regions, so rather than widening every cell,
+//! these worlds keep [`SparseCells`] of the cells that aren't dead, which suits rules like Star Wars
+//! whose decaying trails stay thin.
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{GameOfLife, StepStats};
+use crate::rule::GenerationsRule;
+use crate::state::{CellState, SparseCells};
+
+/// State of a cell that is alive. Dead cells are state 0, and decaying cells count up from 2.
+pub const ALIVE: u8 = 1;
+
+/// Number of a cell's state, alive only at [`ALIVE`] and not while it decays.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+struct State(u8);
+
+impl CellState for State {
+    fn is_alive(self) -> bool { self.0 == ALIVE }
+}
+
+/// Infinite world of cells following a Generations rule.
+#[derive(Debug, Clone)]
+pub struct GenerationsWorld {
+    cells: SparseCells<State>,
+    rule: GenerationsRule
+}
+
+impl GenerationsWorld {
+    /// Create a new empty world using the given rule.
+    pub fn new(rule: GenerationsRule) -> GenerationsWorld {
+        GenerationsWorld { cells: SparseCells::new(), rule }
+    }
+
+    /// Create a world with the live cells of a two-state world, under the given rule.
+    pub fn from_world(world: &GameOfLife, rule: GenerationsRule) -> GenerationsWorld {
+        GenerationsWorld { cells: SparseCells::from_world(world, State(ALIVE)), rule }
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    /// Live cells that stop surviving count as deaths as soon as they start to decay.
+    pub fn step(&mut self) -> StepStats {
+        let rule = self.rule;
+        self.cells.step(
+            rule.rule().neighbourhood(),
+            |neighbours: &mut u8, _, weight| *neighbours += weight,
+            |_, current, neighbours| State(rule.next_state(current.0, neighbours))
+        )
+    }
+
+    /// Returns the state of the cell at the position, 0 if it is dead.
+    pub fn state(&self, position: Position) -> u8 {
+        self.cells.get(position).0
+    }
+
+    /// Set the state of a cell, wrapping states past the rule's last round to its number of states.
+    pub fn set_state(&mut self, position: Position, state: u8) {
+        self.cells.set(position, State(state % self.rule.states()));
+    }
+
+    /// Number of live cells, not counting decaying ones.
+    pub fn population(&self) -> usize {
+        self.cells.population()
+    }
+
+    /// Positions of the live cells, not counting decaying ones, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.live_cells()
+    }
+
+    /// Every cell that isn't dead, with its state, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, u8)> + '_ {
+        self.cells.iter().map(|(position, state)| (position, state.0))
+    }
+
+    /// Returns the bounding box of every cell that isn't dead, or [`None`] if they all are.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.cells.bounds()
+    }
+
+    /// Copy the live cells into a two-state world under the rule's births and survivals, dropping decaying cells.
+    pub fn to_world(&self) -> GameOfLife {
+        self.cells.to_world(self.rule.rule())
+    }
+
+    pub fn rule(&self) -> GenerationsRule { self.rule }
+}
+
+#[cfg(test)]
+mod generations_tests {
+    use super::*;
+    use crate::io::rle::read_rle;
+    use crate::rule::Rule;
+
+    #[test]
+    fn decay() {
+        // Under Star Wars a lone domino dies straight into decay, leaving a trail that fades over two generations
+        let mut world = GenerationsWorld::new(GenerationsRule::STAR_WARS);
+        world.set_state(Position::new(0, 0), ALIVE);
+        world.set_state(Position::new(1, 0), ALIVE);
+        let stats = world.step();
+        assert_eq!(StepStats { births: 4, deaths: 2, population: 4 }, stats);
+        assert_eq!(2, world.state(Position::new(0, 0)));
+        assert_eq!(ALIVE, world.state(Position::new(0, -1)));
+
+        // Decaying cells block births, and count neither as live neighbours nor as population
+        world.step();
+        assert_eq!(3, world.state(Position::new(0, 0)));
+        assert_eq!(world.live_cells().count(), world.population());
+        assert!(world.cells().any(|(_, state)| state == 3));
+        world.set_state(Position::new(5, 5), 9);
+        assert_eq!(1, world.state(Position::new(5, 5)));
+    }
+
+    #[test]
+    fn brians_brain() {
+        // Every live cell dies after a generation, and dying cells can't be born into,
+        // so a domino spreads outwards rather than filling back in
+        let mut world = GenerationsWorld::new(GenerationsRule::BRIANS_BRAIN);
+        world.set_state(Position::new(0, 0), ALIVE);
+        world.set_state(Position::new(1, 0), ALIVE);
+        assert_eq!(StepStats { births: 4, deaths: 2, population: 4 }, world.step());
+        assert_eq!((2, ALIVE), (world.state(Position::new(0, 0)), world.state(Position::new(1, -1))));
+        world.step();
+        assert_eq!((0, 2), (world.state(Position::new(0, 0)), world.state(Position::new(1, -1))));
+        assert_eq!(6, world.population());
+        assert!(world.live_cells().all(|cell| cell.y.abs() == 2 || cell.x == -1 || cell.x == 2));
+    }
+
+    #[test]
+    fn matches_two_states() {
+        // With two states, a Generations world steps just like the main engine
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("b2o$2o$bo!").unwrap());
+        let mut generations = GenerationsWorld::from_world(&world, GenerationsRule::new(Rule::LIFE, 2).unwrap());
+        for _ in 0..20 {
+            assert_eq!(world.step(), generations.step());
+        }
+        let (mut expected, mut actual) = (world.live_cells(), generations.to_world().live_cells());
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,323 @@
+//! Positions and rectangles of cells in the world, shared by the engine, frontends and file formats.
+//! Both are plain values in world coordinates, with y increasing downwards as everywhere else in the crate.
+
+use core::fmt::{Display, Formatter};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::chunk::{chunk_to_world, world_to_chunk, world_to_local};
+
+/// Integer type of world coordinates, 64 bits wide on every target, so worlds run as far on 32-bit targets as elsewhere.
+pub type Coord = i64;
+
+/// Position of a cell in the world, or an offset between two positions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Position {
+    pub x: Coord,
+    pub y: Coord
+}
+
+impl Position {
+    pub const ORIGIN: Position = Position { x: 0, y: 0 };
+
+    pub const fn new(x: Coord, y: Coord) -> Position {
+        Position { x, y }
+    }
+
+    /// Add an offset, or [`None`] if the result would be past the limits of the coordinate space.
+    pub fn checked_add(self, offset: Position) -> Option<Position> {
+        Some(Position::new(self.x.checked_add(offset.x)?, self.y.checked_add(offset.y)?))
+    }
+
+    /// Subtract an offset, or [`None`] if the result would be past the limits of the coordinate space.
+    pub fn checked_sub(self, offset: Position) -> Option<Position> {
+        Some(Position::new(self.x.checked_sub(offset.x)?, self.y.checked_sub(offset.y)?))
+    }
+
+    /// Add an offset, stopping at the limits of the coordinate space.
+    pub fn saturating_add(self, offset: Position) -> Position {
+        Position::new(self.x.saturating_add(offset.x), self.y.saturating_add(offset.y))
+    }
+
+    /// The 8 positions around this one, in reading order.
+    pub fn neighbours(self) -> impl Iterator<Item = Position> {
+        (-1..=1).flat_map(|y| (-1..=1).map(move |x| Position::new(x, y)))
+            .filter(|&offset| offset != Position::ORIGIN)
+            .map(move |offset| self + offset)
+    }
+
+    /// Coordinates of the chunk of the given size holding this position.
+    pub fn chunk(self, size: usize) -> Position {
+        world_to_chunk(self.x, self.y, size).into()
+    }
+
+    /// Position within its chunk of the given size, from 0 0 at the chunk's -x -y corner.
+    pub fn within_chunk(self, size: usize) -> (usize, usize) {
+        world_to_local(self.x, self.y, size)
+    }
+
+    /// World position of a position within the chunk of the given size at the chunk coordinates.
+    pub fn from_chunk(chunk: Position, (local_x, local_y): (usize, usize), size: usize) -> Position {
+        chunk_to_world(chunk.x, chunk.y, local_x, local_y, size).into()
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, other: Position) -> Position {
+        Position::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Position {
+    type Output = Position;
+
+    fn sub(self, other: Position) -> Position {
+        Position::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// Scales an offset, such as the displacement of a spaceship per period.
+impl Mul<Coord> for Position {
+    type Output = Position;
+
+    fn mul(self, factor: Coord) -> Position {
+        Position::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Position {
+    type Output = Position;
+
+    fn neg(self) -> Position {
+        Position::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Position {
+    fn add_assign(&mut self, other: Position) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Position {
+    fn sub_assign(&mut self, other: Position) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<Coord> for Position {
+    fn mul_assign(&mut self, factor: Coord) {
+        *self = *self * factor;
+    }
+}
+
+impl From<(Coord, Coord)> for Position {
+    fn from((x, y): (Coord, Coord)) -> Position {
+        Position::new(x, y)
+    }
+}
+
+impl From<Position> for (Coord, Coord) {
+    fn from(position: Position) -> (Coord, Coord) {
+        (position.x, position.y)
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+/// Rectangle of cells, from its -x -y corner at x y, growing `width` cells in the positive x direction
+/// and `height` in the positive y direction. A rectangle with no width or height holds no cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub x: Coord,
+    pub y: Coord,
+    pub width: usize,
+    pub height: usize
+}
+
+impl Rect {
+    pub const fn new(x: Coord, y: Coord, width: usize, height: usize) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    /// Create the smallest rectangle holding both positions, which can be given in any order.
+    pub fn from_corners(a: Position, b: Position) -> Rect {
+        let (min, max) = (Position::new(a.x.min(b.x), a.y.min(b.y)), Position::new(a.x.max(b.x), a.y.max(b.y)));
+        // Only a rectangle over the entire coordinate space is too wide to count, so it loses a cell
+        Rect::new(min.x, min.y, (max.x.abs_diff(min.x) as usize).saturating_add(1), (max.y.abs_diff(min.y) as usize).saturating_add(1))
+    }
+
+    /// Create the smallest rectangle holding every position, or [`None`] if there are none.
+    pub fn around(positions: impl IntoIterator<Item = Position>) -> Option<Rect> {
+        positions.into_iter()
+            .map(|position| Rect::new(position.x, position.y, 1, 1))
+            .reduce(|bounds, cell| bounds.union(&cell))
+    }
+
+    /// The -x -y corner of the rectangle.
+    pub fn min(&self) -> Position { Position::new(self.x, self.y) }
+
+    /// The +x +y corner of the rectangle, inclusive, so the last cell in it.
+    /// For an empty rectangle this is just before [`Rect::min`] on the empty axis.
+    pub fn max(&self) -> Position {
+        let last = |start: Coord, size: usize| match size {
+            0 => start.saturating_sub(1),
+            size => start.saturating_add_unsigned((size - 1) as u64)
+        };
+        Position::new(last(self.x, self.width), last(self.y, self.height))
+    }
+
+    /// The middle cell of the rectangle, rounding towards -x -y when there are two.
+    pub fn centre(&self) -> Position {
+        Position::new(
+            self.x.saturating_add_unsigned((self.width.saturating_sub(1) / 2) as u64),
+            self.y.saturating_add_unsigned((self.height.saturating_sub(1) / 2) as u64)
+        )
+    }
+
+    /// Check if the rectangle holds no cells at all.
+    pub fn is_empty(&self) -> bool { self.width == 0 || self.height == 0 }
+
+    /// Number of cells in the rectangle, stopping at the largest `usize`.
+    pub fn area(&self) -> usize { self.width.saturating_mul(self.height) }
+
+    /// Check if the position is one of the cells of the rectangle.
+    pub fn contains(&self, position: Position) -> bool {
+        // Subtracting can't overflow for positions right of the corner, and can't pass the size for ones left of it
+        position.x >= self.x && position.y >= self.y
+            && position.x.abs_diff(self.x) < self.width as u64
+            && position.y.abs_diff(self.y) < self.height as u64
+    }
+
+    /// Check if every cell of the other rectangle is also in this one. Empty rectangles are in everything.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty() || (self.contains(other.min()) && self.contains(other.max()))
+    }
+
+    /// Returns the cells in both rectangles, or [`None`] if they share none.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if self.is_empty() || other.is_empty() { return None }
+        let (min, max) = (self.min(), self.max());
+        let (other_min, other_max) = (other.min(), other.max());
+        let min = Position::new(min.x.max(other_min.x), min.y.max(other_min.y));
+        let max = Position::new(max.x.min(other_max.x), max.y.min(other_max.y));
+        if min.x > max.x || min.y > max.y { return None }
+        Some(Rect::from_corners(min, max))
+    }
+
+    /// Check if the rectangles share any cells.
+    pub fn overlaps(&self, other: &Rect) -> bool { self.intersection(other).is_some() }
+
+    /// Returns the smallest rectangle holding the cells of both. Empty rectangles add nothing.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() { return *self }
+        if self.is_empty() { return *other }
+        let (min, max) = (self.min(), self.max());
+        let (other_min, other_max) = (other.min(), other.max());
+        Rect::from_corners(
+            Position::new(min.x.min(other_min.x), min.y.min(other_min.y)),
+            Position::new(max.x.max(other_max.x), max.y.max(other_max.y))
+        )
+    }
+
+    /// Returns the rectangle moved by an offset.
+    pub fn translate(&self, offset: Position) -> Rect {
+        Rect::new(self.x + offset.x, self.y + offset.y, self.width, self.height)
+    }
+
+    /// Returns the rectangle grown by the given number of cells on every side, stopping at the limits of the coordinate space.
+    pub fn expand(&self, amount: usize) -> Rect {
+        let (x, y) = (self.x.saturating_sub_unsigned(amount as u64), self.y.saturating_sub_unsigned(amount as u64));
+        let max = self.max();
+        Rect::from_corners(Position::new(x, y), Position::new(max.x.saturating_add_unsigned(amount as u64), max.y.saturating_add_unsigned(amount as u64)))
+    }
+
+    /// Every position in the rectangle, in rows from the -y edge.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<> {
+        let (x, y, width) = (self.x, self.y, self.width);
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| {
+            Position::new(x.wrapping_add_unsigned(column as u64), y.wrapping_add_unsigned(row as u64))
+        }))
+    }
+}
+
+impl Display for Rect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}x{} at {} {}", self.width, self.height, self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+
+    #[test]
+    fn position_arithmetic() {
+        let mut position = Position::new(3, -4);
+        assert_eq!(Position::new(4, -2), position + Position::new(1, 2));
+        assert_eq!(Position::new(2, -6), position - Position::new(1, 2));
+        assert_eq!(Position::new(-3, 4), -position);
+        position += Position::new(-3, 4);
+        assert_eq!(Position::ORIGIN, position);
+
+        assert_eq!(None, Position::new(Coord::MAX, 0).checked_add(Position::new(1, 0)));
+        assert_eq!(Position::new(Coord::MAX, 1), Position::new(Coord::MAX, 0).saturating_add(Position::new(1, 1)));
+        assert_eq!((5, 6), Position::from((5, 6)).into());
+        assert_eq!("-1 2", Position::new(-1, 2).to_string());
+
+        position = Position::new(1, -2) * 3;
+        assert_eq!(Position::new(3, -6), position);
+        position *= -1;
+        assert_eq!(Position::new(-3, 6), position);
+    }
+
+    #[test]
+    fn neighbours_and_chunks() {
+        let neighbours: Vec<Position> = Position::new(5, 0).neighbours().collect();
+        assert_eq!(8, neighbours.len());
+        assert_eq!((Position::new(4, -1), Position::new(6, 1)), (neighbours[0], neighbours[7]));
+        assert!(!neighbours.contains(&Position::new(5, 0)));
+
+        // Cells just before 0 are in the chunk before it, at its far edge
+        let position = Position::new(-1, 17);
+        assert_eq!((Position::new(-1, 1), (15, 1)), (position.chunk(16), position.within_chunk(16)));
+        assert_eq!(position, Position::from_chunk(position.chunk(16), position.within_chunk(16), 16));
+    }
+
+    #[test]
+    fn rect() {
+        let rect = Rect::from_corners(Position::new(2, 3), Position::new(-1, 0));
+        assert_eq!(Rect::new(-1, 0, 4, 4), rect);
+        assert_eq!((Position::new(-1, 0), Position::new(2, 3)), (rect.min(), rect.max()));
+        assert_eq!(16, rect.area());
+        assert_eq!(Position::new(0, 1), rect.centre());
+        assert_eq!(Position::new(-1, 2), Rect::new(-1, 2, 1, 1).centre());
+        assert!(rect.contains(Position::new(2, 0)) && !rect.contains(Position::new(3, 0)) && !rect.contains(Position::new(-2, 0)));
+        assert!(rect.contains_rect(&Rect::new(0, 1, 2, 2)) && !rect.contains_rect(&Rect::new(0, 1, 4, 2)));
+
+        assert_eq!(Some(Rect::new(1, 0, 1, 4)), rect.intersection(&Rect::new(1, -5, 1, 20)));
+        assert_eq!(None, rect.intersection(&Rect::new(3, 0, 5, 5)));
+        assert!(!rect.overlaps(&Rect::new(0, 0, 0, 5)));
+        assert_eq!(Rect::new(-1, 0, 7, 4), rect.union(&Rect::new(5, 1, 1, 1)));
+        assert_eq!(rect, rect.union(&Rect::new(100, 100, 0, 0)));
+
+        assert_eq!(Rect::new(0, -2, 4, 4), rect.translate(Position::new(1, -2)));
+        assert_eq!(Rect::new(-3, -2, 8, 8), rect.expand(2));
+        assert_eq!(Some(Rect::new(-1, 0, 4, 1)), Rect::around([Position::new(2, 0), Position::new(-1, 0)]));
+        assert_eq!(None, Rect::around([]));
+
+        let cells: Vec<Position> = Rect::new(0, 0, 2, 2).positions().collect();
+        assert_eq!(vec![Position::new(0, 0), Position::new(1, 0), Position::new(0, 1), Position::new(1, 1)], cells);
+
+        // At the limits of the coordinate space
+        let edge = Rect::new(Coord::MAX - 1, 0, 2, 1);
+        assert!(edge.contains(Position::new(Coord::MAX, 0)));
+        assert_eq!(Position::new(Coord::MAX, 0), edge.max());
+        assert!(Rect::new(Coord::MIN, Coord::MIN, usize::MAX, usize::MAX).contains(Position::new(Coord::MAX - 1, 0)));
+    }
+}
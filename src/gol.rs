@@ -1,6 +1,20 @@
 //! Module to hold logic for the Game of Life simulation.
 
-use std::fmt::{Debug, Formatter};
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Index, IndexMut};
+use core::str::FromStr;
+
+use crate::error::GolError;
+use crate::geometry::{Coord, Position, Rect};
+use crate::hooks::SimulationObserver;
+#[cfg(feature = "std")]
+use crate::io::plaintext::read_plaintext;
+use crate::rule::{Rule, RuleError};
 
 /// Enum to represent each cell in the Game of Life world.
 /// Each cell can only either be alive or dead, and this
@@ -12,73 +26,357 @@ pub enum Cell {
     Alive,
 }
 
+/// What changed in a single step of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct StepStats {
+    /// Cells that came alive.
+    pub births: usize,
+    /// Cells that died.
+    pub deaths: usize,
+    /// Cells alive after the step.
+    pub population: usize
+}
+
+impl StepStats {
+    /// Cells that changed state, a measure of how active the world is.
+    pub fn changes(&self) -> usize {
+        self.births + self.deaths
+    }
+}
+
+
+/// Number of dead cells kept between any live cell and the edge of its region.
+/// A buffer of at least one cell guarantees births can only happen inside a region,
+/// and rules with neighbourhoods reaching further keep a buffer as wide as their reach.
+const REGION_BUFFER: usize = 1;
+
+/// Most cells a single region may hold.
+/// Regions are stored densely, so anything larger is almost certainly a mistake rather than a pattern.
+pub const MAX_REGION_CELLS: usize = 1 << 30;
+
+/// When regions give up the dead cells along their edges once their live cells move away from them.
+/// Regions always grow to keep the dead cell buffer around their live cells, so this only changes how they shrink.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TrimPolicy {
+    /// Never shrink, so regions only ever cover more of the world. Cheap for patterns that stay put,
+    /// but a region is stepped in full, so anything that moves drags an ever larger area behind it.
+    Never,
+    /// Shrink back to the buffer every generation, which keeps regions smallest
+    /// but resizes oscillators that expand and contract every period.
+    #[default]
+    Aggressive,
+    /// Only shrink an edge once it has more than this many dead columns or rows past the buffer,
+    /// so oscillators swinging by less settle into a fixed size.
+    Threshold(usize)
+}
+
+impl TrimPolicy {
+    /// Check whether an edge with the given number of dead cells past the buffer should be trimmed.
+    fn trims(self, excess: usize) -> bool {
+        match self {
+            TrimPolicy::Never => false,
+            TrimPolicy::Aggressive => excess > 0,
+            TrimPolicy::Threshold(threshold) => excess > threshold
+        }
+    }
+}
+
+impl Display for TrimPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TrimPolicy::Never => write!(f, "never"),
+            TrimPolicy::Aggressive => write!(f, "aggressive"),
+            TrimPolicy::Threshold(threshold) => write!(f, "{}", threshold)
+        }
+    }
+}
+
+impl FromStr for TrimPolicy {
+    type Err = TrimPolicyError;
+
+    /// Parse `never`, `aggressive` or a threshold as a number of cells, ignoring case.
+    fn from_str(s: &str) -> Result<TrimPolicy, TrimPolicyError> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "never" => Ok(TrimPolicy::Never),
+            "aggressive" => Ok(TrimPolicy::Aggressive),
+            threshold => threshold.parse().map(TrimPolicy::Threshold).map_err(|_| TrimPolicyError(s.to_string()))
+        }
+    }
+}
+
+/// Error from parsing a trim policy that isn't `never`, `aggressive` or a threshold.
+#[derive(Debug, PartialEq)]
+pub struct TrimPolicyError(pub String);
+
+impl Display for TrimPolicyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid trim policy '{}', expected never, aggressive or a number of cells", self.0)
+    }
+}
 
+impl core::error::Error for TrimPolicyError {}
+
+/// Error from [`GameOfLife::diff`] for worlds with different backgrounds, holding the other world's background,
+/// which every cell outside the regions changed to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BackgroundChanged(pub Cell);
+
+impl Display for BackgroundChanged {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let state = if self.0 == Cell::Alive { "alive" } else { "dead" };
+        write!(f, "the background changed to {}, so every cell of it changed", state)
+    }
+}
+
+impl core::error::Error for BackgroundChanged {}
+
+/// Saved cells and rules of a world, to go back to with [`GameOfLife::restore`] as many times as needed.
+/// It shares its regions with the world, which replaces rather than changes any region it steps or edits,
+/// so a checkpoint costs no more than the regions that later change.
+#[derive(Clone)]
+pub struct Snapshot {
+    regions: Vec<Arc<Region>>,
+    rule: Rule,
+    zones: Vec<(Rect, Rule)>,
+    background: Cell
+}
+
+impl Snapshot {
+    /// Number of live cells saved.
+    pub fn population(&self) -> usize {
+        self.regions.iter()
+            .map(|region| region.state.iter().flatten().filter(|&&cell| cell == Cell::Alive).count())
+            .sum()
+    }
+}
 
 /// Main Game of Life simulation struct.
+/// Regions are shared between clones until one of them changes,
+/// so cloning is a cheap way to take a consistent snapshot of a world that keeps running.
+/// Under rules with B0 the infinite background itself comes alive, so regions hold the cells that differ
+/// from the background rather than the live ones, and population and live cells count those instead.
+#[derive(Clone)]
 pub struct GameOfLife {
-    regions: Vec<Region>
+    regions: Vec<Arc<Region>>,
+    rule: Rule,
+    /// Areas following their own rule instead of the world's, later ones taking over where they overlap.
+    zones: Vec<(Rect, Rule)>,
+    /// State of every cell outside the regions, which only rules with B0 ever bring to life.
+    background: Cell,
+    trim_policy: TrimPolicy,
+    /// Population after each of the most recent steps, oldest first, while being recorded.
+    population_history: VecDeque<usize>,
+    /// Most populations kept in the history, or 0 when not recording.
+    population_capacity: usize
 }
 
 impl GameOfLife {
-    /// Create a new empty world.
+    /// Create a new empty world, using the rules of Conway's Game of Life.
     pub fn new() -> GameOfLife {
+        GameOfLife::with_rule(Rule::LIFE)
+    }
+
+    /// Create a new empty world using the given rule.
+    pub fn with_rule(rule: Rule) -> GameOfLife {
         GameOfLife {
-            regions: vec![]
+            regions: vec![],
+            rule,
+            zones: vec![],
+            background: Cell::Dead,
+            trim_policy: TrimPolicy::default(),
+            population_history: VecDeque::new(),
+            population_capacity: 0
         }
     }
 
-    /// Step the simulation to the next state.
-    pub fn step(&mut self) {
-        self.step_regions();
-        // Split Regions that have disjoint cells
-        // Merge regions that are too close
+    /// Change the rule used for following generations.
+    pub fn set_rule(&mut self, rule: Rule) {
+        let buffer = self.region_buffer();
+        self.rule = rule;
+        self.widen_buffers(buffer);
+    }
+
+    /// Have the cells in the area follow their own rule for following generations, such as a Seeds area feeding
+    /// a Life one. Each cell steps by the rule of the area it is in, counting its neighbours in that rule's
+    /// neighbourhood on whichever side of the border they are, so patterns cross borders and change as they do.
+    /// Areas added later take over from earlier ones where they overlap. Fails for rules with B0, as cells
+    /// outside the world's regions are only ever stepped as the background, and while the world's own rule
+    /// has B0 every cell follows it instead.
+    pub fn add_zone(&mut self, area: Rect, rule: Rule) -> Result<(), RuleError> {
+        if rule.has_b0() { return Err(RuleError::BirthOnZero) }
+        let buffer = self.region_buffer();
+        self.zones.push((area, rule));
+        self.widen_buffers(buffer);
+        Ok(())
+    }
+
+    /// Have every cell follow the world's rule again.
+    pub fn clear_zones(&mut self) {
+        self.zones.clear();
     }
 
-    /// Step each region to calculate the next state.
-    fn step_regions(&mut self) {
+    /// Rule the cell at the given x y coordinates steps by.
+    pub fn rule_at(&self, x: Coord, y: Coord) -> Rule {
+        if self.rule.has_b0() { return self.rule }
+        self.zones.iter().rev()
+            .find(|(area, _)| area.contains(Position::new(x, y)))
+            .map_or(self.rule, |&(_, rule)| rule)
+    }
+
+    /// Widen the buffers past the given old width, so cells born further out under a newly reaching rule
+    /// still land inside a region.
+    fn widen_buffers(&mut self, old_buffer: usize) {
+        let buffer = self.region_buffer();
+        if buffer <= old_buffer { return }
+        let mut strips = vec![];
         for region in &mut self.regions {
-            for x in region.x .. region.x.saturating_add_unsigned(region.width) {
-                for y in region.y..region.y.saturating_add_unsigned(region.height) {
-                    Self::step_cell(region, x, y);
-                }
-            }
+            Self::resize_region(Arc::make_mut(region), buffer, self.trim_policy, &mut strips);
         }
+        self.regions.extend(strips.into_iter().map(Arc::new));
+        self.merge_overlapping_regions();
     }
 
-    /// Function for logic run for each cell in given region
-    fn step_cell(region: &mut Region, x: isize, y: isize) {
-        let neighbor_offsets = [
-            (-1, -1), (0, -1), (1, -1),
-            (-1, 0),           (1, 0),
-            (-1, 1),  (0, 1),  (1, 1),
-        ];
+    /// Change when regions shrink for following generations and edits.
+    pub fn set_trim_policy(&mut self, trim_policy: TrimPolicy) {
+        self.trim_policy = trim_policy;
+    }
+
+    /// Save the world's cells and rules, such as before trying out a change that might need taking back.
+    pub fn checkpoint(&self) -> Snapshot {
+        Snapshot { regions: self.regions.clone(), rule: self.rule, zones: self.zones.clone(), background: self.background }
+    }
+
+    /// Put the world's cells and rules back as they were saved. The trim policy is kept,
+    /// and any population history being recorded starts again from the restored population.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.regions = snapshot.regions.clone();
+        self.rule = snapshot.rule;
+        self.zones = snapshot.zones.clone();
+        self.background = snapshot.background;
+        if self.population_capacity > 0 { self.record_population(self.population_capacity) }
+    }
+
+    /// Start recording the population after every step, keeping the most recent `capacity` of them,
+    /// or stop recording and forget them with a capacity of 0. The current population is recorded straight away.
+    pub fn record_population(&mut self, capacity: usize) {
+        self.population_capacity = capacity;
+        self.population_history.clear();
+        if capacity > 0 { self.population_history.push_back(self.population()) }
+    }
+
+    /// Step the simulation to the next state, returning how many cells were born and died.
+    pub fn step(&mut self) -> StepStats {
+        self.step_with(&mut ())
+    }
+
+    /// Step the simulation as [`step`](Self::step) does, telling the observer about each stage along the way.
+    pub fn step_with(&mut self, observer: &mut impl SimulationObserver) -> StepStats {
+        observer.on_step_start(self);
+        let stats = self.step_regions(|position, cell| observer.on_cell_changed(position, cell));
+        if self.population_capacity > 0 {
+            if self.population_history.len() == self.population_capacity { self.population_history.pop_front(); }
+            self.population_history.push_back(stats.population);
+        }
 
-        let mut neighbours = 0;
-        for (x_off, y_off) in neighbor_offsets {
-            match region.get_cell(x + x_off, y + y_off) {
-                None | Some(Cell::Dead) => { continue }
-                Some(Cell::Alive) => { neighbours += 1;}
+        // Split Regions that have disjoint cells, and drop any with nothing left alive
+        for region in core::mem::take(&mut self.regions) {
+            let bounds = region.bounds();
+            let parts = Self::split_region(Arc::unwrap_or_clone(region), self.region_buffer());
+            if parts.len() > 1 {
+                observer.on_region_split(bounds, &parts.iter().map(Region::bounds).collect::<Vec<Rect>>());
             }
+            let mut strips = vec![];
+            for mut part in parts {
+                if Self::resize_region(&mut part, self.region_buffer(), self.trim_policy, &mut strips) {
+                    self.regions.push(Arc::new(part));
+                }
+            }
+            self.regions.extend(strips.into_iter().map(Arc::new));
         }
 
-        let current_state = region.get_cell(x, y).expect("Cell X Y position out of bounds");
-        region.set_cell(x, y, match (current_state, neighbours) {
-            (_, 3) => Cell::Alive,
-            (current, 2) => current,
-            _ => Cell::Dead
-        });
+        // Growing regions can push them into each other
+        self.merge_overlapping_regions_with(observer);
+        observer.on_step_end(self, stats);
+        stats
     }
 
-    /// Check if a position is contained within a region of this world.
-    fn pos_in_bounds(&self, x: isize, y: isize) -> bool {
+    /// Step each region to calculate the next state, counting and passing on the cells that changed along the way.
+    fn step_regions(&mut self, mut changed: impl FnMut(Position, Cell)) -> StepStats {
+        // Every next state has to be calculated before any are applied,
+        // as a region's edge cells can have neighbours in other regions
+        let mut next_states = Vec::with_capacity(self.regions.len());
+        let mut stats = StepStats::default();
+        let next_background = self.rule.next_background(self.background);
         for region in &self.regions {
-            if region.pos_in_bounds(x, y) { return true }
+            let mut state = Vec::with_capacity(region.width);
+            for x in region.x .. region.x.saturating_add_unsigned(region.width as u64) {
+                let mut column = Vec::with_capacity(region.height);
+                for y in region.y..region.y.saturating_add_unsigned(region.height as u64) {
+                    let (current, cell) = self.step_cell(region, x, y, next_background);
+                    match (current, cell) {
+                        (Cell::Dead, Cell::Alive) => stats.births += 1,
+                        (Cell::Alive, Cell::Dead) => stats.deaths += 1,
+                        _ => {}
+                    }
+                    if current != cell { changed(Position::new(x, y), cell) }
+                    if cell == Cell::Alive { stats.population += 1 }
+                    column.push(cell);
+                }
+                state.push(column);
+            }
+            next_states.push(state);
+        }
+
+        // Fresh regions rather than changing the old ones in place, which may be shared with snapshots
+        for (region, state) in self.regions.iter_mut().zip(next_states) {
+            *region = Arc::new(Region { state, ..*region.as_ref() });
         }
-        false
+        self.background = next_background;
+        stats
+    }
+
+    /// Function for logic run for each cell in given region, returning its current and next state as stored,
+    /// relative to the background now and the background next.
+    fn step_cell(&self, region: &Region, x: Coord, y: Coord, next_background: Cell) -> (Cell, Cell) {
+        let rule = self.rule_at(x, y);
+        let (mut neighbours, mut arrangement) = (0, 0u32);
+        for (index, (x_off, y_off, weight)) in rule.neighbourhood().neighbours().enumerate() {
+            // Only look through the rest of the world when the neighbour is off the region's edge,
+            // and positions past the coordinate limits hold nothing, so are always the background
+            let neighbour = match (x.checked_add(x_off), y.checked_add(y_off)) {
+                (Some(x), Some(y)) => region.get_cell(x, y).unwrap_or_else(|| self.stored_cell(x, y)),
+                _ => Cell::Dead
+            };
+            if neighbour == Cell::Alive {
+                neighbours += weight;
+                arrangement |= 1 << index;
+            }
+        }
+
+        // Stored cells are those differing from the background, so against a live one they are the dead cells
+        if self.background == Cell::Alive {
+            neighbours = rule.neighbourhood().max_count() - neighbours;
+            arrangement = !arrangement;
+        }
+
+        let current_state = region.get_cell(x, y).expect("Cell X Y position out of bounds");
+        let current = relative_to(current_state, self.background);
+        let next = if rule.is_totalistic() {
+            rule.next_state(current, neighbours)
+        }
+        else {
+            rule.next_state_of(current, arrangement as u8)
+        };
+        (current_state, relative_to(next, next_background))
     }
 
     /// Get the state of the cell at the given x y coordinates.
-    pub fn get_cell(&self, x: isize, y: isize) -> Cell {
+    pub fn get_cell(&self, x: Coord, y: Coord) -> Cell {
+        relative_to(self.stored_cell(x, y), self.background)
+    }
+
+    /// Get the state of the cell as stored in the regions, alive where it differs from the background.
+    fn stored_cell(&self, x: Coord, y: Coord) -> Cell {
         for region in &self.regions {
             if let Some(state) = region.get_cell(x, y) {
                 return state;
@@ -87,112 +385,941 @@ impl GameOfLife {
         Cell::Dead
     }
 
+    /// Number of live cells in the world, or of cells differing from the background while it is alive.
+    pub fn population(&self) -> usize {
+        self.regions.iter()
+            .map(|region| region.state.iter().flatten().filter(|&&cell| cell == Cell::Alive).count())
+            .sum()
+    }
+
+    /// Positions of every live cell in the world, or of every cell differing from a live background, in no particular order.
+    pub fn live_cells(&self) -> Vec<Position> {
+        self.regions.iter().flat_map(|region| region.live_cells()).collect()
+    }
+
+    /// Cells whose state differs between this world and the other, with their state in the other, ordered by position.
+    /// Regions the worlds still share, such as with a clone or a [`Snapshot`] taken a few steps ago, are skipped
+    /// without looking at their cells, so this is cheap when little has changed.
+    /// Fails if the worlds' backgrounds differ, such as a generation apart under a B0 rule,
+    /// as then every cell of the infinite background changed too.
+    pub fn diff(&self, other: &GameOfLife) -> Result<impl Iterator<Item = (Position, Cell)> + use<>, BackgroundChanged> {
+        if self.background != other.background { return Err(BackgroundChanged(other.background)) }
+
+        // Regions never overlap within a world, so cells in a shared region are the same in both
+        let unshared = |world: &GameOfLife, than: &GameOfLife| -> BTreeSet<Position> {
+            world.regions.iter()
+                .filter(|region| !than.regions.iter().any(|shared| Arc::ptr_eq(region, shared)))
+                .flat_map(|region| region.live_cells())
+                .collect()
+        };
+        let (before, after) = (unshared(self, other), unshared(other, self));
+        // Cells are stored relative to the background, which is the same in both
+        let background = other.background;
+        let mut changes: Vec<(Position, Cell)> = before.difference(&after).map(|&cell| (cell, relative_to(Cell::Dead, background)))
+            .chain(after.difference(&before).map(|&cell| (cell, relative_to(Cell::Alive, background))))
+            .collect();
+        changes.sort_unstable_by_key(|&(position, _)| position);
+        Ok(changes.into_iter())
+    }
+
+    /// Returns the bounding box of every live cell in the world, or [`None`] if there are no live cells.
+    /// Only live cells count, so the box is the same however much dead space the regions hold around them.
+    #[doc(alias = "bounding_box")]
+    pub fn live_bounds(&self) -> Option<Rect> {
+        self.regions.iter()
+            .filter_map(|region| region.live_bounds())
+            .reduce(|bounds, region_bounds| bounds.union(&region_bounds))
+    }
+
+    /// Copy the world into a new region covering the bounding box of its live cells.
+    /// An empty world gives an empty region at 0 0.
+    /// Fails if the live cells are spread too far apart to fit in a single region.
+    pub fn to_region(&self) -> Result<Region, RegionError> {
+        let Some(bounds) = self.live_bounds() else { return Region::new(0, 0, 0, 0) };
+        let mut region = Region::from_rect(bounds)?;
+        self.populate_region(&mut region);
+        Ok(region)
+    }
+
     /// Set the state of a cell in the world.
-    pub fn set_cell(&mut self, x: isize, y: isize, state: Cell) {
-        for region in &mut self.regions {
-            if region.pos_in_bounds(x, y) {
-                region.set_cell(x, y, state);
-                Self::resize_region(region);
+    /// Setting a cell to other than the background outside of every region allocates a new region around it.
+    /// No region can reach cells at [`Coord::MAX`] on either axis, so setting those to other than the background
+    /// does nothing; use [`try_set_cell`](GameOfLife::try_set_cell) to find out.
+    pub fn set_cell(&mut self, x: Coord, y: Coord, state: Cell) {
+        let _ = self.try_set_cell(x, y, state);
+    }
+
+    /// Set the state of a cell in the world as [`set_cell`](GameOfLife::set_cell) does,
+    /// failing with [`RegionError::OutOfRange`] if the cell is at [`Coord::MAX`] where no region can hold it.
+    pub fn try_set_cell(&mut self, x: Coord, y: Coord, state: Cell) -> Result<(), GolError> {
+        let state = relative_to(state, self.background);
+        let buffer = self.region_buffer();
+        let mut strips = vec![];
+        if let Some(index) = self.regions.iter().position(|region| region.pos_in_bounds(x, y)) {
+            let region = Arc::make_mut(&mut self.regions[index]);
+            region.set_cell(x, y, state);
+            if !Self::resize_region(region, buffer, self.trim_policy, &mut strips) {
+                self.regions.swap_remove(index);
+                return Ok(());
+            }
+        }
+        // Cells outside of every region are already dead, so only live cells need a new region
+        else if state == Cell::Alive {
+            // The buffer is cut short against the coordinate limits, as there are no cells past them to be born
+            let (start_x, start_y) = (x.saturating_sub(buffer as Coord), y.saturating_sub(buffer as Coord));
+            let (Some(end_x), Some(end_y)) = (x.checked_add(1), y.checked_add(1))
+            else { return Err(RegionError::OutOfRange { x, y, width: 1, height: 1 }.into()) };
+            let (end_x, end_y) = (end_x.saturating_add(buffer as Coord), end_y.saturating_add(buffer as Coord));
+            let mut region = Region::new(start_x, start_y, end_x.abs_diff(start_x) as usize, end_y.abs_diff(start_y) as usize)?;
+            region.set_cell(x, y, state);
+            self.regions.push(Arc::new(region));
+        }
+        else { return Ok(()) }
+
+        self.regions.extend(strips.into_iter().map(Arc::new));
+        self.merge_overlapping_regions();
+        Ok(())
+    }
+
+    /// Number of dead cells kept around live cells under the current rules.
+    pub(crate) fn region_buffer(&self) -> usize {
+        self.zones.iter()
+            .map(|(_, rule)| rule.neighbourhood().reach())
+            .fold(REGION_BUFFER.max(self.rule.neighbourhood().reach()), usize::max)
+    }
+
+    /// Resizes provided region to keep at least the dead cell buffer on each edge,
+    /// growing as needed and trimming back to the buffer as the policy allows.
+    /// Growth stops at the coordinate limits, and an edge the region can't grow without passing
+    /// [`MAX_REGION_CELLS`] gets its buffer as a separate strip of dead cells added to `strips`.
+    /// Returns false if the region has no live cells left, in which case it is left unchanged.
+    fn resize_region(region: &mut Region, buffer: usize, trim_policy: TrimPolicy, strips: &mut Vec<Region>) -> bool {
+        let Some(bounds) = region.live_bounds() else { return false };
+        let (min, max) = (bounds.min(), bounds.max());
+        let (min_x, min_y, max_x, max_y) = (min.x, min.y, max.x, max.y);
+        let buffer = buffer as Coord;
+
+        // Distance from the outermost live cells to each edge
+        let neg_x_gap = min_x - region.x;
+        let neg_y_gap = min_y - region.y;
+        let x_gap = region.x.saturating_add_unsigned(region.width as u64) - 1 - max_x;
+        let y_gap = region.y.saturating_add_unsigned(region.height as u64) - 1 - max_y;
+
+        for (edge, gap) in [(Edge::NegX, neg_x_gap), (Edge::NegY, neg_y_gap), (Edge::X, x_gap), (Edge::Y, y_gap)] {
+            if gap == buffer { continue }
+            if gap > buffer {
+                if !trim_policy.trims((gap - buffer) as usize) { continue }
+                // Trimming only ever removes dead cells, so always fits
+                region.adjust_size(edge, buffer - gap).expect("Region trimmed past its live cells");
+                region.trimmed += (gap - buffer) as u64;
+                continue;
             }
+
+            // Nothing can be born past the coordinate limits, so growing stops at them
+            let (x, y, width, height) = (region.x, region.y, region.width, region.height);
+            let room = match edge {
+                Edge::NegX => x.abs_diff(Coord::MIN),
+                Edge::NegY => y.abs_diff(Coord::MIN),
+                Edge::X => Coord::MAX.abs_diff(x.saturating_add_unsigned(width as u64)),
+                Edge::Y => Coord::MAX.abs_diff(y.saturating_add_unsigned(height as u64))
+            };
+            let amount = (buffer - gap).min(Coord::try_from(room).unwrap_or(Coord::MAX));
+            if amount == 0 || region.adjust_size(edge, amount).is_ok() { continue }
+
+            // Too large to grow, so the buffer goes in a region of its own alongside
+            let strip = match edge {
+                Edge::NegX => Region::new(x - amount, y, amount as usize, height),
+                Edge::NegY => Region::new(x, y - amount, width, amount as usize),
+                Edge::X => Region::new(x + width as Coord, y, amount as usize, height),
+                Edge::Y => Region::new(x, y + height as Coord, width, amount as usize)
+            };
+            strips.extend(strip);
         }
+        true
     }
 
-    /// Resizes provided to region to maintain dead cell buffer on edges.
-    fn resize_region(region: &mut Region) {
-        // TODO
+    /// Split a region wherever its live cells are separated by a wide enough band of dead columns or rows,
+    /// so patterns moving apart don't keep a single ever growing region alive between them.
+    fn split_region(region: Region, buffer: usize) -> Vec<Region> {
+        // Wide enough that the buffers of the split regions can't overlap
+        let min_gap = buffer * 2 + 1;
+        let mut pending = vec![region];
+        let mut parts = vec![];
+
+        while let Some(mut region) = pending.pop() {
+            let live_columns: Vec<bool> = region.state.iter().map(|column| column.contains(&Cell::Alive)).collect();
+            if let Some(split) = Self::find_gap(&live_columns, min_gap) {
+                // The trimmed count stays with one part, so merging the parts back doesn't count it twice
+                let state = region.state.split_off(split);
+                let right = Region { x: region.x + split as Coord, width: region.width - split, state, trimmed: 0, ..region };
+                region.width = split;
+                pending.push(region);
+                pending.push(right);
+                continue;
+            }
+
+            let live_rows: Vec<bool> = (0..region.height)
+                .map(|y| region.state.iter().any(|column| column[y] == Cell::Alive))
+                .collect();
+            if let Some(split) = Self::find_gap(&live_rows, min_gap) {
+                let state = region.state.iter_mut().map(|column| column.split_off(split)).collect();
+                let bottom = Region { y: region.y + split as Coord, height: region.height - split, state, trimmed: 0, ..region };
+                region.height = split;
+                pending.push(region);
+                pending.push(bottom);
+                continue;
+            }
+
+            parts.push(region);
+        }
+
+        parts
+    }
+
+    /// Find a position in the middle of the first run of at least `min_gap` unoccupied entries
+    /// that has occupied entries on both sides.
+    fn find_gap(occupied: &[bool], min_gap: usize) -> Option<usize> {
+        let mut seen_occupied = false;
+        let mut gap_start = None;
+        for (i, &occupied) in occupied.iter().enumerate() {
+            if !occupied {
+                gap_start.get_or_insert(i);
+                continue;
+            }
+            if let Some(start) = gap_start && seen_occupied && i - start >= min_gap {
+                return Some(start + (i - start) / 2)
+            }
+            seen_occupied = true;
+            gap_start = None;
+        }
+        None
     }
 
     /// Merge overlapping regions into single region
     fn merge_overlapping_regions(&mut self) {
-        // TODO
+        self.merge_overlapping_regions_with(&mut ());
+    }
+
+    /// Merge overlapping regions, telling the observer about each pair merged.
+    /// Pairs too large to merge stay separate, with the smaller split up around the larger as it tells the observer.
+    fn merge_overlapping_regions_with(&mut self, observer: &mut impl SimulationObserver) {
+        // A merged region can overlap regions that neither half did, so repeat until none overlap
+        while let Some((i, j)) = self.find_overlapping_pair() {
+            // j is always after i, so removing it leaves i in place
+            let mut other = self.regions.swap_remove(j);
+            match Self::merge_regions(&self.regions[i], &other) {
+                Ok(region) => {
+                    observer.on_region_merge(self.regions[i].bounds(), other.bounds(), region.bounds());
+                    self.regions[i] = Arc::new(region);
+                }
+                Err(_) => {
+                    if other.bounds().area() > self.regions[i].bounds().area() {
+                        core::mem::swap(&mut self.regions[i], &mut other);
+                    }
+                    let parts = Self::carve_overlap(Arc::make_mut(&mut self.regions[i]), &other);
+                    observer.on_region_split(other.bounds(), &parts.iter().map(Region::bounds).collect::<Vec<Rect>>());
+                    self.regions.extend(parts.into_iter().map(Arc::new));
+                }
+            }
+        }
+    }
+
+    /// Find the indices of the first two regions that overlap, with the lower index first.
+    fn find_overlapping_pair(&self) -> Option<(usize, usize)> {
+        for i in 0..self.regions.len() {
+            for j in i + 1..self.regions.len() {
+                if self.regions[i].is_overlapping(&self.regions[j]) { return Some((i, j)) }
+            }
+        }
+        None
+    }
+
+    /// Create a region covering both given regions, containing the live cells of each.
+    /// Fails if the region covering both would hold more than [`MAX_REGION_CELLS`] cells.
+    fn merge_regions(a: &Region, b: &Region) -> Result<Region, RegionError> {
+        let mut merged = Region::from_rect(a.bounds().union(&b.bounds()))?;
+
+        // Newly grown edges of a region are always dead, so live cells take priority in the overlap
+        for cell in a.live_cells().chain(b.live_cells()) {
+            merged.set_cell(cell.x, cell.y, Cell::Alive);
+        }
+
+        merged.trimmed = a.trimmed + b.trimmed;
+        Ok(merged)
+    }
+
+    /// Move the live cells of `other` that overlap `region` into it, and split the rest of `other`
+    /// into regions around it, for regions too large to merge.
+    /// Overlaps only ever come from regions growing their dead buffers into each other, so no live cell is lost.
+    fn carve_overlap(region: &mut Region, other: &Region) -> Vec<Region> {
+        let Some(overlap) = region.bounds().intersection(&other.bounds()) else { return vec![other.clone()] };
+        for cell in other.live_cells().filter(|&cell| overlap.contains(cell)) {
+            region.set_cell(cell.x, cell.y, Cell::Alive);
+        }
+
+        // Columns either side of the overlap, then the rows above and below it between them.
+        // The far edges of regions are always representable, so none of these overflow
+        let end = |start: Coord, size: usize| start.saturating_add_unsigned(size as u64);
+        let (other_end_x, other_end_y) = (end(other.x, other.width), end(other.y, other.height));
+        let (overlap_end_x, overlap_end_y) = (end(overlap.x, overlap.width), end(overlap.y, overlap.height));
+        let parts = [
+            Rect::new(other.x, other.y, overlap.x.abs_diff(other.x) as usize, other.height),
+            Rect::new(overlap_end_x, other.y, other_end_x.abs_diff(overlap_end_x) as usize, other.height),
+            Rect::new(overlap.x, other.y, overlap.width, overlap.y.abs_diff(other.y) as usize),
+            Rect::new(overlap.x, overlap_end_y, overlap.width, other_end_y.abs_diff(overlap_end_y) as usize)
+        ];
+        let mut trimmed = other.trimmed;
+        parts.into_iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                // Each part is inside the other region, so fits just as it did
+                let mut part = Region::from_rect(part).expect("Part of a region is too large for a region");
+                other.populate_overlap(&mut part);
+                part.trimmed = core::mem::take(&mut trimmed);
+                part
+            })
+            .collect()
     }
 
     /// Populate the provided region with the state of the current world.
     pub fn populate_region(&self, region: &mut Region) {
-        !unimplemented!()
+        // Cells outside of every world region are dead, so clear before copying the overlaps
+        for column in &mut region.state {
+            column.fill(Cell::Dead);
+        }
+        for world_region in &self.regions {
+            world_region.populate_overlap(region);
+        }
     }
 
     /// Set the state of the world to that of the given region.
     pub fn set_region(&mut self, region: &Region) {
-        !unimplemented!()
+        for (position, state) in region.iter() {
+            self.set_cell(position.x, position.y, state);
+        }
     }
 
-    pub fn debug_print(&self) {
-        println!("Num Regions: {}", self.regions.len());
-        for region in &self.regions {
-            println!(
-                "{{ x: {}, y: {}, width: {}, height: {} }}",
-                region.x,
-                region.y,
-                region.width,
-                region.height
-            );
-
-            for y in region.y..region.y.saturating_add_unsigned(region.height) {
-                for x in region.x..region.x.saturating_add_unsigned(region.width) {
-                    print!(
-                        "{}",
-                        match region.get_cell(x, y) {
-                            None => "?",
-                            Some(Cell::Alive) => "1",
-                            Some(Cell::Dead) => "0"
-                        }
-                    );
-                }
-                println!();
-            }
+    /// The regions the world's live cells are stored in, for seeing how the world is laid out in memory.
+    pub fn regions(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter().map(Arc::as_ref)
+    }
+
+    pub fn rule(&self) -> Rule { self.rule }
+    pub fn zones(&self) -> &[(Rect, Rule)] { &self.zones }
+    pub fn background(&self) -> Cell { self.background }
+    pub fn trim_policy(&self) -> TrimPolicy { self.trim_policy }
+    /// Populations recorded by [`GameOfLife::record_population`], oldest first and ending with the latest step.
+    /// Edits between steps are only counted from the next step.
+    pub fn population_history(&self) -> &VecDeque<usize> { &self.population_history }
+}
+
+impl Default for GameOfLife {
+    fn default() -> GameOfLife { GameOfLife::new() }
+}
+
+/// Builds a Life world with a live cell at each position.
+impl FromIterator<Position> for GameOfLife {
+    fn from_iter<I: IntoIterator<Item = Position>>(cells: I) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        world.extend(cells);
+        world
+    }
+}
+
+impl<'a> FromIterator<&'a Position> for GameOfLife {
+    fn from_iter<I: IntoIterator<Item = &'a Position>>(cells: I) -> GameOfLife {
+        cells.into_iter().copied().collect()
+    }
+}
+
+/// Sets the cell at each position alive.
+impl Extend<Position> for GameOfLife {
+    fn extend<I: IntoIterator<Item = Position>>(&mut self, cells: I) {
+        for cell in cells {
+            self.set_cell(cell.x, cell.y, Cell::Alive);
         }
     }
 }
 
+impl<'a> Extend<&'a Position> for GameOfLife {
+    fn extend<I: IntoIterator<Item = &'a Position>>(&mut self, cells: I) {
+        self.extend(cells.into_iter().copied());
+    }
+}
+
+/// State of the cell at the position, as [`GameOfLife::get_cell`]. There's no `IndexMut`, as cells may not be stored
+/// anywhere to borrow, and setting one can grow or merge regions, so cells are set with [`GameOfLife::set_cell`].
+impl Index<Position> for GameOfLife {
+    type Output = Cell;
+
+    fn index(&self, position: Position) -> &Cell {
+        match self.get_cell(position.x, position.y) {
+            Cell::Alive => &Cell::Alive,
+            Cell::Dead => &Cell::Dead
+        }
+    }
+}
+
+/// State of a cell relative to the background, alive where the two differ. Taking it relative to the same
+/// background again gives the cell back, so it converts both ways between stored and actual states.
+fn relative_to(cell: Cell, background: Cell) -> Cell {
+    if cell == background { Cell::Dead } else { Cell::Alive }
+}
+
 #[cfg(test)]
 mod game_of_life_tests {
     use super::*;
 
     #[test]
-    fn pos_in_bounds() {
-        // TODO
+    fn get_cell() {
+        let mut game = GameOfLife::new();
+        assert_eq!(Cell::Dead, game.get_cell(0, 0));
+        assert_eq!(Cell::Dead, game.get_cell(-100, 40));
+
+        game.set_cell(-100, 40, Cell::Alive);
+        assert_eq!(Cell::Alive, game.get_cell(-100, 40));
+        assert_eq!(Cell::Dead, game.get_cell(-101, 40));
+
+        game.set_cell(-100, 40, Cell::Dead);
+        assert_eq!(Cell::Dead, game.get_cell(-100, 40));
     }
 
     #[test]
-    fn get_cell() {
-        // TODO
+    fn set_cell_merges_regions() {
+        let mut game = GameOfLife::new();
+        game.set_cell(0, 0, Cell::Alive);
+        game.set_cell(10, 10, Cell::Alive);
+        assert_eq!(2, game.regions.len());
+
+        // Adjacent cell grows the first region's buffer into the new region's
+        game.set_cell(1, 0, Cell::Alive);
+        game.set_cell(3, 0, Cell::Alive);
+        assert_eq!(2, game.regions.len());
+        assert_eq!(Cell::Alive, game.get_cell(0, 0));
+        assert_eq!(Cell::Alive, game.get_cell(1, 0));
+        assert_eq!(Cell::Alive, game.get_cell(3, 0));
+        assert_eq!(Cell::Dead, game.get_cell(2, 0));
+
+        // Every live cell keeps a dead buffer to its region's edge
+        for region in &game.regions {
+            let (min, max) = (region.live_bounds().unwrap().min(), region.live_bounds().unwrap().max());
+            let (edge_min, edge_max) = (region.bounds().min(), region.bounds().max());
+            assert!(min.x > edge_min.x && min.y > edge_min.y);
+            assert!(max.x < edge_max.x && max.y < edge_max.y);
+        }
+    }
+
+    #[test]
+    fn coordinate_limits() {
+        // No region can hold cells at the largest coordinate, which only the fallible setter reports
+        let mut game = GameOfLife::new();
+        assert!(matches!(game.try_set_cell(Coord::MAX, 0, Cell::Alive), Err(GolError::Region(RegionError::OutOfRange { .. }))));
+        game.set_cell(0, Coord::MAX, Cell::Alive);
+        assert_eq!(0, game.population());
+
+        // Blinkers pressed against the limits turn over as if nothing lies past them
+        for y in -1..=1 {
+            game.set_cell(Coord::MAX - 1, y, Cell::Alive);
+            game.set_cell(Coord::MIN, y, Cell::Alive);
+        }
+        game.step();
+        assert_eq!(4, game.population());
+        for x in [Coord::MIN, Coord::MIN + 1, Coord::MAX - 2, Coord::MAX - 1] {
+            assert_eq!(Cell::Alive, game.get_cell(x, 0));
+        }
+        game.step();
+        assert_eq!(0, game.population());
+    }
+
+    #[test]
+    fn merge_too_large() {
+        // Crossing lines are each small, but a region covering both would be far past the size limit
+        let length: Coord = 1 << 16;
+        let mut horizontal = Region::new(-length / 2, -1, length as usize, 3).unwrap();
+        let mut vertical = Region::new(-1, -length / 2, 3, length as usize).unwrap();
+        for i in -length / 2 + 1..length / 2 - 1 {
+            horizontal.set_cell(i, 0, Cell::Alive);
+            vertical.set_cell(0, i, Cell::Alive);
+        }
+        let mut game = GameOfLife::new();
+        game.regions = vec![Arc::new(horizontal), Arc::new(vertical)];
+        game.merge_overlapping_regions();
+        assert_eq!(3, game.regions.len());
+        assert_eq!(None, game.find_overlapping_pair());
+        assert_eq!(2 * (length as usize - 2) - 1, game.population());
+
+        // They keep apart as they grow back into each other
+        game.step();
+        assert_eq!(None, game.find_overlapping_pair());
+        assert_eq!(Cell::Alive, game.get_cell(1000, 1));
+        assert_eq!(Cell::Alive, game.get_cell(-1, -1000));
+        assert_eq!(Cell::Dead, game.get_cell(0, 0));
+    }
+
+    #[test]
+    fn step_still_life_and_oscillator() {
+        let mut game = GameOfLife::new();
+        // Block
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            game.set_cell(x, y, Cell::Alive);
+        }
+        // Blinker, far enough away to be in its own region
+        for x in 9..12 {
+            game.set_cell(x, 10, Cell::Alive);
+        }
+
+        game.step();
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert_eq!(Cell::Alive, game.get_cell(x, y));
+        }
+        assert_eq!(Cell::Alive, game.get_cell(10, 9));
+        assert_eq!(Cell::Alive, game.get_cell(10, 11));
+        assert_eq!(Cell::Dead, game.get_cell(9, 10));
+        assert_eq!(Cell::Dead, game.get_cell(11, 10));
+
+        game.step();
+        assert_eq!(Cell::Alive, game.get_cell(9, 10));
+        assert_eq!(Cell::Dead, game.get_cell(10, 9));
+    }
+
+    #[test]
+    fn checkpoints() {
+        let mut game = GameOfLife::new();
+        game.set_region(&crate::io::rle::read_rle("b2o$2o$bo!").unwrap());
+        for _ in 0..50 {
+            game.step();
+        }
+        let checkpoint = game.checkpoint();
+        assert!(game.regions.iter().zip(&checkpoint.regions).all(|(a, b)| Arc::ptr_eq(a, b)));
+        let expected = game.live_cells();
+
+        // Branch off the same generation with a different cell added each time, leaving the checkpoint untouched
+        for x in 0..3 {
+            game.restore(&checkpoint);
+            assert_eq!(expected, game.live_cells());
+            game.set_cell(x * 7, -20, Cell::Alive);
+            game.set_rule(Rule::new(&[3, 6], &[2, 3]));
+            game.step();
+        }
+        game.restore(&checkpoint);
+        assert_eq!(expected, game.live_cells());
+        assert_eq!(Rule::LIFE, game.rule());
+        assert_eq!(expected.len(), checkpoint.population());
+    }
+
+    #[test]
+    fn diff() {
+        let mut game = GameOfLife::new();
+        game.set_region(&crate::io::rle::read_rle("3o!").unwrap());
+        game.set_cell(20, 20, Cell::Alive);
+        let before = game.clone();
+        assert_eq!(0, game.diff(&before).unwrap().count());
+
+        // The blinker turns and the lone cell dies
+        game.step();
+        game.set_cell(30, 0, Cell::Alive);
+        let changes: Vec<(Position, Cell)> = before.diff(&game).unwrap().collect();
+        let expected = [((0, 0), Cell::Dead), ((1, -1), Cell::Alive), ((1, 1), Cell::Alive), ((2, 0), Cell::Dead), ((20, 20), Cell::Dead), ((30, 0), Cell::Alive)];
+        assert_eq!(expected.map(|(position, cell)| (Position::from(position), cell)).to_vec(), changes);
+        // Going the other way swaps the states
+        assert!(game.diff(&before).unwrap().any(|change| change == (Position::new(30, 0), Cell::Dead)));
+    }
+
+    #[test]
+    fn diff_b0() {
+        // Under B0 the background turns alive, which changes every cell rather than a list of them
+        let mut game = GameOfLife::with_rule("B03/S23".parse().unwrap());
+        let before = game.clone();
+        game.step();
+        assert_eq!(Some(BackgroundChanged(Cell::Alive)), before.diff(&game).err());
+
+        // Against the same live background, a cell killed is reported dead, not as it is stored
+        let alive = game.clone();
+        game.set_cell(5, 5, Cell::Dead);
+        assert_eq!(vec![(Position::new(5, 5), Cell::Dead)], alive.diff(&game).unwrap().collect::<Vec<_>>());
+        assert_eq!(vec![(Position::new(5, 5), Cell::Alive)], game.diff(&alive).unwrap().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn von_neumann_neighbourhood() {
+        // A lone cell gives birth to every neighbour, which is only the four sharing an edge under von Neumann
+        for (rule, population) in [("B1/S", 8), ("B1/SV", 4)] {
+            let mut game = GameOfLife::with_rule(rule.parse().unwrap());
+            game.set_cell(0, 0, Cell::Alive);
+            assert_eq!(population, game.step().population);
+            assert_eq!(population == 4, game.get_cell(1, 1) == Cell::Dead);
+        }
+    }
+
+    #[test]
+    fn hexagonal_neighbourhood() {
+        // The six neighbours on a hexagonal grid leave out two opposite corners of the square around a cell
+        let mut game = GameOfLife::with_rule("B1/SH".parse().unwrap());
+        game.set_cell(0, 0, Cell::Alive);
+        assert_eq!(6, game.step().population);
+        assert_eq!(Cell::Alive, game.get_cell(1, -1));
+        assert_eq!(Cell::Dead, game.get_cell(1, 1));
+        assert_eq!(Cell::Dead, game.get_cell(-1, -1));
+    }
+
+    #[test]
+    fn custom_neighbourhood() {
+        // Births a knight's move away land past the usual one cell buffer, even when the rule changes after the cell is set
+        let mut game = GameOfLife::new();
+        game.set_cell(0, 0, Cell::Alive);
+        game.set_rule("B1/S/N1,2;2,1;2,-1;1,-2;-1,-2;-2,-1;-2,1;-1,2".parse().unwrap());
+        assert_eq!(8, game.step().population);
+        assert_eq!((Cell::Alive, Cell::Alive), (game.get_cell(2, 1), game.get_cell(-1, -2)));
+        assert_eq!(Some(Rect::new(-2, -2, 5, 5)), game.live_bounds());
+
+        // Weighted neighbours count several times over, so the cell weighing 2 alone gives a birth on 2
+        let mut game = GameOfLife::with_rule("B2/S/N-1,0;1,0*2".parse().unwrap());
+        game.set_cell(0, 0, Cell::Alive);
+        game.step();
+        assert_eq!(vec![Position::new(-1, 0)], game.live_cells());
+    }
+
+    #[test]
+    fn non_totalistic_rule() {
+        // Under B2i only cells between two opposite neighbours are born, not those beside a pair of neighbours
+        for (rule, population) in [("B2/S", 3), ("B2i/S", 1)] {
+            let mut game = GameOfLife::with_rule(rule.parse().unwrap());
+            game.set_cell(0, 0, Cell::Alive);
+            game.set_cell(2, 0, Cell::Alive);
+            assert_eq!(population, game.step().population);
+            assert_eq!(Cell::Alive, game.get_cell(1, 0));
+        }
+
+        // Arrangements are told apart the same way however they are turned, so a rotated pattern stays rotated
+        let rule: Rule = "B2-a3/S12-k".parse().unwrap();
+        let pattern = crate::io::rle::read_rle("b2o$2o$bo!").unwrap();
+        let mut rotated = pattern.clone();
+        rotated.rotate_clockwise().unwrap();
+        let [mut game, mut rotated_game] = [pattern, rotated].map(|region| {
+            let mut game = GameOfLife::with_rule(rule);
+            game.set_region(&region);
+            game
+        });
+        for _ in 0..10 {
+            assert_eq!(game.step(), rotated_game.step());
+        }
+    }
+
+    #[test]
+    fn rule_zones() {
+        // A blinker on the border turns in the Life half, while its end in the Seeds half dies and its corners are born
+        let mut game = GameOfLife::new();
+        game.add_zone(Rect::new(1, -10, 10, 20), "B2/S".parse().unwrap()).unwrap();
+        assert_eq!("B2/S".parse(), Ok(game.rule_at(1, 0)));
+        assert_eq!(Rule::LIFE, game.rule_at(0, 0));
+        for x in -1..=1 {
+            game.set_cell(x, 0, Cell::Alive);
+        }
+        game.step();
+        let mut cells = game.live_cells();
+        cells.sort_unstable();
+        assert_eq!(vec![Position::new(0, -1), Position::new(0, 0), Position::new(0, 1), Position::new(1, -1), Position::new(1, 1)], cells);
+
+        // Zones are saved with the rest of the world, and ones with B0 aren't allowed
+        let snapshot = game.checkpoint();
+        game.clear_zones();
+        assert_eq!(Err(RuleError::BirthOnZero), game.add_zone(Rect::new(0, 0, 1, 1), "B0/S8".parse().unwrap()));
+        game.restore(&snapshot);
+        assert_eq!(1, game.zones().len());
+    }
+
+    #[test]
+    fn birth_on_zero() {
+        // B0 without S8 strobes the background, alive in odd generations and dead in even ones
+        let mut strobe = GameOfLife::with_rule("B03/S23".parse().unwrap());
+        for background in [Cell::Alive, Cell::Dead, Cell::Alive] {
+            strobe.step();
+            assert_eq!((background, background), (strobe.background(), strobe.get_cell(-1000, 1000)));
+        }
+
+        // AntiLife is Life with the states swapped, so a glider of dead cells on its live background flies as in Life
+        let mut anti_life = GameOfLife::with_rule("B0123478/S01234678".parse().unwrap());
+        anti_life.step();
+        let mut life = GameOfLife::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            anti_life.set_cell(x, y, Cell::Dead);
+            life.set_cell(x, y, Cell::Alive);
+        }
+        for _ in 0..8 {
+            assert_eq!(life.step().population, anti_life.step().population);
+        }
+        let [mut glider, mut anti_glider] = [life.live_cells(), anti_life.live_cells()];
+        glider.sort_unstable();
+        anti_glider.sort_unstable();
+        assert_eq!((Cell::Alive, glider), (anti_life.background(), anti_glider));
+        assert_eq!(Cell::Dead, anti_life.get_cell(3, 4));
+    }
+
+    #[test]
+    fn step_stats() {
+        // A blinker turns by losing its two ends and gaining two new ones, while a block never changes
+        let mut game = GameOfLife::new();
+        game.set_region(&crate::io::rle::read_rle("3o!").unwrap());
+        let stats = game.step();
+        assert_eq!(StepStats { births: 2, deaths: 2, population: 3 }, stats);
+        assert_eq!(4, stats.changes());
+
+        let mut block = GameOfLife::new();
+        block.set_region(&crate::io::rle::read_rle("2o$2o!").unwrap());
+        assert_eq!(StepStats { births: 0, deaths: 0, population: 4 }, block.step());
+        assert_eq!(StepStats::default(), GameOfLife::new().step());
+    }
+
+    #[test]
+    fn population_history() {
+        let mut game = GameOfLife::new();
+        game.set_region(&crate::io::rle::read_rle("3o$o!").unwrap());
+        game.step();
+        assert!(game.population_history().is_empty());
+
+        // The tetromino becomes a beehive of 6 cells, and only the last 3 populations are kept
+        game.record_population(3);
+        game.step();
+        game.step();
+        assert_eq!(vec![4, 6, 6], game.population_history().iter().copied().collect::<Vec<_>>());
+        game.step();
+        assert_eq!(vec![6, 6, 6], game.population_history().iter().copied().collect::<Vec<_>>());
+        assert_eq!(game.population(), *game.population_history().back().unwrap());
+
+        game.record_population(10);
+        assert_eq!(1, game.population_history().len());
+        game.record_population(0);
+        game.step();
+        assert!(game.population_history().is_empty());
+    }
+
+    #[test]
+    fn trim_policy() {
+        // Blinker, whose live cells swing out by one cell along each axis in turn
+        let blinker = |trim_policy| {
+            let mut game = GameOfLife::new();
+            game.set_trim_policy(trim_policy);
+            for x in -1..=1 {
+                game.set_cell(x, 0, Cell::Alive);
+            }
+            for _ in 0..4 {
+                game.step();
+            }
+            assert_eq!(1, game.regions().count());
+            let region = game.regions().next().unwrap();
+            (region.width(), region.height(), region.trimmed())
+        };
+        // Trimming every generation leaves the region matching the horizontal phase, having trimmed twice per phase change
+        assert_eq!((5, 3, 8), blinker(TrimPolicy::Aggressive));
+        // A threshold of the swing stops it trimming at all, just like never trimming
+        assert_eq!((5, 5, 0), blinker(TrimPolicy::Threshold(1)));
+        assert_eq!((5, 5, 0), blinker(TrimPolicy::Never));
+
+        // Killing cells only shrinks the region once the threshold is passed
+        let mut game = GameOfLife::new();
+        game.set_trim_policy(TrimPolicy::Threshold(1));
+        for x in 0..5 {
+            game.set_cell(x, 0, Cell::Alive);
+        }
+        game.set_cell(4, 0, Cell::Dead);
+        assert_eq!(7, game.regions().next().unwrap().width());
+        game.set_cell(3, 0, Cell::Dead);
+        assert_eq!(5, game.regions().next().unwrap().width());
+        assert_eq!(2, game.regions().next().unwrap().trimmed());
+
+        assert_eq!(Ok(TrimPolicy::Threshold(4)), "4".parse());
+        assert_eq!(Ok(TrimPolicy::Never), " Never".parse());
+        assert!("sometimes".parse::<TrimPolicy>().is_err());
+        assert_eq!("aggressive", TrimPolicy::default().to_string());
+    }
+
+    #[test]
+    fn step_glider() {
+        let mut game = GameOfLife::new();
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        for (x, y) in glider {
+            game.set_cell(x, y, Cell::Alive);
+        }
+
+        // A glider moves one cell diagonally every four generations, growing its region as it goes
+        for _ in 0..40 {
+            game.step();
+        }
+        assert_eq!(Some(Rect::new(10, 10, 3, 3)), game.live_bounds());
+        for (x, y) in glider {
+            assert_eq!(Cell::Alive, game.get_cell(x + 10, y + 10));
+        }
+
+        // The region is trimmed behind the glider rather than covering its whole path
+        assert_eq!(1, game.regions.len());
+        assert_eq!((3 + REGION_BUFFER * 2, 3 + REGION_BUFFER * 2), (game.regions[0].width, game.regions[0].height));
+
+        // Untrimmed, the region covers the whole path, but the live bounds still only cover the glider
+        game.set_trim_policy(TrimPolicy::Never);
+        for _ in 0..40 {
+            game.step();
+        }
+        assert_eq!(Some(Rect::new(20, 20, 3, 3)), game.live_bounds());
+        assert!(game.regions[0].width > 3 + REGION_BUFFER * 2);
+        assert_eq!(None, GameOfLife::new().live_bounds());
+    }
+
+    #[test]
+    fn split_region() {
+        // Live cells at the corners of a region, with dead space between
+        let mut region = Region::new(0, 0, 10, 10).unwrap();
+        for (x, y) in [(1, 1), (8, 1), (1, 8), (8, 8)] {
+            region.set_cell(x, y, Cell::Alive);
+        }
+        region.trimmed = 5;
+
+        let parts = GameOfLife::split_region(region, REGION_BUFFER);
+        assert_eq!(4, parts.len());
+        for part in &parts {
+            assert!(part.live_bounds().is_some());
+        }
+        // Splitting doesn't copy the trimmed count, or it would double every time the parts merge and split again
+        assert_eq!(5, parts.iter().map(Region::trimmed).sum::<u64>());
+
+        // Setting the last live cell in a region dead removes the region
+        let mut game = GameOfLife::new();
+        game.set_cell(0, 0, Cell::Alive);
+        game.set_cell(0, 0, Cell::Dead);
+        assert!(game.regions.is_empty());
+    }
+
+    #[test]
+    fn step_with_rule() {
+        // Under HighLife, the six neighbours of the dead centre of a hexagon bring it to life
+        let mut game = GameOfLife::with_rule("B36/S23".parse().unwrap());
+        for (x, y) in [(0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)] {
+            game.set_cell(x, y, Cell::Alive);
+        }
+        assert_eq!(6, game.population());
+
+        game.step();
+        assert_eq!(Cell::Alive, game.get_cell(0, 0));
+
+        game.set_rule(Rule::LIFE);
+        assert_eq!(Rule::LIFE, game.rule());
+    }
+
+    #[test]
+    fn set_and_populate_region() {
+        let mut pattern = Region::new(-2, -2, 4, 4).unwrap();
+        pattern.set_cell(-1, -1, Cell::Alive);
+        pattern.set_cell(0, -1, Cell::Alive);
+        pattern.set_cell(1, 1, Cell::Alive);
+
+        let mut game = GameOfLife::new();
+        game.set_region(&pattern);
+
+        let mut copy = Region::new(-3, -3, 6, 6).unwrap();
+        copy.set_cell(-3, -3, Cell::Alive);
+        game.populate_region(&mut copy);
+        for x in -3..3 {
+            for y in -3..3 {
+                assert_eq!(pattern.get_cell(x, y).unwrap_or(Cell::Dead), copy.get_cell(x, y).unwrap());
+            }
+        }
+
+        let cropped = game.to_region().unwrap();
+        assert_eq!((-1, -1, 3, 3), (cropped.x, cropped.y, cropped.width, cropped.height));
+        assert_eq!(Some(Cell::Alive), cropped.get_cell(1, 1));
+        assert_eq!(0, GameOfLife::new().to_region().unwrap().width);
+
+        // Live cells too far apart for a single region
+        game.set_cell(Coord::MAX / 2, Coord::MAX / 2, Cell::Alive);
+        assert!(matches!(game.to_region(), Err(RegionError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn collect_cells() {
+        // A world collected from a glider's cells steps as the glider, and extending it adds more
+        let points = [Position::new(1, 0), Position::new(2, 1), Position::new(0, 2), Position::new(1, 2), Position::new(2, 2)];
+        let mut world: GameOfLife = points.iter().collect();
+        assert_eq!(5, world.population());
+        world.step();
+        world.extend([Position::new(-20, -20)]);
+        let mut cells = world.live_cells();
+        cells.sort_unstable();
+        assert_eq!(vec![Position::new(-20, -20), Position::new(0, 1), Position::new(1, 2), Position::new(1, 3), Position::new(2, 1), Position::new(2, 2)], cells);
     }
 }
 
 
 
+/// Rotation, mirroring, padding and recentring of a region, applied in that order by [`Region::transform`].
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Transform {
+    /// Quarter turns clockwise, as drawn with y increasing downwards. Negative turns go anticlockwise.
+    pub quarter_turns: i32,
+    /// Mirror left to right.
+    pub flip_x: bool,
+    /// Mirror top to bottom.
+    pub flip_y: bool,
+    /// Dead cells to add on every side.
+    pub pad: usize,
+    /// Move the region so its centre is at 0 0.
+    pub recenter: bool
+}
+
 /// Structure to hold the state of a 2D region of a Game of Life world.
 /// The x y position is the -x -y corner of the region,
 /// and the width and height are always positive, growing in the positive x and y direction.
+#[derive(Clone)]
 pub struct Region {
-    x: isize,
-    y: isize,
+    x: Coord,
+    y: Coord,
     width: usize,
     height: usize,
-    state: Vec<Vec<Cell>>
+    state: Vec<Vec<Cell>>,
+    /// Dead columns and rows trimmed off the edges by the world holding the region, including those of regions merged into it.
+    trimmed: u64
 }
 
 impl Region {
     /// Create a new all dead region.
-    pub fn new(x: isize, y: isize, width: usize, height: usize) -> Region {
-        Region {
+    /// Fails if the region would hold more than [`MAX_REGION_CELLS`] cells,
+    /// or reach past the limits of the coordinate space.
+    pub fn new(x: Coord, y: Coord, width: usize, height: usize) -> Result<Region, RegionError> {
+        Self::validate(x, y, width, height)?;
+        Ok(Region {
             x, y, width, height,
-            state: vec![vec![Cell::Dead; height]; width]
+            state: vec![vec![Cell::Dead; height]; width],
+            trimmed: 0
+        })
+    }
+
+    /// Create a new all dead region covering the rectangle, failing as [`Region::new`] does.
+    pub fn from_rect(rect: Rect) -> Result<Region, RegionError> {
+        Region::new(rect.x, rect.y, rect.width, rect.height)
+    }
+
+    /// Check the geometry of a region is one that can be stored and indexed safely.
+    fn validate(x: Coord, y: Coord, width: usize, height: usize) -> Result<(), RegionError> {
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_REGION_CELLS => {}
+            _ => return Err(RegionError::TooLarge { width, height })
+        }
+        // The far edges must be representable, or positions past them couldn't be checked
+        if x.checked_add_unsigned(width as u64).is_none() || y.checked_add_unsigned(height as u64).is_none() {
+            return Err(RegionError::OutOfRange { x, y, width, height })
         }
+        Ok(())
     }
 
     /// Check if a position is in the bounds of this region.
-    fn pos_in_bounds(&self, x: isize, y: isize) -> bool {
+    fn pos_in_bounds(&self, x: Coord, y: Coord) -> bool {
         if x < self.x { return false }
         if y < self.y { return false }
 
-        if let Some(num) = self.x.checked_add_unsigned(self.width) {
+        if let Some(num) = self.x.checked_add_unsigned(self.width as u64) {
             if x >= num { return false }
         }
         else { return false }
 
-        if let Some(num) = self.y.checked_add_unsigned(self.height) {
+        if let Some(num) = self.y.checked_add_unsigned(self.height as u64) {
             if y >= num { return false }
         }
         else { return false }
@@ -202,29 +1329,36 @@ impl Region {
     }
 
     /// Turn world coordinates into local coordinates within this region's internal buffer.
-    fn pos_to_local(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+    fn pos_to_local(&self, x: Coord, y: Coord) -> Option<(usize, usize)> {
         if !self.pos_in_bounds(x, y) { return None }
         Some(((x-self.x) as usize, (y-self.y) as usize))
     }
 
     /// Returns the state of the cell at the given coordinates.
     /// If the position is outside of this region, returns [`None`].
-    pub fn get_cell(&self, x: isize, y: isize) -> Option<Cell> {
+    pub fn get_cell(&self, x: Coord, y: Coord) -> Option<Cell> {
         let (x, y) = self.pos_to_local(x, y)?;
         Some(self.state[x][y])
     }
 
     /// Set the state of a specific cell.
     /// The x y position is in world coordinates, not the local coordinates of the region.
-    /// If the x y position is outside this region, this function will fail silently.
-    pub fn set_cell(&mut self, x: isize, y: isize, state: Cell) {
-        if !self.pos_in_bounds(x, y) { return }
+    /// If the x y position is outside this region, this function will fail silently;
+    /// use [`try_set_cell`](Region::try_set_cell) to find out.
+    pub fn set_cell(&mut self, x: Coord, y: Coord, state: Cell) {
         let Some((x, y)) = self.pos_to_local(x, y)
         else { return };
 
         self.state[x][y] = state;
     }
 
+    /// Set the state of a specific cell, failing with [`RegionError::OutOfBounds`] if the x y position is outside this region.
+    pub fn try_set_cell(&mut self, x: Coord, y: Coord, state: Cell) -> Result<(), GolError> {
+        let (local_x, local_y) = self.pos_to_local(x, y).ok_or(RegionError::OutOfBounds { x, y })?;
+        self.state[local_x][local_y] = state;
+        Ok(())
+    }
+
     /// Fill any overlapping space in the provided region with this regions state.
     /// Other cells are ignored and unaffected.
     pub fn populate_overlap(&self, other: &mut Region) {
@@ -234,43 +1368,88 @@ impl Region {
         // Iterate through coordinates in other and try to get cell from this
         // If problematically slow, overlapping region could be calculated and
         // iterated through instead of full region.
-        for x in other.x..other.x.saturating_add_unsigned(other.width) {
-            for y in other.y..other.y.saturating_add_unsigned(other.width) {
+        for x in other.x..other.x.saturating_add_unsigned(other.width as u64) {
+            for y in other.y..other.y.saturating_add_unsigned(other.height as u64) {
                 let Some(state) = self.get_cell(x, y) else { continue };
                 other.set_cell(x, y, state);
             }
         }
     }
 
+    /// Returns the cells that differ in the other region, with their state there, in reading order.
+    /// Cells outside a region count as dead, so applying the changes to this region with [`Region::apply`]
+    /// leaves it with the same live cells as the other.
+    pub fn diff(&self, other: &Region) -> Vec<(Position, Cell)> {
+        // Only live cells can differ, so compare those rather than every cell between two far apart regions
+        let before: BTreeSet<Position> = self.live_cells().collect();
+        let after: BTreeSet<Position> = other.live_cells().collect();
+        let mut changes: Vec<(Position, Cell)> = before.difference(&after).map(|&cell| (cell, Cell::Dead))
+            .chain(after.difference(&before).map(|&cell| (cell, Cell::Alive)))
+            .collect();
+        changes.sort_unstable_by_key(|&(position, _)| (position.y, position.x));
+        changes
+    }
+
+    /// Set the state of each cell given, growing the region to hold any live cells outside it.
+    /// Dead cells outside the region are already dead, so are skipped.
+    /// Fails without changing the region if it would grow too large.
+    pub fn apply(&mut self, changes: &[(Position, Cell)]) -> Result<(), RegionError> {
+        let outside = changes.iter()
+            .filter(|&&(position, state)| state == Cell::Alive && !self.pos_in_bounds(position.x, position.y))
+            .map(|&(position, _)| position);
+        if let Some(outside) = Rect::around(outside) {
+            let mut grown = Region::from_rect(self.bounds().union(&outside))?;
+            self.populate_overlap(&mut grown);
+            grown.trimmed = self.trimmed;
+            *self = grown;
+        }
+        for &(position, state) in changes {
+            if self.pos_in_bounds(position.x, position.y) { self.set_cell(position.x, position.y, state) }
+        }
+        Ok(())
+    }
+
     /// Check if another region overlaps this one.
+    /// Empty regions hold no cells, so never overlap anything.
     fn is_overlapping(&self, other: &Region) -> bool {
-        // If at least one corner is in bounds, then it is overlapping
-        if self.contains_region_corners(other) { return true }
-
-        // If other completely wraps around this region, above won't work in this direction
-        // so check in other direction too
-        if other.contains_region_corners(self) { return true }
-
-        // No corner was inbounds, so no overlap
-        false
-    }
-
-    /// Checks if any of the corners of the other region are contained within this region.
-    fn contains_region_corners(&self, other: &Region) -> bool {
-        let final_x = other.x.saturating_add_unsigned(other.width) - 1;
-        let final_y = other.y.saturating_add_unsigned(other.height) - 1;
-        let corners = [
-            (other.x, other.y),
-            (other.x, final_y),
-            (final_x, other.y),
-            (final_x, final_y)
-        ];
+        self.bounds().overlaps(&other.bounds())
+    }
 
-        for (x, y) in corners {
-            if self.pos_in_bounds(x, y) { return true }
-        }
+    /// Every cell of the region with its world position, a row at a time from the -y edge, each row from the -x edge.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        (0..self.height).flat_map(move |local_y| {
+            self.state.iter().enumerate()
+                .map(move |(local_x, column)| (Position::new(self.x + local_x as Coord, self.y + local_y as Coord), column[local_y]))
+        })
+    }
+
+    /// Cells of each row of the region, from the -y edge, each row from the -x edge.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Cell> + '_> + '_ {
+        (0..self.height).map(move |local_y| self.state.iter().map(move |column| column[local_y]))
+    }
+
+    /// World positions of the live cells in this region, column by column,
+    /// which is quicker than picking them out of [`Region::iter`].
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.state.iter().enumerate().flat_map(move |(local_x, column)| {
+            column.iter().enumerate()
+                .filter(|(_, cell)| **cell == Cell::Alive)
+                .map(move |(local_y, _)| Position::new(self.x + local_x as Coord, self.y + local_y as Coord))
+        })
+    }
 
-        false
+    /// Returns the world bounding box of the live cells in this region, or [`None`] if every cell is dead.
+    pub fn live_bounds(&self) -> Option<Rect> {
+        // Only the first and last live cells of each column matter, as each is sorted by y
+        let mut bounds: Option<Rect> = None;
+        for (local_x, column) in self.state.iter().enumerate() {
+            let Some(first) = column.iter().position(|&cell| cell == Cell::Alive) else { continue };
+            let last = column.iter().rposition(|&cell| cell == Cell::Alive).expect("Column has a live cell");
+            let x = self.x + local_x as Coord;
+            let column_bounds = Rect::new(x, self.y + first as Coord, 1, last - first + 1);
+            bounds = Some(bounds.map_or(column_bounds, |bounds| bounds.union(&column_bounds)));
+        }
+        bounds
     }
 
     /// Change the size of the region by moving the specified edge.
@@ -279,20 +1458,29 @@ impl Region {
     /// will result in them moving further in the negative direction.
     /// New space is filled with [`Cell::Dead`], while reducing the size truncates the cells.
     /// If adjusting the edges [`Edge::NegX`] or [`Edge::NegY`], the position will be adjusted accordingly.
-    pub fn adjust_size(&mut self, edge: Edge, amount: isize) {
-        // Adjust size and position values
-        match edge {
-            Edge::X => self.width = self.width.saturating_add_signed(amount),
-            Edge::Y => self.height = self.height.saturating_add_signed(amount),
-            Edge::NegX => {
-                self.width = self.width.saturating_add_signed(amount);
-                self.x -= amount;
-            }
-            Edge::NegY => {
-                self.height = self.height.saturating_add_signed(amount);
-                self.y -= amount;
-            }
+    /// Fails without changing the region if it would shrink below zero size or the new geometry isn't valid.
+    pub fn adjust_size(&mut self, edge: Edge, amount: Coord) -> Result<(), RegionError> {
+        let (mut x, mut y, mut width, mut height) = (self.x, self.y, self.width, self.height);
+        let size = match edge {
+            Edge::X | Edge::NegX => &mut width,
+            Edge::Y | Edge::NegY => &mut height
+        };
+        *size = match isize::try_from(amount).ok().and_then(|amount| size.checked_add_signed(amount)) {
+            Some(new_size) => new_size,
+            None if amount < 0 => return Err(RegionError::NegativeSize { size: *size, amount }),
+            None => return Err(RegionError::TooLarge { width: self.width, height: self.height })
+        };
+        let position = match edge {
+            Edge::NegX => Some(&mut x),
+            Edge::NegY => Some(&mut y),
+            _ => None
+        };
+        if let Some(position) = position {
+            *position = position.checked_sub(amount)
+                .ok_or(RegionError::OutOfRange { x: self.x, y: self.y, width, height })?;
         }
+        Self::validate(x, y, width, height)?;
+        (self.x, self.y, self.width, self.height) = (x, y, width, height);
 
         // Adjust state buffer
         match edge {
@@ -312,12 +1500,12 @@ impl Region {
             Edge::NegX => {
                 // Adding extra on the left edge
                 if amount >= 0 {
-                    self.state.resize(self.width, vec![Cell::Dead]);
+                    self.state.resize(self.width, vec![Cell::Dead; self.height]);
                     self.state.as_mut_slice().rotate_right(amount as usize)
                 }
                 // Removing on the left edge
                 else {
-                    self.state.as_mut_slice().rotate_left((amount*-1) as usize);
+                    self.state.as_mut_slice().rotate_left(amount.unsigned_abs() as usize);
                     self.state.resize(self.width, vec![])
                 }
             }
@@ -334,59 +1522,241 @@ impl Region {
                 // Removing on the bottom edge
                 else {
                     for column in &mut self.state {
-                        column.as_mut_slice().rotate_left((amount*-1) as usize);
-                        column.resize(self.width, Cell::Dead)
+                        column.as_mut_slice().rotate_left(amount.unsigned_abs() as usize);
+                        column.resize(self.height, Cell::Dead)
                     }
                 }
             }
         }
+        Ok(())
     }
 
     /// Move the region by the given amount in the x and y directions.
     /// New cells will be filled with [`Cell::Dead`], and old cells will be truncated.
-    pub fn move_region(&mut self, x: isize, y: isize) {
+    /// Fails without changing the region if it would move past the limits of the coordinate space.
+    pub fn move_region(&mut self, x: Coord, y: Coord) -> Result<(), RegionError> {
+        let out_of_range = RegionError::OutOfRange { x: self.x, y: self.y, width: self.width, height: self.height };
+        let (Some(new_x), Some(new_y)) = (self.x.checked_add(x), self.y.checked_add(y))
+        else { return Err(out_of_range) };
+        Self::validate(new_x, new_y, self.width, self.height)?;
+        self.x = new_x;
+        self.y = new_y;
+
+        // Moving further than the size of the region leaves none of the old cells
+        let shift_x = x.unsigned_abs().min(self.width as u64) as usize;
+        let shift_y = y.unsigned_abs().min(self.height as u64) as usize;
+
         // X movement
-        self.x += x;
         if x < 0 {
-            let x = (x*-1) as usize;
-            self.state.as_mut_slice().rotate_right(x);
-            for column in &mut self.state[0..x] {
+            self.state.as_mut_slice().rotate_right(shift_x);
+            for column in &mut self.state[0..shift_x] {
                 *column = vec![Cell::Dead; self.height];
             }
         }
         else {
-            let x = x as usize;
-            self.state.as_mut_slice().rotate_left(x);
-            for column in &mut self.state[self.width - x..] {
+            self.state.as_mut_slice().rotate_left(shift_x);
+            for column in &mut self.state[self.width - shift_x..] {
                 *column = vec![Cell::Dead; self.height];
             }
         }
 
         // Y Movement
-        self.y += y;
         if y < 0 {
-            // Shadow to avoid duplicate code
-            let y = (y*-1) as usize;
             for column in &mut self.state {
-                column.as_mut_slice().rotate_right(y);
-                column.splice(0..y, vec![Cell::Dead; y]);
+                column.as_mut_slice().rotate_right(shift_y);
+                column.splice(0..shift_y, vec![Cell::Dead; shift_y]);
             }
         }
         else {
-            let y = y as usize;
             for column in &mut self.state {
-                column.as_mut_slice().rotate_left(y);
-                column.splice((self.height-y).., vec![Cell::Dead; y]);
+                column.as_mut_slice().rotate_left(shift_y);
+                column.splice((self.height - shift_y).., vec![Cell::Dead; shift_y]);
             }
         }
+        Ok(())
+    }
+
+    /// Set the position of the -x -y corner of the region, carrying its cells along with it.
+    /// Unlike [`Region::move_region`], no cells are truncated.
+    /// Fails without changing the region if it would reach past the limits of the coordinate space.
+    pub fn set_position(&mut self, x: Coord, y: Coord) -> Result<(), RegionError> {
+        Self::validate(x, y, self.width, self.height)?;
+        self.x = x;
+        self.y = y;
+        Ok(())
+    }
+
+    /// Rotate the cells a quarter turn clockwise, as drawn with y increasing downwards.
+    /// The -x -y corner stays where it is, while the width and height swap.
+    /// Fails without changing the region if the rotated region would reach past the limits of the coordinate space.
+    pub fn rotate_clockwise(&mut self) -> Result<(), RegionError> {
+        Self::validate(self.x, self.y, self.height, self.width)?;
+        let mut state = vec![vec![Cell::Dead; self.width]; self.height];
+        for (x, column) in self.state.iter().enumerate() {
+            for (y, &cell) in column.iter().enumerate() {
+                state[self.height - 1 - y][x] = cell;
+            }
+        }
+        (self.width, self.height) = (self.height, self.width);
+        self.state = state;
+        Ok(())
+    }
+
+    /// Mirror the cells left to right, within the same bounds.
+    pub fn flip_x(&mut self) {
+        self.state.reverse();
+    }
+
+    /// Mirror the cells top to bottom, within the same bounds.
+    pub fn flip_y(&mut self) {
+        for column in &mut self.state {
+            column.reverse();
+        }
     }
 
+    /// Add the given number of dead cells on every side of the region.
+    /// Fails without changing the region if the new geometry isn't valid.
+    pub fn pad(&mut self, amount: usize) -> Result<(), RegionError> {
+        let amount = Coord::try_from(amount).map_err(|_| RegionError::TooLarge { width: self.width, height: self.height })?;
+        // Pad a copy, so a failure part way round leaves the region untouched
+        let mut padded = self.clone();
+        for edge in [Edge::X, Edge::Y, Edge::NegX, Edge::NegY] {
+            padded.adjust_size(edge, amount)?;
+        }
+        *self = padded;
+        Ok(())
+    }
+
+    /// Apply each part of the transform in turn.
+    /// Fails part way through if the region would reach past the limits of the coordinate space.
+    pub fn transform(&mut self, transform: &Transform) -> Result<(), RegionError> {
+        for _ in 0..transform.quarter_turns.rem_euclid(4) {
+            self.rotate_clockwise()?;
+        }
+        if transform.flip_x { self.flip_x() }
+        if transform.flip_y { self.flip_y() }
+        self.pad(transform.pad)?;
+        if transform.recenter {
+            self.set_position(-(self.width as Coord / 2), -(self.height as Coord / 2))?;
+        }
+        Ok(())
+    }
+
+    /// Trim dead columns and rows off the edges, down to the bounding box of the live cells with up to
+    /// `margin` dead cells left around it, freeing the memory they took. Edges already within the margin are
+    /// left as they are. A region with no live cells shrinks to hold no cells, keeping its position.
+    /// An empty region is still usable: it overlaps nothing, so adds nothing when merged or set in a world,
+    /// and [`Region::apply`] grows it around just the live cells applied.
+    pub fn shrink_to_fit(&mut self, margin: usize) {
+        let Some(bounds) = self.live_bounds() else {
+            (self.width, self.height, self.state) = (0, 0, vec![]);
+            return
+        };
+        let (min, max) = (bounds.min(), bounds.max());
+        let gaps = [
+            (Edge::NegX, min.x.abs_diff(self.x) as usize),
+            (Edge::NegY, min.y.abs_diff(self.y) as usize),
+            (Edge::X, self.width - 1 - max.x.abs_diff(self.x) as usize),
+            (Edge::Y, self.height - 1 - max.y.abs_diff(self.y) as usize)
+        ];
+        for (edge, gap) in gaps {
+            if gap <= margin { continue }
+            // Shrinking by no more than the dead cells past the live ones always fits
+            self.adjust_size(edge, -((gap - margin) as Coord)).expect("Region shrank past its live cells");
+        }
+        self.state.shrink_to_fit();
+        self.state.iter_mut().for_each(Vec::shrink_to_fit);
+    }
+
+    /// Display the region as a grid of the given characters for live and dead cells, a row to a line.
+    /// Displaying the region itself uses `O` and `.`, as in plaintext patterns.
+    pub fn display_with(&self, alive: char, dead: char) -> RegionDisplay<'_> {
+        RegionDisplay { region: self, alive, dead }
+    }
+
+    /// Row of cells as characters.
+    fn row_string(row: impl Iterator<Item = Cell>, alive: char, dead: char) -> String {
+        row.map(|cell| if cell == Cell::Alive { alive } else { dead }).collect()
+    }
+
+    /// Check if the region holds no cells at all.
+    pub fn is_empty(&self) -> bool { self.width == 0 || self.height == 0 }
+
     // GETTERS
     // Can't just make members public as there are invariants with the vec to maintain.
-    pub fn x(&self) -> isize { self.x }
-    pub fn y(&self) -> isize { self.y }
+    pub fn x(&self) -> Coord { self.x }
+    pub fn y(&self) -> Coord { self.y }
     pub fn width(&self) -> usize { self.width }
     pub fn height(&self) -> usize { self.height }
+    /// Rectangle of world cells covered by the region, dead or alive.
+    pub fn bounds(&self) -> Rect { Rect::new(self.x, self.y, self.width, self.height) }
+    /// Number of dead columns and rows the world has trimmed off this region's edges so far,
+    /// counting those trimmed off any regions merged into it.
+    pub fn trimmed(&self) -> u64 { self.trimmed }
+}
+
+/// The cell at the position, panicking if it is outside the region.
+impl Index<Position> for Region {
+    type Output = Cell;
+
+    fn index(&self, position: Position) -> &Cell {
+        let Some((x, y)) = self.pos_to_local(position.x, position.y) else { panic!("{}", RegionError::OutOfBounds { x: position.x, y: position.y }) };
+        &self.state[x][y]
+    }
+}
+
+impl IndexMut<Position> for Region {
+    fn index_mut(&mut self, position: Position) -> &mut Cell {
+        let Some((x, y)) = self.pos_to_local(position.x, position.y) else { panic!("{}", RegionError::OutOfBounds { x: position.x, y: position.y }) };
+        &mut self.state[x][y]
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Region {
+    type Err = GolError;
+
+    /// Parse a grid of `.` for dead cells and `#` or `O` for live cells, a row to a line, into a region with its
+    /// -x -y corner at 0 0. Blank lines around the grid and indentation are ignored, so grids can be written
+    /// lined up in source, as in tests and examples. Otherwise the grid is read as a plaintext pattern.
+    fn from_str(s: &str) -> Result<Region, GolError> {
+        let grid: Vec<&str> = s.trim().lines().map(str::trim_start).collect();
+        read_plaintext(&grid.join("\n"))
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.display_with('O', '.').fmt(f)
+    }
+}
+
+/// Shows where the region is with its cells as rows of `O` and `.`, a row to a line when pretty printed.
+impl Debug for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let rows: Vec<String> = self.rows().map(|row| Region::row_string(row, 'O', '.')).collect();
+        f.debug_struct("Region")
+            .field("bounds", &self.bounds())
+            .field("trimmed", &self.trimmed)
+            .field("rows", &rows)
+            .finish()
+    }
+}
+
+/// A region displayed with chosen characters for its cells, from [`Region::display_with`].
+pub struct RegionDisplay<'a> {
+    region: &'a Region,
+    alive: char,
+    dead: char
+}
+
+impl Display for RegionDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for row in self.region.rows() {
+            writeln!(f, "{}", Region::row_string(row, self.alive, self.dead))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -396,7 +1766,7 @@ mod region_tests {
     #[test]
     fn pos_in_bounds() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let region = Region::new(-5, -5, 11, 11);
+        let region = Region::new(-5, -5, 11, 11).unwrap();
 
         // In bounds
         assert!(region.pos_in_bounds(0, 0));
@@ -418,7 +1788,7 @@ mod region_tests {
     #[test]
     fn pos_to_local() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let region = Region::new(-5, -5, 11, 11);
+        let region = Region::new(-5, -5, 11, 11).unwrap();
 
         // Outside region
         assert_eq!(None, region.pos_to_local(6, 5));
@@ -434,7 +1804,7 @@ mod region_tests {
     #[test]
     fn get_cell() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let region = Region::new(-5, -5, 11, 11);
+        let region = Region::new(-5, -5, 11, 11).unwrap();
 
         // Inbounds
         assert_eq!(Some(Cell::Dead), region.get_cell(-5, -5));
@@ -452,7 +1822,7 @@ mod region_tests {
     #[test]
     fn set_cell() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let mut region = Region::new(-5, -5, 11, 11);
+        let mut region = Region::new(-5, -5, 11, 11).unwrap();
 
         // Outside region
         region.set_cell(-6, 3, Cell::Alive);
@@ -473,17 +1843,45 @@ mod region_tests {
         assert_eq!(Cell::Alive, region.state[7][1]);
     }
 
+    #[test]
+    fn try_set_cell() {
+        let mut region = Region::new(-5, -5, 11, 11).unwrap();
+        assert!(matches!(region.try_set_cell(-6, 3, Cell::Alive), Err(GolError::Region(RegionError::OutOfBounds { x: -6, y: 3 }))));
+        assert!(matches!(region.try_set_cell(2, 6, Cell::Alive), Err(GolError::Region(RegionError::OutOfBounds { x: 2, y: 6 }))));
+        assert!(region.iter().all(|(_, cell)| cell == Cell::Dead));
+
+        assert!(region.try_set_cell(5, -5, Cell::Alive).is_ok());
+        assert_eq!(Cell::Alive, region.state[10][0]);
+    }
+
+    #[test]
+    fn iterators() {
+        // Cells come row by row in reading order, while live cells come column by column
+        let mut region = Region::new(-1, 2, 2, 2).unwrap();
+        region.set_cell(0, 2, Cell::Alive);
+        region.set_cell(-1, 3, Cell::Alive);
+        let cells: Vec<(Position, Cell)> = region.iter().collect();
+        assert_eq!(vec![
+            (Position::new(-1, 2), Cell::Dead), (Position::new(0, 2), Cell::Alive),
+            (Position::new(-1, 3), Cell::Alive), (Position::new(0, 3), Cell::Dead)
+        ], cells);
+        let rows: Vec<Vec<Cell>> = region.rows().map(Iterator::collect).collect();
+        assert_eq!(vec![vec![Cell::Dead, Cell::Alive], vec![Cell::Alive, Cell::Dead]], rows);
+        assert_eq!(vec![Position::new(-1, 3), Position::new(0, 2)], region.live_cells().collect::<Vec<_>>());
+        assert_eq!(0, Region::new(0, 0, 0, 3).unwrap().iter().count());
+    }
+
     #[test]
     fn populate_overlap() {
         // Base region of all alive cells, -5 -5 to 5 5 inclusive
-        let mut base = Region::new(-5, -5, 11, 11);
+        let mut base = Region::new(-5, -5, 11, 11).unwrap();
         for x in -5..=5 {
             for y in -5..=5 {
                 base.set_cell(x, y, Cell::Alive)
             }
         }
 
-        let mut no_overlap = Region::new(10, 6, 3, 6);
+        let mut no_overlap = Region::new(10, 6, 3, 6).unwrap();
         base.populate_overlap(&mut no_overlap);
         for x in 10..13 {
             for y in 6..12 {
@@ -491,7 +1889,7 @@ mod region_tests {
             }
         }
 
-        let mut partial_overlap = Region::new(-8, 0, 7, 9);
+        let mut partial_overlap = Region::new(-8, 0, 7, 9).unwrap();
         base.populate_overlap(&mut partial_overlap);
         for x in -8..-5 {
             for y in 0..9 {
@@ -507,7 +1905,7 @@ mod region_tests {
             }
         }
 
-        let mut complete_overlap = Region::new(-3, -3, 5, 5);
+        let mut complete_overlap = Region::new(-3, -3, 5, 5).unwrap();
         base.populate_overlap(&mut complete_overlap);
         for x in -3..2 {
             for y in -3..2 {
@@ -519,25 +1917,34 @@ mod region_tests {
     #[test]
     fn is_overlapping() {
         // Base region from -5 -5 to 5 5 inclusive
-        let base = Region::new(-5, -5, 11, 11);
+        let base = Region::new(-5, -5, 11, 11).unwrap();
 
-        let no_overlap = Region::new(10, 6, 3, 6);
+        let no_overlap = Region::new(10, 6, 3, 6).unwrap();
         assert!(!base.is_overlapping(&no_overlap));
         assert!(!no_overlap.is_overlapping(&base));
 
-        let partial_overlap = Region::new(-8, 0, 7, 9);
+        let partial_overlap = Region::new(-8, 0, 7, 9).unwrap();
         assert!(base.is_overlapping(&partial_overlap));
         assert!(partial_overlap.is_overlapping(&base));
 
-        let complete_overlap = Region::new(-3, -3, 5, 5);
+        let complete_overlap = Region::new(-3, -3, 5, 5).unwrap();
         assert!(base.is_overlapping(&complete_overlap));
         assert!(complete_overlap.is_overlapping(&base));
+
+        // Crossing regions where neither contains a corner of the other
+        let crossing = Region::new(-8, -1, 20, 3).unwrap();
+        assert!(base.is_overlapping(&crossing));
+        assert!(crossing.is_overlapping(&base));
+
+        // Touching edges are not overlapping
+        let touching = Region::new(6, -5, 3, 11).unwrap();
+        assert!(!base.is_overlapping(&touching));
     }
 
     #[test]
     fn adjust_size() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let mut region = Region::new(-5, -5, 11, 11);
+        let mut region = Region::new(-5, -5, 11, 11).unwrap();
         // Make all cells alive so we can see the new cells being dead
         for x in -5..=5 {
             for y in -5..=5 {
@@ -546,7 +1953,7 @@ mod region_tests {
         }
 
         // +X edge
-        region.adjust_size(Edge::X, 3);
+        region.adjust_size(Edge::X, 3).unwrap();
         assert_eq!(14, region.width);
         assert_eq!(14, region.state.len());
         for column in &mut region.state[11..] {
@@ -557,7 +1964,7 @@ mod region_tests {
         }
 
         // +Y edge
-        region.adjust_size(Edge::Y, -2);
+        region.adjust_size(Edge::Y, -2).unwrap();
         assert_eq!(9, region.height);
         for column in &region.state {
             assert_eq!(9, column.len());
@@ -567,7 +1974,7 @@ mod region_tests {
         }
 
         // -X edge
-        region.adjust_size(Edge::NegX, -2);
+        region.adjust_size(Edge::NegX, -2).unwrap();
         assert_eq!(12, region.width);
         assert_eq!(12, region.state.len());
         for column in &region.state {
@@ -577,7 +1984,7 @@ mod region_tests {
         }
 
         // -Y edge
-        region.adjust_size(Edge::NegY, 5);
+        region.adjust_size(Edge::NegY, 5).unwrap();
         assert_eq!(14, region.height);
         for column in &region.state {
             assert_eq!(14, column.len());
@@ -587,10 +1994,39 @@ mod region_tests {
         }
     }
 
+    #[test]
+    fn invalid_geometry() {
+        assert_eq!(Err(RegionError::TooLarge { width: usize::MAX, height: 2 }), Region::new(0, 0, usize::MAX, 2).map(|_| ()));
+        assert!(matches!(Region::new(0, 0, MAX_REGION_CELLS, 2), Err(RegionError::TooLarge { .. })));
+        assert!(matches!(Region::new(Coord::MAX - 1, 0, 2, 2), Err(RegionError::OutOfRange { .. })));
+        assert!(Region::new(Coord::MIN, Coord::MIN, 0, 0).unwrap().is_empty());
+
+        // Failed adjustments leave the region untouched
+        let mut region = Region::new(-2, -2, 4, 4).unwrap();
+        assert_eq!(Err(RegionError::NegativeSize { size: 4, amount: -5 }), region.adjust_size(Edge::NegX, -5));
+        assert!(region.adjust_size(Edge::X, Coord::MAX).is_err());
+        assert!(region.move_region(Coord::MAX, 0).is_err());
+        assert!(region.set_position(0, Coord::MAX - 3).is_err());
+        assert_eq!((-2, -2, 4, 4), (region.x, region.y, region.width, region.height));
+        assert_eq!(4, region.state.len());
+
+        // Shrinking to nothing is fine, but empty regions never overlap
+        region.adjust_size(Edge::Y, -4).unwrap();
+        assert!(region.is_empty());
+        assert!(!region.is_overlapping(&Region::new(-2, -2, 4, 4).unwrap()));
+
+        // Moving further than the size of the region clears it
+        let mut region = Region::new(0, 0, 3, 3).unwrap();
+        region.set_cell(1, 1, Cell::Alive);
+        region.move_region(-10, 20).unwrap();
+        assert_eq!((-10, 20), (region.x, region.y));
+        assert!(region.live_bounds().is_none());
+    }
+
     #[test]
     fn move_region() {
         // Region going from (-5, -5) up to (5, 5) inclusive
-        let mut region = Region::new(-5, -5, 11, 11);
+        let mut region = Region::new(-5, -5, 11, 11).unwrap();
         // Make all cells alive so we can see the new cells being dead
         for x in -5..=5 {
             for y in -5..=5 {
@@ -598,7 +2034,7 @@ mod region_tests {
             }
         }
 
-        region.move_region(2, 1);
+        region.move_region(2, 1).unwrap();
         assert_eq!(-3, region.x);
         assert_eq!(-4, region.y);
         for column in &mut region.state[region.width-2..] {
@@ -615,7 +2051,7 @@ mod region_tests {
             *column.last_mut().unwrap() = Cell::Alive;
         }
 
-        region.move_region(-4, -3);
+        region.move_region(-4, -3).unwrap();
         assert_eq!(-7, region.x);
         assert_eq!(-7, region.y);
         for column in &mut region.state[..4] {
@@ -633,14 +2069,200 @@ mod region_tests {
             }
         }
     }
+
+    #[test]
+    fn transforms() {
+        // L shape: a column of three with a foot to the right
+        let mut region = Region::new(1, 2, 2, 3).unwrap();
+        for (x, y) in [(1, 2), (1, 3), (1, 4), (2, 4)] {
+            region.set_cell(x, y, Cell::Alive);
+        }
+        let live = |region: &Region| -> Vec<(Coord, Coord)> {
+            region.iter().filter(|&(_, cell)| cell == Cell::Alive).map(|(position, _)| (position.x, position.y)).collect()
+        };
+
+        let mut rotated = region.clone();
+        rotated.rotate_clockwise().unwrap();
+        assert_eq!((1, 2, 3, 2), (rotated.x, rotated.y, rotated.width, rotated.height));
+        assert_eq!(vec![(1, 2), (2, 2), (3, 2), (1, 3)], live(&rotated));
+        for _ in 0..3 {
+            rotated.rotate_clockwise().unwrap();
+        }
+        assert_eq!(live(&region), live(&rotated));
+
+        let mut flipped = region.clone();
+        flipped.flip_x();
+        assert_eq!(vec![(2, 2), (2, 3), (1, 4), (2, 4)], live(&flipped));
+        flipped.flip_y();
+        assert_eq!(vec![(1, 2), (2, 2), (2, 3), (2, 4)], live(&flipped));
+
+        region.pad(2).unwrap();
+        assert_eq!((-1, 0, 6, 7), (region.x, region.y, region.width, region.height));
+        assert_eq!(vec![(1, 2), (1, 3), (1, 4), (2, 4)], live(&region));
+
+        let mut recentred = region.clone();
+        recentred.transform(&Transform { quarter_turns: -1, flip_y: true, recenter: true, ..Transform::default() }).unwrap();
+        assert_eq!((-3, -3, 7, 6), (recentred.x, recentred.y, recentred.width, recentred.height));
+        assert_eq!(4, live(&recentred).len());
+
+        let mut edge = Region::new(Coord::MAX - 3, 0, 3, 1).unwrap();
+        assert!(edge.pad(1).is_err());
+        assert_eq!((Coord::MAX - 3, 3), (edge.x, edge.width));
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut region = Region::new(-10, -10, 30, 20).unwrap();
+        region.set_cell(0, 0, Cell::Alive);
+        region.set_cell(2, 1, Cell::Alive);
+        let mut cropped = region.clone();
+        cropped.shrink_to_fit(0);
+        assert_eq!(Rect::new(0, 0, 3, 2), cropped.bounds());
+        assert_eq!("O..\n..O\n", cropped.to_string());
+
+        // The margin only shrinks edges further out than it, so the -y edge stays 4 cells out
+        region.adjust_size(Edge::NegY, -6).unwrap();
+        region.shrink_to_fit(5);
+        assert_eq!(Rect::new(-5, -4, 13, 11), region.bounds());
+        assert_eq!(Some(Rect::new(0, 0, 3, 2)), region.live_bounds());
+
+        region.set_cell(0, 0, Cell::Dead);
+        region.set_cell(2, 1, Cell::Dead);
+        region.shrink_to_fit(5);
+        assert_eq!(Rect::new(-5, -4, 0, 0), region.bounds());
+    }
+
+    #[test]
+    fn shrink_to_empty() {
+        // An emptied region takes up no room when merged, set in a world or stepped there
+        let mut empty = Region::new(-5, -4, 3, 3).unwrap();
+        empty.shrink_to_fit(0);
+        let glider: Region = ".#.\n..#\n###".parse().unwrap();
+        assert_eq!(glider.bounds(), GameOfLife::merge_regions(&glider, &empty).unwrap().bounds());
+        let mut game = GameOfLife::new();
+        game.set_region(&empty);
+        game.set_region(&glider);
+        game.step();
+        assert_eq!(1, game.regions().count());
+        assert_eq!(5, game.population());
+
+        // Applying changes grows it around only the live cells
+        empty.apply(&[(Position::new(3, 7), Cell::Alive), (Position::new(-5, -4), Cell::Dead)]).unwrap();
+        assert_eq!(Rect::new(3, 7, 1, 1), empty.bounds());
+        assert_eq!(Some(Cell::Alive), empty.get_cell(3, 7));
+    }
+
+    #[test]
+    fn diff_and_apply() {
+        // A blinker turning over changes four cells, and the diff turns one phase into the other
+        let mut horizontal: Region = "...\n###\n...".parse().unwrap();
+        let vertical: Region = ".#.\n.#.\n.#.".parse().unwrap();
+        let changes = horizontal.diff(&vertical);
+        assert_eq!(vec![
+            (Position::new(1, 0), Cell::Alive), (Position::new(0, 1), Cell::Dead),
+            (Position::new(2, 1), Cell::Dead), (Position::new(1, 2), Cell::Alive)
+        ], changes);
+        horizontal.apply(&changes).unwrap();
+        assert!(horizontal.diff(&vertical).is_empty());
+
+        // Live cells outside the region grow it, while dead ones are already dead there
+        let mut region = Region::new(0, 0, 1, 1).unwrap();
+        region.apply(&[(Position::new(3, -1), Cell::Alive), (Position::new(-9, 0), Cell::Dead)]).unwrap();
+        assert_eq!((Rect::new(0, -1, 4, 2), Some(Cell::Alive)), (region.bounds(), region.get_cell(3, -1)));
+        assert_eq!(vec![(Position::new(3, -1), Cell::Dead)], region.diff(&Region::new(0, 0, 1, 1).unwrap()));
+
+        // Regions at opposite ends of the world only compare their live cells, not everything between them
+        let mut near = Region::new(Coord::MIN, Coord::MIN, 2, 2).unwrap();
+        let mut far = Region::new(Coord::MAX - 2, Coord::MAX - 2, 2, 2).unwrap();
+        near.set_cell(Coord::MIN, Coord::MIN, Cell::Alive);
+        far.set_cell(Coord::MAX - 1, Coord::MAX - 1, Cell::Alive);
+        assert_eq!(vec![
+            (Position::new(Coord::MIN, Coord::MIN), Cell::Dead), (Position::new(Coord::MAX - 1, Coord::MAX - 1), Cell::Alive)
+        ], near.diff(&far));
+    }
+
+    #[test]
+    fn display() {
+        // Rows print top to bottom with the chosen glyphs, and debug output keeps where the region is
+        let mut region = Region::new(-1, 2, 3, 2).unwrap();
+        region.set_cell(-1, 2, Cell::Alive);
+        region.set_cell(1, 3, Cell::Alive);
+        assert_eq!("O..\n..O\n", region.to_string());
+        assert_eq!("#  \n  #\n", region.display_with('#', ' ').to_string());
+        assert_eq!(
+            "Region { bounds: Rect { x: -1, y: 2, width: 3, height: 2 }, trimmed: 0, rows: [\"O..\", \"..O\"] }",
+            format!("{:?}", region)
+        );
+        assert_eq!("", Region::new(0, 0, 0, 0).unwrap().to_string());
+    }
+
+    #[test]
+    fn from_str() {
+        // Indented grids read the same as plaintext, and print back the same
+        let glider: Region = "
+            .#.
+            ..#
+            ###
+        ".parse().unwrap();
+        assert_eq!((0, 0, 3, 3), (glider.x, glider.y, glider.width, glider.height));
+        assert_eq!(".O.\n..O\nOOO\n", glider.to_string());
+        assert_eq!(Cell::Alive, "O.\n.#".parse::<Region>().unwrap().state[1][1]);
+        assert!(matches!(".x.".parse::<Region>(), Err(GolError::Pattern(_))));
+    }
+
+    #[test]
+    fn index() {
+        let mut region: Region = ".#\n..".parse().unwrap();
+        region[Position::new(0, 1)] = Cell::Alive;
+        assert_eq!((Cell::Alive, Cell::Dead), (region[Position::new(0, 1)], region[Position::new(0, 0)]));
+        assert_eq!(Some(Cell::Alive), region.get_cell(0, 1));
+        assert!(std::panic::catch_unwind(|| region[Position::new(2, 0)]).is_err());
+
+        // Worlds index cells relative to the background, and cells far from any region are dead
+        let mut world = GameOfLife::new();
+        world.set_region(&region);
+        assert_eq!((Cell::Alive, Cell::Dead), (world[Position::new(1, 0)], world[Position::new(-70, 900)]));
+    }
 }
 
 
 
 /// Different edges of a region.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Edge {
     X,
     Y,
     NegX,
     NegY
-}
\ No newline at end of file
+}
+
+
+/// Errors from creating or reshaping a region with geometry it can't safely hold.
+#[derive(Debug, PartialEq)]
+pub enum RegionError {
+    /// The region would hold more than [`MAX_REGION_CELLS`] cells.
+    TooLarge { width: usize, height: usize },
+    /// The far edges of the region would be past the limits of the coordinate space.
+    OutOfRange { x: Coord, y: Coord, width: usize, height: usize },
+    /// An edge was moved inwards by more than the size of the region.
+    NegativeSize { size: usize, amount: Coord },
+    /// A cell was set at a position outside the region.
+    OutOfBounds { x: Coord, y: Coord }
+}
+
+impl Display for RegionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RegionError::TooLarge { width, height } =>
+                write!(f, "region of {}x{} cells is larger than the limit of {} cells", width, height, MAX_REGION_CELLS),
+            RegionError::OutOfRange { x, y, width, height } =>
+                write!(f, "region of {}x{} cells at {} {} reaches past the coordinate limits", width, height, x, y),
+            RegionError::NegativeSize { size, amount } =>
+                write!(f, "can't shrink a region of size {} by {}", size, -amount),
+            RegionError::OutOfBounds { x, y } =>
+                write!(f, "cell at {} {} is outside the region", x, y)
+        }
+    }
+}
+
+impl core::error::Error for RegionError {}
@@ -0,0 +1,177 @@
+//! Three dimensional Life, where cells fill a cubic lattice and each counts the live cells among the 26 around it.
+//! Cells are kept in fixed cube chunks, as in [`wireworld`](crate::wireworld) but a dimension up, with only chunks
+//! holding live cells stored, and rounded towards negative infinity with the coordinate math from [`chunk`](crate::chunk).
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::chunk::{floor_div, positive_mod};
+use crate::geometry::Coord;
+use crate::gol::{Cell, StepStats};
+use crate::rule::Rule3d;
+
+/// Width, height and depth of each chunk of cells.
+const CHUNK_SIZE: usize = 8;
+
+/// Position of a cell in a three dimensional world.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Position3d {
+    pub x: Coord,
+    pub y: Coord,
+    pub z: Coord
+}
+
+impl Position3d {
+    pub fn new(x: Coord, y: Coord, z: Coord) -> Position3d {
+        Position3d { x, y, z }
+    }
+
+    /// Coordinates of the chunk holding the position, and the position's index within it.
+    fn chunk(self) -> ((Coord, Coord, Coord), usize) {
+        let size = CHUNK_SIZE as Coord;
+        let chunk = (floor_div(self.x, size), floor_div(self.y, size), floor_div(self.z, size));
+        let (x, y, z) = (positive_mod(self.x, size), positive_mod(self.y, size), positive_mod(self.z, size));
+        (chunk, (z * CHUNK_SIZE + y) * CHUNK_SIZE + x)
+    }
+
+    /// Every position in the cube around this one, leaving out this one.
+    fn neighbours(self) -> impl Iterator<Item = Position3d> {
+        (-1..=1).flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| (x, y, z))))
+            .filter(|&offset| offset != (0, 0, 0))
+            .map(move |(x, y, z)| Position3d::new(self.x + x, self.y + y, self.z + z))
+    }
+}
+
+impl Display for Position3d {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.x, self.y, self.z)
+    }
+}
+
+/// Infinite three dimensional world of cells following a 3D rule.
+#[derive(Debug, Clone)]
+pub struct World3d {
+    /// Cells of every chunk with a live cell in it, in layers of rows, keyed by the chunk's coordinates.
+    chunks: HashMap<(Coord, Coord, Coord), Vec<Cell>>,
+    rule: Rule3d,
+    generation: u64
+}
+
+impl World3d {
+    /// Create a new empty world using the given rule.
+    pub fn new(rule: Rule3d) -> World3d {
+        World3d { chunks: HashMap::new(), rule, generation: 0 }
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    pub fn step(&mut self) -> StepStats {
+        let mut neighbours: HashMap<Position3d, u8> = HashMap::new();
+        for cell in self.live_cells() {
+            for neighbour in cell.neighbours() {
+                *neighbours.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        // Only live cells and cells with live neighbours can be alive next
+        let mut stats = StepStats::default();
+        let mut next = World3d::new(self.rule);
+        let unstored = neighbours.keys().copied().filter(|&cell| self.get_cell(cell) == Cell::Dead);
+        for cell in self.live_cells().chain(unstored) {
+            let current = self.get_cell(cell);
+            let state = self.rule.next_state(current, neighbours.get(&cell).copied().unwrap_or(0));
+            match (current, state) {
+                (Cell::Dead, Cell::Alive) => stats.births += 1,
+                (Cell::Alive, Cell::Dead) => stats.deaths += 1,
+                _ => {}
+            }
+            if state == Cell::Alive {
+                stats.population += 1;
+                next.set_cell(cell, Cell::Alive);
+            }
+        }
+        self.chunks = next.chunks;
+        self.generation += 1;
+        stats
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get_cell(&self, position: Position3d) -> Cell {
+        let (chunk, index) = position.chunk();
+        self.chunks.get(&chunk).map_or(Cell::Dead, |cells| cells[index])
+    }
+
+    /// Set the state of the cell at the position, dropping its chunk once nothing is left alive in it.
+    pub fn set_cell(&mut self, position: Position3d, state: Cell) {
+        let (chunk, index) = position.chunk();
+        if state == Cell::Dead && !self.chunks.contains_key(&chunk) { return }
+
+        let cells = self.chunks.entry(chunk).or_insert_with(|| vec![Cell::Dead; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE]);
+        cells[index] = state;
+        if !cells.contains(&Cell::Alive) { self.chunks.remove(&chunk); }
+    }
+
+    /// Positions of the live cells, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position3d> + '_ {
+        self.chunks.iter().flat_map(|(&(chunk_x, chunk_y, chunk_z), cells)| {
+            let size = CHUNK_SIZE as Coord;
+            cells.iter().enumerate().filter(|&(_, &cell)| cell == Cell::Alive).map(move |(index, _)| {
+                let local = |axis: usize| (index / CHUNK_SIZE.pow(axis as u32) % CHUNK_SIZE) as Coord;
+                Position3d::new(chunk_x * size + local(0), chunk_y * size + local(1), chunk_z * size + local(2))
+            })
+        })
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> usize {
+        self.chunks.values().map(|cells| cells.iter().filter(|&&cell| cell == Cell::Alive).count()).sum()
+    }
+
+    /// Returns the lowest and highest corners of the box around every live cell, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<(Position3d, Position3d)> {
+        self.live_cells().fold(None, |bounds, cell| {
+            let (min, max) = bounds.unwrap_or((cell, cell));
+            Some((
+                Position3d::new(min.x.min(cell.x), min.y.min(cell.y), min.z.min(cell.z)),
+                Position3d::new(max.x.max(cell.x), max.y.max(cell.y), max.z.max(cell.z))
+            ))
+        })
+    }
+
+    // GETTERS
+    pub fn rule(&self) -> Rule3d { self.rule }
+    pub fn generation(&self) -> u64 { self.generation }
+}
+
+/// Write the live cells as `x y z` coordinates, one cell to a line in order, as a point cloud
+/// other tools can load and draw.
+pub fn write_xyz(world: &World3d) -> String {
+    let mut cells: Vec<Position3d> = world.live_cells().collect();
+    cells.sort_unstable();
+    cells.iter().map(|cell| format!("{}\n", cell)).collect()
+}
+
+#[cfg(test)]
+mod gol3d_tests {
+    use super::*;
+
+    #[test]
+    fn step_across_chunks() {
+        // A cube of 8 cells across the origin's chunk corners has 7 neighbours each, so it is still under 5766
+        let mut world = World3d::new(Rule3d::BAYS_5766);
+        for cell in (-1..=0).flat_map(|z| (-1..=0).flat_map(move |y| (-1..=0).map(move |x| Position3d::new(x, y, z)))) {
+            world.set_cell(cell, Cell::Alive);
+        }
+        assert_eq!(8, world.chunks.len());
+        assert_eq!(StepStats { births: 0, deaths: 0, population: 8 }, world.step());
+        assert_eq!(Some((Position3d::new(-1, -1, -1), Position3d::new(0, 0, 0))), world.bounds());
+
+        // Six cells around an empty one bring it to life, while each of them only has four neighbours and dies
+        let mut star = World3d::new(Rule3d::BAYS_5766);
+        for (x, y, z) in [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)] {
+            star.set_cell(Position3d::new(x, y, z), Cell::Alive);
+        }
+        assert_eq!(StepStats { births: 1, deaths: 6, population: 1 }, star.step());
+        assert_eq!("0 0 0\n", write_xyz(&star));
+        assert_eq!(1, star.chunks.len());
+    }
+}
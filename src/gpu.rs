@@ -0,0 +1,86 @@
+//! Graphical frontend drawing cells on the GPU, for worlds with millions of live cells.
+//! Live cell positions are uploaded once per generation and drawn as instanced squares,
+//! while panning and zooming only change a small uniform, so frame times stay flat as the population grows.
+//! Opening the window needs the `gpu` feature, while preparing the data for the GPU is always available.
+
+use crate::geometry::Position;
+use crate::gol::GameOfLife;
+use crate::window::Camera;
+
+/// Positions of the live cells relative to an origin, as uploaded for instancing.
+/// Cells too far from the origin to fit in an `i32` are left out.
+pub fn cell_instances(world: &GameOfLife, origin: Position) -> Vec<[i32; 2]> {
+    world.live_cells().into_iter()
+        .filter_map(|cell| {
+            let offset = cell.checked_sub(origin)?;
+            Some([i32::try_from(offset.x).ok()?, i32::try_from(offset.y).ok()?])
+        })
+        .collect()
+}
+
+/// Transform from instance positions to clip space, matching the layout of the shader's `View` struct.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewUniform {
+    /// Added to instance positions to make them relative to the centre of the view.
+    pub offset: [f32; 2],
+    /// Clip space units per cell, with y flipped so it increases downwards.
+    pub scale: [f32; 2],
+    /// Side of each drawn square in cells, at least a pixel across so distant cells don't vanish.
+    pub cell_size: f32
+}
+
+impl ViewUniform {
+    /// View of a window of the given size in pixels through the camera, for instances uploaded relative to the origin.
+    pub fn new(camera: &Camera, origin: Position, width: usize, height: usize) -> ViewUniform {
+        let pixels_per_cell = 2f32.powi(camera.zoom());
+        ViewUniform {
+            // Origin and camera may both be far out, but their difference is small while the instances are fresh
+            offset: [(origin.x - camera.x()) as f32, (origin.y - camera.y()) as f32],
+            scale: [2.0 * pixels_per_cell / width.max(1) as f32, -2.0 * pixels_per_cell / height.max(1) as f32],
+            cell_size: (1.0 / pixels_per_cell).max(1.0)
+        }
+    }
+
+    /// Bytes of the uniform buffer, with the colour cells are drawn in.
+    pub fn to_bytes(self, colour: [u8; 3]) -> Vec<u8> {
+        let [r, g, b] = colour.map(|channel| channel as f32 / 255.0);
+        [self.offset[0], self.offset[1], self.scale[0], self.scale[1], r, g, b, 1.0, self.cell_size, 0.0, 0.0, 0.0]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+}
+
+#[cfg(feature = "gpu")]
+pub mod frontend;
+
+#[cfg(test)]
+mod gpu_tests {
+    use super::*;
+    use crate::geometry::Coord;
+    use crate::gol::Cell;
+
+    #[test]
+    fn instances_relative_to_origin() {
+        let mut world = GameOfLife::new();
+        world.set_cell(5, -3, Cell::Alive);
+        world.set_cell(Coord::MAX - 2, 0, Cell::Alive);
+        assert_eq!(vec![[-5, -13]], cell_instances(&world, Position::new(10, 10)));
+    }
+
+    #[test]
+    fn view_uniform() {
+        // 4 pixels per cell on a 400 by 200 window
+        let view = ViewUniform::new(&Camera::new(100, -50, 2), Position::new(90, -40), 400, 200);
+        assert_eq!([-10.0, 10.0], view.offset);
+        assert_eq!([0.02, -0.04], view.scale);
+        assert_eq!(1.0, view.cell_size);
+        // The camera's cell is at the centre, and one 50 cells right is at the right edge
+        assert_eq!(0.0, (10.0 + view.offset[0]) * view.scale[0]);
+        assert_eq!(1.0, (60.0 + view.offset[0]) * view.scale[0]);
+
+        // Cells grow to a pixel when several share one
+        assert_eq!(4.0, ViewUniform::new(&Camera::new(0, 0, -2), Position::ORIGIN, 400, 200).cell_size);
+        assert_eq!(48, view.to_bytes([255, 0, 0]).len());
+    }
+}
@@ -0,0 +1,443 @@
+//! Window and GPU setup for drawing worlds with wgpu, which needs the `gpu` feature.
+
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::{Window, WindowId};
+
+use crate::geometry::{Coord, Position};
+use crate::gol::{Cell, GameOfLife};
+use crate::gpu::{cell_instances, ViewUniform};
+use crate::render::Palette;
+use crate::ticker::Ticker;
+use crate::window::Camera;
+
+/// Instanced squares, with corners worked out from the vertex index of a four vertex strip.
+const SHADER: &str = "
+    struct View {
+        offset: vec2<f32>,
+        scale: vec2<f32>,
+        colour: vec4<f32>,
+        cell_size: f32,
+    }
+    @group(0) @binding(0) var<uniform> view: View;
+
+    @vertex
+    fn vs_main(@builtin(vertex_index) corner: u32, @location(0) cell: vec2<i32>) -> @builtin(position) vec4<f32> {
+        let unit = vec2<f32>(f32(corner & 1u), f32(corner >> 1u));
+        let position = (vec2<f32>(cell) + view.offset + unit * view.cell_size) * view.scale;
+        return vec4<f32>(position, 0.0, 1.0);
+    }
+
+    @fragment
+    fn fs_main() -> @location(0) vec4<f32> {
+        return view.colour;
+    }
+";
+
+/// Errors from opening the window or setting up the GPU.
+#[derive(Debug)]
+pub enum GpuError {
+    EventLoop(winit::error::EventLoopError),
+    Window(winit::error::OsError),
+    Surface(wgpu::CreateSurfaceError),
+    Adapter(wgpu::RequestAdapterError),
+    Device(wgpu::RequestDeviceError)
+}
+
+impl Display for GpuError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuError::EventLoop(error) => write!(f, "{}", error),
+            GpuError::Window(error) => write!(f, "couldn't open window: {}", error),
+            GpuError::Surface(error) => write!(f, "couldn't draw to window: {}", error),
+            GpuError::Adapter(error) => write!(f, "no suitable GPU: {}", error),
+            GpuError::Device(error) => write!(f, "couldn't open GPU: {}", error)
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+impl From<winit::error::EventLoopError> for GpuError {
+    fn from(error: winit::error::EventLoopError) -> GpuError { GpuError::EventLoop(error) }
+}
+
+impl From<winit::error::OsError> for GpuError {
+    fn from(error: winit::error::OsError) -> GpuError { GpuError::Window(error) }
+}
+
+impl From<wgpu::CreateSurfaceError> for GpuError {
+    fn from(error: wgpu::CreateSurfaceError) -> GpuError { GpuError::Surface(error) }
+}
+
+impl From<wgpu::RequestAdapterError> for GpuError {
+    fn from(error: wgpu::RequestAdapterError) -> GpuError { GpuError::Adapter(error) }
+}
+
+impl From<wgpu::RequestDeviceError> for GpuError {
+    fn from(error: wgpu::RequestDeviceError) -> GpuError { GpuError::Device(error) }
+}
+
+/// Open a window drawing the world on the GPU, running until it is closed.
+/// Space pauses, `.` steps once, `+`/`-` change speed, arrows pan, the scroll wheel or `z`/`x` zoom,
+/// and clicking toggles a cell.
+pub fn run(world: GameOfLife, palette: Palette) -> Result<(), GpuError> {
+    let event_loop = EventLoop::new()?;
+    let mut app = App {
+        camera: Camera::fit(&world, 1000, 1000),
+        world,
+        generation: 0,
+        paused: true,
+        speed: 16,
+        steps: Ticker::new(16),
+        palette,
+        cursor: None,
+        renderer: None,
+        error: None
+    };
+    event_loop.run_app(&mut app)?;
+    app.error.map_or(Ok(()), Err)
+}
+
+/// Window and GPU state, created once the event loop is running.
+struct Renderer {
+    window: Arc<Window>,
+    surface: wgpu::Surface<'static>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    pipeline: wgpu::RenderPipeline,
+    view_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: wgpu::Buffer,
+    instance_count: u32,
+    /// World position the uploaded instances are relative to.
+    origin: Position,
+    /// Generation the uploaded instances are from, or [`None`] if they need uploading.
+    uploaded: Option<u64>
+}
+
+impl Renderer {
+    fn new(window: Arc<Window>) -> Result<Renderer, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let surface = instance.create_surface(Arc::clone(&window))?;
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            compatible_surface: Some(&surface),
+            ..wgpu::RequestAdapterOptions::default()
+        }))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))?;
+
+        // Colours are given as they should appear, so avoid formats that would convert them from linear
+        let capabilities = surface.get_capabilities(&adapter);
+        let format = capabilities.formats.iter().copied().find(|format| !format.is_srgb()).unwrap_or(capabilities.formats[0]);
+        let size = window.inner_size();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: capabilities.alpha_modes[0],
+            view_formats: vec![]
+        };
+        surface.configure(&device, &config);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cells"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into())
+        });
+        let view_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("view"),
+            size: 48,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("view"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None
+                },
+                count: None
+            }]
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("view"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: view_buffer.as_entire_binding() }]
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cells"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[]
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("cells"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: 8,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Sint32x2]
+                }]
+            },
+            primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleStrip, ..wgpu::PrimitiveState::default() },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(format.into())]
+            }),
+            multiview: None,
+            cache: None
+        });
+        let instances = Renderer::instance_buffer(&device, 1);
+
+        Ok(Renderer {
+            window, surface, device, queue, config, pipeline, view_buffer, bind_group, instances,
+            instance_count: 0,
+            origin: Position::ORIGIN,
+            uploaded: None
+        })
+    }
+
+    /// Create a buffer with room for the given number of cells.
+    fn instance_buffer(device: &wgpu::Device, cells: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cells"),
+            size: (cells.max(1) * 8) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false
+        })
+    }
+
+    fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.config.width = size.width.max(1);
+        self.config.height = size.height.max(1);
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Upload the live cells of the world, relative to the centre of the view.
+    fn upload(&mut self, world: &GameOfLife, generation: u64, camera: &Camera) {
+        self.origin = Position::new(camera.x(), camera.y());
+        let cells: Vec<u8> = cell_instances(world, self.origin).iter()
+            .flat_map(|[x, y]| x.to_le_bytes().into_iter().chain(y.to_le_bytes()))
+            .collect();
+        // Grow with room to spare, so a slowly growing population doesn't reallocate every generation
+        if cells.len() as u64 > self.instances.size() {
+            self.instances = Renderer::instance_buffer(&self.device, cells.len() / 8 * 2);
+        }
+        self.queue.write_buffer(&self.instances, 0, &cells);
+        self.instance_count = (cells.len() / 8) as u32;
+        self.uploaded = Some(generation);
+    }
+
+    fn draw(&mut self, camera: &Camera, palette: &Palette) {
+        let (width, height) = (self.config.width as usize, self.config.height as usize);
+        let view = ViewUniform::new(camera, self.origin, width, height);
+        self.queue.write_buffer(&self.view_buffer, 0, &view.to_bytes(palette.get_colour(1)));
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            // Lost or outdated surfaces come back once reconfigured, so skip this frame
+            Err(_) => {
+                self.surface.configure(&self.device, &self.config);
+                return;
+            }
+        };
+        let target = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let [r, g, b] = palette.get_colour(0).map(|channel| channel as f64 / 255.0);
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("frame") });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("cells"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a: 1.0 }), store: wgpu::StoreOp::Store }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.set_vertex_buffer(0, self.instances.slice(..));
+            pass.draw(0..4, 0..self.instance_count);
+        }
+        self.queue.submit([encoder.finish()]);
+        self.window.pre_present_notify();
+        frame.present();
+    }
+}
+
+/// State of the simulation and its window between events.
+struct App {
+    world: GameOfLife,
+    generation: u64,
+    paused: bool,
+    /// Generations per second while running.
+    speed: u32,
+    steps: Ticker,
+    camera: Camera,
+    palette: Palette,
+    /// Last known position of the mouse in the window, in pixels.
+    cursor: Option<(f64, f64)>,
+    renderer: Option<Renderer>,
+    /// Error that stopped the event loop, to be returned once it has exited.
+    error: Option<GpuError>
+}
+
+impl App {
+    const MAX_SPEED: u32 = 4096;
+
+    fn step(&mut self) {
+        self.world.step();
+        self.generation += 1;
+    }
+
+    /// Step any generations that have come due, but never spend more than a frame doing so.
+    fn catch_up(&mut self) {
+        self.steps.set_rate(self.speed);
+        let started = Instant::now();
+        while self.steps.catch_up(started, Duration::from_millis(16)) {
+            self.step();
+        }
+    }
+
+    /// Handle a key press, returning false if the window should close.
+    fn key(&mut self, key: &Key, size: PhysicalSize<u32>) -> bool {
+        let (width, height) = (size.width as Coord, size.height as Coord);
+        match key {
+            Key::Named(NamedKey::Escape) => return false,
+            Key::Named(NamedKey::Space) => {
+                self.paused = !self.paused;
+                self.steps.restart(Instant::now());
+            }
+            Key::Named(NamedKey::ArrowLeft) => self.camera.pan(-width / 10, 0),
+            Key::Named(NamedKey::ArrowRight) => self.camera.pan(width / 10, 0),
+            Key::Named(NamedKey::ArrowUp) => self.camera.pan(0, -height / 10),
+            Key::Named(NamedKey::ArrowDown) => self.camera.pan(0, height / 10),
+            Key::Character(text) => match text.as_str() {
+                "q" => return false,
+                "." => {
+                    self.paused = true;
+                    self.step();
+                }
+                "+" | "=" => self.speed = (self.speed * 2).min(App::MAX_SPEED),
+                "-" => self.speed = (self.speed / 2).max(1),
+                "z" => self.camera.zoom_in(),
+                "x" => self.camera.zoom_out(),
+                _ => {}
+            },
+            _ => {}
+        }
+        true
+    }
+}
+
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.renderer.is_some() { return }
+        let attributes = Window::default_attributes().with_title("gol").with_inner_size(PhysicalSize::new(1000, 1000));
+        let renderer = event_loop.create_window(attributes)
+            .map_err(GpuError::from)
+            .and_then(|window| Renderer::new(Arc::new(window)));
+        match renderer {
+            Ok(renderer) => self.renderer = Some(renderer),
+            Err(error) => {
+                self.error = Some(error);
+                event_loop.exit();
+            }
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        let Some(size) = self.renderer.as_ref().map(|renderer| renderer.window.inner_size()) else { return };
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => {
+                if let Some(renderer) = &mut self.renderer { renderer.resize(size) }
+            }
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                if !self.key(&event.logical_key, size) { event_loop.exit() }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let scroll = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y as f64,
+                    MouseScrollDelta::PixelDelta(position) => position.y
+                };
+                if scroll > 0.0 { self.camera.zoom_in() } else if scroll < 0.0 { self.camera.zoom_out() }
+            }
+            WindowEvent::CursorMoved { position, .. } => self.cursor = Some((position.x, position.y)),
+            WindowEvent::CursorLeft { .. } => self.cursor = None,
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                if let Some((mouse_x, mouse_y)) = self.cursor {
+                    let Position { x, y } = self.camera.cell_at(mouse_x as usize, mouse_y as usize, size.width as usize, size.height as usize);
+                    let state = if self.world.get_cell(x, y) == Cell::Alive { Cell::Dead } else { Cell::Alive };
+                    self.world.set_cell(x, y, state);
+                    if let Some(renderer) = &mut self.renderer { renderer.uploaded = None }
+                }
+            }
+            WindowEvent::RedrawRequested => {
+                if !self.paused { self.catch_up() }
+                let Some(renderer) = &mut self.renderer else { return };
+                // Only new generations are uploaded, unless the view has drifted far enough to lose precision
+                let drift = self.camera.x().abs_diff(renderer.origin.x).max(self.camera.y().abs_diff(renderer.origin.y));
+                if renderer.uploaded != Some(self.generation) || drift > 1 << 20 {
+                    renderer.upload(&self.world, self.generation, &self.camera);
+                }
+                renderer.draw(&self.camera, &self.palette);
+                renderer.window.set_title(&format!(
+                    "gol - gen {} | pop {} | {} gen/s{}",
+                    self.generation,
+                    self.world.population(),
+                    self.speed,
+                    if self.paused { " (paused)" } else { "" }
+                ));
+            }
+            _ => return
+        }
+        // Anything that got this far may have changed what is shown
+        if let Some(renderer) = &self.renderer { renderer.window.request_redraw() }
+    }
+
+    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+        if self.paused { return }
+        if let Some(renderer) = &self.renderer { renderer.window.request_redraw() }
+    }
+}
+
+#[cfg(test)]
+mod frontend_tests {
+    use super::*;
+
+    #[test]
+    fn shader_is_valid() {
+        use wgpu::naga::valid::{Capabilities, ValidationFlags, Validator};
+        let module = wgpu::naga::front::wgsl::parse_str(SHADER).unwrap();
+        Validator::new(ValidationFlags::all(), Capabilities::empty()).validate(&module).unwrap();
+
+        // Uniform layout must match the bytes written for it
+        let (_, view) = module.global_variables.iter().next().unwrap();
+        assert_eq!(48, module.types[view.ty].inner.size(module.to_ctx()));
+    }
+}
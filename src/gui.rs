@@ -0,0 +1,332 @@
+//! Graphical frontend with a control panel, for those who would rather click than learn the keyboard controls.
+//! The world is drawn by the same pixel renderer as the plain window, with buttons, sliders and a statistics
+//! sidebar around it. Opening it needs the `gui` feature, while the presets it offers are always available.
+
+#[cfg(feature = "gui")]
+use std::time::{Duration, Instant};
+
+use crate::geometry::Coord;
+use crate::gol::{GameOfLife, Region, RegionError};
+use crate::io::rle::read_rle;
+use crate::render::Thumbnail;
+#[cfg(feature = "gui")]
+use crate::ticker::Ticker;
+
+/// Rules offered by the rule picker, by name and rulestring.
+pub const RULE_PRESETS: &[(&str, &str)] = &[
+    ("Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Day & Night", "B3678/S34678"),
+    ("Seeds", "B2/S"),
+    ("Life without Death", "B3/S012345678"),
+    ("Maze", "B3/S12345"),
+    ("2x2", "B36/S125")
+];
+
+/// Patterns offered by the pattern palette, by name and RLE.
+pub const PATTERN_PRESETS: &[(&str, &str)] = &[
+    ("Glider", "bo$2bo$3o!"),
+    ("Lightweight spaceship", "bo2bo$o$o3bo$4o!"),
+    ("Blinker", "3o!"),
+    ("Block", "2o$2o!"),
+    ("R-pentomino", "b2o$2o$bo!"),
+    ("Acorn", "bo$3bo$2o2b3o!"),
+    ("Gosper glider gun", "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!")
+];
+
+/// Parse one of the palette's patterns.
+pub fn preset_pattern(index: usize) -> Region {
+    read_rle(PATTERN_PRESETS[index].1).expect("Preset patterns are valid RLE")
+}
+
+/// Largest side of the previews beside the palette's patterns, in pixels.
+pub const THUMBNAIL_PX: usize = 24;
+
+/// Render a preview of one of the palette's patterns.
+pub fn preset_thumbnail(index: usize) -> Thumbnail {
+    preset_pattern(index).render_thumbnail(THUMBNAIL_PX)
+}
+
+/// Copy a pattern into the world centred on x y, replacing every cell it covers.
+pub fn stamp(world: &mut GameOfLife, pattern: &Region, x: Coord, y: Coord) -> Result<(), RegionError> {
+    let mut pattern = pattern.clone();
+    pattern.set_position(x - pattern.width() as Coord / 2, y - pattern.height() as Coord / 2)?;
+    world.set_region(&pattern);
+    Ok(())
+}
+
+/// Open the control panel window showing the world, running until it is closed.
+#[cfg(feature = "gui")]
+pub fn run(world: GameOfLife, palette: crate::render::Palette) -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        viewport: eframe::egui::ViewportBuilder::default().with_inner_size([1200.0, 900.0]).with_title("gol"),
+        ..eframe::NativeOptions::default()
+    };
+    eframe::run_native("gol", options, Box::new(|_| Ok(Box::new(App::new(world, palette)))))
+}
+
+/// State of the control panel between frames.
+#[cfg(feature = "gui")]
+struct App {
+    world: GameOfLife,
+    generation: u64,
+    paused: bool,
+    /// Generations per second while running.
+    speed: u32,
+    steps: Ticker,
+    camera: crate::window::Camera,
+    /// Drag distance in pixels not yet big enough to move the camera a whole cell.
+    drag: eframe::egui::Vec2,
+    palette: crate::render::Palette,
+    /// Ages of the live cells while colouring by age, and [`None`] otherwise.
+    ages: Option<crate::render::CellAges>,
+    texture: Option<eframe::egui::TextureHandle>,
+    /// Previews of the palette's patterns, loaded the first time the palette is shown.
+    thumbnails: Vec<eframe::egui::TextureHandle>,
+    rule_text: String,
+    rule_error: Option<String>,
+    /// Pattern from the palette placed by clicking, or [`None`] to toggle single cells.
+    pattern: Option<usize>
+}
+
+#[cfg(feature = "gui")]
+impl App {
+    const MAX_SPEED: u32 = 4096;
+
+    fn new(world: GameOfLife, palette: crate::render::Palette) -> App {
+        App {
+            rule_text: world.rule().to_string(),
+            camera: crate::window::Camera::fit(&world, 900, 900),
+            world,
+            generation: 0,
+            paused: true,
+            speed: 16,
+            steps: Ticker::new(16),
+            drag: eframe::egui::Vec2::ZERO,
+            palette,
+            ages: None,
+            texture: None,
+            thumbnails: vec![],
+            rule_error: None,
+            pattern: None
+        }
+    }
+
+    fn step(&mut self) {
+        self.world.step();
+        self.generation += 1;
+        if let Some(ages) = &mut self.ages { ages.update(&self.world) }
+    }
+
+    /// Step any generations that have come due, but never spend more than a frame doing so.
+    fn catch_up(&mut self) {
+        self.steps.set_rate(self.speed);
+        let started = Instant::now();
+        while self.steps.catch_up(started, Duration::from_millis(16)) {
+            self.step();
+        }
+    }
+
+    /// Use the rule in the rule text box, or show why it can't be.
+    fn apply_rule(&mut self) {
+        match self.rule_text.parse() {
+            Ok(rule) => {
+                self.world.set_rule(rule);
+                self.rule_error = None;
+            }
+            Err(error) => self.rule_error = Some(error.to_string())
+        }
+    }
+
+    fn controls(&mut self, ui: &mut eframe::egui::Ui) {
+        use eframe::egui::{Color32, ColorImage, ComboBox, Slider, TextureOptions};
+
+        ui.heading("Simulation");
+        ui.horizontal(|ui| {
+            if ui.button(if self.paused { "Play" } else { "Pause" }).clicked() {
+                self.paused = !self.paused;
+                self.steps.restart(Instant::now());
+            }
+            if ui.button("Step").clicked() {
+                self.paused = true;
+                self.step();
+            }
+        });
+        ui.add(Slider::new(&mut self.speed, 1..=App::MAX_SPEED).logarithmic(true).text("gen/s"));
+        let mut by_age = self.ages.is_some();
+        if ui.checkbox(&mut by_age, "Colour by age").changed() {
+            self.ages = by_age.then(|| {
+                let mut ages = crate::render::CellAges::new();
+                ages.update(&self.world);
+                ages
+            });
+        }
+
+        ui.separator();
+        ui.heading("Rule");
+        ComboBox::from_id_salt("rule presets")
+            .selected_text(RULE_PRESETS.iter().find(|(_, rule)| rule.eq_ignore_ascii_case(&self.rule_text)).map_or("Custom", |(name, _)| name))
+            .show_ui(ui, |ui| {
+                for (name, rule) in RULE_PRESETS {
+                    if ui.selectable_label(self.rule_text.eq_ignore_ascii_case(rule), *name).clicked() {
+                        self.rule_text = rule.to_string();
+                        self.apply_rule();
+                    }
+                }
+            });
+        if ui.text_edit_singleline(&mut self.rule_text).changed() {
+            self.apply_rule();
+        }
+        if let Some(error) = &self.rule_error {
+            ui.colored_label(Color32::RED, error);
+        }
+
+        ui.separator();
+        ui.heading("Patterns");
+        ui.label("Click the world to place the selected pattern.");
+        if ui.selectable_label(self.pattern.is_none(), "Single cell (toggle)").clicked() {
+            self.pattern = None;
+        }
+        if self.thumbnails.is_empty() {
+            self.thumbnails = (0..PATTERN_PRESETS.len()).map(|index| {
+                let thumbnail = preset_thumbnail(index);
+                let image = ColorImage::from_rgb([thumbnail.width(), thumbnail.height()], &thumbnail.to_rgb());
+                ui.ctx().load_texture(format!("pattern {}", index), image, TextureOptions::NEAREST)
+            }).collect();
+        }
+        for (index, (name, _)) in PATTERN_PRESETS.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let thumbnail = &self.thumbnails[index];
+                ui.image((thumbnail.id(), thumbnail.size_vec2()));
+                if ui.selectable_label(self.pattern == Some(index), *name).clicked() {
+                    self.pattern = Some(index);
+                }
+            });
+        }
+        if ui.button("Clear world").clicked() {
+            self.world = GameOfLife::with_rule(self.world.rule());
+            self.generation = 0;
+            if let Some(ages) = &mut self.ages { ages.clear() }
+        }
+
+        ui.separator();
+        ui.heading("Statistics");
+        ui.label(format!("Generation: {}", self.generation));
+        ui.label(format!("Population: {}", self.world.population()));
+        match self.world.live_bounds() {
+            Some(bounds) => {
+                ui.label(format!("Bounds: {} x {}", bounds.width, bounds.height));
+                ui.label(format!("From {} to {}", bounds.min(), bounds.max()));
+            }
+            None => { ui.label("Bounds: empty"); }
+        }
+        ui.label(format!("Rule: {}", self.world.rule()));
+        ui.label(format!("View: {} {}, zoom {}", self.camera.x(), self.camera.y(), self.camera.zoom()));
+    }
+
+    /// Draw the world to fill the rest of the window, handling panning, zooming and clicks on it.
+    fn world_view(&mut self, ui: &mut eframe::egui::Ui) {
+        use eframe::egui::{pos2, Color32, ColorImage, Rect, Sense, TextureOptions};
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        let scale = ui.ctx().pixels_per_point();
+        let (width, height) = ((rect.width() * scale) as usize, (rect.height() * scale) as usize);
+        if width == 0 || height == 0 { return }
+
+        // Drag in whole cells, carrying the remainder over to the next frame
+        if response.dragged() {
+            self.drag -= response.drag_delta() * scale;
+            let cell = if self.camera.zoom() >= 0 { (1 << self.camera.zoom()) as f32 } else { 1.0 };
+            let whole = (self.drag / cell).floor() * cell;
+            self.camera.pan(whole.x as Coord, whole.y as Coord);
+            self.drag -= whole;
+        }
+        if response.hovered() {
+            let scroll = ui.input(|input| input.raw_scroll_delta.y);
+            if scroll > 0.0 { self.camera.zoom_in() } else if scroll < 0.0 { self.camera.zoom_out() }
+        }
+        if response.clicked() && let Some(position) = response.interact_pointer_pos() {
+            let offset = (position - rect.min) * scale;
+            let crate::geometry::Position { x, y } = self.camera.cell_at(offset.x as usize, offset.y as usize, width, height);
+            match self.pattern {
+                Some(index) => stamp(&mut self.world, &preset_pattern(index), x, y).expect("Preset patterns are small"),
+                None => {
+                    let state = if self.world.get_cell(x, y) == crate::gol::Cell::Alive { crate::gol::Cell::Dead } else { crate::gol::Cell::Alive };
+                    self.world.set_cell(x, y, state);
+                }
+            }
+        }
+
+        let frame = match &self.ages {
+            Some(ages) => crate::window::draw_frame_by_age(&self.world, ages, &self.camera, width, height, &self.palette),
+            None => crate::window::draw_frame(&self.world, &self.camera, width, height, &self.palette)
+        };
+        let rgb: Vec<u8> = frame.iter().flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]).collect();
+        let image = ColorImage::from_rgb([width, height], &rgb);
+        let texture = match &mut self.texture {
+            Some(texture) => {
+                texture.set(image, TextureOptions::NEAREST);
+                texture
+            }
+            None => self.texture.insert(ui.ctx().load_texture("world", image, TextureOptions::NEAREST))
+        };
+        ui.painter().image(texture.id(), rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+    }
+}
+
+#[cfg(feature = "gui")]
+impl eframe::App for App {
+    fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
+        use eframe::egui::{CentralPanel, Frame, SidePanel};
+
+        if !self.paused {
+            self.catch_up();
+            ctx.request_repaint();
+        }
+        SidePanel::left("controls").resizable(false).show(ctx, |ui| self.controls(ui));
+        CentralPanel::default().frame(Frame::NONE).show(ctx, |ui| self.world_view(ui));
+    }
+}
+
+#[cfg(test)]
+mod gui_tests {
+    use super::*;
+    use crate::gol::Cell;
+    use crate::rule::Rule;
+
+    #[test]
+    fn presets_are_valid() {
+        for (_, rule) in RULE_PRESETS {
+            assert!(rule.parse::<Rule>().is_ok(), "{}", rule);
+        }
+        for index in 0..PATTERN_PRESETS.len() {
+            assert!(!preset_pattern(index).is_empty());
+        }
+        assert_eq!((36, 9), (preset_pattern(6).width(), preset_pattern(6).height()));
+    }
+
+    #[test]
+    fn preset_thumbnails() {
+        // Small patterns are scaled up to fill the preview, and the gun is scaled down to fit it
+        let glider = preset_thumbnail(0);
+        assert_eq!((24, 24), (glider.width(), glider.height()));
+        let gun = preset_thumbnail(6);
+        assert_eq!((18, 5), (gun.width(), gun.height()));
+    }
+
+    #[test]
+    fn stamp_centres_pattern() {
+        let mut world = GameOfLife::new();
+        world.set_cell(-1, -1, Cell::Alive);
+        stamp(&mut world, &preset_pattern(0), 10, 0).unwrap();
+        // Glider from 9 -1 to 11 1
+        assert_eq!(5, world.population() - 1);
+        assert_eq!(Cell::Alive, world.get_cell(10, -1));
+        assert_eq!(Cell::Alive, world.get_cell(11, 0));
+
+        // Covered cells are replaced
+        stamp(&mut world, &preset_pattern(0), 0, 0).unwrap();
+        assert_eq!(Cell::Dead, world.get_cell(-1, -1));
+        assert_eq!(Cell::Alive, world.get_cell(0, -1));
+    }
+}
@@ -0,0 +1,150 @@
+//! Bounded record of past generations, so a world can be stepped backwards.
+//! Only the latest generation is kept whole. Every earlier one is kept as the cells that changed
+//! on the way to the generation after it, so a long history of a large, mostly settled world stays small.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::geometry::Position;
+use crate::gol::{Cell, GameOfLife};
+
+/// Past states of a world, keeping only the most recent generations.
+pub struct History {
+    /// Cells to toggle to go back from each recorded generation to the one recorded before it, oldest first,
+    /// each with the generation number it goes back to.
+    changes: VecDeque<(u64, Vec<Position>)>,
+    /// Most recently recorded generation and its world, which the changes lead back from.
+    latest: Option<(u64, GameOfLife)>,
+    capacity: usize
+}
+
+impl History {
+    /// Create an empty history that keeps at most `capacity` generations.
+    pub fn new(capacity: usize) -> History {
+        History { changes: VecDeque::new(), latest: None, capacity }
+    }
+
+    /// Record the state of the world at the given generation, forgetting the oldest if full.
+    pub fn record(&mut self, generation: u64, world: &GameOfLife) {
+        if self.capacity == 0 { return }
+        if let Some((previous_generation, previous)) = self.latest.take() {
+            let before: HashSet<Position> = previous.live_cells().into_iter().collect();
+            let after: HashSet<Position> = world.live_cells().into_iter().collect();
+            self.changes.push_back((previous_generation, before.symmetric_difference(&after).copied().collect()));
+        }
+        self.latest = Some((generation, world.clone()));
+        self.forget_oldest();
+    }
+
+    /// Remove and return the most recently recorded generation and its world,
+    /// or [`None`] if nothing is left in the window.
+    /// Earlier generations are rebuilt from the world returned, so they keep its rule.
+    pub fn step_back(&mut self) -> Option<(u64, GameOfLife)> {
+        let (generation, world) = self.latest.take()?;
+        if let Some((previous_generation, changed)) = self.changes.pop_back() {
+            let mut previous = world.clone();
+            for cell in changed {
+                let toggled = match previous.get_cell(cell.x, cell.y) {
+                    Cell::Alive => Cell::Dead,
+                    Cell::Dead => Cell::Alive
+                };
+                previous.set_cell(cell.x, cell.y, toggled);
+            }
+            self.latest = Some((previous_generation, previous));
+        }
+        Some((generation, world))
+    }
+
+    /// Change how many generations are kept, forgetting the oldest if there are more than that.
+    /// A capacity of 0 turns the history off.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.forget_oldest();
+    }
+
+    /// Forget every recorded generation.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+        self.latest = None;
+    }
+
+    fn forget_oldest(&mut self) {
+        if self.capacity == 0 { return self.clear() }
+        while self.len() > self.capacity {
+            self.changes.pop_front();
+        }
+    }
+
+    /// Number of generations that can currently be stepped back.
+    pub fn len(&self) -> usize { self.changes.len() + usize::from(self.latest.is_some()) }
+    pub fn is_empty(&self) -> bool { self.latest.is_none() }
+
+    // GETTERS
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+    use crate::gol::Cell;
+
+    #[test]
+    fn step_back_within_window() {
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut history = History::new(3);
+        for generation in 0..5 {
+            history.record(generation, &world);
+            world.step();
+        }
+        assert_eq!(3, history.len());
+
+        // Blinker is vertical after odd generations
+        let (generation, previous) = history.step_back().unwrap();
+        assert_eq!(4, generation);
+        assert_eq!(Cell::Alive, previous.get_cell(1, 0));
+        assert_eq!((3, Cell::Alive), history.step_back().map(|(generation, world)| (generation, world.get_cell(0, 1))).unwrap());
+        assert_eq!(2, history.step_back().unwrap().0);
+        assert!(history.step_back().is_none());
+
+        let mut disabled = History::new(0);
+        disabled.record(0, &world);
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn edits_between_steps() {
+        // The L becomes a block, and a cell added at generation 1 is there going back to it, and gone going back past it
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(1, 0, Cell::Alive);
+        world.set_cell(0, 1, Cell::Alive);
+        let mut history = History::new(10);
+        history.record(0, &world);
+        world.step();
+        world.set_cell(10, 10, Cell::Alive);
+        history.record(1, &world);
+        world.step();
+
+        let (generation, previous) = history.step_back().unwrap();
+        assert_eq!((1, Cell::Alive, 5), (generation, previous.get_cell(10, 10), previous.population()));
+        let (generation, previous) = history.step_back().unwrap();
+        assert_eq!((0, Cell::Dead, 3), (generation, previous.get_cell(10, 10), previous.population()));
+        assert_eq!(Cell::Alive, previous.get_cell(0, 1));
+
+        // Shrinking the window forgets the oldest generations
+        let mut history = History::new(10);
+        for generation in 0..5 {
+            history.record(generation, &world);
+            world.step();
+        }
+        history.set_capacity(2);
+        assert_eq!(Some(4), history.step_back().map(|(generation, _)| generation));
+        assert_eq!(Some(3), history.step_back().map(|(generation, _)| generation));
+        assert!(history.step_back().is_none());
+        history.record(5, &world);
+        history.set_capacity(0);
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+//! Following a running world from outside the engine, for anything that reacts to it,
+//! such as logging, collecting metrics, playing sounds or drawing to another display.
+//! A [`SimulationObserver`] is told about every stage of a step, and [`StepHooks`] gathers up
+//! the cells born and killed for callbacks that only care about those.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+
+/// Notified of what happens during a step taken with [`GameOfLife::step_with`].
+/// Every method does nothing by default, so observers only implement the ones they need.
+pub trait SimulationObserver {
+    /// Called before anything changes, with the world as it was.
+    fn on_step_start(&mut self, _world: &GameOfLife) {}
+
+    /// Called for every cell born or killed, with its new state. The world is part way through changing,
+    /// so these come before the regions are split and merged, in no particular order.
+    fn on_cell_changed(&mut self, _position: Position, _cell: Cell) {}
+
+    /// Called when a region's live cells have drifted far enough apart to store separately,
+    /// with the bounds of the region and of each part before they are resized.
+    fn on_region_split(&mut self, _region: Rect, _parts: &[Rect]) {}
+
+    /// Called when two regions have grown into each other, with the bounds of each and of the region they became.
+    fn on_region_merge(&mut self, _first: Rect, _second: Rect, _merged: Rect) {}
+
+    /// Called once the step is finished, with the world as it now is.
+    fn on_step_end(&mut self, _world: &GameOfLife, _stats: StepStats) {}
+}
+
+/// Observer that ignores everything, for stepping without one.
+impl SimulationObserver for () {}
+
+/// Callback given the positions of the cells that changed in a step, in no particular order.
+type Hook<'a> = Box<dyn FnMut(&[Position]) + 'a>;
+
+/// Callbacks registered for births and deaths, called after each step taken through them.
+#[derive(Default)]
+pub struct StepHooks<'a> {
+    births: Vec<Hook<'a>>,
+    deaths: Vec<Hook<'a>>,
+    /// Cells born and killed so far in the step being taken.
+    born: Vec<Position>,
+    died: Vec<Position>
+}
+
+impl<'a> StepHooks<'a> {
+    pub fn new() -> StepHooks<'a> {
+        StepHooks::default()
+    }
+
+    /// Register a callback for the cells born in each step.
+    pub fn on_birth(&mut self, hook: impl FnMut(&[Position]) + 'a) {
+        self.births.push(Box::new(hook));
+    }
+
+    /// Register a callback for the cells that die in each step.
+    pub fn on_death(&mut self, hook: impl FnMut(&[Position]) + 'a) {
+        self.deaths.push(Box::new(hook));
+    }
+
+    /// Step the world, then call every hook registered in the order they were registered, births first.
+    /// Hooks are called every step, even if nothing was born or died.
+    pub fn step(&mut self, world: &mut GameOfLife) -> StepStats {
+        if self.is_empty() { return world.step() }
+        world.step_with(self)
+    }
+
+    /// Returns whether any callbacks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.births.is_empty() && self.deaths.is_empty()
+    }
+}
+
+impl SimulationObserver for StepHooks<'_> {
+    fn on_cell_changed(&mut self, position: Position, cell: Cell) {
+        match cell {
+            Cell::Alive => self.born.push(position),
+            Cell::Dead => self.died.push(position)
+        }
+    }
+
+    fn on_step_end(&mut self, _world: &GameOfLife, _stats: StepStats) {
+        for hook in &mut self.births {
+            hook(&self.born);
+        }
+        for hook in &mut self.deaths {
+            hook(&self.died);
+        }
+        self.born.clear();
+        self.died.clear();
+    }
+}
+
+#[cfg(test)]
+mod hooks_tests {
+    use super::*;
+    use crate::geometry::Coord;
+    use crate::io::rle::read_rle;
+
+    #[test]
+    fn hooks() {
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("3o!").unwrap());
+        let mut born = vec![];
+        let mut died = vec![];
+        let mut calls = 0;
+        {
+            let mut hooks = StepHooks::new();
+            hooks.on_birth(|cells| born.extend_from_slice(cells));
+            hooks.on_death(|cells| died.extend_from_slice(cells));
+            hooks.on_death(|_| calls += 1);
+            for _ in 0..2 {
+                let stats = hooks.step(&mut world);
+                assert_eq!((2, 2), (stats.births, stats.deaths));
+            }
+        }
+        born.sort_unstable();
+        died.sort_unstable();
+        let positions = |cells: [(Coord, Coord); 4]| cells.map(Position::from).to_vec();
+        assert_eq!(positions([(0, 0), (1, -1), (1, 1), (2, 0)]), born);
+        assert_eq!(positions([(0, 0), (1, -1), (1, 1), (2, 0)]), died);
+        assert_eq!(2, calls);
+    }
+
+    /// Log of the stages of each step, by name.
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl SimulationObserver for Log {
+        fn on_step_start(&mut self, world: &GameOfLife) {
+            self.0.push(format!("start {}", world.population()));
+        }
+
+        fn on_region_split(&mut self, _region: Rect, parts: &[Rect]) {
+            self.0.push(format!("split {}", parts.len()));
+        }
+
+        fn on_region_merge(&mut self, _first: Rect, _second: Rect, _merged: Rect) {
+            self.0.push("merge".to_string());
+        }
+
+        fn on_step_end(&mut self, _world: &GameOfLife, stats: StepStats) {
+            self.0.push(format!("end {}", stats.population));
+        }
+    }
+
+    #[test]
+    fn observer() {
+        // Two gliders flying apart from each other, which starts as a single region
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("3o$o$bo!").unwrap());
+        let mut glider = read_rle("bo$2bo$3o!").unwrap();
+        glider.set_position(3, 3).unwrap();
+        world.set_region(&glider);
+        assert_eq!(1, world.regions().count());
+
+        let mut log = Log::default();
+        for _ in 0..5 {
+            world.step_with(&mut log);
+        }
+        assert_eq!(vec!["start 10", "end 10"], log.0[..2]);
+        assert_eq!(vec!["start 10", "split 2", "end 10"], log.0[8..]);
+        assert_eq!(2, world.regions().count());
+
+        // Two gliders flying towards each other, whose regions grow into each other before they collide
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("bo$2bo$3o5$8b3o$8bo$9bo!").unwrap());
+        let mut log = Log::default();
+        for _ in 0..7 {
+            world.step_with(&mut log);
+        }
+        assert_eq!(vec!["start 10", "merge", "end 10"], log.0[12..]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Module to hold reading and writing of Game of Life worlds in external formats.
+
+pub mod colors;
+pub mod frames;
+pub mod netpbm;
+pub mod output;
+pub mod plaintext;
+pub mod rle;
+pub mod watch;
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::GolError;
+use crate::gol::{GameOfLife, Region};
+use crate::rule::Rule;
+use plaintext::PlaintextError;
+use rle::RleError;
+
+/// Path that stands for standard input in place of a pattern file, as in `cat glider.rle | gol run -`.
+pub const STDIN_PATH: &str = "-";
+
+/// Text formats that patterns can be read from and written to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PatternFormat {
+    /// Run length encoded, `.rle`.
+    Rle,
+    /// Grid of `.` and `O`, `.cells`.
+    Plaintext
+}
+
+impl PatternFormat {
+    /// Guess the format of a pattern file from its extension.
+    pub fn from_path(path: &Path) -> Option<PatternFormat> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "rle" => Some(PatternFormat::Rle),
+            "cells" | "txt" => Some(PatternFormat::Plaintext),
+            _ => None
+        }
+    }
+
+    /// Guess the format of a pattern from its text, for patterns without a file name to go by.
+    /// Looks at the first line that isn't blank: RLE starts with `#` comments, an `x = ` header or runs of `b`, `o` and `$`,
+    /// while plaintext starts with `!` comments or rows of `.` and `O`.
+    pub fn detect(text: &str) -> Option<PatternFormat> {
+        let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+        if line.starts_with('#') || line.starts_with('x') && line.contains('=') { return Some(PatternFormat::Rle) }
+        if line.starts_with('!') || line.chars().all(|c| matches!(c, '.' | 'O' | '*')) { return Some(PatternFormat::Plaintext) }
+        if line.chars().all(|c| c.is_ascii_digit() || c.is_whitespace() || matches!(c, 'b' | 'o' | '$' | '!')) {
+            return Some(PatternFormat::Rle)
+        }
+        None
+    }
+}
+
+/// Errors from understanding a pattern.
+#[derive(Debug, PartialEq)]
+pub enum PatternError {
+    Rle(RleError),
+    Plaintext(PlaintextError),
+    /// The format couldn't be worked out from the file's extension or its contents.
+    UnknownFormat(PathBuf)
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternError::Rle(error) => write!(f, "{}", error),
+            PatternError::Plaintext(error) => write!(f, "{}", error),
+            PatternError::UnknownFormat(path) => write!(f, "unknown pattern format: {}", path.display())
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<RleError> for PatternError {
+    fn from(error: RleError) -> PatternError { PatternError::Rle(error) }
+}
+
+impl From<PlaintextError> for PatternError {
+    fn from(error: PlaintextError) -> PatternError { PatternError::Plaintext(error) }
+}
+
+/// Read a pattern file, with the format worked out from its extension, or from its contents if the extension is unknown.
+/// A path of [`STDIN_PATH`] reads the pattern from standard input instead.
+pub fn read_pattern(path: &Path) -> Result<Region, GolError> {
+    let text = match path == Path::new(STDIN_PATH) {
+        true => io::read_to_string(io::stdin())?,
+        false => fs::read_to_string(path)?
+    };
+    let format = PatternFormat::from_path(path)
+        .or_else(|| PatternFormat::detect(&text))
+        .ok_or_else(|| PatternError::UnknownFormat(path.to_path_buf()))?;
+    parse_pattern(&text, format)
+}
+
+/// Read a pattern file, as with [`read_pattern`], into a new world under the rule.
+pub fn read_world(path: &Path, rule: Rule) -> Result<GameOfLife, GolError> {
+    let pattern = read_pattern(path)?;
+    let mut world = GameOfLife::with_rule(rule);
+    world.set_region(&pattern);
+    Ok(world)
+}
+
+/// Parse a pattern in the given format.
+pub fn parse_pattern(text: &str, format: PatternFormat) -> Result<Region, GolError> {
+    Ok(match format {
+        PatternFormat::Rle => rle::read_rle(text)?,
+        PatternFormat::Plaintext => plaintext::read_plaintext(text)?
+    })
+}
+
+/// Write the live cells of a region in the given format.
+pub fn format_pattern(region: &Region, format: PatternFormat) -> String {
+    match format {
+        PatternFormat::Rle => rle::write_rle(region),
+        PatternFormat::Plaintext => plaintext::write_plaintext(region)
+    }
+}
+
+/// Write a pattern file in the format of its extension. RLE keeps the region's size and position.
+pub fn write_pattern(region: &Region, path: &Path) -> Result<(), GolError> {
+    let text = match PatternFormat::from_path(path) {
+        Some(PatternFormat::Rle) => rle::write_rle_region(region),
+        Some(format) => format_pattern(region, format),
+        None => return Err(PatternError::UnknownFormat(path.to_path_buf()).into())
+    };
+    fs::write(path, text)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod io_tests {
+    use super::*;
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Some(PatternFormat::Rle), PatternFormat::from_path(Path::new("patterns/glider.RLE")));
+        assert_eq!(Some(PatternFormat::Plaintext), PatternFormat::from_path(Path::new("glider.cells")));
+        assert_eq!(None, PatternFormat::from_path(Path::new("glider")));
+        assert_eq!(None, PatternFormat::from_path(Path::new("glider.png")));
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(Some(PatternFormat::Rle), PatternFormat::detect("#N Glider\nx = 3, y = 3\nbo$2bo$3o!"));
+        assert_eq!(Some(PatternFormat::Rle), PatternFormat::detect("\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"));
+        assert_eq!(Some(PatternFormat::Rle), PatternFormat::detect("bo$2bo$3o!"));
+        assert_eq!(Some(PatternFormat::Plaintext), PatternFormat::detect("!Name: Glider\n.O\n..O\nOOO"));
+        assert_eq!(Some(PatternFormat::Plaintext), PatternFormat::detect(".O.\n"));
+        assert_eq!(None, PatternFormat::detect("P1\n3 3\n"));
+        assert_eq!(None, PatternFormat::detect(" \n"));
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        let glider = parse_pattern("x = 3, y = 3\nbo$2bo$3o!", PatternFormat::Rle).unwrap();
+        let text = format_pattern(&glider, PatternFormat::Plaintext);
+        assert_eq!(".O\n..O\nOOO\n", text);
+
+        let back = parse_pattern(&text, PatternFormat::Plaintext).unwrap();
+        assert_eq!(format_pattern(&glider, PatternFormat::Rle), format_pattern(&back, PatternFormat::Rle));
+    }
+}
@@ -0,0 +1,132 @@
+//! Reading and writing Golly `.colors` files, which give the colour each cell state is drawn with.
+//! Each line is either `color = state r g b` for a single state,
+//! or `gradient = r g b r g b` to blend from the first colour at state 1 to the second at the highest state.
+//! Later lines override earlier ones, so a gradient is usually followed by any special cases.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use crate::error::GolError;
+use crate::render::Palette;
+use crate::render::theme::Theme;
+
+/// Errors from reading a `.colors` file.
+#[derive(Debug, PartialEq)]
+pub enum ColorsError {
+    /// A line that isn't a comment, `color` or `gradient`, or has the wrong number of values.
+    InvalidLine(String),
+    /// A `color` line for a state past the number of states being read.
+    StateOutOfRange(usize)
+}
+
+impl Display for ColorsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorsError::InvalidLine(line) => write!(f, "invalid line in colors file: {}", line),
+            ColorsError::StateOutOfRange(state) => write!(f, "colour given for state {}, past the states of the rule", state)
+        }
+    }
+}
+
+impl std::error::Error for ColorsError {}
+
+/// Parse a `.colors` file into a palette for a rule with the given number of states, including the dead state.
+/// States not mentioned in the file keep the default colours.
+pub fn read_colors(text: &str, states: usize) -> Result<Palette, ColorsError> {
+    let states = states.max(2);
+    let default = Palette::default();
+    let mut colours: Vec<[u8; 3]> = (0..states).map(|state| default.get_colour(state)).collect();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue }
+        let invalid = || ColorsError::InvalidLine(line.to_string());
+
+        let (key, values) = line.split_once('=').ok_or_else(invalid)?;
+        let values: Vec<usize> = values.split_whitespace()
+            .map(|value| value.parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let channel = |value: usize| u8::try_from(value).map_err(|_| invalid());
+
+        match (key.trim(), values.as_slice()) {
+            ("color", &[state, r, g, b]) => {
+                let colour = colours.get_mut(state).ok_or(ColorsError::StateOutOfRange(state))?;
+                *colour = [channel(r)?, channel(g)?, channel(b)?];
+            }
+            ("gradient", &[r1, g1, b1, r2, g2, b2]) => {
+                let start = [channel(r1)?, channel(g1)?, channel(b1)?];
+                let end = [channel(r2)?, channel(g2)?, channel(b2)?];
+                let live_states = states - 1;
+                for (i, colour) in colours[1..].iter_mut().enumerate() {
+                    // Only one live state gets the start colour, as in Golly
+                    let t = if live_states == 1 { 0.0 } else { i as f32 / (live_states - 1) as f32 };
+                    *colour = [0, 1, 2].map(|c| (start[c] as f32 + (end[c] as f32 - start[c] as f32) * t).round() as u8);
+                }
+            }
+            _ => return Err(invalid())
+        }
+    }
+
+    Ok(Palette::new(colours))
+}
+
+/// Read the colours of live and dead cells from a `.colors` file, or take them from the theme if there is no file.
+pub fn load_palette(path: Option<&Path>, theme: &Theme) -> Result<Palette, GolError> {
+    Ok(match path {
+        Some(path) => read_colors(&fs::read_to_string(path)?, 2)?,
+        None => theme.palette()
+    })
+}
+
+/// Write a palette as a `.colors` file, with a `color` line for every state.
+pub fn write_colors(palette: &Palette) -> String {
+    let mut text = String::new();
+    for state in 0..palette.states() {
+        let [r, g, b] = palette.get_colour(state);
+        text.push_str(&format!("color = {} {} {} {}\n", state, r, g, b));
+    }
+    text
+}
+
+#[cfg(test)]
+mod colors_tests {
+    use super::*;
+    use crate::render::{ALIVE_RGB, DEAD_RGB};
+
+    #[test]
+    fn read() {
+        let palette = read_colors("# Wireworld-ish\ncolor = 0 48 48 48\ncolor=1 255 128 0\n", 2).unwrap();
+        assert_eq!([48, 48, 48], palette.get_colour(0));
+        assert_eq!([255, 128, 0], palette.get_colour(1));
+
+        // Unmentioned states keep the defaults
+        assert_eq!(DEAD_RGB, read_colors("", 2).unwrap().get_colour(0));
+        assert_eq!(ALIVE_RGB, read_colors("", 2).unwrap().get_colour(1));
+    }
+
+    #[test]
+    fn gradient() {
+        let palette = read_colors("gradient = 255 0 0 0 0 255\ncolor = 2 0 255 0\n", 4).unwrap();
+        assert_eq!(DEAD_RGB, palette.get_colour(0));
+        assert_eq!([255, 0, 0], palette.get_colour(1));
+        assert_eq!([0, 255, 0], palette.get_colour(2));
+        assert_eq!([0, 0, 255], palette.get_colour(3));
+
+        assert_eq!([10, 20, 30], read_colors("gradient = 10 20 30 40 50 60", 2).unwrap().get_colour(1));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(ColorsError::StateOutOfRange(5)), read_colors("color = 5 0 0 0", 2));
+        assert!(matches!(read_colors("color = 1 256 0 0", 2), Err(ColorsError::InvalidLine(_))));
+        assert!(matches!(read_colors("colour = 1 0 0 0", 2), Err(ColorsError::InvalidLine(_))));
+        assert!(matches!(read_colors("color = 1 0 0", 2), Err(ColorsError::InvalidLine(_))));
+    }
+
+    #[test]
+    fn round_trip() {
+        let palette = read_colors("gradient = 255 0 0 0 0 255", 5).unwrap();
+        assert_eq!(palette, read_colors(&write_colors(&palette), 5).unwrap());
+    }
+}
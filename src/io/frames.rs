@@ -0,0 +1,210 @@
+//! Export of image frames from a running simulation, for assembling into videos with tools like ffmpeg.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::GameOfLife;
+use crate::io::STDIN_PATH;
+use crate::io::netpbm::write_ppm;
+use crate::io::output::ImageFormat;
+use crate::render;
+
+/// Destination for exported frames.
+pub enum FrameOutput {
+    /// Write each frame to a numbered PPM file in the given directory, e.g. `frame_000042.ppm`.
+    Ppm(PathBuf),
+    /// Write each frame to a numbered PNG file in the given directory, e.g. `frame_000042.png`.
+    #[cfg(feature = "png")]
+    Png(PathBuf),
+    /// Write the raw 8-bit RGB pixels of each frame back to back,
+    /// for piping into `ffmpeg -f rawvideo -pix_fmt rgb24`.
+    Raw(Box<dyn Write>)
+}
+
+impl FrameOutput {
+    /// Write numbered frames in the image format to the directory, creating it if needed,
+    /// or raw frames to standard output if the path is `-`.
+    /// Frames can't be black and white PBM images, as they are drawn in colour.
+    pub fn create(path: &Path, format: ImageFormat) -> io::Result<FrameOutput> {
+        if path == Path::new(STDIN_PATH) { return Ok(FrameOutput::Raw(Box::new(BufWriter::new(io::stdout())))) }
+        let output = match format {
+            ImageFormat::Pbm => return Err(io::Error::new(io::ErrorKind::InvalidInput, "frames can't be written as PBM images")),
+            ImageFormat::Ppm => FrameOutput::Ppm(path.to_path_buf()),
+            #[cfg(feature = "png")]
+            ImageFormat::Png => FrameOutput::Png(path.to_path_buf())
+        };
+        fs::create_dir_all(path)?;
+        Ok(output)
+    }
+}
+
+/// Area of the world captured in each frame.
+/// The size is fixed for the whole sequence, as video encoders need every frame to match.
+pub enum FrameViewport {
+    /// A fixed area of the world.
+    Fixed(Rect),
+    /// An area of the given width and height in cells, kept centred on the bounding box of the live cells.
+    Tracking { width: usize, height: usize }
+}
+
+impl FrameViewport {
+    /// Frame the live cells of the world as they are now, with `margin` dead cells around them,
+    /// either fixed there or tracking them as they move.
+    pub fn around(world: &GameOfLife, margin: usize, tracking: bool) -> FrameViewport {
+        let area = world.live_bounds().unwrap_or(Rect::new(0, 0, 1, 1)).expand(margin);
+        match tracking {
+            true => FrameViewport::Tracking { width: area.width, height: area.height },
+            false => FrameViewport::Fixed(area)
+        }
+    }
+}
+
+/// Writes a numbered image frame of the world every Nth generation.
+pub struct FrameExporter {
+    output: FrameOutput,
+    viewport: FrameViewport,
+    interval: usize,
+    scale: usize,
+    frames_written: usize,
+    /// Last centre used by a tracking viewport, kept for when every cell has died.
+    centre: Position
+}
+
+impl FrameExporter {
+    /// Create an exporter writing a frame every `interval` generations,
+    /// drawing each cell as a square of `scale` pixels per side.
+    pub fn new(output: FrameOutput, viewport: FrameViewport, interval: usize, scale: usize) -> FrameExporter {
+        FrameExporter {
+            output,
+            viewport,
+            interval: interval.max(1),
+            scale: scale.max(1),
+            frames_written: 0,
+            centre: Position::ORIGIN
+        }
+    }
+
+    /// Step the world the given number of generations,
+    /// writing a frame of the starting state and then every `interval` generations after it.
+    pub fn run(&mut self, world: &mut GameOfLife, generations: usize) -> io::Result<()> {
+        self.write_frame(world)?;
+        for generation in 1..=generations {
+            world.step();
+            if generation % self.interval == 0 {
+                self.write_frame(world)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a single frame of the current state of the world.
+    pub fn write_frame(&mut self, world: &GameOfLife) -> io::Result<()> {
+        let area = self.frame_area(world);
+        let pixels = render::rgb_image(world, area.x, area.y, area.width, area.height, self.scale);
+        let (pixel_width, pixel_height) = (area.width * self.scale, area.height * self.scale);
+
+        match &mut self.output {
+            FrameOutput::Ppm(directory) => {
+                let path = directory.join(format!("frame_{:06}.ppm", self.frames_written));
+                let mut file = BufWriter::new(File::create(path)?);
+                write_ppm(&mut file, pixel_width, pixel_height, &pixels)?;
+                file.flush()?;
+            }
+            #[cfg(feature = "png")]
+            FrameOutput::Png(directory) => {
+                let path = directory.join(format!("frame_{:06}.png", self.frames_written));
+                let file = BufWriter::new(File::create(path)?);
+                let mut encoder = png::Encoder::new(file, pixel_width as u32, pixel_height as u32);
+                encoder.set_color(png::ColorType::Rgb);
+                encoder.set_depth(png::BitDepth::Eight);
+                let mut writer = encoder.write_header().map_err(io::Error::other)?;
+                writer.write_image_data(&pixels).map_err(io::Error::other)?;
+            }
+            FrameOutput::Raw(writer) => {
+                writer.write_all(&pixels)?;
+                writer.flush()?;
+            }
+        }
+
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    /// Returns the width and height of every frame in pixels.
+    pub fn frame_size(&self) -> (usize, usize) {
+        let (width, height) = match self.viewport {
+            FrameViewport::Fixed(area) => (area.width, area.height),
+            FrameViewport::Tracking { width, height } => (width, height)
+        };
+        (width * self.scale, height * self.scale)
+    }
+
+    /// Number of frames written so far, which is also the number of the next frame.
+    pub fn frames_written(&self) -> usize { self.frames_written }
+
+    /// Calculate the area of the world in the next frame.
+    fn frame_area(&mut self, world: &GameOfLife) -> Rect {
+        match self.viewport {
+            FrameViewport::Fixed(area) => area,
+            FrameViewport::Tracking { width, height } => {
+                if let Some(bounds) = world.live_bounds() {
+                    self.centre = bounds.centre();
+                }
+                let x = self.centre.x - (width / 2) as Coord;
+                let y = self.centre.y - (height / 2) as Coord;
+                Rect::new(x, y, width, height)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod frames_tests {
+    use super::*;
+    use crate::gol::Cell;
+    use std::fs;
+
+    #[test]
+    fn numbered_ppm_frames() {
+        let directory = std::env::temp_dir().join(format!("rust-gol-frames-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let mut world = GameOfLife::new();
+        for x in 0..3 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+
+        let viewport = FrameViewport::Fixed(Rect::new(-1, -1, 5, 3));
+        let mut exporter = FrameExporter::new(FrameOutput::Ppm(directory.clone()), viewport, 2, 2);
+        exporter.run(&mut world, 5).unwrap();
+
+        // Generations 0, 2 and 4
+        assert_eq!(3, exporter.frames_written());
+        let frame = fs::read(directory.join("frame_000002.ppm")).unwrap();
+        let header = b"P6\n10 6\n255\n";
+        assert_eq!(header, &frame[..header.len()]);
+        assert_eq!(header.len() + 10 * 6 * 3, frame.len());
+        assert!(!directory.join("frame_000003.ppm").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn tracking_viewport() {
+        let mut world = GameOfLife::new();
+        for (x, y) in [(101, 50), (102, 51), (100, 52), (101, 52), (102, 52)] {
+            world.set_cell(x, y, Cell::Alive);
+        }
+
+        let viewport = FrameViewport::around(&world, 2, true);
+        let mut exporter = FrameExporter::new(FrameOutput::Raw(Box::new(io::sink())), viewport, 1, 1);
+        assert_eq!(Rect::new(98, 48, 7, 7), exporter.frame_area(&world));
+        assert_eq!((7, 7), exporter.frame_size());
+
+        // Stays put once there is nothing left to follow
+        let empty = GameOfLife::new();
+        assert_eq!(Rect::new(98, 48, 7, 7), exporter.frame_area(&empty));
+    }
+}
@@ -0,0 +1,116 @@
+//! Dependency-free NetPBM image output, readable by practically every image tool and shell pipeline.
+
+use std::io::{self, Write};
+
+use crate::geometry::Coord;
+use crate::gol::{Cell, GameOfLife};
+use crate::render::DensityGrid;
+
+/// Longest line allowed in the plain formats.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Write the area of the world starting at x y with the given width and height in cells
+/// as a plain (ASCII) PBM bitmap, one pixel per cell.
+/// Live cells are written as `1`, which PBM displays as black.
+pub fn write_pbm(writer: &mut impl Write, world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize) -> io::Result<()> {
+    write!(writer, "P1\n{} {}\n", width, height)?;
+    for cell_y in y..y.saturating_add_unsigned(height as u64) {
+        let row = (x..x.saturating_add_unsigned(width as u64)).map(|cell_x| match world.get_cell(cell_x, cell_y) {
+            Cell::Alive => "1",
+            Cell::Dead => "0"
+        });
+        write_plain_row(writer, row, "")?;
+    }
+    Ok(())
+}
+
+/// Write a density grid as a plain (ASCII) PGM greymap, one pixel per sample.
+/// Densities are scaled so all alive is white and all dead is black.
+pub fn write_pgm(writer: &mut impl Write, grid: &DensityGrid) -> io::Result<()> {
+    write!(writer, "P2\n{} {}\n255\n", grid.width(), grid.height())?;
+    let block = grid.block_size() as Coord;
+    for row in 0..grid.height() {
+        let y = grid.y() + row as Coord * block;
+        let values = (0..grid.width()).map(|column| {
+            let x = grid.x() + column as Coord * block;
+            let density = grid.get_sample(x, y).expect("Sample X Y position out of bounds");
+            ((density * 255.0).round() as u8).to_string()
+        });
+        write_plain_row(writer, values, " ")?;
+    }
+    Ok(())
+}
+
+/// Write 8-bit RGB pixels, in rows, as a binary PPM image.
+pub fn write_ppm(writer: &mut impl Write, width: usize, height: usize, pixels: &[u8]) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", width, height)?;
+    writer.write_all(pixels)
+}
+
+/// Write a row of values on its own line, wrapping before lines get longer than the plain formats allow.
+fn write_plain_row(writer: &mut impl Write, values: impl Iterator<Item = impl AsRef<str>>, separator: &str) -> io::Result<()> {
+    let mut line_length = 0;
+    for value in values {
+        let value = value.as_ref();
+        if line_length > 0 {
+            if line_length + separator.len() + value.len() > MAX_LINE_LENGTH {
+                writeln!(writer)?;
+                line_length = 0;
+            }
+            else {
+                writer.write_all(separator.as_bytes())?;
+                line_length += separator.len();
+            }
+        }
+        writer.write_all(value.as_bytes())?;
+        line_length += value.len();
+    }
+    writeln!(writer)
+}
+
+#[cfg(test)]
+mod netpbm_tests {
+    use super::*;
+
+    #[test]
+    fn pbm() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(2, 1, Cell::Alive);
+
+        let mut output = vec![];
+        write_pbm(&mut output, &world, -1, 0, 4, 2).unwrap();
+        assert_eq!("P1\n4 2\n0100\n0001\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn pbm_wraps_long_rows() {
+        let world = GameOfLife::new();
+
+        let mut output = vec![];
+        write_pbm(&mut output, &world, 0, 0, 100, 1).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(vec!["P1", "100 1", &"0".repeat(70), &"0".repeat(30)], lines);
+    }
+
+    #[test]
+    fn pgm() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(1, 0, Cell::Alive);
+        world.set_cell(2, 2, Cell::Alive);
+
+        let grid = DensityGrid::new(&world, 0, 0, 4, 4, 1);
+        let mut output = vec![];
+        write_pgm(&mut output, &grid).unwrap();
+        assert_eq!("P2\n2 2\n255\n128 0\n0 64\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn ppm() {
+        let mut output = vec![];
+        write_ppm(&mut output, 1, 1, &[1, 2, 3]).unwrap();
+        assert_eq!(b"P6\n1 1\n255\n\x01\x02\x03", output.as_slice());
+    }
+}
@@ -0,0 +1,232 @@
+//! Writing the final state of a world out, as a pattern, text for the terminal or an image.
+//! The format is chosen by name, or worked out from the extension of the file being written.
+
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::GolError;
+use crate::geometry::Rect;
+use crate::gol::GameOfLife;
+use crate::io::{format_pattern, netpbm, PatternFormat};
+use crate::render::{self, Palette, Shading, Thumbnail};
+use crate::render::terminal::{self, Glyphs};
+use crate::render::theme::Theme;
+
+/// Formats a world's final state can be written in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Rle,
+    Cells,
+    Pbm,
+    /// Grid of `#` and `.` characters, or the glyphs set in the config file's theme.
+    Text,
+    /// Half block characters, two cells per character.
+    Blocks,
+    /// Braille characters, eight cells per character.
+    Braille,
+    /// Grid of `#` and `.` characters, or the theme's glyphs, with each row shifted half a cell,
+    /// for rules on the hexagonal neighbourhood such as B2/S34H.
+    Hex
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 7] = [
+        OutputFormat::Rle, OutputFormat::Cells, OutputFormat::Pbm, OutputFormat::Text,
+        OutputFormat::Blocks, OutputFormat::Braille, OutputFormat::Hex
+    ];
+
+    /// Guess the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<OutputFormat> {
+        match PatternFormat::from_path(path) {
+            Some(PatternFormat::Rle) => Some(OutputFormat::Rle),
+            Some(PatternFormat::Plaintext) => Some(OutputFormat::Cells),
+            None if path.extension()? == "pbm" => Some(OutputFormat::Pbm),
+            None => None
+        }
+    }
+
+    /// Returns the format given, or else the one for the output file's extension, or text if neither says.
+    pub fn or_from_path(format: Option<OutputFormat>, out: Option<&Path>) -> OutputFormat {
+        format
+            .or_else(|| out.and_then(OutputFormat::from_path))
+            .unwrap_or(OutputFormat::Text)
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Rle => write!(f, "rle"),
+            OutputFormat::Cells => write!(f, "cells"),
+            OutputFormat::Pbm => write!(f, "pbm"),
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Blocks => write!(f, "blocks"),
+            OutputFormat::Braille => write!(f, "braille"),
+            OutputFormat::Hex => write!(f, "hex")
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<OutputFormat, FormatError> {
+        OutputFormat::ALL.into_iter()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| FormatError(s.to_string()))
+    }
+}
+
+/// Image formats a world can be rendered to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageFormat {
+    Pbm,
+    Ppm,
+    #[cfg(feature = "png")]
+    Png
+}
+
+impl ImageFormat {
+    pub const ALL: &[ImageFormat] = &[
+        ImageFormat::Pbm,
+        ImageFormat::Ppm,
+        #[cfg(feature = "png")]
+        ImageFormat::Png
+    ];
+
+    /// Guess the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension = path.extension()?.to_str()?;
+        ImageFormat::ALL.iter().copied().find(|format| format.to_string() == extension)
+    }
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageFormat::Pbm => write!(f, "pbm"),
+            ImageFormat::Ppm => write!(f, "ppm"),
+            #[cfg(feature = "png")]
+            ImageFormat::Png => write!(f, "png")
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<ImageFormat, FormatError> {
+        ImageFormat::ALL.iter().copied()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| FormatError(s.to_string()))
+    }
+}
+
+/// Error from parsing the name of an output or image format that doesn't exist.
+#[derive(Debug, PartialEq)]
+pub struct FormatError(pub String);
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown format '{}'", self.0)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Encode the live part of the world in the given output format, drawing text with the theme's glyphs.
+pub fn format_world(world: &GameOfLife, format: OutputFormat, theme: &Theme) -> Result<Vec<u8>, GolError> {
+    let region = world.to_region()?;
+    Ok(match format {
+        OutputFormat::Rle => format_pattern(&region, PatternFormat::Rle).into_bytes(),
+        OutputFormat::Cells => format_pattern(&region, PatternFormat::Plaintext).into_bytes(),
+        OutputFormat::Text => render::text_grid_with_theme(world, region.x(), region.y(), region.width(), region.height(), theme).into_bytes(),
+        OutputFormat::Hex => render::hex_grid_with_theme(world, region.x(), region.y(), region.width(), region.height(), theme).into_bytes(),
+        OutputFormat::Blocks | OutputFormat::Braille => {
+            let glyphs = if format == OutputFormat::Blocks { Glyphs::HalfBlocks } else { Glyphs::Braille };
+            let (columns, rows) = terminal::chars_for_cells(region.width(), region.height(), glyphs);
+            terminal::draw(world, region.x(), region.y(), columns, rows, glyphs).into_bytes()
+        }
+        OutputFormat::Pbm => {
+            let mut output = vec![];
+            netpbm::write_pbm(&mut output, world, region.x(), region.y(), region.width(), region.height())?;
+            output
+        }
+    })
+}
+
+/// Write the live part of the world to the file in the given format, or to standard output if there is no file.
+pub fn write_world(world: &GameOfLife, format: OutputFormat, out: Option<&Path>, theme: &Theme) -> Result<(), GolError> {
+    let output = format_world(world, format, theme)?;
+    match out {
+        Some(path) => fs::write(path, output)?,
+        None => io::stdout().write_all(&output)?
+    }
+    Ok(())
+}
+
+/// Render the live part of the world to an image file, drawing each cell as a square of `scale` pixels per side,
+/// shaded as given, in the palette's colours and with the theme's grid lines. PBM images are only ever black and white.
+pub fn write_image(world: &GameOfLife, format: ImageFormat, out: &Path, scale: usize, shading: &Shading, palette: &Palette, theme: &Theme) -> Result<(), GolError> {
+    let Rect { x, y, width, height } = world.live_bounds().unwrap_or(Rect::new(0, 0, 1, 1));
+    if format == ImageFormat::Pbm {
+        let mut file = BufWriter::new(File::create(out)?);
+        netpbm::write_pbm(&mut file, world, x, y, width, height)?;
+        file.flush()?;
+        return Ok(())
+    }
+
+    let (pixel_width, pixel_height) = (width * scale, height * scale);
+    let mut pixels = shading.rgb_image(world, x, y, width, height, scale, palette);
+    theme.draw_grid(&mut pixels, pixel_width, scale);
+    write_rgb(format, out, pixel_width, pixel_height, &pixels)
+}
+
+/// Write a thumbnail to an image file, shaded from the dead to the live colour.
+/// Can't be a PBM image, which has no shades in between.
+pub fn write_thumbnail(thumbnail: &Thumbnail, format: ImageFormat, out: &Path) -> Result<(), GolError> {
+    write_rgb(format, out, thumbnail.width(), thumbnail.height(), &thumbnail.to_rgb())
+}
+
+/// Write 8-bit RGB pixels, in rows, to an image file in one of the colour formats.
+fn write_rgb(format: ImageFormat, out: &Path, width: usize, height: usize, pixels: &[u8]) -> Result<(), GolError> {
+    let mut image = vec![];
+    match format {
+        ImageFormat::Pbm => return Err(io::Error::new(io::ErrorKind::InvalidInput, "PBM images can only be black and white").into()),
+        ImageFormat::Ppm => netpbm::write_ppm(&mut image, width, height, pixels)?,
+        #[cfg(feature = "png")]
+        ImageFormat::Png => {
+            let mut encoder = png::Encoder::new(&mut image, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().map_err(io::Error::other)?;
+            writer.write_image_data(pixels).map_err(io::Error::other)?;
+        }
+    }
+    fs::write(out, image)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+
+    #[test]
+    fn formats_by_name_and_path() {
+        for format in OutputFormat::ALL {
+            assert_eq!(Ok(format), format.to_string().parse());
+        }
+        assert_eq!(Ok(OutputFormat::Braille), " BRAILLE".parse());
+        assert_eq!(Err(FormatError("svg".to_string())), "svg".parse::<OutputFormat>());
+        assert_eq!(OutputFormat::Cells, OutputFormat::or_from_path(None, Some(Path::new("out.cells"))));
+        assert_eq!(OutputFormat::Hex, OutputFormat::or_from_path(Some(OutputFormat::Hex), Some(Path::new("out.rle"))));
+        assert_eq!(OutputFormat::Text, OutputFormat::or_from_path(None, Some(Path::new("out.png"))));
+
+        assert_eq!(Some(ImageFormat::Ppm), ImageFormat::from_path(Path::new("frame.ppm")));
+        assert_eq!(None, ImageFormat::from_path(Path::new("frame.rle")));
+        assert_eq!(Ok(ImageFormat::Pbm), "pbm".parse());
+    }
+}
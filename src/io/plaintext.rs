@@ -0,0 +1,104 @@
+//! Reading and writing patterns in the plaintext (`.cells`) format, a grid of `.` and `O` characters.
+
+use std::fmt::{Display, Formatter};
+
+use crate::error::GolError;
+use crate::geometry::Coord;
+use crate::gol::{Cell, Region, RegionError};
+
+/// Errors from reading a plaintext pattern.
+#[derive(Debug, PartialEq)]
+pub enum PlaintextError {
+    /// A character other than a dead or live cell in the pattern grid.
+    UnexpectedCharacter(char),
+    /// The pattern is too large to fit in a region.
+    InvalidSize(RegionError)
+}
+
+impl Display for PlaintextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaintextError::UnexpectedCharacter(c) => write!(f, "unexpected character in plaintext pattern: {:?}", c),
+            PlaintextError::InvalidSize(error) => write!(f, "invalid plaintext pattern size: {}", error)
+        }
+    }
+}
+
+impl std::error::Error for PlaintextError {}
+
+impl From<RegionError> for PlaintextError {
+    fn from(error: RegionError) -> PlaintextError { PlaintextError::InvalidSize(error) }
+}
+
+/// Parse a plaintext pattern into a region with its -x -y corner at 0 0.
+/// Lines starting with `!` are comments, `.` is a dead cell and `O` (or `*` or `#`) is a live cell.
+pub fn read_plaintext(text: &str) -> Result<Region, GolError> {
+    let mut live_cells = vec![];
+    let (mut width, mut height) = (0, 0);
+
+    for line in text.lines().filter(|line| !line.starts_with('!')) {
+        let line = line.trim_end();
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => {}
+                'O' | 'o' | '*' | '#' => live_cells.push((x, height)),
+                c => return Err(PlaintextError::UnexpectedCharacter(c).into())
+            }
+        }
+        width = width.max(line.chars().count());
+        height += 1;
+    }
+
+    let mut region = Region::new(0, 0, width, height).map_err(PlaintextError::from)?;
+    for (x, y) in live_cells {
+        region.set_cell(x as Coord, y as Coord, Cell::Alive);
+    }
+    Ok(region)
+}
+
+/// Write the live cells of a region as a plaintext pattern, cropped to their bounding box.
+/// Dead cells at the end of each row are left off.
+pub fn write_plaintext(region: &Region) -> String {
+    let Some(bounds) = region.live_bounds() else { return String::new() };
+    let (min, max) = (bounds.min(), bounds.max());
+
+    let mut text = String::new();
+    for y in min.y..=max.y {
+        let row: String = (min.x..=max.x)
+            .map(|x| match region.get_cell(x, y) {
+                Some(Cell::Alive) => 'O',
+                _ => '.'
+            })
+            .collect();
+        text.push_str(row.trim_end_matches('.'));
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod plaintext_tests {
+    use super::*;
+    use crate::io::PatternError;
+
+    #[test]
+    fn read() {
+        let glider = read_plaintext("!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+        assert_eq!((3, 3), (glider.width(), glider.height()));
+        assert_eq!(Some(Cell::Alive), glider.get_cell(1, 0));
+        assert_eq!(Some(Cell::Dead), glider.get_cell(2, 0));
+        assert_eq!(Some(Cell::Alive), glider.get_cell(0, 2));
+
+        assert!(matches!(read_plaintext(".x\n"), Err(GolError::Pattern(PatternError::Plaintext(PlaintextError::UnexpectedCharacter('x'))))));
+    }
+
+    #[test]
+    fn write() {
+        let mut region = Region::new(-4, -4, 8, 8).unwrap();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            region.set_cell(x, y, Cell::Alive);
+        }
+        assert_eq!(".O\n..O\nOOO\n", write_plaintext(&region));
+        assert_eq!("", write_plaintext(&Region::new(0, 0, 2, 2).unwrap()));
+    }
+}
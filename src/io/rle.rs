@@ -0,0 +1,351 @@
+//! Reading and writing patterns in the run length encoded (RLE) format used by Golly and LifeWiki.
+
+use std::fmt::{Display, Formatter};
+
+use crate::error::GolError;
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, MAX_REGION_CELLS, Region, RegionError};
+
+/// Longest line written in the pattern body, as recommended by the format.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Errors from reading an RLE pattern.
+#[derive(Debug, PartialEq)]
+pub enum RleError {
+    /// The `x = ..., y = ...` header line couldn't be understood.
+    InvalidHeader(String),
+    /// A character that isn't part of a two-state pattern body.
+    UnexpectedCharacter(char),
+    /// The pattern is too large, or too far out, to fit in a region.
+    InvalidSize(RegionError)
+}
+
+impl Display for RleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RleError::InvalidHeader(line) => write!(f, "invalid RLE header: {}", line),
+            RleError::UnexpectedCharacter(c) => write!(f, "unexpected character in RLE body: {:?}", c),
+            RleError::InvalidSize(error) => write!(f, "invalid RLE pattern size: {}", error)
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+impl From<RegionError> for RleError {
+    fn from(error: RegionError) -> RleError { RleError::InvalidSize(error) }
+}
+
+/// Parse an RLE pattern into a region with its -x -y corner at 0 0, or at the position given by a Golly
+/// `#CXRLE Pos=x,y` line if there is one.
+/// The region is the size given in the header, or larger if the body doesn't fit it.
+pub fn read_rle(text: &str) -> Result<Region, GolError> {
+    let (mut width, mut height) = (0, 0);
+    let (mut body_width, mut body_height) = (0, 0);
+    let mut position = Position::ORIGIN;
+    let mut live_cells = vec![];
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+
+    'lines: for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("#CXRLE") {
+            position = parse_position(line)?.unwrap_or(position);
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') { continue }
+        if line.starts_with('x') {
+            (width, height) = parse_header(line)?;
+            continue;
+        }
+
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).expect("Matched digit") as usize;
+                    count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                    continue;
+                }
+                'b' | '.' => x = x.saturating_add(count.unwrap_or(1)),
+                'o' | 'A' => {
+                    // Catch huge runs before storing their cells, rather than once the region is created
+                    let end = x.saturating_add(count.unwrap_or(1));
+                    let rows = y.checked_add(1).ok_or(RleError::InvalidSize(RegionError::TooLarge { width: end, height: y }))?;
+                    if end.saturating_mul(rows) > MAX_REGION_CELLS {
+                        return Err(RleError::InvalidSize(RegionError::TooLarge { width: end, height: rows }).into())
+                    }
+                    (body_width, body_height) = (body_width.max(end), body_height.max(rows));
+                    for _ in 0..count.unwrap_or(1) {
+                        live_cells.push((x, y));
+                        x += 1;
+                    }
+                }
+                '$' => {
+                    y = y.saturating_add(count.unwrap_or(1));
+                    x = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => {}
+                c => return Err(RleError::UnexpectedCharacter(c).into())
+            }
+            count = None;
+        }
+    }
+
+    let mut region = Region::new(0, 0, width.max(body_width), height.max(body_height)).map_err(RleError::from)?;
+    for (x, y) in live_cells {
+        region.set_cell(x as Coord, y as Coord, Cell::Alive);
+    }
+    region.set_position(position.x, position.y).map_err(RleError::from)?;
+    Ok(region)
+}
+
+/// Parse a multi-state RLE pattern, as Golly writes for rules such as Wireworld, into the position and state
+/// of every cell that isn't in state 0, offset by any `#CXRLE Pos=x,y` line. States 1 to 24 are written `A` to `X`,
+/// with `.` for state 0, and the two-state `b` and `o` are read as states 0 and 1.
+pub fn read_rle_states(text: &str) -> Result<Vec<(Position, u8)>, GolError> {
+    let mut position = Position::ORIGIN;
+    let mut cells = vec![];
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+
+    'lines: for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("#CXRLE") {
+            position = parse_position(line)?.unwrap_or(position);
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') { continue }
+        if line.starts_with('x') {
+            parse_header(line)?;
+            continue;
+        }
+
+        for c in line.chars() {
+            let state = match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).expect("Matched digit") as usize;
+                    count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                    continue;
+                }
+                'b' | '.' => 0,
+                'o' => 1,
+                'A'..='X' => c as u8 - b'A' + 1,
+                '$' => {
+                    y = y.saturating_add(count.unwrap_or(1));
+                    x = 0;
+                    count = None;
+                    continue;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => continue,
+                c => return Err(RleError::UnexpectedCharacter(c).into())
+            };
+
+            let run = count.take().unwrap_or(1);
+            if state != 0 {
+                let end = x.saturating_add(run);
+                let rows = y.checked_add(1).ok_or(RleError::InvalidSize(RegionError::TooLarge { width: end, height: y }))?;
+                if cells.len().saturating_add(run) > MAX_REGION_CELLS {
+                    return Err(RleError::InvalidSize(RegionError::TooLarge { width: end, height: rows }).into())
+                }
+                for offset in 0..run {
+                    // Runs and rows can take cells past the coordinate limits even when there are few of them
+                    let cell = Coord::try_from(x.saturating_add(offset)).ok().zip(Coord::try_from(y).ok())
+                        .and_then(|(x, y)| position.checked_add(Position::new(x, y)))
+                        .ok_or(RleError::InvalidSize(RegionError::OutOfRange { x: position.x, y: position.y, width: end, height: rows }))?;
+                    cells.push((cell, state));
+                }
+            }
+            x = x.saturating_add(run);
+        }
+    }
+    Ok(cells)
+}
+
+/// Parse the width and height from a header line like `x = 3, y = 3, rule = B3/S23`.
+fn parse_header(line: &str) -> Result<(usize, usize), RleError> {
+    let invalid = || RleError::InvalidHeader(line.to_string());
+    let (mut width, mut height) = (None, None);
+
+    for field in line.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => width = Some(value.trim().parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.trim().parse().map_err(|_| invalid())?),
+            // Other fields, such as the rule, don't affect the layout of the pattern
+            _ => {}
+        }
+    }
+
+    Ok((width.ok_or_else(invalid)?, height.ok_or_else(invalid)?))
+}
+
+/// Parse the position from a Golly extension line like `#CXRLE Pos=-3,5 Gen=10`, if it has one.
+fn parse_position(line: &str) -> Result<Option<Position>, RleError> {
+    let invalid = || RleError::InvalidHeader(line.to_string());
+    let Some(field) = line.split_whitespace().find_map(|field| field.strip_prefix("Pos=")) else { return Ok(None) };
+    let (x, y) = field.split_once(',').ok_or_else(invalid)?;
+    Ok(Some(Position::new(x.parse().map_err(|_| invalid())?, y.parse().map_err(|_| invalid())?)))
+}
+
+/// Write the live cells of a region as an RLE pattern, cropped to their bounding box.
+/// Regions don't know the rule they're run under, so the header leaves it out and readers use their current rule.
+pub fn write_rle(region: &Region) -> String {
+    let Some(bounds) = region.live_bounds()
+    else { return "x = 0, y = 0\n!\n".to_string() };
+    write_cells(region, bounds, String::new())
+}
+
+/// Write every cell of a region as an RLE pattern, keeping any dead space around the live cells,
+/// with a Golly `#CXRLE Pos=x,y` line so it is read back in the same place.
+pub fn write_rle_region(region: &Region) -> String {
+    let header = format!("#CXRLE Pos={},{}\n", region.x(), region.y());
+    if region.is_empty() { return header + "x = 0, y = 0\n!\n" }
+    write_cells(region, region.bounds(), header)
+}
+
+/// Append the header and body for the cells of a region within the bounds to the text.
+fn write_cells(region: &Region, bounds: Rect, mut text: String) -> String {
+    let (Position { x: min_x, y: min_y }, Position { x: max_x, y: max_y }) = (bounds.min(), bounds.max());
+    text.push_str(&format!("x = {}, y = {}\n", bounds.width, bounds.height));
+    let mut line_length = 0;
+    let mut push_run = |text: &mut String, run: usize, tag: char| {
+        let token = if run == 1 { tag.to_string() } else { format!("{}{}", run, tag) };
+        if line_length + token.len() > MAX_LINE_LENGTH {
+            text.push('\n');
+            line_length = 0;
+        }
+        line_length += token.len();
+        text.push_str(&token);
+    };
+
+    // Row ends are held back so that runs of empty rows are written as a single count
+    let mut pending_rows = 0;
+    for y in min_y..=max_y {
+        let mut runs: Vec<(usize, Cell)> = vec![];
+        for x in min_x..=max_x {
+            let state = region.get_cell(x, y).expect("Cell X Y position out of bounds");
+            match runs.last_mut() {
+                Some((run, last)) if *last == state => *run += 1,
+                _ => runs.push((1, state))
+            }
+        }
+        // Dead cells at the end of a row are implied
+        if let Some((_, Cell::Dead)) = runs.last() { runs.pop(); }
+
+        if !runs.is_empty() {
+            if pending_rows > 0 { push_run(&mut text, pending_rows, '$') }
+            pending_rows = 0;
+            for (run, state) in runs {
+                push_run(&mut text, run, if state == Cell::Alive { 'o' } else { 'b' });
+            }
+        }
+        pending_rows += 1;
+    }
+    text.push_str("!\n");
+    text
+}
+
+#[cfg(test)]
+mod rle_tests {
+    use super::*;
+    use crate::io::PatternError;
+
+    /// The RLE error reading a pattern failed with.
+    fn rle_error<T>(result: Result<T, GolError>) -> RleError {
+        match result {
+            Err(GolError::Pattern(PatternError::Rle(error))) => error,
+            _ => panic!("Expected an RLE error")
+        }
+    }
+
+    #[test]
+    fn read() {
+        let glider = read_rle("#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n").unwrap();
+        assert_eq!((0, 0, 3, 3), (glider.x(), glider.y(), glider.width(), glider.height()));
+        for x in 0..3 {
+            for y in 0..3 {
+                let alive = matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2));
+                assert_eq!(alive, glider.get_cell(x, y) == Some(Cell::Alive), "cell {} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn read_multi_digit_runs_and_blank_rows() {
+        let region = read_rle("x = 12, y = 4\n12o3$\n11bo!").unwrap();
+        assert_eq!((12, 4), (region.width(), region.height()));
+        assert_eq!(Some(Cell::Alive), region.get_cell(11, 0));
+        assert_eq!(Some(Cell::Dead), region.get_cell(5, 2));
+        assert_eq!(Some(Cell::Alive), region.get_cell(11, 3));
+        assert_eq!(Some(Cell::Dead), region.get_cell(10, 3));
+    }
+
+    #[test]
+    fn read_errors() {
+        assert_eq!(RleError::InvalidHeader("x = three, y = 3".to_string()), rle_error(read_rle("x = three, y = 3\no!")));
+        assert_eq!(RleError::UnexpectedCharacter('z'), rle_error(read_rle("x = 1, y = 1\nz!")));
+
+        // Absurd sizes are rejected before anything is allocated
+        let huge_header = read_rle("x = 4000000000, y = 4000000000\no!");
+        assert_eq!(RleError::InvalidSize(RegionError::TooLarge { width: 4000000000, height: 4000000000 }), rle_error(huge_header));
+        assert!(matches!(rle_error(read_rle("x = 1, y = 1\n99999999999999999999999o!")), RleError::InvalidSize(_)));
+        assert!(matches!(rle_error(read_rle("x = 1, y = 1\n99999999999999999999999$o!")), RleError::InvalidSize(_)));
+        assert!(matches!(rle_error(read_rle_states("99999999999999999999999$o!")), RleError::InvalidSize(_)));
+        assert!(matches!(rle_error(read_rle_states("9999999999999999999bo!")), RleError::InvalidSize(_)));
+    }
+
+    #[test]
+    fn read_states() {
+        let cells = read_rle_states("#CXRLE Pos=-1,0\nx = 5, y = 2, rule = WireWorld\n.B2A$o2.C!").unwrap();
+        let expected = [((0, 0), 2), ((1, 0), 1), ((2, 0), 1), ((-1, 1), 1), ((2, 1), 3)];
+        assert_eq!(expected.map(|(position, state)| (Position::from(position), state)).to_vec(), cells);
+        assert_eq!(RleError::UnexpectedCharacter('Z'), rle_error(read_rle_states("Z!")));
+    }
+
+    #[test]
+    fn write() {
+        let mut region = Region::new(-5, -5, 10, 10).unwrap();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            region.set_cell(x, y, Cell::Alive);
+        }
+        assert_eq!("x = 3, y = 3\nbo$2bo$3o!\n", write_rle(&region));
+
+        let mut gap = Region::new(0, 0, 3, 5).unwrap();
+        gap.set_cell(0, 0, Cell::Alive);
+        gap.set_cell(2, 4, Cell::Alive);
+        assert_eq!("x = 3, y = 5\no4$2bo!\n", write_rle(&gap));
+
+        assert_eq!("x = 0, y = 0\n!\n", write_rle(&Region::new(0, 0, 3, 3).unwrap()));
+    }
+
+    #[test]
+    fn region_round_trip() {
+        let mut region = Region::new(-4, 2, 5, 4).unwrap();
+        region.set_cell(-3, 3, Cell::Alive);
+        region.set_cell(-2, 4, Cell::Alive);
+        let text = write_rle_region(&region);
+        assert_eq!("#CXRLE Pos=-4,2\nx = 5, y = 4\n$bo$2bo!\n", text);
+
+        let back = read_rle(&text).unwrap();
+        assert_eq!((-4, 2, 5, 4), (back.x(), back.y(), back.width(), back.height()));
+        assert_eq!(Some(Cell::Alive), back.get_cell(-2, 4));
+        assert_eq!(RleError::InvalidHeader("#CXRLE Pos=1".to_string()), rle_error(read_rle("#CXRLE Pos=1\no!")));
+    }
+
+    #[test]
+    fn write_wraps_long_lines() {
+        let mut region = Region::new(0, 0, 100, 1).unwrap();
+        for x in (0..100).step_by(2) {
+            region.set_cell(x, 0, Cell::Alive);
+        }
+
+        let text = write_rle(&region);
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        let copy = read_rle(&text).unwrap();
+        for x in 0..100 {
+            assert_eq!(region.get_cell(x, 0).unwrap(), copy.get_cell(x, 0).unwrap_or(Cell::Dead));
+        }
+    }
+}
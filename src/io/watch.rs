@@ -0,0 +1,91 @@
+//! Noticing when a file changes, by polling its modification time and size.
+//! Polling needs no platform specific support, and a pattern file is only checked a few times a second.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::io::STDIN_PATH;
+
+/// Modification time and size of a file, or [`None`] if it doesn't exist.
+/// Editors often save by replacing the file, so it can briefly be missing.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Watches a file for changes since it was last checked.
+#[derive(Debug)]
+pub struct FileWatcher {
+    path: PathBuf,
+    last: Stamp
+}
+
+impl FileWatcher {
+    /// Start watching the file, taking its current contents as seen.
+    /// Standard input, given as [`STDIN_PATH`], can't be watched.
+    pub fn new(path: &Path) -> io::Result<FileWatcher> {
+        if path == Path::new(STDIN_PATH) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "can't watch standard input for changes"))
+        }
+        Ok(FileWatcher { path: path.to_path_buf(), last: stamp(path)? })
+    }
+
+    /// Check if the file has changed since the last check, or since the watcher was created.
+    pub fn changed(&mut self) -> io::Result<bool> {
+        let current = stamp(&self.path)?;
+        if current == self.last { return Ok(false) }
+        self.last = current;
+        // A deleted file is only a change once something is written back in its place
+        Ok(current.is_some())
+    }
+
+    /// Check the file every interval until it changes.
+    pub fn wait_for_change(&mut self, interval: Duration) -> io::Result<()> {
+        while !self.changed()? {
+            thread::sleep(interval);
+        }
+        Ok(())
+    }
+
+    /// Call `run` now, then again every time the file changes, checking every interval. Only returns if checking fails.
+    pub fn run_on_changes(&mut self, interval: Duration, mut run: impl FnMut()) -> io::Result<()> {
+        loop {
+            run();
+            self.wait_for_change(interval)?;
+        }
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+fn stamp(path: &Path) -> io::Result<Stamp> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(Some((metadata.modified()?, metadata.len()))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error)
+    }
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use super::*;
+
+    #[test]
+    fn changes() {
+        let path = std::env::temp_dir().join(format!("rust-gol-watch-{}.rle", std::process::id()));
+        fs::write(&path, "o!").unwrap();
+        let mut watcher = FileWatcher::new(&path).unwrap();
+        assert!(!watcher.changed().unwrap());
+
+        fs::write(&path, "3o!").unwrap();
+        assert!(watcher.changed().unwrap());
+        assert!(!watcher.changed().unwrap());
+
+        // Removing the file isn't a change, but writing it back is
+        fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed().unwrap());
+        fs::write(&path, "2o!").unwrap();
+        watcher.wait_for_change(Duration::from_millis(1)).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
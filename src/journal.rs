@@ -0,0 +1,235 @@
+//! Journal of the edits made to a world while it runs, each tagged with the generation it was made at,
+//! so an interactive session can be reproduced exactly by replaying it over the same starting pattern.
+//!
+//! Journals are saved as text, one edit per line after its generation:
+//! ```text
+//! #J rust-gol edit journal
+//! 12 set 3 -4 alive
+//! 15 rule B36/S23
+//! 20 paste 0 0 3 3 bo$2bo$3o!
+//! ```
+//! A paste gives the -x -y corner and size of the area it replaced, then the RLE body of the pattern.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::GolError;
+use crate::geometry::{Coord, Position};
+use crate::gol::{Cell, GameOfLife, Region};
+use crate::io::rle::{read_rle, write_rle_region};
+use crate::rule::Rule;
+
+/// A single change made to a world by the user, rather than by stepping.
+#[derive(Clone)]
+pub enum Edit {
+    SetCell(Position, Cell),
+    /// A pattern placed in the world, replacing every cell in its region.
+    Paste(Region),
+    Rule(Rule)
+}
+
+impl Edit {
+    /// Make the same change to the world.
+    pub fn apply(&self, world: &mut GameOfLife) {
+        match self {
+            Edit::SetCell(position, state) => world.set_cell(position.x, position.y, *state),
+            Edit::Paste(region) => world.set_region(region),
+            Edit::Rule(rule) => world.set_rule(*rule)
+        }
+    }
+
+    /// The edit that puts back what this one would change, given the world before this one is applied.
+    pub fn inverse(&self, world: &GameOfLife) -> Edit {
+        match self {
+            Edit::SetCell(position, _) => Edit::SetCell(*position, world.get_cell(position.x, position.y)),
+            Edit::Paste(region) => {
+                let mut previous = region.clone();
+                world.populate_region(&mut previous);
+                Edit::Paste(previous)
+            }
+            Edit::Rule(_) => Edit::Rule(world.rule())
+        }
+    }
+}
+
+/// Edits in the order they were made, each with the generation the world was at.
+#[derive(Clone, Default)]
+pub struct Journal {
+    edits: Vec<(u64, Edit)>
+}
+
+impl Journal {
+    pub fn new() -> Journal {
+        Journal::default()
+    }
+
+    /// Record an edit made at the given generation. Edits must be recorded in the order they happen,
+    /// so the generation can't be earlier than the last recorded edit's; use [`Journal::forget_after`] when going back.
+    pub fn record(&mut self, generation: u64, edit: Edit) {
+        debug_assert!(self.last_generation().is_none_or(|last| last <= generation), "Edits recorded out of order");
+        self.edits.push((generation, edit));
+    }
+
+    /// Forget every edit made after the given generation, such as when the world is stepped back to it.
+    pub fn forget_after(&mut self, generation: u64) {
+        self.edits.retain(|&(edited_at, _)| edited_at <= generation);
+    }
+
+    /// Step the world from generation 0 up to the given generation,
+    /// making each edit at the generation it was originally made.
+    pub fn replay(&self, world: &mut GameOfLife, generations: u64) {
+        let mut edits = self.edits.iter().peekable();
+        for generation in 0..=generations {
+            while let Some((_, edit)) = edits.next_if(|(edited_at, _)| *edited_at == generation) {
+                edit.apply(world);
+            }
+            if generation < generations { world.step(); }
+        }
+    }
+
+    /// Generation of the last edit, or [`None`] if there are none.
+    pub fn last_generation(&self) -> Option<u64> {
+        self.edits.last().map(|&(generation, _)| generation)
+    }
+
+    pub fn edits(&self) -> &[(u64, Edit)] { &self.edits }
+    pub fn len(&self) -> usize { self.edits.len() }
+    pub fn is_empty(&self) -> bool { self.edits.is_empty() }
+}
+
+impl Display for Journal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "#J rust-gol edit journal")?;
+        for (generation, edit) in &self.edits {
+            match edit {
+                Edit::SetCell(position, state) => {
+                    writeln!(f, "{} set {} {}", generation, position, if *state == Cell::Alive { "alive" } else { "dead" })?
+                }
+                Edit::Paste(region) => writeln!(f, "{} paste {}", generation, write_placed(region))?,
+                Edit::Rule(rule) => writeln!(f, "{} rule {}", generation, rule)?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Journal {
+    type Err = GolError;
+
+    fn from_str(s: &str) -> Result<Journal, GolError> {
+        Ok(Journal::parse(s)?)
+    }
+}
+
+impl Journal {
+    /// Parse a journal, with errors giving the line they are on so a recipe can count on from its own lines.
+    pub(crate) fn parse(s: &str) -> Result<Journal, JournalError> {
+        let mut journal = Journal::new();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+            let invalid = || JournalError { line: index + 1, text: line.to_string() };
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let generation = words[0].parse().map_err(|_| invalid())?;
+            let number = |word: &str| word.parse::<Coord>().map_err(|_| invalid());
+            let edit = match words[1..] {
+                ["set", x, y, "alive"] => Edit::SetCell(Position::new(number(x)?, number(y)?), Cell::Alive),
+                ["set", x, y, "dead"] => Edit::SetCell(Position::new(number(x)?, number(y)?), Cell::Dead),
+                ["rule", rule] => Edit::Rule(rule.parse().map_err(|_| invalid())?),
+                ["paste", ref placed @ ..] => Edit::Paste(read_placed(placed).ok_or_else(invalid)?),
+                _ => return Err(invalid())
+            };
+            if journal.last_generation().is_some_and(|last| last > generation) { return Err(invalid()) }
+            journal.record(generation, edit);
+        }
+        Ok(journal)
+    }
+}
+
+/// Write a region on one line as its -x -y corner, size and RLE body, so it is read back in the same place.
+pub(crate) fn write_placed(region: &Region) -> String {
+    // Only the body, as the position and size are given separately
+    let rle = write_rle_region(region);
+    let body: String = rle.lines().filter(|line| !line.starts_with('#') && !line.starts_with('x')).collect();
+    format!("{} {} {} {} {}", region.x(), region.y(), region.width(), region.height(), body)
+}
+
+/// Read a region written by [`write_placed`], already split into words.
+pub(crate) fn read_placed(words: &[&str]) -> Option<Region> {
+    let [x, y, width, height, body] = words else { return None };
+    let mut region = read_rle(&format!("x = {}, y = {}\n{}", width, height, body)).ok()?;
+    region.set_position(x.parse().ok()?, y.parse().ok()?).ok()?;
+    Some(region)
+}
+
+/// Error from reading a journal, with the line number, counting from 1, and text of the line that couldn't be understood.
+#[derive(Debug, PartialEq)]
+pub struct JournalError {
+    pub line: usize,
+    pub text: String
+}
+
+impl Display for JournalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid journal entry on line {}: {}", self.line, self.text)
+    }
+}
+
+impl std::error::Error for JournalError {}
+
+#[cfg(test)]
+mod journal_tests {
+    use super::*;
+
+    #[test]
+    fn replay() {
+        let mut journal = Journal::new();
+        let mut live = GameOfLife::new();
+        // Make edits while stepping, as a frontend would
+        for generation in 0..6 {
+            let edit = match generation {
+                0 => Some(Edit::SetCell(Position::new(0, 0), Cell::Alive)),
+                2 => Some(Edit::Paste(read_rle("3o!").unwrap())),
+                4 => Some(Edit::Rule("B36/S23".parse().unwrap())),
+                _ => None
+            };
+            if let Some(edit) = edit {
+                edit.apply(&mut live);
+                journal.record(generation, edit);
+            }
+            live.step();
+        }
+
+        let mut replayed = GameOfLife::new();
+        journal.replay(&mut replayed, 6);
+        let (mut expected, mut actual) = (live.live_cells(), replayed.live_cells());
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+        assert_eq!(live.rule(), replayed.rule());
+
+        journal.forget_after(3);
+        assert_eq!((2, Some(2)), (journal.len(), journal.last_generation()));
+    }
+
+    #[test]
+    fn text() {
+        let mut journal = Journal::new();
+        journal.record(12, Edit::SetCell(Position::new(3, -4), Cell::Alive));
+        journal.record(15, Edit::Rule("B36/S23".parse().unwrap()));
+        let mut glider = read_rle("bo$2bo$3o!").unwrap();
+        glider.set_position(-1, 2).unwrap();
+        journal.record(20, Edit::Paste(glider));
+
+        let text = journal.to_string();
+        assert_eq!("#J rust-gol edit journal\n12 set 3 -4 alive\n15 rule B36/S23\n20 paste -1 2 3 3 bo$2bo$3o!\n", text);
+        let read: Journal = text.parse().unwrap();
+        assert_eq!(text, read.to_string());
+
+        assert_eq!(Err(JournalError { line: 2, text: "3 jump".to_string() }), Journal::parse("#J\n3 jump").map(|_| ()));
+        assert!(matches!("#J\n3 jump".parse::<Journal>(), Err(GolError::Journal(JournalError { line: 2, .. }))));
+        // Edits out of order can't have been recorded
+        assert!("5 rule B3/S23\n4 rule B3/S23".parse::<Journal>().is_err());
+    }
+}
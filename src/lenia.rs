@@ -0,0 +1,165 @@
+//! Experimental continuous automata in the style of Lenia and SmoothLife, where every cell holds a state from 0 to 1
+//! and grows or shrinks by a smooth function of a weighted average over a wide neighbourhood. Patterns spread thin
+//! haze far past their edges, so rather than an infinite world the cells are kept on a fixed grid that wraps around
+//! at its edges. States are drawn and exported through the usual renderers and writers by quantising them.
+
+use crate::geometry::{Coord, Position};
+use crate::gol::{Cell, GameOfLife, MAX_REGION_CELLS, RegionError};
+use crate::render::{heat_colour, Palette, DEAD_RGB};
+use crate::rule::LeniaRule;
+
+/// Number of levels states are quantised to for drawing, with level 0 for cells at exactly 0.
+pub const LEVELS: usize = 256;
+
+/// Grid of continuous cells following a Lenia rule, wrapping around at its edges.
+#[derive(Debug, Clone)]
+pub struct LeniaWorld {
+    width: usize,
+    height: usize,
+    /// State of every cell, in rows.
+    cells: Vec<f64>,
+    rule: LeniaRule,
+    /// Offset to every cell within the radius with its weight, the weights adding up to 1.
+    kernel: Vec<(Coord, Coord, f64)>,
+    generation: u64
+}
+
+impl LeniaWorld {
+    /// Create a grid of cells all at 0, at least 1 by 1 and failing if it would hold more than
+    /// [`MAX_REGION_CELLS`] cells.
+    pub fn new(rule: LeniaRule, width: usize, height: usize) -> Result<LeniaWorld, RegionError> {
+        let (width, height) = (width.max(1), height.max(1));
+        if width.checked_mul(height).is_none_or(|cells| cells > MAX_REGION_CELLS) {
+            return Err(RegionError::TooLarge { width, height })
+        }
+        Ok(LeniaWorld { width, height, cells: vec![0.0; width * height], rule, kernel: kernel(&rule), generation: 0 })
+    }
+
+    /// Create a grid of the given size with the live cells of a two-state world in it at 1, taking the cell at x y
+    /// as the grid's corner.
+    pub fn from_world(world: &GameOfLife, rule: LeniaRule, x: Coord, y: Coord, width: usize, height: usize) -> Result<LeniaWorld, RegionError> {
+        let mut lenia = LeniaWorld::new(rule, width, height)?;
+        for position in world.live_cells() {
+            let (column, row) = (position.x - x, position.y - y);
+            if (0..lenia.width as Coord).contains(&column) && (0..lenia.height as Coord).contains(&row) {
+                lenia.set_value(column, row, 1.0);
+            }
+        }
+        Ok(lenia)
+    }
+
+    /// Step every cell to its next state by the weighted average of the cells around it.
+    pub fn step(&mut self) {
+        let mut next = Vec::with_capacity(self.cells.len());
+        for y in 0..self.height as Coord {
+            for x in 0..self.width as Coord {
+                let potential = self.kernel.iter().map(|&(x_off, y_off, weight)| weight * self.value(x + x_off, y + y_off)).sum();
+                next.push(self.rule.next_state(self.value(x, y), potential));
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// Returns the state of the cell at the position, wrapping around the edges.
+    pub fn value(&self, x: Coord, y: Coord) -> f64 {
+        self.cells[self.index(x, y)]
+    }
+
+    /// Set the state of the cell at the position, wrapping around the edges and clamping the state from 0 to 1.
+    pub fn set_value(&mut self, x: Coord, y: Coord, value: f64) {
+        let index = self.index(x, y);
+        self.cells[index] = value.clamp(0.0, 1.0);
+    }
+
+    fn index(&self, x: Coord, y: Coord) -> usize {
+        y.rem_euclid(self.height as Coord) as usize * self.width + x.rem_euclid(self.width as Coord) as usize
+    }
+
+    /// Total of every cell's state, the continuous counterpart of population.
+    pub fn mass(&self) -> f64 {
+        self.cells.iter().sum()
+    }
+
+    /// State of the cell at the position quantised to one of [`LEVELS`], for drawing with
+    /// [`rgb_image_of_states`](crate::render::rgb_image_of_states) and the [`palette`](LeniaWorld::palette).
+    /// Only cells at exactly 0 are level 0, so faint haze still shows.
+    pub fn level(&self, position: Position) -> u8 {
+        let value = self.value(position.x, position.y);
+        if value <= 0.0 { 0 } else { (value * (LEVELS - 1) as f64).ceil() as u8 }
+    }
+
+    /// Colours to draw each level with, from the dead colour for level 0 up the heat gradient to level 255.
+    pub fn palette() -> Palette {
+        let colours = (1..LEVELS).map(|level| heat_colour(level as f32 / (LEVELS - 1) as f32));
+        Palette::new(std::iter::once(DEAD_RGB).chain(colours).collect())
+    }
+
+    /// Copy the grid into a two-state world, with the cells at or above the threshold alive,
+    /// so it can be saved with the usual pattern writers.
+    pub fn to_world(&self, threshold: f64) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for (index, &value) in self.cells.iter().enumerate() {
+            if value >= threshold { world.set_cell((index % self.width) as Coord, (index / self.width) as Coord, Cell::Alive) }
+        }
+        world
+    }
+
+    // GETTERS
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn rule(&self) -> &LeniaRule { &self.rule }
+    pub fn generation(&self) -> u64 { self.generation }
+}
+
+/// Offsets to every cell within the rule's radius with their weights, scaled so the weights add up to 1.
+fn kernel(rule: &LeniaRule) -> Vec<(Coord, Coord, f64)> {
+    let radius = rule.radius() as Coord;
+    let mut kernel: Vec<(Coord, Coord, f64)> = (-radius..=radius)
+        .flat_map(|y| (-radius..=radius).map(move |x| (x, y)))
+        .map(|(x, y)| (x, y, rule.kernel(((x * x + y * y) as f64).sqrt() / radius as f64)))
+        .filter(|&(_, _, weight)| weight > 0.0)
+        .collect();
+    let total: f64 = kernel.iter().map(|&(_, _, weight)| weight).sum();
+    kernel.iter_mut().for_each(|(_, _, weight)| *weight /= total);
+    kernel
+}
+
+#[cfg(test)]
+mod lenia_tests {
+    use super::*;
+    use crate::render::rgb_image_of_states;
+
+    #[test]
+    fn uniform_growth() {
+        // Kernel weights add up to 1, so a grid all at the growth curve's peak grows by the full step everywhere
+        let rule = LeniaRule::ORBIUM;
+        assert!((kernel(&rule).iter().map(|&(_, _, weight)| weight).sum::<f64>() - 1.0).abs() < 1e-9);
+        let mut world = LeniaWorld::new(rule, 32, 32).unwrap();
+        for (x, y) in (0..32).flat_map(|y| (0..32).map(move |x| (x, y))) {
+            world.set_value(x, y, rule.mu());
+        }
+        world.step();
+        assert!((world.value(-1, 40) - 0.25).abs() < 1e-9);
+
+        // Anything far from the peak shrinks, and an empty grid stays empty
+        world.step();
+        assert!((world.value(5, 5) - 0.15).abs() < 1e-9);
+        let mut empty = LeniaWorld::new(rule, 16, 16).unwrap();
+        empty.step();
+        assert_eq!((0.0, 1), (empty.mass(), empty.generation()));
+    }
+
+    #[test]
+    fn quantised_render() {
+        let mut world = LeniaWorld::new(LeniaRule::ORBIUM, 4, 1).unwrap();
+        world.set_value(1, 0, 0.001);
+        world.set_value(2, 0, 2.0);
+        assert_eq!([0, 1, 255], [0, 1, 2].map(|x| world.level(Position::new(x, 0))));
+
+        let pixels = rgb_image_of_states(0, 0, 3, 1, 1, &LeniaWorld::palette(), |position| world.level(position));
+        assert_eq!(DEAD_RGB, pixels[0..3]);
+        assert_ne!(DEAD_RGB, pixels[3..6]);
+        assert_eq!(vec![Position::new(2, 0)], world.to_world(0.5).live_cells());
+    }
+}
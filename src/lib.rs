@@ -0,0 +1,94 @@
+//! Conway's Game of Life, written in rust.
+//! The engine, file formats and frontends, as used by the `gol` binary.
+//! The types most uses of the engine start from are re-exported here, with the rest in their modules.
+//! Without the default `std` feature only the engine modules are built, on `alloc`, for `no_std` targets.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod ant;
+#[cfg(feature = "std")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod census;
+#[cfg(feature = "std")]
+pub mod channel;
+pub mod chunk;
+#[cfg(feature = "std")]
+pub mod clipboard;
+#[cfg(feature = "std")]
+pub mod coloured;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod elementary;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod explore;
+#[cfg(feature = "std")]
+pub mod frame;
+#[cfg(feature = "std")]
+pub mod generations;
+pub mod geometry;
+pub mod gol;
+#[cfg(feature = "std")]
+pub mod gol3d;
+#[cfg(feature = "std")]
+pub mod gpu;
+#[cfg(feature = "std")]
+pub mod gui;
+#[cfg(feature = "std")]
+pub mod history;
+pub mod hooks;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod journal;
+#[cfg(feature = "std")]
+pub mod lenia;
+#[cfg(feature = "std")]
+pub mod ltl;
+#[cfg(feature = "std")]
+pub mod margolus;
+#[cfg(feature = "std")]
+pub mod predecessor;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "std")]
+pub mod recipe;
+#[cfg(feature = "std")]
+pub mod render;
+pub mod rule;
+#[cfg(feature = "std")]
+pub mod runner;
+#[cfg(feature = "std")]
+pub mod sampler;
+#[cfg(feature = "std")]
+pub mod soup;
+#[cfg(feature = "std")]
+pub mod state;
+#[cfg(feature = "std")]
+pub mod stochastic;
+#[cfg(feature = "std")]
+pub mod ticker;
+pub mod topology;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod tui;
+#[cfg(feature = "std")]
+pub mod undo;
+#[cfg(feature = "std")]
+pub mod view;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod window;
+#[cfg(feature = "std")]
+pub mod wireworld;
+
+pub use error::GolError;
+pub use gol::{Cell, GameOfLife, Region};
+pub use rule::Rule;
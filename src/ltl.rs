@@ -0,0 +1,156 @@
+//! Worlds under Larger than Life rules, whose neighbourhoods reach further than the 8 cells around each cell.
+//! Counting every cell in a square of radius 5 for every cell would take 120 lookups each, so instead the live cells
+//! are summed into a table of running totals once per step, from which any square's count takes four lookups.
+
+use std::collections::HashSet;
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+use crate::rule::LtlRule;
+
+/// Infinite world of cells following a Larger than Life rule.
+#[derive(Debug, Clone)]
+pub struct LtlWorld {
+    cells: HashSet<Position>,
+    rule: LtlRule
+}
+
+impl LtlWorld {
+    /// Create a new empty world using the given rule.
+    pub fn new(rule: LtlRule) -> LtlWorld {
+        LtlWorld { cells: HashSet::new(), rule }
+    }
+
+    /// Create a world with the live cells of a world under an ordinary rule, under the given rule.
+    pub fn from_world(world: &GameOfLife, rule: LtlRule) -> LtlWorld {
+        LtlWorld { cells: world.live_cells().into_iter().collect(), rule }
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    pub fn step(&mut self) -> StepStats {
+        let mut stats = StepStats::default();
+        // Only cells within the radius of a live cell can have any live cells in their square
+        let Some(bounds) = Rect::around(self.cells.iter().copied()) else { return stats };
+        let area = bounds.expand(self.rule.radius());
+        let totals = SummedArea::new(area, &self.cells);
+
+        let mut next = HashSet::with_capacity(self.cells.len());
+        for position in area.positions() {
+            let current = if self.cells.contains(&position) { Cell::Alive } else { Cell::Dead };
+            let square = Rect::new(position.x, position.y, 1, 1).expand(self.rule.radius());
+            let neighbours = totals.count(square) - (current == Cell::Alive) as usize;
+            let cell = self.rule.next_state(current, neighbours);
+            match (current, cell) {
+                (Cell::Dead, Cell::Alive) => stats.births += 1,
+                (Cell::Alive, Cell::Dead) => stats.deaths += 1,
+                _ => {}
+            }
+            if cell == Cell::Alive { next.insert(position); }
+        }
+        stats.population = next.len();
+        self.cells = next;
+        stats
+    }
+
+    /// Get the state of the cell at the position.
+    pub fn get_cell(&self, position: Position) -> Cell {
+        if self.cells.contains(&position) { Cell::Alive } else { Cell::Dead }
+    }
+
+    /// Set the state of the cell at the position.
+    pub fn set_cell(&mut self, position: Position, state: Cell) {
+        match state {
+            Cell::Alive => self.cells.insert(position),
+            Cell::Dead => self.cells.remove(&position)
+        };
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Positions of the live cells, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// Copy the live cells into a world under Conway's Life, such as to save or draw them.
+    pub fn to_world(&self) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for position in self.live_cells() {
+            world.set_cell(position.x, position.y, Cell::Alive);
+        }
+        world
+    }
+
+    pub fn rule(&self) -> LtlRule { self.rule }
+}
+
+/// Live cells counted over an area, as the number in the rectangle from the area's -x -y corner to each cell,
+/// so the count in any rectangle comes from the totals at its corners.
+struct SummedArea {
+    area: Rect,
+    /// Totals with an extra row and column of zeros before the area, row by row.
+    totals: Vec<usize>
+}
+
+impl SummedArea {
+    fn new(area: Rect, cells: &HashSet<Position>) -> SummedArea {
+        let stride = area.width + 1;
+        let mut totals = vec![0; stride * (area.height + 1)];
+        for y in 0..area.height {
+            let mut row = 0;
+            for x in 0..area.width {
+                if cells.contains(&Position::new(area.x + x as Coord, area.y + y as Coord)) { row += 1 }
+                totals[(y + 1) * stride + x + 1] = totals[y * stride + x + 1] + row;
+            }
+        }
+        SummedArea { area, totals }
+    }
+
+    /// Live cells in the part of the rectangle inside the area.
+    fn count(&self, rect: Rect) -> usize {
+        let Some(rect) = rect.intersection(&self.area) else { return 0 };
+        let stride = self.area.width + 1;
+        let (x, y) = ((rect.x - self.area.x) as usize, (rect.y - self.area.y) as usize);
+        let total = |x: usize, y: usize| self.totals[y * stride + x];
+        total(x + rect.width, y + rect.height) + total(x, y) - total(x + rect.width, y) - total(x, y + rect.height)
+    }
+}
+
+#[cfg(test)]
+mod ltl_tests {
+    use super::*;
+    use crate::io::rle::read_rle;
+
+    #[test]
+    fn matches_life() {
+        // With a radius of 1 and Life's counts, a Larger than Life world steps just like the main engine
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("b2o$2o$bo!").unwrap());
+        let mut ltl = LtlWorld::from_world(&world, "R1,C0,M0,S2..3,B3..3,NM".parse().unwrap());
+        for _ in 0..20 {
+            assert_eq!(world.step(), ltl.step());
+        }
+        let (mut expected, mut actual): (Vec<Position>, Vec<Position>) = (world.live_cells(), ltl.live_cells().collect());
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn radius() {
+        // A lone cell that counts itself survives, and every cell within two of it sees it and is born
+        let mut world = LtlWorld::new("R2,C0,M1,S1..1,B1..1,NM".parse().unwrap());
+        world.set_cell(Position::ORIGIN, Cell::Alive);
+        assert_eq!(StepStats { births: 24, deaths: 0, population: 25 }, world.step());
+        assert_eq!(Cell::Alive, world.get_cell(Position::new(-2, 2)));
+        assert_eq!(Cell::Dead, world.get_cell(Position::new(3, 0)));
+
+        // Bosco's rule clears away a small blob
+        let mut world = LtlWorld::new(LtlRule::BOSCO);
+        world.set_cell(Position::ORIGIN, Cell::Alive);
+        assert_eq!(StepStats { births: 0, deaths: 1, population: 0 }, world.step());
+        assert_eq!(StepStats::default(), world.step());
+    }
+}
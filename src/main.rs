@@ -1,10 +1,621 @@
-use crate::gol::GameOfLife;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-mod gol;
+use clap::{Parser, Subcommand};
+
+use rust_gol::analysis::{detect_recurrence, settle, symmetry, CycleDetector};
+use rust_gol::bench::{Backend, Measurement, Workload};
+use rust_gol::census::Census;
+use rust_gol::config::Config;
+use rust_gol::elementary::{self, Row};
+use rust_gol::explore::{fingerprint, FingerprintSettings};
+use rust_gol::gol::{GameOfLife, TrimPolicy, Transform};
+use rust_gol::io::colors::load_palette;
+use rust_gol::io::frames::{FrameExporter, FrameOutput, FrameViewport};
+use rust_gol::io::output::{write_image, write_thumbnail, write_world, ImageFormat, OutputFormat};
+use rust_gol::io::watch::FileWatcher;
+use rust_gol::io::{read_pattern, read_world, rle, write_pattern};
+use rust_gol::journal::Journal;
+use rust_gol::recipe::{Recipe, Start};
+use rust_gol::render::{CellAges, Heatmap, Shading};
+#[cfg(any(feature = "window", feature = "gpu", feature = "gui"))]
+use rust_gol::render::Palette;
+use rust_gol::render::theme::Theme;
+use rust_gol::rule::{ElementaryRule, Rule};
+use rust_gol::runner::{self, RunSettings};
+use rust_gol::topology::Topology;
+use rust_gol::tui;
+#[cfg(feature = "gpu")]
+use rust_gol::gpu;
+#[cfg(feature = "gui")]
+use rust_gol::gui;
+#[cfg(feature = "window")]
+use rust_gol::window;
+
+/// How often a watched pattern file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+/// How often headless runs print their progress.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Conway's Game of Life, written in rust.
+#[derive(Parser)]
+#[command(name = "gol", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Config file to read settings from, instead of rust-gol/config.toml in the user's config directory.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a pattern for a number of generations and output the final state.
+    Run {
+        /// Pattern file to start from (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Number of generations to step.
+        #[arg(short, long, default_value_t = 0)]
+        generations: usize,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// File to write the final state to, instead of printing it.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Output format: rle, cells, pbm, text, blocks, braille or hex.
+        /// Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<OutputFormat>,
+        /// Directory to save RLE snapshots to while running, without pausing the simulation.
+        #[arg(long)]
+        archive_dir: Option<PathBuf>,
+        /// Seconds between snapshots saved to the archive directory.
+        #[arg(long, default_value_t = 60.0, requires = "archive_dir")]
+        archive_every: f64,
+        /// Print summary statistics instead of the final state, which is only written to the output file.
+        /// Progress is printed to stderr every few seconds while running.
+        #[arg(long)]
+        headless: bool,
+        /// Space to run in: plane, or torus:WxH for a torus of that many cells centred on 0 0, wrapping around at its edges.
+        #[arg(long, default_value_t = Topology::Plane, conflicts_with = "archive_dir")]
+        topology: Topology,
+        /// When regions shrink away from dead space: never, aggressive, or a number of dead cells past the buffer to allow.
+        #[arg(long, default_value_t = TrimPolicy::Aggressive)]
+        trim: TrimPolicy,
+        /// Keep watching the pattern file, running again from its new contents every time it is saved.
+        #[arg(short, long)]
+        watch: bool,
+        /// Stop early once the world becomes a still life or repeats itself, with at most this period if given.
+        /// Without a period, cycles of any length are found, but only some generations after they start.
+        /// The number of generations is then the most that will be stepped.
+        #[arg(long, num_args = 0..=1, conflicts_with = "archive_dir")]
+        until_stable: Option<Option<usize>>
+    },
+    /// Render a pattern, optionally after some generations, to an image.
+    Render {
+        /// Pattern file to start from (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Image file to write.
+        out: PathBuf,
+        /// Number of generations to step before rendering.
+        #[arg(short, long, default_value_t = 0)]
+        generations: usize,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Pixels along each side of a cell.
+        #[arg(short, long, default_value_t = 4)]
+        scale: usize,
+        /// Image format: pbm, ppm or png, if built with it. Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<ImageFormat>,
+        /// Golly .colors file for the cell colours. Defaults to a .colors file beside the pattern, if there is one.
+        #[arg(long)]
+        colors: Option<PathBuf>,
+        /// Colour live cells by how many generations they have been alive, from hot yellow to ash. Not used for PBM.
+        #[arg(long)]
+        age: bool,
+        /// Colour cells by how many times they changed in the last N generations, showing where the pattern is
+        /// still busy. Cells that didn't change keep their usual colours. Not used for PBM.
+        #[arg(long, value_name = "N", conflicts_with = "age")]
+        heatmap: Option<usize>
+    },
+    /// Write an image of a pattern every few generations, for assembling into a video with a tool like ffmpeg.
+    Frames {
+        /// Pattern file to start from (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Directory to write numbered frames to, such as frame_000042.ppm, or - to write the raw 8-bit RGB pixels
+        /// of each frame to standard output, for `ffmpeg -f rawvideo -pix_fmt rgb24`.
+        out: PathBuf,
+        /// Number of generations to step.
+        #[arg(short, long, default_value_t = 100)]
+        generations: usize,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Generations between frames, after the first frame of the starting pattern.
+        #[arg(long, default_value_t = 1)]
+        every: usize,
+        /// Pixels along each side of a cell.
+        #[arg(short, long, default_value_t = 4)]
+        scale: usize,
+        /// Image format of the numbered frames: ppm, or png if built with it.
+        #[arg(short, long, default_value_t = ImageFormat::Ppm)]
+        format: ImageFormat,
+        /// Dead cells to leave around the starting pattern in each frame.
+        #[arg(long, default_value_t = 8)]
+        margin: usize,
+        /// Keep each frame centred on the live cells as they move, instead of fixed where the pattern started.
+        #[arg(long)]
+        track: bool
+    },
+    /// Draw a small preview of a pattern, cropped to its live cells, with cells averaged together to fit if it is large.
+    Thumbnail {
+        /// Pattern file to preview (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Image file to write, instead of printing the preview in shaded block characters.
+        out: Option<PathBuf>,
+        /// Most pixels, or characters, along either side.
+        #[arg(short, long, default_value_t = 32)]
+        size: usize,
+        /// Image format: ppm, or png if built with it. Worked out from the output file's extension if not given.
+        #[arg(short, long, requires = "out")]
+        format: Option<ImageFormat>
+    },
+    /// Convert a pattern file to a different format.
+    Convert {
+        /// Pattern file to read (.rle or .cells, or - for standard input).
+        input: PathBuf,
+        /// File to write.
+        output: PathBuf,
+        /// Output format: rle, cells, pbm, text, blocks, braille or hex.
+        /// Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<OutputFormat>
+    },
+    /// Rotate, flip, pad and recenter a pattern, applied in that order.
+    Transform {
+        /// Pattern file to read (.rle or .cells, or - for standard input).
+        input: PathBuf,
+        /// Degrees to rotate clockwise, a multiple of 90.
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        rotate: i32,
+        /// Mirror left to right.
+        #[arg(long)]
+        flip_x: bool,
+        /// Mirror top to bottom.
+        #[arg(long)]
+        flip_y: bool,
+        /// Dead cells to add on every side.
+        #[arg(long, default_value_t = 0)]
+        pad: usize,
+        /// Move the pattern so its centre is at 0 0.
+        #[arg(long)]
+        recenter: bool,
+        /// File to write, instead of printing it as RLE. RLE output keeps the padding and position.
+        #[arg(short, long)]
+        out: Option<PathBuf>
+    },
+    /// Time how quickly patterns can be stepped on each backend, printing generations per second.
+    Bench {
+        /// Pattern file to time (.rle or .cells, or - for standard input), instead of the standard workloads:
+        /// R-pentomino for 10000 generations, a 1024x1024 random soup for 100 and a glider gun for 2000.
+        pattern: Option<PathBuf>,
+        /// Number of generations to step, instead of 1000 for a pattern file or each workload's own number.
+        #[arg(short, long)]
+        generations: Option<u64>,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Only time this backend: direct or runner.
+        #[arg(short, long)]
+        backend: Option<Backend>
+    },
+    /// Run a pattern interactively in the terminal.
+    Play {
+        /// Pattern file to start from (.rle or .cells, or - for standard input). A random soup is used if not given.
+        pattern: Option<PathBuf>,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Seed for the random soup, when no pattern is given.
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// File to save the journal of edits made during the session to, for `gol replay`.
+        #[arg(long)]
+        record: Option<PathBuf>,
+        /// File to save a recipe of the session to, with its starting pattern or soup seed, for `gol reproduce`.
+        #[arg(long)]
+        recipe: Option<PathBuf>
+    },
+    /// Reproduce a session by stepping a pattern and making the edits from its journal at the same generations.
+    Replay {
+        /// Pattern file the session started from (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Journal of edits saved by `gol play --record`.
+        journal: PathBuf,
+        /// Number of generations to step, instead of stopping at the last edit.
+        #[arg(short, long)]
+        generations: Option<u64>,
+        /// Rulestring the session started with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// File to write the final state to, instead of printing it.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Output format: rle, cells, pbm, text, blocks, braille or hex.
+        /// Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<OutputFormat>
+    },
+    /// Reproduce a run exactly from a recipe saved by `gol play --recipe`.
+    Reproduce {
+        /// Recipe of the run, with what it started from, its rule and the edits made.
+        recipe: PathBuf,
+        /// Number of generations to step, instead of as many as the run went.
+        #[arg(short, long)]
+        generations: Option<u64>,
+        /// File to write the final state to, instead of printing it.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Output format: rle, cells, pbm, text, blocks, braille or hex.
+        /// Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<OutputFormat>
+    },
+    /// Run a pattern in a window, drawing cells as pixels.
+    #[cfg(feature = "window")]
+    Window {
+        /// Pattern file to start from (.rle or .cells, or - for standard input). A random soup is used if not given.
+        pattern: Option<PathBuf>,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Seed for the random soup, when no pattern is given.
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Golly .colors file for the cell colours.
+        #[arg(long)]
+        colors: Option<PathBuf>
+    },
+    /// Run a pattern in a window drawn on the GPU, for worlds with millions of live cells.
+    #[cfg(feature = "gpu")]
+    Gpu {
+        /// Pattern file to start from (.rle or .cells, or - for standard input). A random soup is used if not given.
+        pattern: Option<PathBuf>,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Seed for the random soup, when no pattern is given.
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Golly .colors file for the cell colours.
+        #[arg(long)]
+        colors: Option<PathBuf>
+    },
+    /// Run a pattern in a window with a control panel, rule picker and pattern palette.
+    #[cfg(feature = "gui")]
+    Gui {
+        /// Pattern file to start from (.rle or .cells, or - for standard input). A random soup is used if not given.
+        pattern: Option<PathBuf>,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Seed for the random soup, when no pattern is given.
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Golly .colors file for the cell colours.
+        #[arg(long)]
+        colors: Option<PathBuf>
+    },
+    /// Work out whether a pattern is a still life, an oscillator or a spaceship, with its period and speed,
+    /// and the symmetry of the pattern as given.
+    Identify {
+        /// Pattern file to identify (.rle or .cells, or - for standard input).
+        pattern: PathBuf,
+        /// Most generations to wait for the pattern to recur.
+        #[arg(short, long, default_value_t = 1000)]
+        generations: u64,
+        /// Rulestring to simulate with, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule
+    },
+    /// Run a pattern until it settles, then count the objects left, such as blocks, blinkers and gliders.
+    Census {
+        /// Pattern file to start from (.rle or .cells, or - for standard input). A random 16 by 16 soup is used if not given.
+        pattern: Option<PathBuf>,
+        /// Most generations to wait for the pattern to settle before counting anyway.
+        #[arg(short, long, default_value_t = 10000)]
+        generations: u64,
+        /// Rulestring to simulate with, such as B36/S23. Objects are only named under B3/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Seed for the random soup, when no pattern is given.
+        #[arg(long, default_value_t = 1)]
+        seed: u64
+    },
+    /// Run a one dimensional elementary automaton from a single live cell, drawing each generation as a row below the last.
+    Elementary {
+        /// Wolfram rule number, from 0 to 255, such as 30 or 110.
+        rule: ElementaryRule,
+        /// Number of generations to step.
+        #[arg(short, long, default_value_t = 32)]
+        generations: usize,
+        /// File to write the generations to, instead of printing them.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Output format: rle, cells, pbm, text, blocks, braille or hex.
+        /// Worked out from the output file's extension if not given.
+        #[arg(short, long)]
+        format: Option<OutputFormat>
+    },
+    /// Measure the statistical fingerprint of a rule from random soups.
+    Analyze {
+        /// Rulestring to analyze, such as B36/S23.
+        #[arg(short, long, default_value_t = Rule::LIFE)]
+        rule: Rule,
+        /// Number of soups to run.
+        #[arg(long, default_value_t = FingerprintSettings::default().soups)]
+        soups: usize,
+        /// Seed for the first soup.
+        #[arg(long, default_value_t = FingerprintSettings::default().seed)]
+        seed: u64
+    }
+}
 
 fn main() {
+    let cli = Cli::parse();
+    let config = match &cli.config {
+        Some(path) => Config::load(path),
+        None => Config::load_default()
+    };
+    let theme = match config {
+        Ok(config) => config.theme,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match cli.command {
+        Command::Run { pattern, generations, rule, out, format, archive_dir, archive_every, headless, trim, watch, until_stable, topology } => {
+            let archive = archive_dir.map(|dir| (dir, Duration::from_secs_f64(archive_every)));
+            let settings = RunSettings { generations: generations as u64, until_stable, archive, topology, progress_interval: PROGRESS_INTERVAL };
+            let format = OutputFormat::or_from_path(format, out.as_deref());
+            let run_once = || run(&pattern, rule, trim, &settings, headless, out.as_deref(), format, &theme);
+            match watch {
+                true => run_watched(&pattern, run_once),
+                false => run_once()
+            }
+        }
+        Command::Render { pattern, out, generations, rule, scale, format, colors, age, heatmap } => {
+            let shading = match (age, heatmap) {
+                (true, _) => Shading::Age(CellAges::new()),
+                (false, Some(window)) => Shading::Heat(Heatmap::new(window)),
+                (false, None) => Shading::Plain
+            };
+            render(&pattern, &out, generations, rule, scale, format, colors.as_deref(), shading, &theme)
+        }
+        Command::Frames { pattern, out, generations, rule, every, scale, format, margin, track } => {
+            frames(&pattern, &out, generations, rule, every, scale, format, margin, track)
+        }
+        Command::Thumbnail { pattern, out, size, format } => thumbnail(&pattern, out.as_deref(), size, format),
+        Command::Convert { input, output, format } => convert(&input, &output, format, &theme),
+        Command::Transform { input, rotate, flip_x, flip_y, pad, recenter, out } => {
+            if rotate % 90 != 0 {
+                Err(format!("rotation must be a multiple of 90 degrees, not {}", rotate).into())
+            } else {
+                transform(&input, &Transform { quarter_turns: rotate / 90, flip_x, flip_y, pad, recenter }, out.as_deref())
+            }
+        }
+        Command::Bench { pattern, generations, rule, backend } => bench(pattern.as_deref(), generations, rule, backend),
+        Command::Play { pattern, rule, seed, record, recipe } => play(pattern.as_deref(), rule, seed, record.as_deref(), recipe.as_deref()),
+        Command::Replay { pattern, journal, generations, rule, out, format } => {
+            replay(&pattern, &journal, generations, rule).and_then(|world| output(&world, out.as_deref(), format, &theme))
+        }
+        Command::Reproduce { recipe, generations, out, format } => {
+            reproduce(&recipe, generations).and_then(|world| output(&world, out.as_deref(), format, &theme))
+        }
+        #[cfg(feature = "window")]
+        Command::Window { pattern, rule, seed, colors } => {
+            open(pattern.as_deref(), rule, seed, colors.as_deref(), &theme, |world, palette| Ok(window::run(world, palette)?))
+        }
+        #[cfg(feature = "gpu")]
+        Command::Gpu { pattern, rule, seed, colors } => {
+            open(pattern.as_deref(), rule, seed, colors.as_deref(), &theme, |world, palette| Ok(gpu::frontend::run(world, palette)?))
+        }
+        #[cfg(feature = "gui")]
+        Command::Gui { pattern, rule, seed, colors } => {
+            open(pattern.as_deref(), rule, seed, colors.as_deref(), &theme, |world, palette| Ok(gui::run(world, palette)?))
+        }
+        Command::Identify { pattern, generations, rule } => identify(&pattern, generations, rule),
+        Command::Census { pattern, generations, rule, seed } => census(pattern.as_deref(), generations, rule, seed),
+        Command::Elementary { rule, generations, out, format } => {
+            elementary(rule, generations).and_then(|world| output(&world, out.as_deref(), format, &theme))
+        }
+        Command::Analyze { rule, soups, seed } => {
+            let settings = FingerprintSettings { soups, seed, ..FingerprintSettings::default() };
+            print!("{}", fingerprint(rule, &settings));
+            Ok(())
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run(pattern: &Path, rule: Rule, trim: TrimPolicy, settings: &RunSettings, headless: bool, out: Option<&Path>, format: OutputFormat, theme: &Theme) -> Result<(), Box<dyn Error>> {
+    let mut world = read_world(pattern, rule)?;
+    world.set_trim_policy(trim);
+    let report = runner::run(world, settings, |progress| if headless { eprintln!("{}", progress) })?;
+    if let Some(period) = report.stable {
+        eprintln!("stable with period {} by generation {}", period, report.generations);
+    }
+    if out.is_some() || !headless {
+        write_world(&report.world, format, out, theme)?;
+    }
+    if headless { print!("{}", report) }
+    Ok(())
+}
+
+/// Run the pattern, then again every time the file is saved, until interrupted.
+/// A run that fails, such as on a half written pattern, is reported and the file watched for the next save.
+fn run_watched(pattern: &Path, run: impl Fn() -> Result<(), Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+    FileWatcher::new(pattern)?.run_on_changes(WATCH_INTERVAL, || {
+        if let Err(error) = run() {
+            eprintln!("error: {}", error);
+        }
+        eprintln!("watching {} for changes, ctrl+c to stop", pattern.display());
+    })?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render(pattern: &Path, out: &Path, generations: usize, rule: Rule, scale: usize, format: Option<ImageFormat>, colors: Option<&Path>, mut shading: Shading, theme: &Theme) -> Result<(), Box<dyn Error>> {
+    let format = format
+        .or_else(|| ImageFormat::from_path(out))
+        .ok_or_else(|| format!("unknown image format: {}", out.display()))?;
+    let mut world = read_world(pattern, rule)?;
+    let sidecar = pattern.with_extension("colors");
+    let palette = load_palette(colors.or(sidecar.is_file().then_some(sidecar.as_path())), theme)?;
+    shading.update(&world);
+    shading.step(&mut world, generations);
+    write_image(&world, format, out, scale, &shading, &palette, theme)?;
+    Ok(())
+}
 
-    let game = GameOfLife::new();
+#[allow(clippy::too_many_arguments)]
+fn frames(pattern: &Path, out: &Path, generations: usize, rule: Rule, every: usize, scale: usize, format: ImageFormat, margin: usize, track: bool) -> Result<(), Box<dyn Error>> {
+    let mut world = read_world(pattern, rule)?;
+    let viewport = FrameViewport::around(&world, margin, track);
+    let mut exporter = FrameExporter::new(FrameOutput::create(out, format)?, viewport, every, scale);
+    exporter.run(&mut world, generations)?;
+    let (width, height) = exporter.frame_size();
+    eprintln!("wrote {} frames of {}x{} pixels", exporter.frames_written(), width, height);
+    Ok(())
+}
+
+fn thumbnail(pattern: &Path, out: Option<&Path>, size: usize, format: Option<ImageFormat>) -> Result<(), Box<dyn Error>> {
+    let thumbnail = read_pattern(pattern)?.render_thumbnail(size);
+    let Some(out) = out else {
+        print!("{}", thumbnail.to_text());
+        return Ok(())
+    };
+    let format = format
+        .or_else(|| ImageFormat::from_path(out))
+        .ok_or_else(|| format!("unknown image format: {}", out.display()))?;
+    write_thumbnail(&thumbnail, format, out)?;
+    Ok(())
+}
+
+fn convert(input: &Path, output: &Path, format: Option<OutputFormat>, theme: &Theme) -> Result<(), Box<dyn Error>> {
+    let format = format
+        .or_else(|| OutputFormat::from_path(output))
+        .ok_or_else(|| format!("unknown output format: {}", output.display()))?;
+    write_world(&read_world(input, Rule::LIFE)?, format, Some(output), theme)?;
+    Ok(())
+}
+
+fn transform(input: &Path, transform: &Transform, out: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let mut pattern = read_pattern(input)?;
+    pattern.transform(transform)?;
+    match out {
+        Some(path) => write_pattern(&pattern, path)?,
+        None => print!("{}", rle::write_rle_region(&pattern))
+    }
+    Ok(())
+}
+
+fn bench(pattern: Option<&Path>, generations: Option<u64>, rule: Rule, backend: Option<Backend>) -> Result<(), Box<dyn Error>> {
+    let backends = match backend {
+        Some(backend) => vec![backend],
+        None => Backend::ALL.to_vec()
+    };
+    let workloads = match pattern {
+        Some(pattern) => vec![(pattern.display().to_string(), read_world(pattern, rule)?, generations.unwrap_or(1000))],
+        None => Workload::ALL.into_iter()
+            .map(|workload| (workload.to_string(), workload.world(rule), generations.unwrap_or(workload.generations())))
+            .collect()
+    };
+
+    for (name, world, generations) in workloads {
+        for &backend in &backends {
+            println!("{} on {}: {}", name, backend, Measurement::take(&world, backend, generations));
+        }
+    }
+    Ok(())
+}
+
+fn identify(pattern: &Path, generations: u64, rule: Rule) -> Result<(), Box<dyn Error>> {
+    let mut world = read_world(pattern, rule)?;
+    let symmetry = symmetry(&world);
+    match detect_recurrence(&mut world, generations) {
+        Some(recurrence) => println!("{}", recurrence),
+        None => println!("doesn't recur within {} generations", generations)
+    }
+    println!("{} symmetry", symmetry);
+    Ok(())
+}
+
+fn census(pattern: Option<&Path>, generations: u64, rule: Rule, seed: u64) -> Result<(), Box<dyn Error>> {
+    let mut world = Start::load(pattern, seed, 16, 16)?.world(rule)?;
+    // Escaping gliders keep the world from ever repeating, so it is counted anyway once out of generations
+    match settle(&mut world, CycleDetector::any_period(), generations) {
+        Some((generation, period)) => eprintln!("stable with period {} by generation {}", period, generation),
+        None => eprintln!("still changing after {} generations", generations)
+    }
+    print!("{}", Census::take(&world));
+    Ok(())
+}
+
+fn play(pattern: Option<&Path>, rule: Rule, seed: u64, record: Option<&Path>, recipe: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let start = Start::load(pattern, seed, 64, 32)?;
+    let journal = tui::run(start.world(rule)?)?;
+    if let Some(path) = record {
+        fs::write(path, journal.to_string())?;
+    }
+    if let Some(path) = recipe {
+        let generations = journal.last_generation().unwrap_or(0);
+        fs::write(path, Recipe { start, rule, journal, generations }.to_string())?;
+    }
+    Ok(())
+}
+
+fn replay(pattern: &Path, journal: &Path, generations: Option<u64>, rule: Rule) -> Result<GameOfLife, Box<dyn Error>> {
+    let journal: Journal = fs::read_to_string(journal)?.parse()?;
+    let mut world = read_world(pattern, rule)?;
+    journal.replay(&mut world, generations.or(journal.last_generation()).unwrap_or(0));
+    Ok(world)
+}
+
+fn reproduce(recipe: &Path, generations: Option<u64>) -> Result<GameOfLife, Box<dyn Error>> {
+    let mut recipe: Recipe = fs::read_to_string(recipe)?.parse()?;
+    if let Some(generations) = generations { recipe.generations = generations }
+    Ok(recipe.replay()?)
+}
+
+fn elementary(rule: ElementaryRule, generations: usize) -> Result<GameOfLife, Box<dyn Error>> {
+    let mut world = GameOfLife::new();
+    world.set_region(&elementary::history(rule, &Row::single_cell(), generations)?);
+    Ok(world)
+}
+
+/// Open a frontend on the pattern, or a random soup if there is no pattern, with the colours from the file or theme.
+#[cfg(any(feature = "window", feature = "gpu", feature = "gui"))]
+fn open(pattern: Option<&Path>, rule: Rule, seed: u64, colors: Option<&Path>, theme: &Theme,
+        frontend: impl FnOnce(GameOfLife, Palette) -> Result<(), Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+    let world = Start::load(pattern, seed, 256, 256)?.world(rule)?;
+    frontend(world, load_palette(colors, theme)?)
+}
 
-    game.debug_print();
+/// Write the world to the file, or standard output, in the format given or else the one for the file's extension.
+fn output(world: &GameOfLife, out: Option<&Path>, format: Option<OutputFormat>, theme: &Theme) -> Result<(), Box<dyn Error>> {
+    write_world(world, OutputFormat::or_from_path(format, out), out, theme)?;
+    Ok(())
 }
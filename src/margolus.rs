@@ -0,0 +1,158 @@
+//! Block cellular automata over the Margolus neighbourhood, where each generation replaces whole 2 by 2 blocks
+//! rather than stepping each cell by its neighbours, with the blocks shifted diagonally every other generation.
+//! Rules whose blocks each come from exactly one block, such as Critters and the billiard ball machine,
+//! are reversible, so their worlds can be stepped backwards as well as forwards.
+
+use std::collections::HashSet;
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife};
+use crate::rule::MargolusRule;
+
+/// Offsets from a block's top left corner to each of its cells, in the order of their bits in the block's number.
+const BLOCK_OFFSETS: [(Coord, Coord); 4] = [(0, 0), (1, 0), (0, 1), (1, 1)];
+
+/// Infinite world of cells following a Margolus block rule.
+#[derive(Debug, Clone)]
+pub struct MargolusWorld {
+    /// Every cell in a different state to the background.
+    cells: HashSet<Position>,
+    /// State of every cell outside the stored ones, which is only ever alive under rules that flip empty blocks.
+    background: Cell,
+    rule: MargolusRule,
+    generation: u64
+}
+
+impl MargolusWorld {
+    /// Create a new empty world using the given rule.
+    pub fn new(rule: MargolusRule) -> MargolusWorld {
+        MargolusWorld { cells: HashSet::new(), background: Cell::Dead, rule, generation: 0 }
+    }
+
+    /// Create a world with the live cells of a two-state world, under the given rule.
+    pub fn from_world(world: &GameOfLife, rule: MargolusRule) -> MargolusWorld {
+        MargolusWorld { cells: world.live_cells().into_iter().collect(), ..MargolusWorld::new(rule) }
+    }
+
+    /// Step the world to the next state. Even generations replace the blocks with their corners at
+    /// even coordinates, and odd generations those shifted one cell down and right.
+    pub fn step(&mut self) {
+        let rule = self.rule;
+        self.apply(&rule, self.generation % 2);
+        self.generation += 1;
+    }
+
+    /// Step the world back to the state before, with the rule's inverse. Fails if the rule isn't reversible
+    /// or the world is at generation 0, leaving the world as it was.
+    pub fn step_back(&mut self) -> bool {
+        let Some(inverse) = self.rule.inverse() else { return false };
+        if self.generation == 0 { return false }
+        self.generation -= 1;
+        self.apply(&inverse, self.generation % 2);
+        true
+    }
+
+    /// Replace every block in the partition shifted by the offset with the block the rule gives.
+    fn apply(&mut self, rule: &MargolusRule, offset: u64) {
+        let offset = offset as Coord;
+        let uniform = |cell: Cell| if cell == Cell::Alive { 15 } else { 0 };
+        let background = if rule.next_block(uniform(self.background)) == 15 { Cell::Alive } else { Cell::Dead };
+
+        // Only blocks with a stored cell in them can be anything but the background
+        let corners: HashSet<Position> = self.cells.iter()
+            .map(|cell| Position::new((cell.x - offset).div_euclid(2) * 2 + offset, (cell.y - offset).div_euclid(2) * 2 + offset))
+            .collect();
+        let mut next = HashSet::with_capacity(self.cells.len());
+        for corner in corners {
+            let block = BLOCK_OFFSETS.iter().enumerate()
+                .filter(|&(_, &(x, y))| self.get_cell(corner + Position::new(x, y)) == Cell::Alive)
+                .fold(0, |block, (bit, _)| block | 1 << bit);
+            let block = rule.next_block(block);
+            for (bit, &(x, y)) in BLOCK_OFFSETS.iter().enumerate() {
+                if (block & (1 << bit) != 0) != (background == Cell::Alive) { next.insert(corner + Position::new(x, y)); }
+            }
+        }
+        self.cells = next;
+        self.background = background;
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get_cell(&self, position: Position) -> Cell {
+        match (self.cells.contains(&position), self.background) {
+            (true, Cell::Alive) | (false, Cell::Dead) => Cell::Dead,
+            _ => Cell::Alive
+        }
+    }
+
+    /// Set the state of the cell at the position.
+    pub fn set_cell(&mut self, position: Position, state: Cell) {
+        if state == self.background { self.cells.remove(&position); } else { self.cells.insert(position); }
+    }
+
+    /// Number of cells in a different state to the background, which is the live cells while it is dead.
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Positions of the cells in a different state to the background, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// Returns the bounding box of the cells in a different state to the background, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::around(self.cells.iter().copied())
+    }
+
+    /// Copy the cells in a different state to the background into a two-state world as its live cells.
+    pub fn to_world(&self) -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for position in &self.cells {
+            world.set_cell(position.x, position.y, Cell::Alive);
+        }
+        world
+    }
+
+    // GETTERS
+    pub fn rule(&self) -> &MargolusRule { &self.rule }
+    pub fn background(&self) -> Cell { self.background }
+    pub fn generation(&self) -> u64 { self.generation }
+}
+
+#[cfg(test)]
+mod margolus_tests {
+    use super::*;
+    use crate::soup::{random_soup, Rng};
+
+    #[test]
+    fn billiard_ball() {
+        // A lone ball crosses to the far corner of each block, moving diagonally a cell every generation
+        let mut world = MargolusWorld::new(MargolusRule::BILLIARD_BALL);
+        world.set_cell(Position::new(0, 0), Cell::Alive);
+        world.step();
+        assert_eq!(vec![Position::new(1, 1)], world.cells().collect::<Vec<_>>());
+        world.step();
+        assert_eq!(vec![Position::new(2, 2)], world.cells().collect::<Vec<_>>());
+        assert!(world.step_back());
+        assert_eq!((Cell::Alive, 1), (world.get_cell(Position::new(1, 1)), world.generation()));
+    }
+
+    #[test]
+    fn critters_reversible() {
+        // Critters flips the empty background every generation, and stepping back undoes every step exactly
+        let mut soup = GameOfLife::new();
+        soup.set_region(&random_soup(&mut Rng::new(5), 0, 0, 16, 16, 0.3).unwrap());
+        let mut world = MargolusWorld::from_world(&soup, MargolusRule::CRITTERS);
+        let start: HashSet<Position> = world.cells().collect();
+        world.step();
+        assert_eq!(Cell::Alive, world.background());
+        for _ in 0..29 {
+            world.step();
+        }
+        for _ in 0..30 {
+            assert!(world.step_back());
+        }
+        assert!(!world.step_back());
+        assert_eq!((Cell::Dead, start), (world.background(), world.cells().collect()));
+    }
+}
@@ -0,0 +1,128 @@
+//! Searching backwards in time, for a state that steps to a given pattern.
+//! This is experimental: the search is a brute force over every cell near the pattern, pruned as it goes,
+//! so it is only practical for patterns of a few dozen cells.
+
+use crate::geometry::{Coord, Rect};
+use crate::gol::{Cell, Region};
+use crate::rule::Rule;
+
+/// Search for a state that becomes the pattern after one generation under the rule, with every live cell
+/// within `margin` cells of the pattern's live cells. The rest of the world is taken to be dead, before and after.
+/// Returns [`None`] if there is no such state, which for a large enough margin means the pattern is a Garden of Eden.
+/// Also returns [`None`] for rules whose neighbourhood reaches past the 8 cells around each cell,
+/// as the search only checks each cell once the cells touching it have been filled in, and for rules with B0,
+/// under which a dead world doesn't stay dead.
+pub fn find_predecessor(pattern: &Region, rule: Rule, margin: usize) -> Option<Region> {
+    if rule.neighbourhood().reach() > 1 || rule.has_b0() { return None }
+    let Some(bounds) = pattern.live_bounds() else { return Region::new(0, 0, 0, 0).ok() };
+    let area = bounds.expand(margin);
+    let mut search = Search { pattern, rule, area, cells: vec![false; area.area()] };
+    if !search.assign(0) { return None }
+
+    let mut predecessor = Region::from_rect(area).ok()?;
+    for (index, &alive) in search.cells.iter().enumerate() {
+        if alive {
+            predecessor.set_cell(area.x + (index % area.width) as Coord, area.y + (index / area.width) as Coord, Cell::Alive);
+        }
+    }
+    Some(predecessor)
+}
+
+/// Cells of a predecessor being filled in, in rows across the area searched, with everything outside it dead.
+struct Search<'a> {
+    pattern: &'a Region,
+    rule: Rule,
+    area: Rect,
+    cells: Vec<bool>
+}
+
+impl Search<'_> {
+    /// Try each state for the cell at the index and every cell after it, returning whether a predecessor was found.
+    /// The cells are left as the predecessor if one was.
+    fn assign(&mut self, index: usize) -> bool {
+        if index == self.cells.len() { return true }
+        // Dead first, as sparse predecessors are more likely
+        for alive in [false, true] {
+            self.cells[index] = alive;
+            if self.consistent(index) && self.assign(index + 1) { return true }
+        }
+        self.cells[index] = false;
+        false
+    }
+
+    /// Check the next state of every cell whose neighbourhood has just been completed by filling in the cell at the index,
+    /// including those outside the area, which must stay dead. Positions are relative to the area's corner.
+    fn consistent(&self, index: usize) -> bool {
+        let (width, height) = (self.area.width as Coord, self.area.height as Coord);
+        let (x, y) = ((index % self.area.width) as Coord, (index / self.area.width) as Coord);
+        // A cell's neighbourhood is complete once the cell below and to the right of it is filled,
+        // or straight away for cells past the edges, whose neighbours there are always dead
+        let columns = if x == 0 { -1 } else { x - 1 } ..= if x == width - 1 { width } else { x - 1 };
+        let rows = y - 1 ..= if y == height - 1 { height } else { y - 1 };
+        rows.into_iter().all(|row| columns.clone().all(|column| self.steps_to_pattern(column, row)))
+    }
+
+    /// Whether the cell at the position relative to the area's corner steps to its state in the pattern.
+    fn steps_to_pattern(&self, x: Coord, y: Coord) -> bool {
+        let (mut neighbours, mut arrangement) = (0, 0u32);
+        for (index, (x_off, y_off, weight)) in self.rule.neighbourhood().neighbours().enumerate() {
+            if self.get(x + x_off, y + y_off) {
+                neighbours += weight;
+                arrangement |= 1 << index;
+            }
+        }
+        let current = if self.get(x, y) { Cell::Alive } else { Cell::Dead };
+        let target = self.pattern.get_cell(self.area.x + x, self.area.y + y).unwrap_or(Cell::Dead);
+        let next = if self.rule.is_totalistic() { self.rule.next_state(current, neighbours) } else { self.rule.next_state_of(current, arrangement as u8) };
+        next == target
+    }
+
+    /// Whether the cell at the position relative to the area's corner is alive, with every cell outside the area dead.
+    fn get(&self, x: Coord, y: Coord) -> bool {
+        if x < 0 || y < 0 || x >= self.area.width as Coord || y >= self.area.height as Coord { return false }
+        self.cells[y as usize * self.area.width + x as usize]
+    }
+}
+
+#[cfg(test)]
+mod predecessor_tests {
+    use super::*;
+    use crate::gol::GameOfLife;
+    use crate::io::rle::read_rle;
+
+    /// Step the predecessor and check it becomes the pattern.
+    fn assert_steps_to(predecessor: &Region, pattern: &Region) {
+        let mut world = GameOfLife::new();
+        world.set_region(predecessor);
+        world.step();
+        let mut expected = GameOfLife::new();
+        expected.set_region(pattern);
+        let mut cells = world.live_cells();
+        let mut expected = expected.live_cells();
+        cells.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(expected, cells);
+    }
+
+    #[test]
+    fn predecessors() {
+        for rle in ["3o!", "2o$2o!", "bo$2bo$3o!", "o!"] {
+            let pattern = read_rle(rle).unwrap();
+            let predecessor = find_predecessor(&pattern, Rule::LIFE, 1).unwrap();
+            assert_steps_to(&predecessor, &pattern);
+        }
+        assert!(find_predecessor(&Region::new(0, 0, 2, 2).unwrap(), Rule::LIFE, 1).unwrap().live_bounds().is_none());
+    }
+
+    #[test]
+    fn no_predecessor() {
+        // A lone cell can only be born from three neighbours, which don't fit without a margin
+        let cell = read_rle("o!").unwrap();
+        assert_eq!(None, find_predecessor(&cell, Rule::LIFE, 0).map(|region| region.bounds()));
+
+        // Under Seeds nothing survives, and nothing within two cells can give every cell of a block
+        // exactly two neighbours without also giving birth around it
+        let seeds: Rule = "B2/S".parse().unwrap();
+        assert!(find_predecessor(&read_rle("2o$2o!").unwrap(), seeds, 2).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Progress of long runs, reported every so often so it's clear a run hasn't hung and how long it has left.
+
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::gol::GameOfLife;
+use crate::ticker::Ticker;
+
+/// How far through its generations a run is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub generation: u64,
+    /// Generations the run will step in all.
+    pub total: u64,
+    pub population: usize,
+    /// Time spent stepping so far.
+    pub elapsed: Duration
+}
+
+impl Progress {
+    /// Average generations stepped per second so far.
+    pub fn per_second(&self) -> f64 {
+        self.generation as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Fraction of the generations stepped, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 { return 1.0 }
+        self.generation as f64 / self.total as f64
+    }
+
+    /// Estimate of the time left at the average speed so far, or [`None`] before any generations have been stepped.
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.generation == 0 { return None }
+        let left = self.total.saturating_sub(self.generation) as f64;
+        Some(self.elapsed.mul_f64(left / self.generation as f64))
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gen {}/{} ({:.1}%) | pop {} | {:.1} gen/s | eta ",
+            self.generation,
+            self.total,
+            self.fraction() * 100.0,
+            self.population,
+            self.per_second()
+        )?;
+        match self.remaining() {
+            Some(remaining) => write_duration(f, remaining),
+            None => write!(f, "unknown")
+        }
+    }
+}
+
+/// Write a duration to the nearest second, as `1h 02m 03s`, `2m 03s` or `3s`.
+fn write_duration(f: &mut Formatter<'_>, duration: Duration) -> std::fmt::Result {
+    let seconds = duration.as_secs_f64().round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes) {
+        (0, 0) => write!(f, "{}s", seconds),
+        (0, _) => write!(f, "{}m {:02}s", minutes, seconds),
+        _ => write!(f, "{}h {:02}m {:02}s", hours, minutes, seconds)
+    }
+}
+
+/// Times a run from when it is created, and says when its progress is next due to be reported.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    total: u64,
+    start: Instant,
+    reports: Ticker
+}
+
+impl ProgressReporter {
+    /// Start timing a run of the given number of generations, reporting every interval.
+    pub fn new(total: u64, interval: Duration) -> ProgressReporter {
+        let start = Instant::now();
+        let mut reports = Ticker::every(interval);
+        reports.restart(start + interval);
+        ProgressReporter { total, start, reports }
+    }
+
+    /// Returns the progress if a report is due, given the generation the world has been stepped to.
+    pub fn update(&mut self, generation: u64, world: &GameOfLife) -> Option<Progress> {
+        let now = Instant::now();
+        if !self.reports.tick(now) { return None }
+        Some(Progress { generation, total: self.total, population: world.population(), elapsed: now - self.start })
+    }
+}
+
+/// Step the world the given number of generations, calling `report` with the progress every interval.
+pub fn step_with_progress(world: &mut GameOfLife, generations: u64, interval: Duration, mut report: impl FnMut(&Progress)) {
+    let mut reporter = ProgressReporter::new(generations, interval);
+    for generation in 1..=generations {
+        world.step();
+        if let Some(progress) = reporter.update(generation, world) { report(&progress) }
+    }
+}
+
+#[cfg(test)]
+mod progress_tests {
+    use super::*;
+    use crate::gol::Cell;
+
+    #[test]
+    fn report() {
+        let progress = Progress { generation: 250, total: 1000, population: 5, elapsed: Duration::from_secs(100) };
+        assert_eq!(2.5, progress.per_second());
+        assert_eq!(Some(Duration::from_secs(300)), progress.remaining());
+        assert_eq!("gen 250/1000 (25.0%) | pop 5 | 2.5 gen/s | eta 5m 00s", progress.to_string());
+
+        let starting = Progress { generation: 0, elapsed: Duration::ZERO, ..progress.clone() };
+        assert!(starting.to_string().ends_with("eta unknown"));
+        let long = Progress { elapsed: Duration::from_secs(1500), ..progress };
+        assert!(long.to_string().ends_with("eta 1h 15m 00s"));
+    }
+
+    #[test]
+    fn reports_while_stepping() {
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut reports = vec![];
+        step_with_progress(&mut world, 100, Duration::ZERO, |progress| reports.push(progress.clone()));
+        assert_eq!(100, reports.len());
+        assert!(reports.iter().all(|progress| progress.population == 3 && progress.total == 100));
+        assert_eq!(100, reports[99].generation);
+
+        step_with_progress(&mut world, 100, Duration::from_secs(60), |_| panic!("Reported before the interval"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Recipes for reproducing a run exactly: what it started from, the rule, the edits made along the way
+//! and how far it went. A random soup is kept as its seed rather than its cells, as the generator gives
+//! the same soup for the same seed everywhere, so a recipe is small enough to attach to a bug report.
+//!
+//! Recipes are saved as text, with the run's settings followed by its [`Journal`]:
+//! ```text
+//! #R rust-gol recipe
+//! rule B3/S23
+//! soup 7 64 32 0.35
+//! generations 120
+//! #J rust-gol edit journal
+//! 12 set 3 -4 alive
+//! ```
+//! A pattern to start from is given as `pattern` followed by its -x -y corner, size and RLE body, instead of `soup`.
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::GolError;
+use crate::geometry::Coord;
+use crate::gol::{GameOfLife, Region};
+use crate::io::read_pattern;
+use crate::journal::{read_placed, write_placed, Journal};
+use crate::rule::Rule;
+use crate::soup::{random_soup, Rng};
+
+/// Line the journal starts after.
+const JOURNAL_HEADER: &str = "#J rust-gol edit journal";
+/// Chance of each cell being alive in the soups [`Start::load`] falls back to.
+pub const SOUP_DENSITY: f64 = 0.35;
+
+/// What a run started from.
+#[derive(Clone)]
+pub enum Start {
+    /// Random soup from the seed, of the given width and height centred on 0 0, with each cell alive with the given probability.
+    Soup { seed: u64, width: usize, height: usize, density: f64 },
+    /// Pattern at its place in the world.
+    Pattern(Region)
+}
+
+impl Start {
+    /// Read the pattern file to start from, or use a random soup from the seed of the given size if there is no file.
+    pub fn load(pattern: Option<&Path>, seed: u64, width: usize, height: usize) -> Result<Start, GolError> {
+        Ok(match pattern {
+            Some(path) => Start::Pattern(read_pattern(path)?),
+            None => Start::Soup { seed, width, height, density: SOUP_DENSITY }
+        })
+    }
+
+    /// Create the starting world under the rule.
+    pub fn world(&self, rule: Rule) -> Result<GameOfLife, GolError> {
+        let mut world = GameOfLife::with_rule(rule);
+        match self {
+            Start::Soup { seed, width, height, density } => {
+                let (x, y) = (-(*width as Coord) / 2, -(*height as Coord) / 2);
+                world.set_region(&random_soup(&mut Rng::new(*seed), x, y, *width, *height, *density)?);
+            }
+            Start::Pattern(region) => world.set_region(region)
+        }
+        Ok(world)
+    }
+}
+
+/// Everything needed to run the same generations again and end up with the same world.
+#[derive(Clone)]
+pub struct Recipe {
+    pub start: Start,
+    /// Rule the run started with, which edits in the journal may change.
+    pub rule: Rule,
+    pub journal: Journal,
+    /// Generations stepped in all.
+    pub generations: u64
+}
+
+impl Recipe {
+    /// Create the starting world and step it through the run, making each edit at the generation it was made.
+    pub fn replay(&self) -> Result<GameOfLife, GolError> {
+        let mut world = self.start.world(self.rule)?;
+        self.journal.replay(&mut world, self.generations);
+        Ok(world)
+    }
+}
+
+impl Display for Recipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "#R rust-gol recipe")?;
+        writeln!(f, "rule {}", self.rule)?;
+        match &self.start {
+            Start::Soup { seed, width, height, density } => writeln!(f, "soup {} {} {} {}", seed, width, height, density)?,
+            Start::Pattern(region) => writeln!(f, "pattern {}", write_placed(region))?
+        }
+        writeln!(f, "generations {}", self.generations)?;
+        // The journal writes its own header
+        write!(f, "{}", self.journal)
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = GolError;
+
+    fn from_str(s: &str) -> Result<Recipe, GolError> {
+        let (mut rule, mut start, mut generations) = (None, None, None);
+        let mut lines = s.lines().enumerate();
+        for (index, line) in lines.by_ref() {
+            let line = line.trim();
+            if line == JOURNAL_HEADER { break }
+            if line.is_empty() || line.starts_with('#') { continue }
+            let invalid = || RecipeError { line: index + 1, text: line.to_string() };
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                ["rule", text] => rule = Some(text.parse().map_err(|_| invalid())?),
+                ["generations", count] => generations = Some(count.parse().map_err(|_| invalid())?),
+                ["soup", seed, width, height, density] => {
+                    let number = |word: &str| word.parse::<usize>().map_err(|_| invalid());
+                    let seed = seed.parse().map_err(|_| invalid())?;
+                    let density = density.parse().map_err(|_| invalid())?;
+                    start = Some(Start::Soup { seed, width: number(width)?, height: number(height)?, density });
+                }
+                ["pattern", ref placed @ ..] => start = Some(Start::Pattern(read_placed(placed).ok_or_else(invalid)?)),
+                _ => return Err(invalid().into())
+            }
+        }
+
+        // Journal errors count lines from the header, so count on from there
+        let journal_start = lines.clone().next().map_or(0, |(index, _)| index);
+        let rest: Vec<&str> = lines.map(|(_, line)| line).collect();
+        let journal = Journal::parse(&rest.join("\n"))
+            .map_err(|error| RecipeError { line: journal_start + error.line, text: error.text })?;
+
+        let missing = |setting: &str| RecipeError { line: 0, text: format!("missing {}", setting) };
+        Ok(Recipe {
+            start: start.ok_or_else(|| missing("soup or pattern"))?,
+            rule: rule.ok_or_else(|| missing("rule"))?,
+            journal,
+            generations: generations.ok_or_else(|| missing("generations"))?
+        })
+    }
+}
+
+/// Error from reading a recipe, with the line number, counting from 1, and text of the line that couldn't be understood.
+/// A setting missing altogether is reported on line 0.
+#[derive(Debug, PartialEq)]
+pub struct RecipeError {
+    pub line: usize,
+    pub text: String
+}
+
+impl Display for RecipeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 { return write!(f, "invalid recipe: {}", self.text) }
+        write!(f, "invalid recipe on line {}: {}", self.line, self.text)
+    }
+}
+
+impl std::error::Error for RecipeError {}
+
+#[cfg(test)]
+mod recipe_tests {
+    use super::*;
+    use crate::geometry::{Position, Rect};
+    use crate::gol::Cell;
+    use crate::io::rle::read_rle;
+    use crate::journal::Edit;
+
+    #[test]
+    fn replay() {
+        let mut journal = Journal::new();
+        journal.record(3, Edit::SetCell(Position::new(40, 40), Cell::Alive));
+        journal.record(5, Edit::Rule("B36/S23".parse().unwrap()));
+        let recipe = Recipe { start: Start::Soup { seed: 7, width: 16, height: 16, density: 0.35 }, rule: Rule::LIFE, journal, generations: 30 };
+
+        // Written out and read back, the run ends the same
+        let text = recipe.to_string();
+        assert_eq!("#R rust-gol recipe\nrule B3/S23\nsoup 7 16 16 0.35\ngenerations 30\n#J rust-gol edit journal\n3 set 40 40 alive\n5 rule B36/S23\n", text);
+        let read: Recipe = text.parse().unwrap();
+        let (mut expected, mut actual) = (recipe.replay().unwrap().live_cells(), read.replay().unwrap().live_cells());
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+        assert_eq!("B36/S23", read.replay().unwrap().rule().to_string());
+    }
+
+    #[test]
+    fn text() {
+        let mut glider = read_rle("bo$2bo$3o!").unwrap();
+        glider.set_position(-1, 2).unwrap();
+        let recipe = Recipe { start: Start::Pattern(glider), rule: Rule::LIFE, journal: Journal::new(), generations: 4 };
+        let text = recipe.to_string();
+        assert!(text.contains("\npattern -1 2 3 3 bo$2bo$3o!\n"));
+        assert_eq!(text, text.parse::<Recipe>().unwrap().to_string());
+        // A glider moves one cell diagonally every four generations
+        assert_eq!(Some(Rect::new(0, 3, 3, 3)), recipe.replay().unwrap().live_bounds());
+
+        let error = |text: &str| match text.parse::<Recipe>() {
+            Err(GolError::Recipe(error)) => error,
+            _ => panic!("Expected a recipe error in {}", text)
+        };
+        assert_eq!(RecipeError { line: 2, text: "soup 7 16".to_string() }, error("#R\nsoup 7 16"));
+        assert_eq!("invalid recipe: missing rule", error("soup 7 16 16 0.5\ngenerations 3").to_string());
+        // Journal lines are counted from the top of the recipe
+        let bad_journal = "rule B3/S23\nsoup 7 16 16 0.5\ngenerations 3\n#J rust-gol edit journal\n1 jump";
+        assert_eq!(RecipeError { line: 5, text: "1 jump".to_string() }, error(bad_journal));
+    }
+}
@@ -0,0 +1,882 @@
+//! Module to prepare the state of a Game of Life world for display by frontends.
+
+pub mod terminal;
+pub mod theme;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::chunk;
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife, Region};
+use theme::Theme;
+
+/// Grid of live cell densities covering an area of the world.
+/// Each sample covers a square block of `2^level` cells per side, so level 0 is cell-exact
+/// and each following level is the next coarser mip.
+/// The x y position is the -x -y corner of the first block, which is always aligned to the block size.
+pub struct DensityGrid {
+    x: Coord,
+    y: Coord,
+    level: u32,
+    width: usize,
+    height: usize,
+    samples: Vec<Vec<f32>>
+}
+
+impl DensityGrid {
+    /// Coarsest level, with blocks as large as both coordinates and sizes can hold.
+    pub const MAX_LEVEL: u32 = if usize::BITS < Coord::BITS { usize::BITS } else { Coord::BITS } - 2;
+
+    /// Sample the area of the world starting at x y with the given width and height in cells.
+    /// The grid is expanded out to whole blocks, so can cover slightly more than the requested area.
+    /// Levels past [`DensityGrid::MAX_LEVEL`] are sampled at it.
+    pub fn new(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, level: u32) -> DensityGrid {
+        let level = level.min(Self::MAX_LEVEL);
+        let block: Coord = 1 << level;
+
+        // Block coordinates of the blocks touched by the area
+        let span_x = chunk::chunk_span(x, width, block as usize);
+        let span_y = chunk::chunk_span(y, height, block as usize);
+        let (origin_x, origin_y) = chunk::chunk_origin(span_x.start, span_y.start, block as usize);
+
+        let mut grid = DensityGrid {
+            x: origin_x,
+            y: origin_y,
+            level,
+            width: span_x.end.abs_diff(span_x.start) as usize,
+            height: span_y.end.abs_diff(span_y.start) as usize,
+            samples: vec![]
+        };
+
+        // Bin the live cells of the regions under the grid into their blocks, rather than looking up every cell
+        let covered = Rect::new(grid.x, grid.y, grid.width.saturating_mul(block as usize), grid.height.saturating_mul(block as usize));
+        let mut counts = vec![vec![0usize; grid.height]; grid.width];
+        for region in world.regions().filter(|region| region.bounds().overlaps(&covered)) {
+            for cell in region.live_cells().filter(|&cell| covered.contains(cell)) {
+                let block_x = cell.x.abs_diff(grid.x) / block as u64;
+                let block_y = cell.y.abs_diff(grid.y) / block as u64;
+                counts[block_x as usize][block_y as usize] += 1;
+            }
+        }
+
+        // Stored cells are the ones differing from the background, so against a live one they are the dead cells
+        let cells_per_block = block as f32 * block as f32;
+        let density = |stored: usize| match world.background() {
+            Cell::Dead => stored as f32 / cells_per_block,
+            Cell::Alive => 1.0 - stored as f32 / cells_per_block
+        };
+        grid.samples = counts.into_iter().map(|column| column.into_iter().map(density).collect()).collect();
+        grid
+    }
+
+    /// Returns the density of the block containing the given world coordinates,
+    /// from 0.0 for all dead to 1.0 for all alive.
+    /// If the position is outside of this grid, returns [`None`].
+    pub fn get_sample(&self, x: Coord, y: Coord) -> Option<f32> {
+        if x < self.x || y < self.y { return None }
+        let block = 1u64 << self.level;
+        let block_x = usize::try_from(x.abs_diff(self.x) / block).ok()?;
+        let block_y = usize::try_from(y.abs_diff(self.y) / block).ok()?;
+        if block_x >= self.width || block_y >= self.height { return None }
+        Some(self.samples[block_x][block_y])
+    }
+
+    /// Number of cells along each side of a single sample.
+    pub fn block_size(&self) -> usize { 1 << self.level }
+
+    // GETTERS
+    pub fn x(&self) -> Coord { self.x }
+    pub fn y(&self) -> Coord { self.y }
+    pub fn level(&self) -> u32 { self.level }
+    /// Width in samples, not cells.
+    pub fn width(&self) -> usize { self.width }
+    /// Height in samples, not cells.
+    pub fn height(&self) -> usize { self.height }
+}
+
+/// Pair of density grids either side of a continuous zoom level,
+/// letting frontends crossfade between them rather than popping between representations.
+pub struct ZoomFrame {
+    /// The finest mip that is not more detailed than the zoom level.
+    pub fine: DensityGrid,
+    /// The next coarser mip after `fine`.
+    pub coarse: DensityGrid,
+    /// How far the zoom level is between the two grids, from 0.0 (all `fine`) to 1.0 (all `coarse`).
+    pub blend: f32
+}
+
+impl ZoomFrame {
+    /// Sample the viewport starting at x y with the given width and height in cells,
+    /// displayed with the given number of cells per pixel (or character).
+    /// When zoomed in past one cell per pixel, the fine grid is cell-exact and the blend is 0.0,
+    /// and zoomed out past the coarsest level, the blend is all the way to it.
+    pub fn new(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, cells_per_pixel: f32) -> ZoomFrame {
+        let zoom = cells_per_pixel.max(1.0).log2();
+        let level = (zoom.floor() as u32).min(DensityGrid::MAX_LEVEL - 1);
+
+        ZoomFrame {
+            fine: DensityGrid::new(world, x, y, width, height, level),
+            coarse: DensityGrid::new(world, x, y, width, height, level + 1),
+            blend: (zoom - level as f32).min(1.0)
+        }
+    }
+}
+
+/// Per-block rate of cell changes over recent generations, so frontends can dim chaotic areas
+/// and let stable structure stand out.
+/// Each generation is compared to the one `period` generations before it rather than the one just before,
+/// so oscillators with a period dividing it count as unchanging, rather than as busy as a soup.
+pub struct ActivityMap {
+    level: u32,
+    period: usize,
+    /// Weight kept by the old rate each update, from 0.0 (only the latest generation) towards 1.0 (slow to react).
+    decay: f32,
+    /// Live cells of the last `period` generations, oldest first.
+    previous: VecDeque<HashSet<Position>>,
+    rates: HashMap<(Coord, Coord), f32>
+}
+
+impl ActivityMap {
+    /// Rates below this are dropped, so blocks that have settled don't stay in the map forever.
+    const MIN_RATE: f32 = 1e-3;
+
+    /// Create an empty map over blocks of `2^level` cells per side.
+    pub fn new(level: u32, period: usize, decay: f32) -> ActivityMap {
+        ActivityMap {
+            level,
+            period: period.max(1),
+            decay: decay.clamp(0.0, 1.0),
+            previous: VecDeque::new(),
+            rates: HashMap::new()
+        }
+    }
+
+    /// Record the latest generation of the world, updating the rate of every block.
+    /// Rates only start once `period` generations have been recorded.
+    pub fn update(&mut self, world: &GameOfLife) {
+        let current: HashSet<Position> = world.live_cells().into_iter().collect();
+        let block = self.block_size();
+        let cells_per_block = (block * block) as f32;
+
+        let mut changes: HashMap<(Coord, Coord), usize> = HashMap::new();
+        if self.previous.len() == self.period {
+            let compared = self.previous.pop_front().expect("History is full");
+            for position in current.symmetric_difference(&compared) {
+                *changes.entry(chunk::world_to_chunk(position.x, position.y, block)).or_default() += 1;
+            }
+        }
+
+        // Every known block decays, and blocks with changes gain their share of this generation
+        for rate in self.rates.values_mut() {
+            *rate *= self.decay;
+        }
+        for (position, count) in changes {
+            *self.rates.entry(position).or_default() += (1.0 - self.decay) * count as f32 / cells_per_block;
+        }
+        self.rates.retain(|_, rate| *rate >= Self::MIN_RATE);
+
+        self.previous.push_back(current);
+    }
+
+    /// Returns the change rate of the block containing the given world position,
+    /// from 0.0 for unchanging to 1.0 for every cell changing every generation.
+    pub fn get_rate(&self, x: Coord, y: Coord) -> f32 {
+        let position = chunk::world_to_chunk(x, y, self.block_size());
+        self.rates.get(&position).copied().unwrap_or(0.0).min(1.0)
+    }
+
+    /// Returns how brightly to draw the given world position, from 1.0 for calm blocks
+    /// down to `min_brightness` for the most active. Rates are scaled up so a soup's
+    /// typical churn of a few cells per block is already well dimmed.
+    pub fn brightness(&self, x: Coord, y: Coord, min_brightness: f32) -> f32 {
+        let activity = (self.get_rate(x, y) * 4.0).min(1.0);
+        1.0 - activity * (1.0 - min_brightness)
+    }
+
+    /// Number of cells along each side of a block.
+    pub fn block_size(&self) -> usize { 1 << self.level }
+
+    // GETTERS
+    pub fn level(&self) -> u32 { self.level }
+    pub fn period(&self) -> usize { self.period }
+    pub fn decay(&self) -> f32 { self.decay }
+}
+
+/// Number of generations each live cell has been continuously alive, so frontends can colour
+/// long settled ash differently from fresh growth.
+/// Like [`ActivityMap`] it is updated from outside with each new generation, so frontends only pay for it while it is shown.
+#[derive(Debug, Clone, Default)]
+pub struct CellAges {
+    ages: HashMap<Position, u32>
+}
+
+impl CellAges {
+    pub fn new() -> CellAges {
+        CellAges::default()
+    }
+
+    /// Record the latest generation of the world. Cells alive at the last update get a generation older,
+    /// and every other live cell starts at age 0.
+    pub fn update(&mut self, world: &GameOfLife) {
+        self.ages = world.live_cells().into_iter()
+            .map(|position| (position, self.ages.get(&position).map_or(0, |age| age.saturating_add(1))))
+            .collect();
+    }
+
+    /// Returns the age of the cell at the given position, or [`None`] if it wasn't alive at the last update.
+    pub fn get_age(&self, x: Coord, y: Coord) -> Option<u32> {
+        self.ages.get(&Position::new(x, y)).copied()
+    }
+
+    /// Forget every age, so the next update counts every live cell as newborn.
+    /// Used when the world jumps rather than steps, such as when going back through history.
+    pub fn clear(&mut self) {
+        self.ages.clear();
+    }
+
+    /// Positions and ages of every cell alive at the last update, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, u32)> + '_ {
+        self.ages.iter().map(|(&position, &age)| (position, age))
+    }
+}
+
+/// Number of times each cell has changed state over the last few generations, so frontends can show where
+/// a large soup is still busy and where it has settled into quiet ash.
+/// Unlike [`ActivityMap`] it counts every cell exactly rather than blending blocks, and forgets changes
+/// once they are more than `window` generations old. Like [`CellAges`] it is updated from outside with each new generation.
+#[derive(Debug, Clone, Default)]
+pub struct Heatmap {
+    window: usize,
+    /// Live cells at the last update, or [`None`] before the first.
+    previous: Option<HashSet<Position>>,
+    /// Cells that changed at each of the last `window` updates, oldest first.
+    changes: VecDeque<Vec<Position>>,
+    counts: HashMap<Position, u32>
+}
+
+impl Heatmap {
+    /// Count the changes over the last `window` generations, which is at least 1.
+    pub fn new(window: usize) -> Heatmap {
+        Heatmap { window: window.max(1), ..Heatmap::default() }
+    }
+
+    /// Record the latest generation of the world, counting every cell that was born or died since the last update.
+    /// The first update only records the world, as there is nothing to compare it with.
+    pub fn update(&mut self, world: &GameOfLife) {
+        let current: HashSet<Position> = world.live_cells().into_iter().collect();
+        let Some(previous) = self.previous.replace(current) else { return };
+        let current = self.previous.as_ref().expect("Just recorded");
+        let changed: Vec<Position> = current.symmetric_difference(&previous).copied().collect();
+        for &position in &changed {
+            *self.counts.entry(position).or_default() += 1;
+        }
+        self.changes.push_back(changed);
+        if self.changes.len() > self.window {
+            for position in self.changes.pop_front().expect("Changes are over the window") {
+                let Some(count) = self.counts.get_mut(&position) else { continue };
+                *count -= 1;
+                if *count == 0 { self.counts.remove(&position); }
+            }
+        }
+    }
+
+    /// Returns the number of times the cell at the given position changed in the window.
+    pub fn get_count(&self, x: Coord, y: Coord) -> u32 {
+        self.counts.get(&Position::new(x, y)).copied().unwrap_or(0)
+    }
+
+    /// Returns how active the cell at the given position has been, from 0.0 for unchanged
+    /// to 1.0 for changing every generation of the window.
+    pub fn heat(&self, x: Coord, y: Coord) -> f32 {
+        self.get_count(x, y) as f32 / self.window as f32
+    }
+
+    /// Forget every change, so counting starts again from the next update.
+    /// Used when the world jumps rather than steps, such as when going back through history.
+    pub fn clear(&mut self) {
+        self.previous = None;
+        self.changes.clear();
+        self.counts.clear();
+    }
+
+    /// Positions and counts of every cell that changed in the window, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, u32)> + '_ {
+        self.counts.iter().map(|(&position, &count)| (position, count))
+    }
+
+    // GETTERS
+    pub fn window(&self) -> usize { self.window }
+}
+
+/// Stops of the age gradient, as the log2 of one more than the age and the colour there.
+/// Newborn cells are a hot yellow, cooling through orange and red to a dull blue for long dead ash.
+const AGE_GRADIENT: [(f32, [u8; 3]); 6] = [
+    (0.0, [255, 255, 200]),
+    (1.0, [255, 200, 60]),
+    (2.0, [240, 110, 30]),
+    (4.0, [180, 40, 60]),
+    (6.0, [100, 50, 130]),
+    (8.0, [60, 70, 100])
+];
+
+/// Returns the colour of a live cell of the given age. The gradient is on a log scale,
+/// so the first few generations are clearly apart while anything past a few hundred looks the same.
+pub fn age_colour(age: u32) -> [u8; 3] {
+    gradient_colour(&AGE_GRADIENT, (age as f32 + 1.0).log2())
+}
+
+/// Stops of the heat gradient, as the fraction of generations a cell changed in and the colour there.
+/// Barely active cells are a deep blue, warming through red to a glaring yellow for cells changing every generation.
+const HEAT_GRADIENT: [(f32, [u8; 3]); 4] = [
+    (0.0, [30, 40, 120]),
+    (0.25, [150, 40, 110]),
+    (0.5, [240, 80, 30]),
+    (1.0, [255, 240, 120])
+];
+
+/// Returns the colour of a cell with the given heat from [`Heatmap::heat`].
+pub fn heat_colour(heat: f32) -> [u8; 3] {
+    gradient_colour(&HEAT_GRADIENT, heat)
+}
+
+/// Colour at a position along a gradient, blending between the stops either side of it
+/// and taking the colour of the last stop past the end.
+fn gradient_colour(stops: &[(f32, [u8; 3])], position: f32) -> [u8; 3] {
+    for pair in stops.windows(2) {
+        let ((start, from), (end, to)) = (pair[0], pair[1]);
+        if position > end { continue }
+        let t = ((position - start) / (end - start)).max(0.0);
+        return std::array::from_fn(|channel| (from[channel] as f32 + (to[channel] as f32 - from[channel] as f32) * t).round() as u8)
+    }
+    stops[stops.len() - 1].1
+}
+
+/// Returns the closest colour in the 6x6x6 cube of the 256 colour ANSI palette to the colour of the given age,
+/// for terminals that can't show arbitrary colours.
+pub fn age_ansi(age: u32) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest = |channel: u8| (0..LEVELS.len()).min_by_key(|&level| LEVELS[level].abs_diff(channel)).expect("Levels aren't empty") as u8;
+    let [r, g, b] = age_colour(age).map(nearest);
+    16 + 36 * r + 6 * g + b
+}
+
+/// Colour used for live cells in rendered images.
+pub const ALIVE_RGB: [u8; 3] = [255, 255, 255];
+/// Colour used for dead cells in rendered images.
+pub const DEAD_RGB: [u8; 3] = [0, 0, 0];
+
+/// Colours to draw each cell state with, indexed by state as in Golly, so state 0 is dead and 1 is alive.
+/// Only the first two states are used while cells are two-state, but palettes for rules with more are kept whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colours: Vec<[u8; 3]>
+}
+
+impl Palette {
+    /// Create a palette from the colour of each state, starting from state 0.
+    /// Missing states fall back to [`DEAD_RGB`] and [`ALIVE_RGB`].
+    pub fn new(mut colours: Vec<[u8; 3]>) -> Palette {
+        if colours.is_empty() { colours.push(DEAD_RGB) }
+        if colours.len() == 1 { colours.push(ALIVE_RGB) }
+        Palette { colours }
+    }
+
+    /// Returns the colour of a state, or the colour of the highest state if it is past the end.
+    pub fn get_colour(&self, state: usize) -> [u8; 3] {
+        self.colours[state.min(self.colours.len() - 1)]
+    }
+
+    /// Returns the colour a cell is drawn with.
+    pub fn cell_colour(&self, cell: Cell) -> [u8; 3] {
+        match cell {
+            Cell::Dead => self.get_colour(0),
+            Cell::Alive => self.get_colour(1)
+        }
+    }
+
+    /// Number of states with a colour.
+    pub fn states(&self) -> usize { self.colours.len() }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::new(vec![DEAD_RGB, ALIVE_RGB])
+    }
+}
+
+/// Render the area of the world starting at x y with the given width and height in cells
+/// to a buffer of 8-bit RGB pixels, in rows from the -y edge.
+/// Each cell is drawn as a square of `scale` pixels per side, so the image is
+/// `width * scale` by `height * scale` pixels.
+pub fn rgb_image(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, scale: usize) -> Vec<u8> {
+    rgb_image_with_palette(world, x, y, width, height, scale, &Palette::default())
+}
+
+/// Render like [`rgb_image`], drawing cells with the colours of the given palette.
+pub fn rgb_image_with_palette(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, scale: usize, palette: &Palette) -> Vec<u8> {
+    rgb_image_with(x, y, width, height, scale, |cell_x, cell_y| palette.cell_colour(world.get_cell(cell_x, cell_y)))
+}
+
+/// Render like [`rgb_image`] cells with more states than alive and dead, such as the colours of
+/// [`ColouredWorld`](crate::coloured::ColouredWorld) populations, drawing each state with its colour in the palette.
+pub fn rgb_image_of_states(x: Coord, y: Coord, width: usize, height: usize, scale: usize, palette: &Palette, state_at: impl Fn(Position) -> u8) -> Vec<u8> {
+    rgb_image_with(x, y, width, height, scale, |cell_x, cell_y| palette.get_colour(state_at(Position::new(cell_x, cell_y)) as usize))
+}
+
+/// Render like [`rgb_image`] the cells alive at the last update of the ages, coloured by age with [`age_colour`],
+/// over state 0 of the palette.
+pub fn rgb_image_by_age(ages: &CellAges, x: Coord, y: Coord, width: usize, height: usize, scale: usize, palette: &Palette) -> Vec<u8> {
+    rgb_image_with(x, y, width, height, scale, |cell_x, cell_y| {
+        ages.get_age(cell_x, cell_y).map_or(palette.get_colour(0), age_colour)
+    })
+}
+
+/// Render like [`rgb_image_with_palette`], colouring every cell that changed in the heatmap's window
+/// with [`heat_colour`], so quiet cells keep their palette colours and busy ones glow.
+#[allow(clippy::too_many_arguments)]
+pub fn rgb_image_by_heat(world: &GameOfLife, heatmap: &Heatmap, x: Coord, y: Coord, width: usize, height: usize, scale: usize, palette: &Palette) -> Vec<u8> {
+    rgb_image_with(x, y, width, height, scale, |cell_x, cell_y| match heatmap.get_count(cell_x, cell_y) {
+        0 => palette.cell_colour(world.get_cell(cell_x, cell_y)),
+        _ => heat_colour(heatmap.heat(cell_x, cell_y))
+    })
+}
+
+/// How cells are coloured in images, tracking whatever it needs of the world's past generations.
+#[derive(Debug, Clone)]
+pub enum Shading {
+    /// Each cell in its palette colour, with [`rgb_image_with_palette`].
+    Plain,
+    /// Live cells by how long they have been alive, with [`rgb_image_by_age`].
+    Age(CellAges),
+    /// Cells by how often they changed lately, with [`rgb_image_by_heat`].
+    Heat(Heatmap)
+}
+
+impl Shading {
+    /// Record the latest generation of the world.
+    pub fn update(&mut self, world: &GameOfLife) {
+        match self {
+            Shading::Plain => {}
+            Shading::Age(ages) => ages.update(world),
+            Shading::Heat(heatmap) => heatmap.update(world)
+        }
+    }
+
+    /// Step the world the given number of generations, recording each of them.
+    pub fn step(&mut self, world: &mut GameOfLife, generations: usize) {
+        for _ in 0..generations {
+            world.step();
+            self.update(world);
+        }
+    }
+
+    /// Render like [`rgb_image`], shading cells by what has been recorded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rgb_image(&self, world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, scale: usize, palette: &Palette) -> Vec<u8> {
+        match self {
+            Shading::Plain => rgb_image_with_palette(world, x, y, width, height, scale, palette),
+            Shading::Age(ages) => rgb_image_by_age(ages, x, y, width, height, scale, palette),
+            Shading::Heat(heatmap) => rgb_image_by_heat(world, heatmap, x, y, width, height, scale, palette)
+        }
+    }
+}
+
+/// Render the area with the colour of each cell given by a function of its position.
+fn rgb_image_with(x: Coord, y: Coord, width: usize, height: usize, scale: usize, colour_at: impl Fn(Coord, Coord) -> [u8; 3]) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * scale * scale * 3);
+    for cell_y in y..y.saturating_add_unsigned(height as u64) {
+        let mut row = Vec::with_capacity(width * scale * 3);
+        for cell_x in x..x.saturating_add_unsigned(width as u64) {
+            let colour = colour_at(cell_x, cell_y);
+            for _ in 0..scale {
+                row.extend_from_slice(&colour);
+            }
+        }
+        for _ in 0..scale {
+            pixels.extend_from_slice(&row);
+        }
+    }
+    pixels
+}
+
+/// Render the area of the world starting at x y with the given width and height in cells
+/// as lines of text, with `#` for live cells and `.` for dead cells.
+pub fn text_grid(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize) -> String {
+    text_grid_with_theme(world, x, y, width, height, &Theme::default())
+}
+
+/// Render the area of the world as lines of text, like [`text_grid`], with the theme's characters for live and dead cells.
+pub fn text_grid_with_theme(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, theme: &Theme) -> String {
+    let mut text = String::with_capacity((width + 1) * height);
+    for cell_y in y..y.saturating_add_unsigned(height as u64) {
+        for cell_x in x..x.saturating_add_unsigned(width as u64) {
+            text.push(theme.glyph(world.get_cell(cell_x, cell_y)));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Render the area of the world as a hexagonal grid of text, for rules on the [`Hexagonal`](crate::rule::Neighbourhood::Hexagonal)
+/// neighbourhood, with the theme's characters for live and dead cells. Cells are spaced out along each row and every row
+/// is shifted half a cell right of the one above, so the area, a rhombus in axial coordinates, is drawn as one.
+pub fn hex_grid_with_theme(world: &GameOfLife, x: Coord, y: Coord, width: usize, height: usize, theme: &Theme) -> String {
+    let mut text = String::with_capacity((width * 2 + height) * height);
+    for (row, cell_y) in (y..y.saturating_add_unsigned(height as u64)).enumerate() {
+        text.extend(std::iter::repeat_n(' ', row));
+        for cell_x in x..x.saturating_add_unsigned(width as u64) {
+            if cell_x != x { text.push(' ') }
+            text.push(theme.glyph(world.get_cell(cell_x, cell_y)));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Glyphs for increasing densities when drawing thumbnails as text.
+const SHADE_GLYPHS: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Small preview image of a pattern, with the live cell density of each pixel.
+pub struct Thumbnail {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec<f32>>
+}
+
+impl Thumbnail {
+    /// Returns the density of the pixel at the given position, from 0.0 for all dead to 1.0 for all alive.
+    /// If the position is outside of the thumbnail, returns [`None`].
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<f32> {
+        self.pixels.get(x)?.get(y).copied()
+    }
+
+    /// Convert to 8-bit RGB pixels, in rows, shading between [`DEAD_RGB`] and [`ALIVE_RGB`].
+    pub fn to_rgb(&self) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.width * self.height * 3);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let density = self.pixels[x][y];
+                for channel in 0..3 {
+                    let dead = DEAD_RGB[channel] as f32;
+                    let alive = ALIVE_RGB[channel] as f32;
+                    pixels.push((dead + (alive - dead) * density).round() as u8);
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Convert to lines of shaded block characters, one character per pixel.
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let shade = (self.pixels[x][y] * (SHADE_GLYPHS.len() - 1) as f32).round() as usize;
+                text.push(SHADE_GLYPHS[shade]);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    // GETTERS
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+}
+
+impl Region {
+    /// Render a preview of the pattern in this region, cropped to its live cells,
+    /// with neither side longer than `max_px` pixels.
+    /// Small patterns are scaled up by a whole number of pixels per cell,
+    /// while large ones are scaled down by averaging square blocks of cells into each pixel.
+    pub fn render_thumbnail(&self, max_px: usize) -> Thumbnail {
+        let Some(bounds) = self.live_bounds()
+        else { return Thumbnail { width: 0, height: 0, pixels: vec![] } };
+        let (min_x, min_y) = (bounds.x, bounds.y);
+        let (cells_wide, cells_high) = (bounds.width, bounds.height);
+        let longest = cells_wide.max(cells_high);
+        let max_px = max_px.max(1);
+
+        // Scaling up
+        if longest <= max_px {
+            let scale = max_px / longest;
+            let (width, height) = (cells_wide * scale, cells_high * scale);
+            let pixels = (0..width).map(|x| {
+                (0..height).map(|y| {
+                    let cell_x = min_x + (x / scale) as Coord;
+                    let cell_y = min_y + (y / scale) as Coord;
+                    match self.get_cell(cell_x, cell_y) {
+                        Some(Cell::Alive) => 1.0,
+                        _ => 0.0
+                    }
+                }).collect()
+            }).collect();
+            return Thumbnail { width, height, pixels }
+        }
+
+        // Scaling down
+        let block = longest.div_ceil(max_px);
+        let (width, height) = (cells_wide.div_ceil(block), cells_high.div_ceil(block));
+        let cells_per_block = (block * block) as f32;
+        let pixels = (0..width).map(|x| {
+            (0..height).map(|y| {
+                let origin_x = min_x + (x * block) as Coord;
+                let origin_y = min_y + (y * block) as Coord;
+                let mut alive = 0;
+                for cell_x in origin_x..origin_x + block as Coord {
+                    for cell_y in origin_y..origin_y + block as Coord {
+                        if self.get_cell(cell_x, cell_y) == Some(Cell::Alive) { alive += 1 }
+                    }
+                }
+                alive as f32 / cells_per_block
+            }).collect()
+        }).collect();
+        Thumbnail { width, height, pixels }
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    #[test]
+    fn activity_map() {
+        let mut world = GameOfLife::new();
+        // Blinker near the origin, and a glider far away in its own block
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            world.set_cell(x + 40, y + 40, Cell::Alive);
+        }
+
+        let mut activity = ActivityMap::new(3, 2, 0.5);
+        for _ in 0..8 {
+            activity.update(&world);
+            world.step();
+        }
+
+        // The blinker repeats every 2 generations so is calm, while the glider never repeats
+        assert_eq!(0.0, activity.get_rate(0, 0));
+        assert_eq!(1.0, activity.brightness(0, 0, 0.2));
+        assert!(activity.get_rate(41, 41) > 0.0);
+        assert!(activity.brightness(41, 41, 0.2) < 1.0);
+        assert_eq!(0.0, activity.get_rate(-100, 100));
+
+        // Once everything is still, rates decay away
+        let still = GameOfLife::new();
+        for _ in 0..20 {
+            activity.update(&still);
+        }
+        assert_eq!(0.0, activity.get_rate(41, 41));
+    }
+
+    #[test]
+    fn heatmap() {
+        // Blinker, whose ends change every generation, beside a block that never does
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        for (x, y) in [(10, 10), (11, 10), (10, 11), (11, 11)] {
+            world.set_cell(x, y, Cell::Alive);
+        }
+        let mut heatmap = Heatmap::new(4);
+        heatmap.update(&world);
+        assert_eq!(0, heatmap.iter().count());
+        for _ in 0..5 {
+            world.step();
+            heatmap.update(&world);
+        }
+        assert_eq!(4, heatmap.get_count(1, 0));
+        assert_eq!(1.0, heatmap.heat(0, -1));
+        assert_eq!(0, heatmap.get_count(0, 0));
+        assert_eq!(0, heatmap.get_count(10, 10));
+        assert_eq!(4, heatmap.iter().count());
+
+        let pixels = rgb_image_by_heat(&world, &heatmap, -1, 0, 3, 1, 1, &Palette::default());
+        assert_eq!(vec![255, 240, 120, 255, 255, 255, 255, 240, 120], pixels);
+        assert_eq!([30, 40, 120], heat_colour(0.0));
+
+        // Changes are forgotten once they fall out of the window
+        for _ in 0..4 {
+            heatmap.update(&world);
+        }
+        assert_eq!(0, heatmap.iter().count());
+        heatmap.clear();
+        world.step();
+        heatmap.update(&world);
+        assert_eq!(0, heatmap.iter().count());
+    }
+
+    #[test]
+    fn cell_ages() {
+        // Blinker, whose centre cell never dies while its ends swap each generation
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut ages = CellAges::new();
+        ages.update(&world);
+        assert_eq!(Some(0), ages.get_age(1, 0));
+        for _ in 0..5 {
+            world.step();
+            ages.update(&world);
+        }
+        assert_eq!(Some(5), ages.get_age(0, 0));
+        assert_eq!(Some(0), ages.get_age(0, 1));
+        assert_eq!(None, ages.get_age(1, 0));
+        assert_eq!(3, ages.iter().count());
+
+        ages.clear();
+        assert_eq!(None, ages.get_age(0, 0));
+
+        // Young cells are bright, old ones fade to the end of the gradient
+        assert_eq!([255, 255, 200], age_colour(0));
+        assert_eq!([255, 200, 60], age_colour(1));
+        assert_eq!([60, 70, 100], age_colour(10_000));
+        assert_eq!(230, age_ansi(0));
+        assert_eq!(59, age_ansi(u32::MAX));
+
+        ages.update(&world);
+        let pixels = rgb_image_by_age(&ages, -1, 0, 3, 1, 1, &Palette::default());
+        assert_eq!(vec![0, 0, 0, 255, 255, 200, 0, 0, 0], pixels);
+    }
+
+    #[test]
+    fn density_grid() {
+        let mut world = GameOfLife::new();
+        // Fill the 2x2 block from (-2, -2) and one cell of the block from (0, 0)
+        world.set_cell(-2, -2, Cell::Alive);
+        world.set_cell(-1, -2, Cell::Alive);
+        world.set_cell(-2, -1, Cell::Alive);
+        world.set_cell(-1, -1, Cell::Alive);
+        world.set_cell(1, 1, Cell::Alive);
+
+        let cells = DensityGrid::new(&world, -2, -2, 4, 4, 0);
+        assert_eq!(4, cells.width());
+        assert_eq!(Some(1.0), cells.get_sample(-2, -1));
+        assert_eq!(Some(0.0), cells.get_sample(0, 0));
+        assert_eq!(Some(1.0), cells.get_sample(1, 1));
+        assert_eq!(None, cells.get_sample(2, 0));
+
+        let mip = DensityGrid::new(&world, -2, -2, 4, 4, 1);
+        assert_eq!((2, 2), (mip.width(), mip.height()));
+        assert_eq!(Some(1.0), mip.get_sample(-2, -2));
+        assert_eq!(Some(1.0), mip.get_sample(-1, -1));
+        assert_eq!(Some(0.25), mip.get_sample(0, 1));
+        assert_eq!(Some(0.0), mip.get_sample(-1, 0));
+
+        // Against a live background, the density counts the background too
+        let mut strobe = GameOfLife::with_rule("B0/S012345678".parse().unwrap());
+        strobe.step();
+        strobe.set_cell(0, 0, Cell::Dead);
+        let mip = DensityGrid::new(&strobe, 0, 0, 4, 4, 1);
+        assert_eq!((Some(0.75), Some(1.0)), (mip.get_sample(0, 0), mip.get_sample(2, 2)));
+    }
+
+    #[test]
+    fn density_grid_alignment() {
+        let world = GameOfLife::new();
+
+        // Area from -3 to 2 inclusive touches blocks of 4 starting at -4 and 0
+        let grid = DensityGrid::new(&world, -3, -3, 6, 6, 2);
+        assert_eq!((-4, -4), (grid.x(), grid.y()));
+        assert_eq!((2, 2), (grid.width(), grid.height()));
+        assert_eq!(4, grid.block_size());
+        assert_eq!(Some(0.0), grid.get_sample(-4, 3));
+        assert_eq!(None, grid.get_sample(4, 0));
+        assert_eq!(None, grid.get_sample(-5, 0));
+    }
+
+    #[test]
+    fn rgb_image() {
+        let mut world = GameOfLife::new();
+        world.set_cell(1, 0, Cell::Alive);
+
+        let pixels = super::rgb_image(&world, 0, 0, 2, 1, 2);
+        assert_eq!(2 * 2 * 2 * 3, pixels.len());
+        // Rows are 4 pixels wide, with the live cell on the right half
+        let row: Vec<u8> = [DEAD_RGB, DEAD_RGB, ALIVE_RGB, ALIVE_RGB].concat();
+        assert_eq!(row, pixels[..12]);
+        assert_eq!(row, pixels[12..]);
+    }
+
+    #[test]
+    fn rgb_image_of_states() {
+        // Each state takes its own colour, and states past the palette's end take its last
+        let palette = Palette::new(vec![DEAD_RGB, [255, 0, 0], [0, 0, 255]]);
+        let pixels = super::rgb_image_of_states(0, 0, 4, 1, 1, &palette, |position| position.x as u8);
+        assert_eq!([DEAD_RGB, [255, 0, 0], [0, 0, 255], [0, 0, 255]].concat(), pixels);
+    }
+
+    #[test]
+    fn thumbnail_scaled_up() {
+        // Glider in the middle of a larger region, which gets cropped away
+        let mut glider = Region::new(-10, -10, 20, 20).unwrap();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            glider.set_cell(x, y, Cell::Alive);
+        }
+
+        let thumbnail = glider.render_thumbnail(7);
+        assert_eq!((6, 6), (thumbnail.width(), thumbnail.height()));
+        assert_eq!(Some(0.0), thumbnail.get_pixel(1, 1));
+        assert_eq!(Some(1.0), thumbnail.get_pixel(2, 1));
+        assert_eq!(Some(1.0), thumbnail.get_pixel(3, 0));
+        assert_eq!(None, thumbnail.get_pixel(6, 0));
+        assert_eq!("  ██  \n  ██  \n    ██\n    ██\n██████\n██████\n", thumbnail.to_text());
+    }
+
+    #[test]
+    fn thumbnail_scaled_down() {
+        // 8x4 block of cells with the right half alive in alternating rows
+        let mut region = Region::new(0, 0, 8, 4).unwrap();
+        for x in 0..8 {
+            region.set_cell(x, 0, Cell::Alive);
+            region.set_cell(x, 3, Cell::Alive);
+        }
+        for x in 4..8 {
+            region.set_cell(x, 1, Cell::Alive);
+        }
+
+        let thumbnail = region.render_thumbnail(4);
+        assert_eq!((4, 2), (thumbnail.width(), thumbnail.height()));
+        assert_eq!(Some(0.5), thumbnail.get_pixel(0, 0));
+        assert_eq!(Some(1.0), thumbnail.get_pixel(3, 0));
+        assert_eq!(Some(0.5), thumbnail.get_pixel(2, 1));
+        assert_eq!(&[128, 128, 128], &thumbnail.to_rgb()[..3]);
+
+        let empty = Region::new(0, 0, 5, 5).unwrap().render_thumbnail(4);
+        assert_eq!((0, 0), (empty.width(), empty.height()));
+    }
+
+    #[test]
+    fn text_grid() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(1, 1, Cell::Alive);
+        assert_eq!("#..\n.#.\n", super::text_grid(&world, 0, 0, 3, 2));
+        assert_eq!("# . .\n . # .\n", hex_grid_with_theme(&world, 0, 0, 3, 2, &Theme::default()));
+    }
+
+    #[test]
+    fn zoom_frame() {
+        let world = GameOfLife::new();
+
+        let zoomed_in = ZoomFrame::new(&world, 0, 0, 8, 8, 0.5);
+        assert_eq!(0, zoomed_in.fine.level());
+        assert_eq!(1, zoomed_in.coarse.level());
+        assert_eq!(0.0, zoomed_in.blend);
+
+        let between = ZoomFrame::new(&world, 0, 0, 8, 8, 2.0f32.powf(2.5));
+        assert_eq!(2, between.fine.level());
+        assert_eq!(3, between.coarse.level());
+        assert!((between.blend - 0.5).abs() < 1e-5);
+
+        // However far out, the levels stop at blocks the coordinates can hold
+        let zoomed_out = ZoomFrame::new(&world, Coord::MIN, 0, 8, 8, f32::INFINITY);
+        assert_eq!((DensityGrid::MAX_LEVEL - 1, DensityGrid::MAX_LEVEL), (zoomed_out.fine.level(), zoomed_out.coarse.level()));
+        assert_eq!(1.0, zoomed_out.blend);
+        assert_eq!((Some(0.0), None), (zoomed_out.coarse.get_sample(Coord::MIN + 7, 7), zoomed_out.coarse.get_sample(Coord::MAX, 0)));
+    }
+}
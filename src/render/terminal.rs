@@ -0,0 +1,418 @@
+//! Drawing a viewport of the world as text for a terminal.
+//! Terminal characters are about twice as tall as they are wide,
+//! so each character covers a block of cells twice as tall as it is wide to keep cells square.
+
+use std::io::{self, Write};
+
+use crate::chunk;
+use crate::geometry::{Coord, Position};
+use crate::gol::{Cell, GameOfLife};
+use crate::render::{self, CellAges};
+
+/// Half blocks indexed by the bits of the top (1) and bottom (2) cells.
+const HALF_BLOCK_GLYPHS: [char; 4] = [' ', '▀', '▄', '█'];
+
+/// Quadrant blocks indexed by the bits of the top left (1), top right (2), bottom left (4) and bottom right (8) cells.
+const QUADRANT_GLYPHS: [char; 16] = [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'];
+
+/// Glyphs for increasing live cell densities when zoomed out past individual cells.
+const SHADE_GLYPHS: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Bit of a braille character for the dot at each position, indexed `[x][y]`.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Characters to draw cells with.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Glyphs {
+    /// One cell per character, using `█`. Cells look twice as tall as they are wide.
+    Blocks,
+    /// One cell across and two down per character, using `▀`, `▄` and `█`.
+    #[default]
+    HalfBlocks,
+    /// Two cells across and two down per character, using quadrant blocks.
+    Quadrants,
+    /// Two cells across and four down per character, using braille dots.
+    Braille,
+    /// `2^level` cells across and twice that down per character,
+    /// shaded by how many of them are alive rather than showing individual cells.
+    Shade(u32)
+}
+
+impl Glyphs {
+    /// Returns the number of cells across and down covered by each character.
+    pub fn cells_per_char(self) -> (usize, usize) {
+        match self {
+            Glyphs::Blocks => (1, 1),
+            Glyphs::HalfBlocks => (1, 2),
+            Glyphs::Quadrants => (2, 2),
+            Glyphs::Braille => (2, 4),
+            Glyphs::Shade(level) => (1 << level, 2 << level)
+        }
+    }
+
+    /// Returns the character for a block of cells, where `alive` is indexed `[x][y]` within the block.
+    fn glyph(self, alive: impl Fn(usize, usize) -> bool) -> char {
+        match self {
+            Glyphs::Blocks => if alive(0, 0) { '█' } else { ' ' },
+            Glyphs::HalfBlocks => HALF_BLOCK_GLYPHS[alive(0, 0) as usize | (alive(0, 1) as usize) << 1],
+            Glyphs::Quadrants => {
+                let bits = alive(0, 0) as usize | (alive(1, 0) as usize) << 1 | (alive(0, 1) as usize) << 2 | (alive(1, 1) as usize) << 3;
+                QUADRANT_GLYPHS[bits]
+            }
+            Glyphs::Braille => {
+                let mut bits = 0;
+                for (x, column) in BRAILLE_DOTS.iter().enumerate() {
+                    for (y, bit) in column.iter().enumerate() {
+                        if alive(x, y) { bits |= bit }
+                    }
+                }
+                char::from_u32(0x2800 + bits).expect("Braille block is all valid characters")
+            }
+            Glyphs::Shade(_) => {
+                let (across, down) = self.cells_per_char();
+                let alive = (0..across).flat_map(|x| (0..down).map(move |y| (x, y))).filter(|&(x, y)| alive(x, y)).count();
+                Self::shade(alive, across * down)
+            }
+        }
+    }
+
+    /// Returns the shade glyph for the given number of live cells out of the total.
+    /// Any live cells at all show as at least the lightest shade, so sparse patterns don't vanish when zoomed out.
+    fn shade(alive: usize, total: usize) -> char {
+        if alive == 0 { return SHADE_GLYPHS[0] }
+        let shade = (alive * (SHADE_GLYPHS.len() - 1)).div_ceil(total);
+        SHADE_GLYPHS[shade.clamp(1, SHADE_GLYPHS.len() - 1)]
+    }
+}
+
+/// Draw the area of the world with its -x -y corner at x y, `columns` characters wide and `rows` characters tall,
+/// as lines of text. The number of cells covered depends on the [`Glyphs`] used.
+pub fn draw(world: &GameOfLife, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> String {
+    if let Glyphs::Shade(_) = glyphs { return draw_shaded(world, x, y, columns, rows, glyphs) }
+    let (cells_across, cells_down) = glyphs.cells_per_char();
+    let mut text = String::with_capacity((columns + 1) * rows * 3);
+    for row in 0..rows {
+        let origin_y = y.saturating_add_unsigned((row * cells_down) as u64);
+        for column in 0..columns {
+            let origin_x = x.saturating_add_unsigned((column * cells_across) as u64);
+            text.push(glyphs.glyph(|dx, dy| {
+                world.get_cell(origin_x.saturating_add_unsigned(dx as u64), origin_y.saturating_add_unsigned(dy as u64)) == Cell::Alive
+            }));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Draw with shade glyphs by counting the live cells in each character,
+/// which is much quicker than checking every cell when zoomed out far.
+fn draw_shaded(world: &GameOfLife, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> String {
+    let (cells_across, cells_down) = glyphs.cells_per_char();
+    let mut counts = vec![vec![0; rows]; columns];
+    for cell in world.live_cells() {
+        let column = chunk::floor_div(cell.x - x, cells_across as Coord);
+        let row = chunk::floor_div(cell.y - y, cells_down as Coord);
+        if column < 0 || row < 0 || column as usize >= columns || row as usize >= rows { continue }
+        counts[column as usize][row as usize] += 1;
+    }
+
+    let mut text = String::with_capacity((columns + 1) * rows * 3);
+    for row in 0..rows {
+        for column in &counts {
+            text.push(Glyphs::shade(column[row], cells_across * cells_down));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Youngest age of the live cells under each character of the same area as [`draw`], in rows,
+/// or [`None`] for characters with no cells recorded in the ages.
+/// Using the youngest lets growth show through even when it shares a character with old ash.
+pub fn age_grid(ages: &CellAges, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> Vec<Vec<Option<u32>>> {
+    let (cells_across, cells_down) = glyphs.cells_per_char();
+    let mut grid = vec![vec![None; columns]; rows];
+    for (cell, age) in ages.iter() {
+        let Some(offset) = cell.checked_sub(Position::new(x, y)) else { continue };
+        let column = chunk::floor_div(offset.x, cells_across as Coord);
+        let row = chunk::floor_div(offset.y, cells_down as Coord);
+        if column < 0 || row < 0 || column as usize >= columns || row as usize >= rows { continue }
+        let youngest: &mut Option<u32> = &mut grid[row as usize][column as usize];
+        *youngest = Some(youngest.map_or(age, |youngest| youngest.min(age)));
+    }
+    grid
+}
+
+/// Character with the 256 colour ANSI codes of its foreground and background, [`None`] for the terminal's defaults.
+pub type Styled = (char, Option<u8>, Option<u8>);
+
+/// Characters of the same area as [`draw`], each coloured by the youngest cell under it.
+/// Characters without any aged cells keep the terminal's default colours.
+/// With [`Glyphs::HalfBlocks`], a character whose two cells are different colours is drawn as `▀`
+/// over a background of the bottom cell's colour, so every cell shows its own age.
+pub fn styled_by_age(world: &GameOfLife, ages: &CellAges, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> Vec<Vec<Styled>> {
+    let grid = age_grid(ages, x, y, columns, rows, glyphs);
+    draw(world, x, y, columns, rows, glyphs).lines().zip(grid).enumerate().map(|(row, (line, ages_in_line))| {
+        line.chars().zip(ages_in_line).enumerate().map(|(column, (glyph, age))| {
+            let colour = age.map(render::age_ansi);
+            if glyphs != Glyphs::HalfBlocks || glyph != '█' { return (glyph, colour, None) }
+            let (cell_x, cell_y) = (x.saturating_add_unsigned(column as u64), y.saturating_add_unsigned((row * 2) as u64));
+            let top = ages.get_age(cell_x, cell_y).map(render::age_ansi);
+            let bottom = ages.get_age(cell_x, cell_y.saturating_add(1)).map(render::age_ansi);
+            match (top, bottom) {
+                (Some(top), Some(bottom)) if top != bottom => ('▀', Some(top), Some(bottom)),
+                _ => (glyph, colour, None)
+            }
+        }).collect()
+    }).collect()
+}
+
+/// Draw like [`draw`], colouring characters as in [`styled_by_age`] with 256 colour ANSI escapes.
+pub fn draw_by_age(world: &GameOfLife, ages: &CellAges, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> String {
+    let mut text = String::with_capacity((columns * 12 + 1) * rows);
+    for line in styled_by_age(world, ages, x, y, columns, rows, glyphs) {
+        let (mut foreground, mut background) = (None, None);
+        for (glyph, wanted_foreground, wanted_background) in line {
+            if wanted_foreground != foreground {
+                match wanted_foreground {
+                    Some(code) => text.push_str(&format!("\x1b[38;5;{code}m")),
+                    None => text.push_str("\x1b[39m")
+                }
+                foreground = wanted_foreground;
+            }
+            if wanted_background != background {
+                match wanted_background {
+                    Some(code) => text.push_str(&format!("\x1b[48;5;{code}m")),
+                    None => text.push_str("\x1b[49m")
+                }
+                background = wanted_background;
+            }
+            text.push(glyph);
+        }
+        // Reset before the newline so colours never leak into the next line or the rest of the terminal
+        if foreground.is_some() { text.push_str("\x1b[39m") }
+        if background.is_some() { text.push_str("\x1b[49m") }
+        text.push('\n');
+    }
+    text
+}
+
+/// Work out how many characters across and down are needed to draw `width` by `height` cells.
+pub fn chars_for_cells(width: usize, height: usize, glyphs: Glyphs) -> (usize, usize) {
+    let (cells_across, cells_down) = glyphs.cells_per_char();
+    (width.div_ceil(cells_across), height.div_ceil(cells_down))
+}
+
+/// Zoom levels, from closest to furthest.
+const ZOOM_LEVELS: [Glyphs; 8] = [
+    Glyphs::Blocks, Glyphs::HalfBlocks, Glyphs::Quadrants, Glyphs::Braille,
+    Glyphs::Shade(2), Glyphs::Shade(3), Glyphs::Shade(4), Glyphs::Shade(6)
+];
+
+/// Area of the world shown in the terminal, described by the cell at its centre and how far it is zoomed out.
+/// Unlike a [`view::Viewport`](crate::view::Viewport) it doesn't hold the world, so it can pan and zoom as the world steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalViewport {
+    x: Coord,
+    y: Coord,
+    zoom: usize
+}
+
+impl TerminalViewport {
+    /// Create a viewport centred on x y, with square cells at the closest zoom that keeps them square.
+    pub fn new(x: Coord, y: Coord) -> TerminalViewport {
+        let zoom = ZOOM_LEVELS.iter().position(|&glyphs| glyphs == Glyphs::HalfBlocks).expect("Half blocks is a zoom level");
+        TerminalViewport { x, y, zoom }
+    }
+
+    /// Create a viewport centred on the live cells of the world, or 0 0 if there are none.
+    pub fn centred_on(world: &GameOfLife) -> TerminalViewport {
+        let centre = world.live_bounds().map_or(Position::ORIGIN, |bounds| bounds.centre());
+        TerminalViewport::new(centre.x, centre.y)
+    }
+
+    /// Centre the view on the given cell, keeping the zoom.
+    pub fn move_to(&mut self, centre: Position) {
+        (self.x, self.y) = (centre.x, centre.y);
+    }
+
+    /// Move the view by the given number of characters, so panning covers more cells when zoomed out.
+    pub fn pan(&mut self, columns: Coord, rows: Coord) {
+        let (across, down) = self.glyphs().cells_per_char();
+        self.x = self.x.saturating_add(columns.saturating_mul(across as Coord));
+        self.y = self.y.saturating_add(rows.saturating_mul(down as Coord));
+    }
+
+    /// Show more detail, if not already at the closest zoom.
+    pub fn zoom_in(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1);
+    }
+
+    /// Show more of the world, if not already at the furthest zoom.
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom + 1).min(ZOOM_LEVELS.len() - 1);
+    }
+
+    /// Returns the world position of the cell in the top left character of a screen of the given size.
+    pub fn origin(&self, columns: usize, rows: usize) -> Position {
+        let (across, down) = self.glyphs().cells_per_char();
+        Position::new(self.x.saturating_sub_unsigned((columns / 2 * across) as u64), self.y.saturating_sub_unsigned((rows / 2 * down) as u64))
+    }
+
+    /// Returns the column and row of the character showing the given cell on a screen of the given size,
+    /// or [`None`] if it is off screen.
+    pub fn char_at(&self, cell: Position, columns: usize, rows: usize) -> Option<(usize, usize)> {
+        let offset = cell.checked_sub(self.origin(columns, rows))?;
+        let (across, down) = self.glyphs().cells_per_char();
+        let column = chunk::floor_div(offset.x, across as Coord);
+        let row = chunk::floor_div(offset.y, down as Coord);
+        if column < 0 || row < 0 || column as usize >= columns || row as usize >= rows { return None }
+        Some((column as usize, row as usize))
+    }
+
+    /// Returns the world position of the top left cell shown by the character at the given column and row
+    /// of a screen of the given size. Only at the closest zoom is that the only cell the character shows.
+    pub fn cell_at(&self, column: usize, row: usize, columns: usize, rows: usize) -> Position {
+        let (across, down) = self.glyphs().cells_per_char();
+        let origin = self.origin(columns, rows);
+        Position::new(origin.x.saturating_add_unsigned((column * across) as u64), origin.y.saturating_add_unsigned((row * down) as u64))
+    }
+
+    /// Re-centre on the given cell if it is off a screen of the given size.
+    pub fn keep_visible(&mut self, cell: Position, columns: usize, rows: usize) {
+        if self.char_at(cell, columns, rows).is_some() { return }
+        self.x = cell.x;
+        self.y = cell.y;
+    }
+
+    /// Draw the view of the world on a screen `columns` characters wide and `rows` characters tall.
+    pub fn draw(&self, world: &GameOfLife, columns: usize, rows: usize) -> String {
+        let origin = self.origin(columns, rows);
+        draw(world, origin.x, origin.y, columns, rows, self.glyphs())
+    }
+
+    /// Characters used at the current zoom level.
+    pub fn glyphs(&self) -> Glyphs { ZOOM_LEVELS[self.zoom] }
+
+    // GETTERS
+    pub fn x(&self) -> Coord { self.x }
+    pub fn y(&self) -> Coord { self.y }
+}
+
+/// Draw the area of the world with its -x -y corner at x y to stdout.
+/// See [`draw`] for how the size is measured.
+pub fn print_viewport(world: &GameOfLife, x: Coord, y: Coord, columns: usize, rows: usize, glyphs: Glyphs) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(draw(world, x, y, columns, rows, glyphs).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod terminal_tests {
+    use super::*;
+
+    fn glider() -> GameOfLife {
+        let mut world = GameOfLife::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            world.set_cell(x, y, Cell::Alive);
+        }
+        world
+    }
+
+    #[test]
+    fn half_blocks() {
+        let world = glider();
+        assert_eq!(" ▀▄\n▀▀▀\n", draw(&world, 0, 0, 3, 2, Glyphs::HalfBlocks));
+        // Negative viewport positions
+        assert_eq!("  ▄\n ▄▄\n", draw(&world, -1, -1, 3, 2, Glyphs::HalfBlocks));
+    }
+
+    #[test]
+    fn braille() {
+        let world = glider();
+        assert_eq!("⠬⠆\n", draw(&world, 0, 0, 2, 1, Glyphs::Braille));
+        assert_eq!("⠀\n", draw(&world, 10, 10, 1, 1, Glyphs::Braille));
+        assert_eq!((2, 1), chars_for_cells(3, 3, Glyphs::Braille));
+        assert_eq!((3, 2), chars_for_cells(3, 3, Glyphs::HalfBlocks));
+    }
+
+    #[test]
+    fn blocks_and_quadrants() {
+        let world = glider();
+        assert_eq!(" █ \n  █\n███\n", draw(&world, 0, 0, 3, 3, Glyphs::Blocks));
+        assert_eq!("▝▖\n▀▘\n", draw(&world, 0, 0, 2, 2, Glyphs::Quadrants));
+    }
+
+    #[test]
+    fn shaded() {
+        let world = glider();
+        // 3 of the 8 cells in the second character, and none in the first
+        assert_eq!(" ▒\n", draw(&world, -2, 0, 2, 1, Glyphs::Shade(1)));
+        assert_eq!("░\n", draw(&world, 0, 0, 1, 1, Glyphs::Shade(4)));
+    }
+
+    #[test]
+    fn coloured_by_age() {
+        let mut world = glider();
+        let mut ages = CellAges::new();
+        ages.update(&world);
+        world.step();
+        ages.update(&world);
+        // The glider keeps (2, 1), (1, 2) and (2, 2), while (0, 1) and (1, 3) are newborn
+        let grid = age_grid(&ages, 0, 0, 3, 2, Glyphs::HalfBlocks);
+        assert_eq!(vec![vec![Some(0), None, Some(1)], vec![None, Some(0), Some(1)]], grid);
+
+        let (young, old) = (render::age_ansi(0), render::age_ansi(1));
+        let text = draw_by_age(&world, &ages, 0, 0, 3, 2, Glyphs::HalfBlocks);
+        // The old top and newborn bottom of the middle character each keep their own colour
+        assert_eq!(
+            format!("\x1b[38;5;{young}m▄\x1b[39m \x1b[38;5;{old}m▄\x1b[39m\n \x1b[38;5;{old}m\x1b[48;5;{young}m▀\x1b[49m▀\x1b[39m\n"),
+            text
+        );
+        assert_eq!(('▀', Some(old), Some(young)), styled_by_age(&world, &ages, 0, 0, 3, 2, Glyphs::HalfBlocks)[1][1]);
+        // Other glyphs colour the whole character by its youngest cell
+        assert_eq!(('▛', Some(young), None), styled_by_age(&world, &ages, 1, 2, 1, 1, Glyphs::Quadrants)[0][0]);
+    }
+
+    #[test]
+    fn viewport() {
+        let world = glider();
+        let mut viewport = TerminalViewport::centred_on(&world);
+        assert_eq!((1, 1), (viewport.x(), viewport.y()));
+        assert_eq!(Glyphs::HalfBlocks, viewport.glyphs());
+        assert_eq!(Position::new(0, -1), viewport.origin(3, 2));
+
+        viewport.zoom_in();
+        viewport.zoom_in();
+        assert_eq!(Glyphs::Blocks, viewport.glyphs());
+        assert_eq!(" █ \n  █\n███\n", viewport.draw(&world, 3, 3));
+
+        // Panning moves by whole characters
+        viewport.zoom_out();
+        viewport.zoom_out();
+        viewport.pan(1, -1);
+        assert_eq!((3, -1), (viewport.x(), viewport.y()));
+        for _ in 0..20 {
+            viewport.zoom_out();
+        }
+        assert_eq!(Glyphs::Shade(6), viewport.glyphs());
+    }
+
+    #[test]
+    fn viewport_cursor() {
+        let mut viewport = TerminalViewport::new(0, 0);
+        // Half blocks on a 4 by 2 screen show -2 -2 to 1 1
+        assert_eq!(Some((0, 0)), viewport.char_at(Position::new(-2, -2), 4, 2));
+        assert_eq!(Some((3, 1)), viewport.char_at(Position::new(1, 1), 4, 2));
+        assert_eq!(Some((2, 1)), viewport.char_at(Position::new(0, 0), 4, 2));
+        assert_eq!(None, viewport.char_at(Position::new(2, 0), 4, 2));
+        assert_eq!(None, viewport.char_at(Position::new(Coord::MIN, 0), 4, 2));
+        assert_eq!(Position::new(1, 0), viewport.cell_at(3, 1, 4, 2));
+        assert_eq!(Some((3, 1)), viewport.char_at(viewport.cell_at(3, 1, 4, 2), 4, 2));
+
+        viewport.keep_visible(Position::new(1, 1), 4, 2);
+        assert_eq!((0, 0), (viewport.x(), viewport.y()));
+        viewport.keep_visible(Position::new(10, -10), 4, 2);
+        assert_eq!((10, -10), (viewport.x(), viewport.y()));
+    }
+}
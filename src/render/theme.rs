@@ -0,0 +1,82 @@
+//! Characters and colours cells are drawn with, so output can suit the user's terminal and taste.
+//! Themes are usually read from the `[theme]` table of the config file, see [`crate::config`].
+
+use crate::gol::Cell;
+use crate::render::{Palette, ALIVE_RGB, DEAD_RGB};
+
+/// Smallest cell, in pixels per side, that grid lines are drawn around. Any smaller and the lines would hide the cells.
+pub const MIN_GRID_SCALE: usize = 3;
+
+/// How cells are drawn, in text and in images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Character for live cells in text output.
+    pub alive_glyph: char,
+    /// Character for dead cells in text output.
+    pub dead_glyph: char,
+    /// Colour of live cells.
+    pub alive: [u8; 3],
+    /// Colour of dead cells, which is also the background behind the world.
+    pub background: [u8; 3],
+    /// Colour of the lines between cells in images, or [`None`] for no grid lines.
+    pub grid: Option<[u8; 3]>
+}
+
+impl Theme {
+    /// Returns the character a cell is written as in text output.
+    pub fn glyph(&self, cell: Cell) -> char {
+        match cell {
+            Cell::Alive => self.alive_glyph,
+            Cell::Dead => self.dead_glyph
+        }
+    }
+
+    /// Palette of the theme's colours, for dead and live cells.
+    pub fn palette(&self) -> Palette {
+        Palette::new(vec![self.background, self.alive])
+    }
+
+    /// Draw the grid lines, if the theme has them, over an image of cells `scale` pixels per side,
+    /// in 8-bit RGB rows as made by [`crate::render::rgb_image`]. The line runs along the -x and -y edges of each cell.
+    /// Nothing is drawn for cells smaller than [`MIN_GRID_SCALE`].
+    pub fn draw_grid(&self, pixels: &mut [u8], width: usize, scale: usize) {
+        let Some(colour) = self.grid else { return };
+        if scale < MIN_GRID_SCALE { return }
+        for (index, pixel) in pixels.chunks_exact_mut(3).enumerate() {
+            let (x, y) = (index % width, index / width);
+            if x % scale == 0 || y % scale == 0 {
+                pixel.copy_from_slice(&colour);
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme { alive_glyph: '#', dead_glyph: '.', alive: ALIVE_RGB, background: DEAD_RGB, grid: None }
+    }
+}
+
+#[cfg(test)]
+mod theme_tests {
+    use super::*;
+
+    #[test]
+    fn grid() {
+        let theme = Theme { grid: Some([1, 2, 3]), ..Theme::default() };
+        assert_eq!([255, 255, 255], theme.palette().cell_colour(Cell::Alive));
+        assert_eq!('.', theme.glyph(Cell::Dead));
+
+        // Two cells across at 3 pixels each, with the line along the top and left of each
+        let mut pixels = vec![0; 6 * 3 * 3];
+        theme.draw_grid(&mut pixels, 6, 3);
+        let lined: Vec<bool> = pixels.chunks(3).map(|pixel| pixel == [1, 2, 3]).collect();
+        assert_eq!(vec![true; 6], lined[..6]);
+        assert_eq!(vec![true, false, false, true, false, false], lined[6..12]);
+
+        // Too small for lines
+        let mut pixels = vec![0; 2 * 2 * 3];
+        theme.draw_grid(&mut pixels, 2, 2);
+        assert!(pixels.iter().all(|&value| value == 0));
+    }
+}
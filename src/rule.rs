@@ -0,0 +1,1470 @@
+//! Module to hold the birth and survival rules deciding how cells change each generation.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use crate::geometry::Coord;
+use crate::gol::Cell;
+
+/// Most neighbours a custom neighbourhood can have.
+pub const MAX_CUSTOM_NEIGHBOURS: usize = 24;
+
+/// Furthest the neighbours of a custom neighbourhood can be from the cell along either axis.
+pub const MAX_REACH: usize = 4;
+
+/// Highest neighbour count a rule can respond to, adding up the weights of a custom neighbourhood.
+pub const MAX_COUNT: u8 = 31;
+
+const MOORE_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0),           (1, 0),
+    (-1, 1),  (0, 1),  (1, 1),
+];
+const VON_NEUMANN_OFFSETS: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const HEXAGONAL_OFFSETS: [(i8, i8); 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+
+/// Names MCell's Weighted Life notation gives each Moore neighbour, in the same order as their offsets.
+const WEIGHTED_DIRECTIONS: [&str; 8] = ["NW", "NN", "NE", "WW", "EE", "SW", "SS", "SE"];
+
+/// Letters naming the arrangements of each count of live Moore neighbours in Hensel notation, in the order Golly uses.
+const HENSEL_LETTERS: [&str; 9] = ["", "ce", "ceaikn", "ceaiknjqry", "ceaiknjqrytwz", "ceaiknjqry", "ceaikn", "ce", ""];
+
+/// One arrangement for each letter of the counts up to 4, numbered as Golly numbers them, with a bit for each cell
+/// in reading order and the cell itself at bit 4. The letters of higher counts name the arrangements whose dead
+/// neighbours are arranged like the lower count's live ones.
+const HENSEL_ARRANGEMENTS: [&[u16]; 5] = [
+    &[0],
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+    &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108]
+];
+
+/// Which of the cells around a cell count as its neighbours.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Neighbourhood {
+    /// The 8 cells touching it, including diagonally.
+    #[default]
+    Moore,
+    /// The 4 cells sharing an edge with it.
+    VonNeumann,
+    /// The 6 cells around it on a hexagonal grid. Cells are at axial coordinates, with x and y along two of the
+    /// grid's axes, so each cell's neighbours are those around it on the square grid bar the -x -y and +x +y corners.
+    Hexagonal,
+    /// Any cells near it, each counting as one or more neighbours.
+    Custom(CustomNeighbourhood)
+}
+
+impl Neighbourhood {
+    /// Offsets from a cell to each of its neighbours, with how many neighbours each counts as.
+    pub fn neighbours(&self) -> impl Iterator<Item = (Coord, Coord, u8)> + '_ {
+        let (offsets, weights): (&[(i8, i8)], Option<&[u8]>) = match self {
+            Neighbourhood::Moore => (&MOORE_OFFSETS, None),
+            Neighbourhood::VonNeumann => (&VON_NEUMANN_OFFSETS, None),
+            Neighbourhood::Hexagonal => (&HEXAGONAL_OFFSETS, None),
+            Neighbourhood::Custom(custom) => (custom.offsets(), Some(custom.weights()))
+        };
+        offsets.iter().enumerate().map(move |(index, &(x, y))| (x as Coord, y as Coord, weights.map_or(1, |weights| weights[index])))
+    }
+
+    /// Highest count a cell can have, when every neighbour is alive.
+    pub fn max_count(&self) -> u8 {
+        self.neighbours().map(|(_, _, weight)| weight).sum()
+    }
+
+    /// Furthest any neighbour is from the cell along either axis.
+    pub fn reach(&self) -> usize {
+        (self.neighbours().map(|(x, y, _)| x.unsigned_abs().max(y.unsigned_abs())).max().unwrap_or(0)) as usize
+    }
+}
+
+/// Neighbourhood of chosen offsets, such as the 8 cells a knight's move away, where each neighbour
+/// can count more than once to make weighted rules. Stored inline, so rules stay cheap to copy.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CustomNeighbourhood {
+    offsets: [(i8, i8); MAX_CUSTOM_NEIGHBOURS],
+    weights: [u8; MAX_CUSTOM_NEIGHBOURS],
+    len: usize
+}
+
+impl CustomNeighbourhood {
+    /// Create a neighbourhood from the offsets to each neighbour, each counting once.
+    pub fn new(offsets: &[(Coord, Coord)]) -> Result<CustomNeighbourhood, RuleError> {
+        let weighted: Vec<((Coord, Coord), u8)> = offsets.iter().map(|&offset| (offset, 1)).collect();
+        CustomNeighbourhood::weighted(&weighted)
+    }
+
+    /// Create a neighbourhood from the offsets to each neighbour and how many neighbours each counts as.
+    /// Fails for the cell itself, repeated offsets, offsets further than [`MAX_REACH`], weights of 0,
+    /// more than [`MAX_CUSTOM_NEIGHBOURS`] neighbours, or weights adding up to more than [`MAX_COUNT`].
+    pub fn weighted(neighbours: &[((Coord, Coord), u8)]) -> Result<CustomNeighbourhood, RuleError> {
+        let invalid = |reason: &str| Err(RuleError::InvalidNeighbourhood(reason.to_string()));
+        if neighbours.len() > MAX_CUSTOM_NEIGHBOURS { return invalid("too many neighbours") }
+        if neighbours.iter().map(|&(_, weight)| weight as usize).sum::<usize>() > MAX_COUNT as usize {
+            return invalid("weights add up to too many neighbours");
+        }
+
+        let mut custom = CustomNeighbourhood { offsets: [(0, 0); MAX_CUSTOM_NEIGHBOURS], weights: [0; MAX_CUSTOM_NEIGHBOURS], len: 0 };
+        for &((x, y), weight) in neighbours {
+            if (x, y) == (0, 0) { return invalid("a cell can't be its own neighbour") }
+            if x.unsigned_abs() > MAX_REACH as u64 || y.unsigned_abs() > MAX_REACH as u64 { return invalid("neighbour too far away") }
+            if weight == 0 { return invalid("neighbours must count at least once") }
+            let offset = (x as i8, y as i8);
+            if custom.offsets().contains(&offset) { return invalid("repeated neighbour") }
+            custom.offsets[custom.len] = offset;
+            custom.weights[custom.len] = weight;
+            custom.len += 1;
+        }
+        Ok(custom)
+    }
+
+    /// Offsets from a cell to each of its neighbours.
+    pub fn offsets(&self) -> &[(i8, i8)] { &self.offsets[..self.len] }
+    /// How many neighbours each offset counts as, in the same order.
+    pub fn weights(&self) -> &[u8] { &self.weights[..self.len] }
+}
+
+/// Offsets separated by `;`, as `x,y`, followed by `*weight` for those counting more than once.
+impl Display for CustomNeighbourhood {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (index, (&(x, y), &weight)) in self.offsets().iter().zip(self.weights()).enumerate() {
+            if index > 0 { write!(f, ";")? }
+            write!(f, "{},{}", x, y)?;
+            if weight > 1 { write!(f, "*{}", weight)? }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CustomNeighbourhood {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<CustomNeighbourhood, RuleError> {
+        let invalid = || RuleError::InvalidNeighbourhood(s.to_string());
+        let neighbours = s.split(';').filter(|neighbour| !neighbour.trim().is_empty()).map(|neighbour| {
+            let (offset, weight) = neighbour.split_once('*').unwrap_or((neighbour, "1"));
+            let (x, y) = offset.split_once(',').ok_or_else(invalid)?;
+            let number = |text: &str| text.trim().parse().map_err(|_| invalid());
+            Ok(((number(x)?, number(y)?), weight.trim().parse().map_err(|_| invalid())?))
+        }).collect::<Result<Vec<_>, RuleError>>()?;
+        CustomNeighbourhood::weighted(&neighbours)
+    }
+}
+
+/// Outer-totalistic rule, where the next state of a cell depends only on its own state
+/// and how many of its neighbours are alive, out of the 8 around it unless another neighbourhood is chosen.
+/// Each set of counts is a bitmask, where bit `n` being set means the condition holds for `n` neighbours.
+/// Rules written in Hensel notation, such as `B2-a/S12`, are isotropic non-totalistic, telling apart the different
+/// arrangements of each count of Moore neighbours, and rules from Golly's `MAP` tables can give every arrangement
+/// its own transition. Their masks hold each count with any arrangement included.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u32,
+    survival: u32,
+    neighbourhood: Neighbourhood,
+    /// Arrangements each condition holds for, if the rule isn't totalistic.
+    arrangements: Option<Arrangements>
+}
+
+/// Which arrangements of live Moore neighbours a non-totalistic rule's conditions hold for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Arrangements {
+    /// Indexed by count, as a bitmask of the count's letters in Hensel notation.
+    /// Counts 0 and 8 have one arrangement, at bit 0.
+    Isotropic { birth: [u16; 9], survival: [u16; 9] },
+    /// A bit for every arrangement as [`Rule::next_state_of`] numbers them, dead cells' 256 and then live cells'.
+    Anisotropic([u64; 8])
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub const LIFE: Rule = Rule { birth: 1 << 3, survival: 1 << 2 | 1 << 3, neighbourhood: Neighbourhood::Moore, arrangements: None };
+
+    /// Create a rule from the neighbour counts that cause a dead cell to be born,
+    /// and those that let a live cell survive.
+    /// Counts above 8 are ignored.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Rule {
+        Rule::with_counts(Neighbourhood::Moore, birth, survival)
+    }
+
+    /// Create a rule over the given neighbourhood from the counts that cause a dead cell to be born,
+    /// and those that let a live cell survive. Counts above the neighbourhood's highest are ignored.
+    pub fn with_counts(neighbourhood: Neighbourhood, birth: &[u8], survival: &[u8]) -> Rule {
+        let max = neighbourhood.max_count();
+        Rule { birth: counts_to_mask(birth, max), survival: counts_to_mask(survival, max), neighbourhood, arrangements: None }
+    }
+
+    /// Create a rule from bitmasks of the birth and survival neighbour counts.
+    pub fn from_masks(birth: u16, survival: u16) -> Rule {
+        Rule {
+            birth: birth as u32 & 0x1ff,
+            survival: survival as u32 & 0x1ff,
+            neighbourhood: Neighbourhood::Moore,
+            arrangements: None
+        }
+    }
+
+    /// Create a rule from the letters of each count that cause a dead cell to be born, and those that let
+    /// a live cell survive, as parsed from Hensel notation. Rules including every letter of the counts
+    /// they include are totalistic.
+    fn from_letters(birth: [u16; 9], survival: [u16; 9]) -> Rule {
+        let mask = |letters: [u16; 9]| (0..9).filter(|&count| letters[count] != 0).fold(0, |mask, count| mask | 1 << count);
+        let totalistic = (0..9).all(|count| [birth[count], survival[count]].iter().all(|&letters| letters == 0 || letters == all_letters(count)));
+        Rule {
+            birth: mask(birth),
+            survival: mask(survival),
+            neighbourhood: Neighbourhood::Moore,
+            arrangements: (!totalistic).then_some(Arrangements::Isotropic { birth, survival })
+        }
+    }
+
+    /// The same rule counting neighbours over the given neighbourhood.
+    /// Counts above the neighbourhood's highest can never happen, so are dropped. Arrangements only mean anything
+    /// in the Moore neighbourhood, so a non-totalistic rule moved to another holds for any arrangement of its counts.
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Rule {
+        let mask = counts_to_mask(&(0..=neighbourhood.max_count()).collect::<Vec<u8>>(), MAX_COUNT);
+        let arrangements = if neighbourhood == Neighbourhood::Moore { self.arrangements } else { None };
+        Rule { birth: self.birth & mask, survival: self.survival & mask, neighbourhood, arrangements }
+    }
+
+    /// Whether dead cells are born with no live neighbours, so the infinite dead background comes alive.
+    /// The main engine emulates these rules by tracking the background's state, but sparse worlds that only
+    /// step cells near live ones can't follow them.
+    pub fn has_b0(&self) -> bool {
+        self.birth & 1 != 0
+    }
+
+    /// Next state of the infinite background, every cell of which is in the given state. Only rules with B0
+    /// bring a dead background to life, and a live one stays alive only under rules surviving on every neighbour.
+    pub fn next_background(&self, background: Cell) -> Cell {
+        match (background, self.is_totalistic()) {
+            (Cell::Dead, _) => self.next_state(Cell::Dead, 0),
+            (Cell::Alive, true) => self.next_state(Cell::Alive, self.neighbourhood.max_count()),
+            (Cell::Alive, false) => self.next_state_of(Cell::Alive, u8::MAX)
+        }
+    }
+
+    /// Calculate the next state of a cell given its current state and number of live neighbours.
+    /// Non-totalistic rules hold for a count if they do for any arrangement of it.
+    pub fn next_state(&self, current: Cell, neighbours: u8) -> Cell {
+        let mask = match current {
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth
+        };
+        if neighbours <= MAX_COUNT && mask & (1 << neighbours) != 0 { Cell::Alive } else { Cell::Dead }
+    }
+
+    /// Calculate the next state of a cell given its current state and which of its Moore neighbours are alive,
+    /// as a bit for each in the order [`Neighbourhood::neighbours`] gives them, telling apart the arrangements
+    /// non-totalistic rules depend on.
+    pub fn next_state_of(&self, current: Cell, arrangement: u8) -> Cell {
+        let letters = match (&self.arrangements, current) {
+            (None, _) => return self.next_state(current, arrangement.count_ones() as u8),
+            (Some(Arrangements::Anisotropic(table)), _) => {
+                let index = current as usize * 256 + arrangement as usize;
+                return if table[index / 64] & (1 << (index % 64)) != 0 { Cell::Alive } else { Cell::Dead }
+            }
+            (Some(Arrangements::Isotropic { survival, .. }), Cell::Alive) => *survival,
+            (Some(Arrangements::Isotropic { birth, .. }), Cell::Dead) => *birth
+        };
+        let (count, letter) = HENSEL_CLASSES[arrangement as usize];
+        if letters[count as usize] & (1 << letter) != 0 { Cell::Alive } else { Cell::Dead }
+    }
+
+    /// Create a rule from the next state of every arrangement of the cell and its Moore neighbours, a bit for each,
+    /// dead cells' 256 and then live cells' in the order [`Rule::next_state_of`] numbers the arrangements.
+    /// Tables where each arrangement's rotations and reflections all do the same are isotropic, and become rules
+    /// in Hensel notation, or totalistic if they only depend on the count.
+    pub fn from_table(table: [u64; 8]) -> Rule {
+        let next = |current: usize, arrangement: u8| table[current * 4 + arrangement as usize / 64] & (1 << (arrangement % 64)) != 0;
+        let isotropic = (0..2).all(|current| (0..=255).all(|arrangement| {
+            (1..8).all(|symmetry| next(current, transform(arrangement, symmetry)) == next(current, arrangement))
+        }));
+        if isotropic {
+            let letters = |current: usize| (0..=255).filter(|&arrangement| next(current, arrangement)).fold([0; 9], |mut letters, arrangement| {
+                let (count, letter) = HENSEL_CLASSES[arrangement as usize];
+                letters[count as usize] |= 1 << letter;
+                letters
+            });
+            return Rule::from_letters(letters(0), letters(1))
+        }
+
+        let mask = |current: usize| (0..=255).filter(|&arrangement| next(current, arrangement)).fold(0, |mask, arrangement: u8| mask | 1 << arrangement.count_ones());
+        Rule { birth: mask(0), survival: mask(1), neighbourhood: Neighbourhood::Moore, arrangements: Some(Arrangements::Anisotropic(table)) }
+    }
+
+    /// Whether the rule depends only on how many neighbours are alive, rather than how they are arranged.
+    pub fn is_totalistic(&self) -> bool {
+        self.arrangements.is_none()
+    }
+
+    /// The one rulestring every way of writing the rule parses back from, such as `B3/S23` for `23/3`,
+    /// `S23/B3` and `b3s23`: birth before survival, counts in order and Hensel letters in their shortest form.
+    /// It is the same as the rule's [`Display`], so this is what shows and is saved everywhere.
+    pub fn canonical_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Neighbour counts on which a dead cell is born, in ascending order.
+    pub fn birth_counts(&self) -> Vec<u8> { mask_to_counts(self.birth) }
+    /// Neighbour counts on which a live cell survives, in ascending order.
+    pub fn survival_counts(&self) -> Vec<u8> { mask_to_counts(self.survival) }
+
+    // GETTERS
+    pub fn birth_mask(&self) -> u32 { self.birth }
+    pub fn survival_mask(&self) -> u32 { self.survival }
+    pub fn neighbourhood(&self) -> &Neighbourhood { &self.neighbourhood }
+}
+
+impl Default for Rule {
+    fn default() -> Rule { Rule::LIFE }
+}
+
+/// B/S notation, with a suffix for the neighbourhood unless it is the Moore neighbourhood. Custom neighbourhoods
+/// follow a third `/N`, and can have counts over 9, so their counts are separated by commas. Non-totalistic rules
+/// are in Hensel notation, giving each count's letters or those it leaves out after a `-`, whichever is shorter,
+/// unless they aren't isotropic, when they are in Golly's `MAP` notation.
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match &self.arrangements {
+            Some(Arrangements::Isotropic { birth, survival }) => {
+                write!(f, "B")?;
+                write_letters(f, birth)?;
+                write!(f, "/S")?;
+                return write_letters(f, survival)
+            }
+            Some(Arrangements::Anisotropic(table)) => return write!(f, "MAP{}", write_map(table)),
+            None => {}
+        }
+        let separator = if let Neighbourhood::Custom(_) = self.neighbourhood { "," } else { "" };
+        let join = |counts: Vec<u8>| counts.iter().map(u8::to_string).collect::<Vec<_>>().join(separator);
+        write!(f, "B{}/S{}", join(self.birth_counts()), join(self.survival_counts()))?;
+        write_neighbourhood(f, &self.neighbourhood)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    /// Parse a rulestring in B/S notation, such as `B3/S23`, ignoring case. The conditions can also be
+    /// the other way around or run together, as in `S23/B3` and `B3S23`, or in the older survival/birth
+    /// notation without letters, as in `23/3`. A `V` on the end, as in `B2/SV`, counts neighbours over the von Neumann neighbourhood,
+    /// and an `H`, as in `B2/S34H`, over the hexagonal one. A custom neighbourhood follows a third `/N`
+    /// as offsets separated by `;`, with counts separated by commas, as in `B3/S2,3/N1,2;2,1;-1,2*2`.
+    /// Counts in the Moore neighbourhood can be followed by Hensel notation's letters to include only those
+    /// arrangements, or by a `-` and letters to leave them out, as in `B2-a/S12`.
+    /// Weighted rules can also be written in MCell's Weighted Life notation, as in `NW2,NN1,NE2,WW1,EE1,SW2,SS1,SE2,RS3,RS4,RB5`,
+    /// and rules giving every arrangement of Moore neighbours its own transition in Golly's `MAP` notation,
+    /// `MAP` and the 512 bit table in base 64, case sensitive.
+    fn from_str(s: &str) -> Result<Rule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        if let Some(map) = s.trim().strip_prefix("MAP") { return read_map(map).map(Rule::from_table).ok_or_else(invalid) }
+        let upper = s.trim().to_ascii_uppercase();
+        if is_weighted(&upper) {
+            let (rule, states) = parse_weighted(s)?;
+            return if states == 2 { Ok(rule) } else { Err(invalid()) }
+        }
+        let (rule, neighbourhood) = split_neighbourhood(&upper)?;
+        let (birth, survival) = split_conditions(rule).ok_or_else(|| match rule.starts_with(['B', 'S']) {
+            true => RuleError::MissingConditions(s.to_string()),
+            false => invalid()
+        })?;
+
+        if neighbourhood == Neighbourhood::Moore && !birth.chars().chain(survival.chars()).all(|c| c.is_ascii_digit()) {
+            let (Some(birth), Some(survival)) = (parse_letters(birth), parse_letters(survival)) else { return Err(invalid()) };
+            return Ok(Rule::from_letters(birth, survival))
+        }
+
+        let max = neighbourhood.max_count();
+        let parse_counts = |counts: &str| -> Result<Vec<u8>, RuleError> {
+            let counts: Vec<Option<u8>> = match neighbourhood {
+                Neighbourhood::Custom(_) => counts.split(',').filter(|count| !count.is_empty()).map(|count| count.parse().ok()).collect(),
+                _ => counts.chars().map(|c| c.to_digit(10).map(|count| count as u8)).collect()
+            };
+            counts.into_iter()
+                .map(|count| match count.ok_or_else(invalid)? {
+                    count if count > max => Err(RuleError::InvalidCount { count, max }),
+                    count => Ok(count)
+                })
+                .collect()
+        };
+
+        Ok(Rule::with_counts(neighbourhood, &parse_counts(birth)?, &parse_counts(survival)?))
+    }
+}
+
+/// Generations rule, where a live cell that doesn't survive takes a number of generations to die,
+/// going through decaying states in which it neither counts as a live neighbour nor can be born into.
+/// With 2 states there is nothing to decay through, and it is the same as its outer-totalistic rule.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+    rule: Rule,
+    states: u8
+}
+
+impl GenerationsRule {
+    /// Star Wars, 345/2/4.
+    pub const STAR_WARS: GenerationsRule = GenerationsRule {
+        rule: Rule { birth: 1 << 2, survival: 1 << 3 | 1 << 4 | 1 << 5, neighbourhood: Neighbourhood::Moore, arrangements: None },
+        states: 4
+    };
+
+    /// Brian's Brain, /2/3, where nothing survives and every cell spends a generation dying after it is alive.
+    pub const BRIANS_BRAIN: GenerationsRule = GenerationsRule {
+        rule: Rule { birth: 1 << 2, survival: 0, neighbourhood: Neighbourhood::Moore, arrangements: None },
+        states: 3
+    };
+
+    /// Create a rule that decays through the given number of states in all, counting the dead and live states,
+    /// with births and survival from the outer-totalistic rule.
+    pub fn new(rule: Rule, states: u8) -> Result<GenerationsRule, RuleError> {
+        if states < 2 { return Err(RuleError::TooFewStates(states)) }
+        if rule.has_b0() { return Err(RuleError::BirthOnZero) }
+        Ok(GenerationsRule { rule, states })
+    }
+
+    /// Calculate the next state of a cell given its current state and number of live neighbours.
+    /// State 0 is dead, 1 alive and the rest decaying, with each step taking a decaying cell to the next state
+    /// until it wraps round to dead.
+    pub fn next_state(&self, current: u8, neighbours: u8) -> u8 {
+        match current {
+            0 => if self.rule.next_state(Cell::Dead, neighbours) == Cell::Alive { 1 } else { 0 },
+            1 if self.rule.next_state(Cell::Alive, neighbours) == Cell::Alive => 1,
+            decaying => (decaying + 1) % self.states
+        }
+    }
+
+    // GETTERS
+    pub fn rule(&self) -> Rule { self.rule }
+    pub fn states(&self) -> u8 { self.states }
+}
+
+impl Display for GenerationsRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/C{}", self.rule, self.states)
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = RuleError;
+
+    /// Parse a rulestring in B/S/C notation, such as `B2/S345/C4`, or Golly's survival/birth/states notation,
+    /// such as `345/2/4`, ignoring case.
+    fn from_str(s: &str) -> Result<GenerationsRule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        if is_weighted(&upper) {
+            let (rule, states) = parse_weighted(s)?;
+            return GenerationsRule::new(rule, states)
+        }
+        let (rule, states) = upper.rsplit_once('/').ok_or_else(invalid)?;
+        let states = states.strip_prefix('C').unwrap_or(states).parse().map_err(|_| invalid())?;
+
+        let rule = match rule.split_once('/') {
+            Some((survival, birth)) if !rule.starts_with('B') => format!("B{}/S{}", birth, survival),
+            _ => rule.to_string()
+        };
+        let rule = rule.parse().map_err(|error| match error {
+            RuleError::InvalidRulestring(_) => invalid(),
+            error => error
+        })?;
+        GenerationsRule::new(rule, states)
+    }
+}
+
+/// Larger than Life rule, where cells count the live cells in a square of the given radius around them,
+/// and are born or survive when the count falls in a range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LtlRule {
+    radius: usize,
+    /// Whether a cell counts itself as well as its neighbours.
+    include_centre: bool,
+    birth: (usize, usize),
+    survival: (usize, usize)
+}
+
+impl LtlRule {
+    /// Bosco's rule, R5,C0,M1,S34..58,B34..45,NM, home of the bugs.
+    pub const BOSCO: LtlRule = LtlRule { radius: 5, include_centre: true, birth: (34, 45), survival: (34, 58) };
+
+    /// Create a rule from the radius and the inclusive ranges of counts for births and survival.
+    pub fn new(radius: usize, include_centre: bool, birth: (usize, usize), survival: (usize, usize)) -> Result<LtlRule, RuleError> {
+        if radius == 0 { return Err(RuleError::ZeroRadius) }
+        // Births with no neighbours would fill the infinite dead background every generation
+        if birth.0 == 0 && birth.0 <= birth.1 { return Err(RuleError::BirthOnZero) }
+        Ok(LtlRule { radius, include_centre, birth, survival })
+    }
+
+    /// Calculate whether a cell is alive next, given whether it is now and the live cells in its square,
+    /// not counting itself.
+    pub fn next_state(&self, current: Cell, neighbours: usize) -> Cell {
+        let (count, (min, max)) = match current {
+            Cell::Alive => (neighbours + self.include_centre as usize, self.survival),
+            Cell::Dead => (neighbours, self.birth)
+        };
+        if (min..=max).contains(&count) { Cell::Alive } else { Cell::Dead }
+    }
+
+    // GETTERS
+    pub fn radius(&self) -> usize { self.radius }
+    pub fn include_centre(&self) -> bool { self.include_centre }
+    pub fn birth(&self) -> (usize, usize) { self.birth }
+    pub fn survival(&self) -> (usize, usize) { self.survival }
+}
+
+impl Display for LtlRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "R{},C0,M{},S{}..{},B{}..{},NM",
+            self.radius, self.include_centre as u8, self.survival.0, self.survival.1, self.birth.0, self.birth.1
+        )
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = RuleError;
+
+    /// Parse a rulestring in Golly's Larger than Life notation, such as `R5,C0,M1,S34..58,B34..45,NM`, ignoring case.
+    /// Only two states and the square neighbourhood are supported, as `C0` or `C2` and `NM`.
+    fn from_str(s: &str) -> Result<LtlRule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        let [radius, states, centre, survival, birth, neighbourhood] = upper.split(',').collect::<Vec<_>>()[..] else { return Err(invalid()) };
+
+        let range = |text: &str, prefix| -> Result<(usize, usize), RuleError> {
+            let (min, max) = text.strip_prefix(prefix).and_then(|range| range.split_once("..")).ok_or_else(invalid)?;
+            Ok((min.parse().map_err(|_| invalid())?, max.parse().map_err(|_| invalid())?))
+        };
+        let radius = radius.strip_prefix('R').and_then(|radius| radius.parse().ok()).ok_or_else(invalid)?;
+        if !matches!(states, "C0" | "C2") || neighbourhood != "NM" { return Err(invalid()) }
+        let include_centre = match centre {
+            "M0" => false,
+            "M1" => true,
+            _ => return Err(invalid())
+        };
+        LtlRule::new(radius, include_centre, range(birth, 'B')?, range(survival, 'S')?)
+    }
+}
+
+/// Block rule over the Margolus neighbourhood, where the grid is cut into 2 by 2 blocks, each replaced as a whole
+/// each generation, and the blocks are shifted by one cell diagonally every other generation. Blocks are numbered
+/// by their live cells as in Golly, with the top left cell worth 1, top right 2, bottom left 4 and bottom right 8,
+/// and the rule gives the block each becomes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MargolusRule {
+    table: [u8; 16]
+}
+
+impl MargolusRule {
+    /// Critters, where blocks with two live cells stay as they are, and every other block flips every cell,
+    /// turning half way around too if it had three.
+    pub const CRITTERS: MargolusRule = MargolusRule { table: [15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0] };
+    /// The billiard ball machine, where lone cells cross their block like balls, and pairs of them meeting head on
+    /// glance off each other, while everything else stays as walls.
+    pub const BILLIARD_BALL: MargolusRule = MargolusRule { table: [0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15] };
+    /// Tron, where only blocks all alive or all dead flip.
+    pub const TRON: MargolusRule = MargolusRule { table: [15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0] };
+
+    /// Create a rule from the block each block becomes. Blocks all dead and all alive must stay all dead
+    /// or all alive, so the infinite background stays the same all over.
+    pub fn new(table: [u8; 16]) -> Result<MargolusRule, RuleError> {
+        let rule = MargolusRule { table: table.map(|block| block & 15) };
+        if ![rule.table[0], rule.table[15]].iter().all(|&block| block == 0 || block == 15) {
+            return Err(RuleError::InvalidRulestring(rule.to_string()))
+        }
+        Ok(rule)
+    }
+
+    /// The block a block becomes.
+    pub fn next_block(&self, block: u8) -> u8 {
+        self.table[block as usize & 15]
+    }
+
+    /// Whether every block comes from a different block, so any state has exactly one before it.
+    pub fn is_reversible(&self) -> bool {
+        (0..16).all(|block| self.table.contains(&block))
+    }
+
+    /// The rule undoing this one, or [`None`] if it isn't reversible.
+    pub fn inverse(&self) -> Option<MargolusRule> {
+        if !self.is_reversible() { return None }
+        let mut table = [0; 16];
+        for (block, &next) in self.table.iter().enumerate() {
+            table[next as usize] = block as u8;
+        }
+        Some(MargolusRule { table })
+    }
+
+    // GETTERS
+    pub fn table(&self) -> &[u8; 16] { &self.table }
+}
+
+/// Golly's notation, an `M` followed by the block each block becomes.
+impl Display for MargolusRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "M{}", self.table.iter().map(u8::to_string).collect::<Vec<_>>().join(","))
+    }
+}
+
+impl FromStr for MargolusRule {
+    type Err = RuleError;
+
+    /// Parse a rule in Golly's notation, such as `M0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,15`, ignoring case.
+    fn from_str(s: &str) -> Result<MargolusRule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        let blocks = upper.strip_prefix('M').ok_or_else(invalid)?.split(',')
+            .map(|block| block.trim().parse().ok().filter(|&block: &u8| block < 16))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        MargolusRule::new(blocks.try_into().map_err(|_| invalid())?)
+    }
+}
+
+/// Rule for three dimensional worlds, where each cell counts the live cells among the 26 in the cube around it.
+/// Counts run past 9, so they are written separated by commas, as in `B6/S5,6,7`, or in Bays' notation as the
+/// lowest and highest counts a live cell survives on and then the lowest and highest a dead cell is born on,
+/// as in `5766`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rule3d {
+    birth: u32,
+    survival: u32
+}
+
+impl Rule3d {
+    /// Most live neighbours a cell can have, all 26 around it.
+    pub const MAX_COUNT: u8 = 26;
+    /// Bays' 5766, born on 6 and surviving on 5 to 7, with small oscillators and still lifes.
+    pub const BAYS_5766: Rule3d = Rule3d { birth: 1 << 6, survival: 1 << 5 | 1 << 6 | 1 << 7 };
+    /// Bays' 4555, born on 5 and surviving on 4 or 5, which has gliders.
+    pub const BAYS_4555: Rule3d = Rule3d { birth: 1 << 5, survival: 1 << 4 | 1 << 5 };
+
+    /// Create a rule born on the birth counts and surviving on the survival counts.
+    /// Fails for counts past 26, and for B0, as the whole infinite background would come alive.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Result<Rule3d, RuleError> {
+        let mask = |counts: &[u8]| counts.iter().try_fold(0u32, |mask, &count| match count {
+            count if count > Rule3d::MAX_COUNT => Err(RuleError::InvalidCount { count, max: Rule3d::MAX_COUNT }),
+            count => Ok(mask | 1 << count)
+        });
+        let rule = Rule3d { birth: mask(birth)?, survival: mask(survival)? };
+        if rule.birth & 1 != 0 { return Err(RuleError::BirthOnZero) }
+        Ok(rule)
+    }
+
+    /// Calculate the next state of a cell given its current state and how many of the 26 cells around it are alive.
+    pub fn next_state(&self, current: Cell, neighbours: u8) -> Cell {
+        let mask = match current {
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth
+        };
+        if neighbours <= Rule3d::MAX_COUNT && mask & (1 << neighbours) != 0 { Cell::Alive } else { Cell::Dead }
+    }
+
+    /// Neighbour counts on which a dead cell is born, in ascending order.
+    pub fn birth_counts(&self) -> Vec<u8> { mask_to_counts(self.birth) }
+    /// Neighbour counts on which a live cell survives, in ascending order.
+    pub fn survival_counts(&self) -> Vec<u8> { mask_to_counts(self.survival) }
+}
+
+impl Display for Rule3d {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let join = |counts: Vec<u8>| counts.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+        write!(f, "B{}/S{}", join(self.birth_counts()), join(self.survival_counts()))
+    }
+}
+
+impl FromStr for Rule3d {
+    type Err = RuleError;
+
+    /// Parse a rulestring in B/S notation with counts separated by commas, such as `B6/S5,6,7`,
+    /// or Bays' four digit notation, such as `5766`, ignoring case.
+    fn from_str(s: &str) -> Result<Rule3d, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        if let [survive_low, survive_high, birth_low, birth_high] = upper.as_bytes()
+            && upper.bytes().all(|c| c.is_ascii_digit())
+        {
+            let range = |low: &u8, high: &u8| (low - b'0'..=high - b'0').collect::<Vec<u8>>();
+            return Rule3d::new(&range(birth_low, birth_high), &range(survive_low, survive_high))
+        }
+
+        let (birth, survival) = upper.split_once('/').ok_or_else(invalid)?;
+        let parse_counts = |counts: Option<&str>| -> Result<Vec<u8>, RuleError> {
+            counts.ok_or_else(invalid)?.split(',')
+                .filter(|count| !count.is_empty())
+                .map(|count| count.trim().parse().map_err(|_| invalid()))
+                .collect()
+        };
+        Rule3d::new(&parse_counts(birth.strip_prefix('B'))?, &parse_counts(survival.strip_prefix('S'))?)
+    }
+}
+
+/// Lenia rule for cells with continuous states from 0 to 1. Each cell weighs the states of the cells within
+/// the radius by a smooth ring, and grows by a bell curve of the weighted average peaking at `mu` with width `sigma`,
+/// shrinking where it is far from it. Each generation moves `1 / time` of the way along the growth.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LeniaRule {
+    radius: usize,
+    mu: f64,
+    sigma: f64,
+    time: u32
+}
+
+impl LeniaRule {
+    /// The rule Orbium, the glider of Lenia, lives under, R13,M0.15,S0.015,T10.
+    pub const ORBIUM: LeniaRule = LeniaRule { radius: 13, mu: 0.15, sigma: 0.015, time: 10 };
+
+    /// Create a rule from the radius, the centre and width of the growth curve, and the generations per unit of time.
+    pub fn new(radius: usize, mu: f64, sigma: f64, time: u32) -> Result<LeniaRule, RuleError> {
+        if radius == 0 { return Err(RuleError::ZeroRadius) }
+        let rule = LeniaRule { radius, mu, sigma, time };
+        if !sigma.is_finite() || sigma <= 0.0 || time == 0 || !mu.is_finite() { return Err(RuleError::InvalidRulestring(rule.to_string())) }
+        Ok(rule)
+    }
+
+    /// Weight of a cell at the given distance, as a fraction of the radius, in the ring the neighbourhood is made of.
+    /// Weights are highest half way out and fall smoothly to 0 at the centre and the radius.
+    #[cfg(feature = "std")]
+    pub fn kernel(&self, distance: f64) -> f64 {
+        if distance <= 0.0 || distance >= 1.0 { return 0.0 }
+        (4.0 - 1.0 / (distance * (1.0 - distance))).exp()
+    }
+
+    /// How much a cell grows given the weighted average of its neighbourhood, from -1 to 1.
+    #[cfg(feature = "std")]
+    pub fn growth(&self, potential: f64) -> f64 {
+        2.0 * (-(potential - self.mu).powi(2) / (2.0 * self.sigma * self.sigma)).exp() - 1.0
+    }
+
+    /// Calculate the next state of a cell given its current state and the weighted average of its neighbourhood.
+    #[cfg(feature = "std")]
+    pub fn next_state(&self, current: f64, potential: f64) -> f64 {
+        (current + self.growth(potential) / self.time as f64).clamp(0.0, 1.0)
+    }
+
+    // GETTERS
+    pub fn radius(&self) -> usize { self.radius }
+    pub fn mu(&self) -> f64 { self.mu }
+    pub fn sigma(&self) -> f64 { self.sigma }
+    pub fn time(&self) -> u32 { self.time }
+}
+
+impl Display for LeniaRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "R{},M{},S{},T{}", self.radius, self.mu, self.sigma, self.time)
+    }
+}
+
+impl FromStr for LeniaRule {
+    type Err = RuleError;
+
+    /// Parse a rule as its radius, growth centre and width, and generations per unit of time,
+    /// such as `R13,M0.15,S0.015,T10`, ignoring case.
+    fn from_str(s: &str) -> Result<LeniaRule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        let [radius, mu, sigma, time] = upper.split(',').collect::<Vec<_>>()[..] else { return Err(invalid()) };
+        let radius = radius.strip_prefix('R').and_then(|radius| radius.parse().ok()).ok_or_else(invalid)?;
+        let mu = mu.strip_prefix('M').and_then(|mu| mu.parse().ok()).ok_or_else(invalid)?;
+        let sigma = sigma.strip_prefix('S').and_then(|sigma| sigma.parse().ok()).ok_or_else(invalid)?;
+        let time = time.strip_prefix('T').and_then(|time| time.parse().ok()).ok_or_else(invalid)?;
+        LeniaRule::new(radius, mu, sigma, time)
+    }
+}
+
+/// Wolfram's elementary rule for a one dimensional automaton, where bit `n` of the rule number is the next state
+/// of a cell whose left neighbour, itself and right neighbour spell out `n` in binary, alive being 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ElementaryRule {
+    number: u8
+}
+
+impl ElementaryRule {
+    /// Rule 30, whose centre column looks random.
+    pub const RULE_30: ElementaryRule = ElementaryRule { number: 30 };
+    /// Rule 110, which is capable of universal computation.
+    pub const RULE_110: ElementaryRule = ElementaryRule { number: 110 };
+
+    /// Create a rule from its number. Odd numbers give birth on three dead cells, so flip the infinite
+    /// dead background every generation, which rows follow as their background state.
+    pub fn new(number: u8) -> ElementaryRule {
+        ElementaryRule { number }
+    }
+
+    /// Calculate the next state of a cell given its current state and those of the cells either side of it.
+    pub fn next_state(&self, left: Cell, centre: Cell, right: Cell) -> Cell {
+        let pattern = (left == Cell::Alive) as u8 * 4 + (centre == Cell::Alive) as u8 * 2 + (right == Cell::Alive) as u8;
+        if self.number & (1 << pattern) != 0 { Cell::Alive } else { Cell::Dead }
+    }
+
+    // GETTERS
+    pub fn number(&self) -> u8 { self.number }
+}
+
+/// Golly's notation, such as `W110`.
+impl Display for ElementaryRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "W{}", self.number)
+    }
+}
+
+impl FromStr for ElementaryRule {
+    type Err = RuleError;
+
+    /// Parse a rule number from 0 to 255, with or without Golly's `W` in front, ignoring case.
+    fn from_str(s: &str) -> Result<ElementaryRule, RuleError> {
+        let upper = s.trim().to_ascii_uppercase();
+        let number = upper.strip_prefix('W').unwrap_or(&upper);
+        number.parse().map(ElementaryRule::new).map_err(|_| RuleError::InvalidRulestring(s.to_string()))
+    }
+}
+
+/// Outer-totalistic rule where each birth and survival condition only holds with some chance,
+/// for experiments with noisy automata. Conditions that always hold or never do make an ordinary [`Rule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StochasticRule {
+    neighbourhood: Neighbourhood,
+    /// Chance of a dead cell being born, indexed by its neighbour count.
+    birth: [f64; MAX_COUNT as usize + 1],
+    /// Chance of a live cell surviving, indexed by its neighbour count.
+    survival: [f64; MAX_COUNT as usize + 1]
+}
+
+impl StochasticRule {
+    /// Create a rule where the conditions of the given rule always hold. Rules with B0 aren't supported,
+    /// as stochastic worlds only step the cells near live ones.
+    pub fn new(rule: Rule) -> Result<StochasticRule, RuleError> {
+        if rule.has_b0() { return Err(RuleError::BirthOnZero) }
+        let chances = |mask: u32| core::array::from_fn(|count| if mask & (1 << count) != 0 { 1.0 } else { 0.0 });
+        Ok(StochasticRule { neighbourhood: rule.neighbourhood, birth: chances(rule.birth), survival: chances(rule.survival) })
+    }
+
+    /// The same rule with the chance of a dead cell being born on the count set.
+    /// Counts above the neighbourhood's highest are ignored.
+    pub fn with_birth_chance(mut self, count: u8, chance: f64) -> Result<StochasticRule, RuleError> {
+        if count == 0 && chance > 0.0 { return Err(RuleError::BirthOnZero) }
+        if count <= self.neighbourhood.max_count() { self.birth[count as usize] = validate_chance(chance)? }
+        Ok(self)
+    }
+
+    /// The same rule with the chance of a live cell surviving on the count set.
+    /// Counts above the neighbourhood's highest are ignored.
+    pub fn with_survival_chance(mut self, count: u8, chance: f64) -> Result<StochasticRule, RuleError> {
+        if count <= self.neighbourhood.max_count() { self.survival[count as usize] = validate_chance(chance)? }
+        Ok(self)
+    }
+
+    /// Chance of a cell being alive next, given its current state and number of live neighbours.
+    pub fn chance(&self, current: Cell, neighbours: u8) -> f64 {
+        let chances = match current {
+            Cell::Alive => &self.survival,
+            Cell::Dead => &self.birth
+        };
+        chances.get(neighbours as usize).copied().unwrap_or(0.0)
+    }
+
+    /// The rule with every condition that has any chance of holding, which is what it follows with certain chances.
+    pub fn rule(&self) -> Rule {
+        let mask = |chances: &[f64]| (0..chances.len()).filter(|&count| chances[count] > 0.0).fold(0, |mask, count| mask | 1 << count);
+        Rule { birth: mask(&self.birth), survival: mask(&self.survival), neighbourhood: self.neighbourhood, arrangements: None }
+    }
+
+    // GETTERS
+    pub fn neighbourhood(&self) -> &Neighbourhood { &self.neighbourhood }
+}
+
+/// B/S notation with counts separated by commas, each followed by `:chance` unless it always holds,
+/// such as `B3,6:0.1/S2,3:0.95`, then any suffix for the neighbourhood.
+impl Display for StochasticRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let join = |chances: &[f64]| {
+            (0..chances.len()).filter(|&count| chances[count] > 0.0).map(|count| match chances[count] {
+                1.0 => count.to_string(),
+                chance => format!("{}:{}", count, chance)
+            }).collect::<Vec<_>>().join(",")
+        };
+        write!(f, "B{}/S{}", join(&self.birth), join(&self.survival))?;
+        write_neighbourhood(f, &self.neighbourhood)
+    }
+}
+
+impl FromStr for StochasticRule {
+    type Err = RuleError;
+
+    /// Parse a rulestring as written by [`Display`], ignoring case. Neighbourhoods are given as for [`Rule`].
+    fn from_str(s: &str) -> Result<StochasticRule, RuleError> {
+        let invalid = || RuleError::InvalidRulestring(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        let (rule, neighbourhood) = split_neighbourhood(&upper)?;
+        let (birth, survival) = rule.split_once('/').ok_or_else(invalid)?;
+        let birth = birth.strip_prefix('B').ok_or_else(invalid)?;
+        let survival = survival.strip_prefix('S').ok_or_else(invalid)?;
+
+        let parse_chances = |conditions: &str| -> Result<Vec<(u8, f64)>, RuleError> {
+            conditions.split(',').filter(|condition| !condition.is_empty()).map(|condition| {
+                let (count, chance) = condition.split_once(':').unwrap_or((condition, "1"));
+                let count = count.parse().ok().filter(|&count| count <= neighbourhood.max_count()).ok_or_else(invalid)?;
+                Ok((count, chance.parse().map_err(|_| invalid())?))
+            }).collect()
+        };
+
+        let mut stochastic = StochasticRule::new(Rule { birth: 0, survival: 0, neighbourhood, arrangements: None })?;
+        for (count, chance) in parse_chances(birth)? {
+            stochastic = stochastic.with_birth_chance(count, chance)?;
+        }
+        for (count, chance) in parse_chances(survival)? {
+            stochastic = stochastic.with_survival_chance(count, chance)?;
+        }
+        Ok(stochastic)
+    }
+}
+
+/// Check a chance is a probability, from 0 to 1.
+fn validate_chance(chance: f64) -> Result<f64, RuleError> {
+    if (0.0..=1.0).contains(&chance) { Ok(chance) } else { Err(RuleError::InvalidChance(chance)) }
+}
+
+/// Errors from creating or parsing a rule.
+#[derive(Debug, PartialEq)]
+pub enum RuleError {
+    /// The rulestring isn't in a recognised notation.
+    InvalidRulestring(String),
+    /// The rulestring has only one of its birth and survival conditions.
+    MissingConditions(String),
+    /// A neighbour count in the rulestring is more than the most the neighbourhood can count.
+    InvalidCount { count: u8, max: u8 },
+    /// Rules with birth on 0 neighbours aren't supported by the world or rule family they were used with.
+    BirthOnZero,
+    /// Generations rules need at least the dead and live states.
+    TooFewStates(u8),
+    /// Larger than Life rules need a neighbourhood reaching at least one cell.
+    ZeroRadius,
+    /// A custom neighbourhood can't be used, for the given reason or with the given text.
+    InvalidNeighbourhood(String),
+    /// A stochastic rule's chance isn't from 0 to 1.
+    InvalidChance(f64)
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RuleError::InvalidRulestring(rule) => write!(f, "invalid rulestring: {}", rule),
+            RuleError::MissingConditions(rule) => write!(f, "rulestring {} needs both birth and survival conditions, as in B3/S23", rule),
+            RuleError::InvalidCount { count, max } => write!(f, "invalid neighbour count {}, the neighbourhood only counts up to {}", count, max),
+            RuleError::BirthOnZero => write!(f, "rules with birth on 0 neighbours (B0) aren't supported here"),
+            RuleError::TooFewStates(states) => write!(f, "generations rules need at least 2 states, not {}", states),
+            RuleError::ZeroRadius => write!(f, "larger than life rules need a radius of at least 1"),
+            RuleError::InvalidNeighbourhood(reason) => write!(f, "invalid neighbourhood: {}", reason),
+            RuleError::InvalidChance(chance) => write!(f, "chances must be from 0 to 1, not {}", chance)
+        }
+    }
+}
+
+impl core::error::Error for RuleError {}
+
+/// Write the suffix for the neighbourhood on the end of a rulestring.
+fn write_neighbourhood(f: &mut Formatter<'_>, neighbourhood: &Neighbourhood) -> core::fmt::Result {
+    match neighbourhood {
+        Neighbourhood::Moore => Ok(()),
+        Neighbourhood::VonNeumann => write!(f, "V"),
+        Neighbourhood::Hexagonal => write!(f, "H"),
+        Neighbourhood::Custom(custom) => write!(f, "/N{}", custom)
+    }
+}
+
+/// Split the neighbourhood suffix off the end of an upper case rulestring.
+fn split_neighbourhood(rule: &str) -> Result<(&str, Neighbourhood), RuleError> {
+    if let Some((rule, custom)) = rule.split_once("/N") { return Ok((rule, Neighbourhood::Custom(custom.parse()?))) }
+    Ok(match rule.chars().last() {
+        Some('V') => (&rule[..rule.len() - 1], Neighbourhood::VonNeumann),
+        Some('H') => (&rule[..rule.len() - 1], Neighbourhood::Hexagonal),
+        _ => (rule, Neighbourhood::Moore)
+    })
+}
+
+/// Characters of base 64, in order of the value each stands for.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Index into Golly's `MAP` table of a cell in the given state with the given arrangement of Moore neighbours.
+/// Golly reads the cell and its neighbours as a 9 bit number, the north west cell highest and the cell itself
+/// in the middle, and stores the table highest bit first.
+fn map_index(current: usize, arrangement: u8) -> usize {
+    (0..8).filter(|bit| arrangement & (1 << bit) != 0)
+        .fold(current << 4, |golly, bit| golly | 1 << (8 - if bit < 4 { bit } else { bit + 1 }))
+}
+
+/// Read the base 64 of a `MAP` rulestring into a table for [`Rule::from_table`], or [`None`] if it isn't
+/// the 86 characters of a Moore neighbourhood table, with or without its padding.
+fn read_map(map: &str) -> Option<[u64; 8]> {
+    let map = map.trim_end_matches('=');
+    if map.len() != 86 { return None }
+    let values = map.bytes().map(|c| BASE64.iter().position(|&digit| digit == c)).collect::<Option<Vec<usize>>>()?;
+    let bit = |index: usize| values[index / 6] & (1 << (5 - index % 6)) != 0;
+    let mut table = [0; 8];
+    for current in 0..2 {
+        for arrangement in 0..=255 {
+            if bit(map_index(current, arrangement)) { table[current * 4 + arrangement as usize / 64] |= 1 << (arrangement % 64) }
+        }
+    }
+    Some(table)
+}
+
+/// Write a table as the base 64 of a `MAP` rulestring, without padding.
+fn write_map(table: &[u64; 8]) -> String {
+    let mut bits = [false; 516];
+    for current in 0..2 {
+        for arrangement in 0..=255u8 {
+            bits[map_index(current, arrangement)] = table[current * 4 + arrangement as usize / 64] & (1 << (arrangement % 64)) != 0;
+        }
+    }
+    bits.chunks(6)
+        .map(|digit| BASE64[digit.iter().fold(0, |value, &bit| value << 1 | bit as usize)] as char)
+        .collect()
+}
+
+/// Whether an upper case rulestring is in MCell's Weighted Life notation, the only one with commas but no `/`.
+fn is_weighted(rule: &str) -> bool {
+    rule.contains(',') && !rule.contains('/')
+}
+
+/// Parse a rule in MCell's Weighted Life notation into the rule and its number of states. Each Moore neighbour
+/// is named by its direction and followed by its weight, as in `NW2` and `SS1`, with those left out or weighing 0
+/// not counting at all, then `RS` and `RB` give each weighted sum to survive and be born on, and `HI` the number
+/// of states for Generations rules. Neighbours all weighing 1 make the plain Moore neighbourhood. The cell itself,
+/// `ME`, can't be weighted, as custom neighbourhoods don't count it.
+fn parse_weighted(s: &str) -> Result<(Rule, u8), RuleError> {
+    let invalid = || RuleError::InvalidRulestring(s.to_string());
+    let (mut neighbours, mut birth, mut survival, mut states) = (vec![], vec![], vec![], 2);
+    for part in s.trim().to_ascii_uppercase().split(',') {
+        let (key, value) = part.trim().split_at_checked(2).ok_or_else(invalid)?;
+        let value: u8 = value.parse().map_err(|_| invalid())?;
+        match key {
+            "RS" => survival.push(value),
+            "RB" => birth.push(value),
+            "HI" => states = value.max(2),
+            "ME" if value == 0 => {}
+            "ME" => return Err(RuleError::InvalidNeighbourhood("a cell can't be its own neighbour".to_string())),
+            direction => {
+                let index = WEIGHTED_DIRECTIONS.iter().position(|&name| name == direction).ok_or_else(invalid)?;
+                let (x, y) = MOORE_OFFSETS[index];
+                if value > 0 { neighbours.push(((x as Coord, y as Coord), value)) }
+            }
+        }
+    }
+
+    let neighbourhood = match neighbours.len() == MOORE_OFFSETS.len() && neighbours.iter().all(|&(_, weight)| weight == 1) {
+        true => Neighbourhood::Moore,
+        false => Neighbourhood::Custom(CustomNeighbourhood::weighted(&neighbours)?)
+    };
+    let max = neighbourhood.max_count();
+    if let Some(&count) = birth.iter().chain(&survival).find(|&&count| count > max) {
+        return Err(RuleError::InvalidCount { count, max })
+    }
+    Ok((Rule::with_counts(neighbourhood, &birth, &survival), states))
+}
+
+/// Split an upper case rulestring without its neighbourhood into its birth and survival conditions, without
+/// their letters, from B/S notation either way around and with or without the `/`, or survival/birth notation.
+fn split_conditions(rule: &str) -> Option<(&str, &str)> {
+    let (first, second) = match rule.split_once('/') {
+        Some(conditions) => conditions,
+        // Hensel letters never include B or S, so they can only be where the second condition starts
+        None => rule.get(1..)?.find(['B', 'S']).map(|i| rule.split_at(i + 1))?
+    };
+    match (first.split_at_checked(1), second.split_at_checked(1)) {
+        (Some(("B", birth)), Some(("S", survival))) | (Some(("S", survival)), Some(("B", birth))) => Some((birth, survival)),
+        _ if rule.contains('/') && !rule.contains(['B', 'S']) => Some((second, first)),
+        _ => None
+    }
+}
+
+/// Bitmask of every letter of the count in Hensel notation, or of its one arrangement if it has no letters.
+fn all_letters(count: usize) -> u16 {
+    (1 << HENSEL_LETTERS[count].len().max(1)) - 1
+}
+
+/// Parse the counts of one upper case condition in Hensel notation into a bitmask of letters for each count,
+/// or [`None`] if they aren't valid.
+fn parse_letters(counts: &str) -> Option<[u16; 9]> {
+    let mut letters = [0; 9];
+    let mut chars = counts.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c.to_digit(10).filter(|&count| count <= 8)? as usize;
+        let exclude = chars.next_if_eq(&'-').is_some();
+        let mut named = 0;
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            named |= 1 << HENSEL_LETTERS[count].find(letter.to_ascii_lowercase())?;
+        }
+        letters[count] |= match (exclude, named) {
+            (false, 0) => all_letters(count),
+            (true, 0) => return None,
+            (false, named) => named,
+            (true, named) => all_letters(count) & !named
+        };
+    }
+    Some(letters)
+}
+
+/// Write the counts of one condition in Hensel notation.
+fn write_letters(f: &mut Formatter<'_>, letters: &[u16; 9]) -> core::fmt::Result {
+    for (count, &included) in letters.iter().enumerate() {
+        if included == 0 { continue }
+        write!(f, "{}", count)?;
+        let all = all_letters(count);
+        if included == all { continue }
+
+        let names = |mask: u16| -> String {
+            HENSEL_LETTERS[count].chars().enumerate().filter(|&(index, _)| mask & (1 << index) != 0).map(|(_, c)| c).collect()
+        };
+        let (named, excluded) = (names(included), names(all & !included));
+        if excluded.len() < named.len() { write!(f, "-{}", excluded)? } else { write!(f, "{}", named)? }
+    }
+    Ok(())
+}
+
+/// Count and Hensel letter of every arrangement of live Moore neighbours, indexed by a bit for each neighbour
+/// in the order of [`MOORE_OFFSETS`]. Worked out when compiling, so looking an arrangement up costs nothing.
+const HENSEL_CLASSES: [(u8, u8); 256] = hensel_classes();
+
+const fn hensel_classes() -> [(u8, u8); 256] {
+    let mut classes = [(0, 0); 256];
+    let mut count = 0;
+    while count < HENSEL_ARRANGEMENTS.len() {
+        let mut letter = 0;
+        while letter < HENSEL_ARRANGEMENTS[count].len() {
+            // Drop the cell itself from the middle of Golly's numbering
+            let golly = HENSEL_ARRANGEMENTS[count][letter];
+            let arrangement = (golly & 0xf | golly >> 5 << 4) as u8;
+            let mut symmetry = 0;
+            while symmetry < 8 {
+                let image = transform(arrangement, symmetry);
+                classes[image as usize] = (count as u8, letter as u8);
+                // Each count past 4 is named by where its dead neighbours are
+                if count < 4 { classes[!image as usize] = (8 - count as u8, letter as u8) }
+                symmetry += 1;
+            }
+            letter += 1;
+        }
+        count += 1;
+    }
+    classes
+}
+
+/// Rotate an arrangement of Moore neighbours by the symmetry's quarter turns, mirroring it too for symmetries from 4.
+const fn transform(arrangement: u8, symmetry: u8) -> u8 {
+    let mut image = 0;
+    let mut bit = 0;
+    while bit < 8 {
+        if arrangement & (1 << bit) != 0 {
+            let (mut x, mut y) = MOORE_OFFSETS[bit];
+            let mut turn = 0;
+            while turn < symmetry % 4 {
+                (x, y) = (-y, x);
+                turn += 1;
+            }
+            if symmetry >= 4 { x = -x }
+            // Offsets are in reading order around the cell, which is left out
+            let index = (y + 1) * 3 + x + 1;
+            image |= 1 << if index > 4 { index - 1 } else { index };
+        }
+        bit += 1;
+    }
+    image
+}
+
+fn counts_to_mask(counts: &[u8], max: u8) -> u32 {
+    counts.iter().filter(|&&count| count <= max).fold(0, |mask, count| mask | 1 << count)
+}
+
+fn mask_to_counts(mask: u32) -> Vec<u8> {
+    (0..=MAX_COUNT).filter(|count| mask & (1 << count) != 0).collect()
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(Ok(Rule::LIFE), "B3/S23".parse());
+        assert_eq!(Ok(Rule::LIFE), "b3/s32".parse());
+
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(vec![3, 6], highlife.birth_counts());
+        assert_eq!("B36/S23", highlife.to_string());
+
+        let seeds: Rule = "B2/S".parse().unwrap();
+        assert_eq!("B2/S", seeds.to_string());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Err(RuleError::InvalidCount { count: 9, max: 8 }), "B39/S23".parse::<Rule>());
+        assert_eq!(Err(RuleError::MissingConditions("B3".to_string())), "B3".parse::<Rule>());
+        assert_eq!(Err(RuleError::MissingConditions("B3/23".to_string())), "B3/23".parse::<Rule>());
+        assert_eq!(Err(RuleError::InvalidRulestring("Life".to_string())), "Life".parse::<Rule>());
+    }
+
+    #[test]
+    fn canonical_string() {
+        // Every notation for the same rule comes out the same
+        for rule in ["B3/S23", "23/3", "S23/B3", "b3s23", " s32b3 ", "B33/S32"] {
+            assert_eq!(Ok("B3/S23".to_string()), rule.parse().map(|rule: Rule| rule.canonical_string()));
+        }
+        assert_eq!("B2/S34H", "34/2H".parse::<Rule>().unwrap().canonical_string());
+        assert_eq!("B2-a/S12", "S21B2-a".parse::<Rule>().unwrap().canonical_string());
+        assert_eq!("B/S012345678", "012345678/".parse::<Rule>().unwrap().canonical_string());
+    }
+
+    #[test]
+    fn birth_on_zero() {
+        // B0 turns the dead background alive, and it dies again unless the rule survives on every neighbour
+        let flashing: Rule = "B03/S23".parse().unwrap();
+        assert!(flashing.has_b0() && !Rule::LIFE.has_b0());
+        assert_eq!([Cell::Alive, Cell::Dead], [Cell::Dead, Cell::Alive].map(|background| flashing.next_background(background)));
+        assert_eq!(Cell::Alive, "B0/S8".parse::<Rule>().unwrap().next_background(Cell::Alive));
+        assert_eq!(Cell::Alive, "B0/S4V".parse::<Rule>().unwrap().next_background(Cell::Alive));
+        assert_eq!(Cell::Dead, Rule::LIFE.next_background(Cell::Dead));
+
+        // Sparse worlds can't follow the background, so their rules still turn B0 down
+        assert_eq!(Err(RuleError::BirthOnZero), GenerationsRule::new(flashing, 3));
+        assert_eq!(Err(RuleError::BirthOnZero), StochasticRule::new(flashing));
+    }
+
+    #[test]
+    fn weighted_notation() {
+        // Corners weigh 2 and edges 1, and neighbours weighing 0 are left out
+        let rule: Rule = "NW2,NN1,NE2,WW1,ME0,EE1,SW2,SS1,SE0,RS3,RS4,rb5".parse().unwrap();
+        assert_eq!("B5/S3,4/N-1,-1*2;0,-1;1,-1*2;-1,0;1,0;-1,1*2;0,1", rule.to_string());
+        assert_eq!(Ok(rule), rule.to_string().parse());
+        assert_eq!(Ok(Rule::LIFE), "NW1,NN1,NE1,WW1,EE1,SW1,SS1,SE1,RS2,RS3,RB3".parse());
+
+        // A number of states gives a Generations rule, and weighting the cell itself isn't supported
+        let generations: GenerationsRule = "NW5,NN1,NE5,WW1,ME0,EE1,SW5,SS1,SE5,HI7,RS2,RS4,RS6,RB1,RB4,RB5".parse().unwrap();
+        assert_eq!((7, 24), (generations.states(), generations.rule().neighbourhood().max_count()));
+        assert!("NW1,NN1,HI3,RB1".parse::<Rule>().is_err());
+        assert!(matches!("NW1,ME1,RB1".parse::<Rule>(), Err(RuleError::InvalidNeighbourhood(_))));
+        assert_eq!(Err(RuleError::InvalidCount { count: 9, max: 8 }), "NW1,NN1,NE1,WW1,EE1,SW1,SS1,SE1,RB9".parse::<Rule>());
+    }
+
+    #[test]
+    fn map_notation() {
+        // Golly's table for Life is totalistic, so it comes out as B3/S23
+        let life = "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA";
+        assert_eq!(Ok(Rule::LIFE), life.parse());
+        assert_eq!(Ok(Rule::LIFE), format!("{}==", life).parse());
+
+        // Births only under a lone north neighbour depend on direction, so keep the table
+        let north = "MAPAAD//wAA//8AAP//AAD//4AA//8AAP//AAD//wAA//8AAP//AAD//wAA//8AAP//AAD//wAA//8AAP//AAD//w";
+        let rule: Rule = north.parse().unwrap();
+        assert!(!rule.is_totalistic());
+        assert_eq!(north, rule.to_string());
+        assert_eq!([Cell::Alive, Cell::Dead], [0b10, 0b1000000].map(|arrangement| rule.next_state_of(Cell::Dead, arrangement)));
+        assert_eq!((vec![1], (0..=8).collect()), (rule.birth_counts(), rule.survival_counts()));
+        assert!("MAPAAD".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn rule_3d() {
+        // Bays' notation gives the survival range before the birth range
+        let rule: Rule3d = "5766".parse().unwrap();
+        assert_eq!(Rule3d::BAYS_5766, rule);
+        assert_eq!("B6/S5,6,7", rule.to_string());
+        assert_eq!(Ok(Rule3d::BAYS_4555), "b5/s4,5".parse());
+        assert_eq!((Cell::Alive, Cell::Dead), (rule.next_state(Cell::Dead, 6), rule.next_state(Cell::Alive, 8)));
+        assert_eq!(Err(RuleError::InvalidCount { count: 27, max: 26 }), "B27/S".parse::<Rule3d>());
+        assert_eq!(Err(RuleError::BirthOnZero), "5706".parse::<Rule3d>());
+    }
+
+    #[test]
+    fn hensel_notation() {
+        let rule: Rule = "b2-a/s12".parse().unwrap();
+        assert_eq!("B2-a/S12", rule.to_string());
+        assert!(!rule.is_totalistic());
+        assert_eq!((vec![2], vec![1, 2]), (rule.birth_counts(), rule.survival_counts()));
+        assert_eq!("B2ce3-cer/S4t", "B2ec3aiknjqy/S4t".parse::<Rule>().unwrap().to_string());
+
+        // Neighbours in reading order from the north west, so N and NE make 2a and N and S make 2i
+        assert_eq!(Cell::Dead, rule.next_state_of(Cell::Dead, 0b110));
+        assert_eq!(Cell::Alive, rule.next_state_of(Cell::Dead, 0b1000010));
+        assert_eq!(Cell::Alive, rule.next_state(Cell::Dead, 2));
+
+        // Every letter of a count is the same as the bare count
+        assert_eq!(Ok(Rule::LIFE), "B3/S2ceaikn3".parse());
+        for invalid in ["B3-/S23", "B2x/S23", "B1a/S", "B3/S2aV"] {
+            assert_eq!(Err(RuleError::InvalidRulestring(invalid.to_string())), invalid.parse::<Rule>());
+        }
+    }
+
+    #[test]
+    fn hensel_classes_partition() {
+        // Rotations and reflections of each letter's arrangement cover every arrangement of its count exactly once
+        let classes = &HENSEL_CLASSES;
+        for count in 0..=8 {
+            let arrangements: Vec<usize> = (0..256).filter(|&arrangement: &usize| arrangement.count_ones() == count).collect();
+            assert!(arrangements.iter().all(|&arrangement| classes[arrangement].0 as u32 == count));
+            let letters: std::collections::HashSet<u8> = arrangements.iter().map(|&arrangement| classes[arrangement].1).collect();
+            assert_eq!(HENSEL_LETTERS[count as usize].len().max(1), letters.len());
+        }
+        // Golly's 4c is the four corners and 4e the four edges
+        assert_eq!([(4, 0), (4, 1)], [0b10100101, 0b01011010].map(|arrangement| classes[arrangement]));
+    }
+
+    #[test]
+    fn next_state() {
+        let life = Rule::LIFE;
+        assert_eq!(Cell::Alive, life.next_state(Cell::Dead, 3));
+        assert_eq!(Cell::Dead, life.next_state(Cell::Dead, 2));
+        assert_eq!(Cell::Alive, life.next_state(Cell::Alive, 2));
+        assert_eq!(Cell::Dead, life.next_state(Cell::Alive, 4));
+        assert_eq!(Cell::Dead, life.next_state(Cell::Alive, 9));
+    }
+
+    #[test]
+    fn von_neumann() {
+        let rule: Rule = "b2/sv".parse().unwrap();
+        assert_eq!(&Neighbourhood::VonNeumann, rule.neighbourhood());
+        assert_eq!("B2/SV", rule.to_string());
+        assert_eq!(Err(RuleError::InvalidCount { count: 5, max: 4 }), "B5/SV".parse::<Rule>());
+        let hex: Rule = "B2/S34H".parse().unwrap();
+        assert_eq!((&Neighbourhood::Hexagonal, 6), (hex.neighbourhood(), hex.neighbourhood().max_count()));
+        assert_eq!("B2/S34H", hex.to_string());
+        assert!("B7/SH".parse::<Rule>().is_err());
+
+        // Counts past 4 neighbours are dropped
+        let rule = Rule::new(&[3], &[1, 6]).with_neighbourhood(Neighbourhood::VonNeumann);
+        assert_eq!((vec![3], vec![1]), (rule.birth_counts(), rule.survival_counts()));
+    }
+
+    #[test]
+    fn custom_neighbourhood() {
+        let knight = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+        let knight = Neighbourhood::Custom(CustomNeighbourhood::new(&knight).unwrap());
+        assert_eq!((8, 2), (knight.max_count(), knight.reach()));
+        assert_eq!((1, 1), (Neighbourhood::Moore.reach(), Neighbourhood::VonNeumann.reach()));
+
+        // Weighted neighbours can push counts past 9, so counts are separated by commas
+        let rule: Rule = "b3/s2,10/n1,2;2,1;-1,2*2;0,-4*8".parse().unwrap();
+        assert_eq!((vec![3], vec![2, 10]), (rule.birth_counts(), rule.survival_counts()));
+        assert_eq!(12, rule.neighbourhood().max_count());
+        assert_eq!("B3/S2,10/N1,2;2,1;-1,2*2;0,-4*8", rule.to_string());
+        assert_eq!(Ok(rule), rule.to_string().parse());
+        assert_eq!(Cell::Alive, rule.next_state(Cell::Alive, 10));
+
+        // Counts past the total weight can never happen
+        assert_eq!(Err(RuleError::InvalidCount { count: 13, max: 12 }), "B3/S13/N1,0*12".parse::<Rule>());
+        assert_eq!(Err(RuleError::InvalidNeighbourhood("1,X".to_string())), "B3/S/N1,X".parse::<Rule>());
+        let invalid = |neighbours: &[((Coord, Coord), u8)]| {
+            matches!(CustomNeighbourhood::weighted(neighbours), Err(RuleError::InvalidNeighbourhood(_)))
+        };
+        assert!(invalid(&[((0, 0), 1)]));
+        assert!(invalid(&[((1, 0), 1), ((1, 0), 2)]));
+        assert!(invalid(&[((5, 0), 1)]));
+        assert!(invalid(&[((1, 0), 0)]));
+        assert!(invalid(&[((1, 0), 16), ((0, 1), 16)]));
+        assert!(invalid(&[((1, 0), 1); MAX_CUSTOM_NEIGHBOURS + 1]));
+    }
+
+    #[test]
+    fn larger_than_life() {
+        assert_eq!(Ok(LtlRule::BOSCO), "R5,C0,M1,S34..58,B34..45,NM".parse());
+        assert_eq!("R5,C0,M1,S34..58,B34..45,NM", LtlRule::BOSCO.to_string());
+        assert_eq!(Err(RuleError::BirthOnZero), "R2,C0,M0,S1..3,B0..2,NM".parse::<LtlRule>());
+        assert!("R2,C3,M0,S1..3,B1..2,NM".parse::<LtlRule>().is_err());
+        assert_eq!(Err(RuleError::ZeroRadius), "R0,C0,M0,S1..3,B1..2,NM".parse::<LtlRule>());
+
+        // Live cells count themselves when the middle is included
+        let rule: LtlRule = "r1,c2,m1,s3..4,b3..3,nm".parse().unwrap();
+        assert_eq!(Cell::Alive, rule.next_state(Cell::Alive, 2));
+        assert_eq!(Cell::Dead, rule.next_state(Cell::Dead, 2));
+    }
+
+    #[test]
+    fn margolus() {
+        let bbm: MargolusRule = "m0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,15".parse().unwrap();
+        assert_eq!(MargolusRule::BILLIARD_BALL, bbm);
+        assert_eq!("M15,14,13,3,11,5,6,1,7,9,10,2,12,4,8,0", MargolusRule::CRITTERS.to_string());
+        assert!("M0,1,2".parse::<MargolusRule>().is_err());
+        assert!("M1,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15".parse::<MargolusRule>().is_err());
+
+        // Critters turns a block of three around, which its inverse turns back
+        let inverse = MargolusRule::CRITTERS.inverse().unwrap();
+        assert_eq!(1, MargolusRule::CRITTERS.next_block(7));
+        assert!((0..16).all(|block| inverse.next_block(MargolusRule::CRITTERS.next_block(block)) == block));
+        let merging = MargolusRule::new([0, 1, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]).unwrap();
+        assert_eq!(None, merging.inverse());
+    }
+
+    #[test]
+    fn lenia() {
+        assert_eq!(Ok(LeniaRule::ORBIUM), "r13,m0.15,s0.015,t10".parse());
+        assert_eq!("R13,M0.15,S0.015,T10", LeniaRule::ORBIUM.to_string());
+        assert_eq!(Err(RuleError::ZeroRadius), "R0,M0.15,S0.015,T10".parse::<LeniaRule>());
+        assert_eq!(Err(RuleError::InvalidRulestring("R13,M0.15,S0,T10".to_string())), "R13,M0.15,S0,T10".parse::<LeniaRule>());
+
+        // Growth peaks at mu and falls to shrinking either side, with the kernel's ring heaviest half way out
+        let rule = LeniaRule::ORBIUM;
+        assert_eq!(1.0, rule.growth(0.15));
+        assert!(rule.growth(0.3) < -0.99);
+        assert_eq!((0.0, 1.0), (rule.kernel(0.0), rule.kernel(0.5)));
+        assert_eq!(0.0, rule.next_state(0.05, 0.0));
+    }
+
+    #[test]
+    fn elementary() {
+        assert_eq!(Ok(ElementaryRule::RULE_110), "w110".parse());
+        assert_eq!(Ok(ElementaryRule::RULE_30), "30".parse());
+        assert_eq!("W30", ElementaryRule::RULE_30.to_string());
+        assert_eq!(Err(RuleError::InvalidRulestring("W256".to_string())), "W256".parse::<ElementaryRule>());
+
+        // 110 is 01101110 in binary, so a live cell with a live left neighbour only dies when the right one is alive too
+        let (rule, alive, dead) = (ElementaryRule::RULE_110, Cell::Alive, Cell::Dead);
+        assert_eq!(dead, rule.next_state(alive, alive, alive));
+        assert_eq!(alive, rule.next_state(alive, alive, dead));
+        assert_eq!(alive, rule.next_state(dead, dead, alive));
+        assert_eq!(dead, rule.next_state(alive, dead, dead));
+    }
+
+    #[test]
+    fn stochastic() {
+        let rule: StochasticRule = "b3,6:0.1/s2:0.95,3".parse().unwrap();
+        assert_eq!((0.1, 1.0, 0.0), (rule.chance(Cell::Dead, 6), rule.chance(Cell::Dead, 3), rule.chance(Cell::Dead, 2)));
+        assert_eq!(0.95, rule.chance(Cell::Alive, 2));
+        assert_eq!("B3,6:0.1/S2:0.95,3", rule.to_string());
+        assert_eq!(Ok(rule.rule()), "B36/S23".parse());
+        assert_eq!(StochasticRule::new(Rule::LIFE), "B3/S2,3".parse());
+
+        assert_eq!(Err(RuleError::InvalidChance(1.5)), "B3:1.5/S".parse::<StochasticRule>());
+        assert_eq!(Err(RuleError::BirthOnZero), "B0:0.5/S".parse::<StochasticRule>());
+        assert!("B9/S".parse::<StochasticRule>().is_err());
+        assert_eq!("B2:0.5/SV", "B2:0.5/SV".parse::<StochasticRule>().unwrap().to_string());
+    }
+
+    #[test]
+    fn generations() {
+        assert_eq!(Ok(GenerationsRule::STAR_WARS), "345/2/4".parse());
+        assert_eq!(Ok(GenerationsRule::STAR_WARS), "b2/s345/c4".parse());
+        assert_eq!("B2/S345/C4", GenerationsRule::STAR_WARS.to_string());
+        assert_eq!(Err(RuleError::TooFewStates(1)), "B3/S23/C1".parse::<GenerationsRule>());
+        assert_eq!(Err(RuleError::BirthOnZero), "23/03/3".parse::<GenerationsRule>());
+        assert!("B3/S23".parse::<GenerationsRule>().is_err());
+        assert_eq!(Ok(GenerationsRule::BRIANS_BRAIN), "/2/3".parse());
+        assert_eq!("B2/S/C3", GenerationsRule::BRIANS_BRAIN.to_string());
+
+        // Live cells that don't survive decay through states 2 and 3 before dying
+        let star_wars = GenerationsRule::STAR_WARS;
+        assert_eq!(1, star_wars.next_state(0, 2));
+        assert_eq!(0, star_wars.next_state(0, 3));
+        assert_eq!(1, star_wars.next_state(1, 4));
+        assert_eq!(2, star_wars.next_state(1, 2));
+        assert_eq!(3, star_wars.next_state(2, 2));
+        assert_eq!(0, star_wars.next_state(3, 2));
+        // With two states cells die straight away
+        let life = GenerationsRule::new(Rule::LIFE, 2).unwrap();
+        assert_eq!(0, life.next_state(1, 1));
+    }
+}
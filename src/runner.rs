@@ -0,0 +1,248 @@
+//! Running a world on a background thread, while other threads take consistent snapshots of it.
+//! Snapshots are [`WorldView`]s, which share their regions with the running world until it changes them,
+//! so taking one never has to wait for, or pause, the simulation.
+//! Also the headless runs of `gol run`, which step a world to the end while reporting progress and saving snapshots.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::analysis::CycleDetector;
+use crate::error::GolError;
+use crate::gol::GameOfLife;
+use crate::io::{format_pattern, PatternFormat};
+use crate::progress::{Progress, ProgressReporter};
+use crate::topology::Topology;
+use crate::view::WorldView;
+
+/// State shared between the runner and its thread.
+struct Shared {
+    latest: Mutex<WorldView>,
+    stop: AtomicBool
+}
+
+/// Steps a world on a background thread until stopped or out of generations.
+pub struct Runner {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl Runner {
+    /// Start stepping the world on a new thread, for the given number of generations or until stopped.
+    pub fn start(world: GameOfLife, generations: Option<u64>) -> Runner {
+        let shared = Arc::new(Shared {
+            latest: Mutex::new(WorldView::new(&world, 0)),
+            stop: AtomicBool::new(false)
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        let thread = thread::spawn(move || {
+            let mut world = world;
+            let mut generation = 0;
+            while generations.is_none_or(|limit| generation < limit) && !thread_shared.stop.load(Ordering::Relaxed) {
+                world.step();
+                generation += 1;
+                // Views only copy references to the regions, so holding the lock is brief
+                let snapshot = WorldView::new(&world, generation);
+                *thread_shared.latest.lock().expect("Runner lock poisoned") = snapshot;
+            }
+        });
+
+        Runner { shared, thread: Some(thread) }
+    }
+
+    /// Returns the most recently completed generation, numbered from when the runner started.
+    /// The runner keeps stepping while the snapshot is in use.
+    pub fn snapshot(&self) -> WorldView {
+        self.shared.latest.lock().expect("Runner lock poisoned").clone()
+    }
+
+    /// Check if the runner has stepped every generation it was asked to.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Stop stepping, wait for the current generation to finish, and return the final snapshot.
+    pub fn stop(mut self) -> WorldView {
+        self.join();
+        self.snapshot()
+    }
+
+    /// Signal the thread to stop and wait for it.
+    fn join(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("Runner thread panicked");
+        }
+    }
+}
+
+impl Drop for Runner {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// How [`run`] steps a world.
+#[derive(Debug, Clone, Default)]
+pub struct RunSettings {
+    pub generations: u64,
+    /// Whether to stop once the world is stable, and the longest period of cycle to stop at if there is one.
+    /// Without a period, cycles of any length are found, but only some generations after they start.
+    pub until_stable: Option<Option<usize>>,
+    /// Directory to save RLE snapshots to while running, without pausing the simulation, and the time between them.
+    /// The world is stepped on a [`Runner`] to do this, so it isn't checked for stability and is always on the plane.
+    pub archive: Option<(PathBuf, Duration)>,
+    /// Space to step the world in. Cells outside a torus are wrapped into it first.
+    pub topology: Topology,
+    /// Time between reports of the progress.
+    pub progress_interval: Duration
+}
+
+/// Outcome of a [`run`], which is displayed as a summary of it.
+#[derive(Clone)]
+pub struct RunReport {
+    /// World after the last generation stepped.
+    pub world: GameOfLife,
+    /// Generations stepped, fewer than asked for if the world became stable.
+    pub generations: u64,
+    /// Period of the cycle the world was found to be stable in, 1 for a still life.
+    pub stable: Option<u64>,
+    pub initial_population: usize,
+    pub elapsed: Duration
+}
+
+impl Display for RunReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let elapsed = self.elapsed.as_secs_f64();
+        writeln!(f, "generations: {}", self.generations)?;
+        writeln!(f, "rule: {}", self.world.rule())?;
+        writeln!(f, "initial population: {}", self.initial_population)?;
+        writeln!(f, "final population: {}", self.world.population())?;
+        match self.world.live_bounds() {
+            Some(bounds) => writeln!(f, "bounding box: {}", bounds)?,
+            None => writeln!(f, "bounding box: empty")?
+        }
+        let (regions, cells, trimmed) = self.world.regions()
+            .fold((0, 0, 0), |(regions, cells, trimmed), region| (regions + 1, cells + region.width() * region.height(), trimmed + region.trimmed()));
+        writeln!(f, "regions: {} covering {} cells, {} rows and columns trimmed (trim policy {})", regions, cells, trimmed, self.world.trim_policy())?;
+        writeln!(f, "elapsed: {:.3}s ({:.1} generations/s)", elapsed, self.generations as f64 / elapsed)
+    }
+}
+
+/// Step the world as the settings say, calling `report` with the progress every interval.
+pub fn run(mut world: GameOfLife, settings: &RunSettings, mut report: impl FnMut(&Progress)) -> Result<RunReport, GolError> {
+    let initial_population = world.population();
+    let start = Instant::now();
+    let mut generations = settings.generations;
+    let mut stable = None;
+    match &settings.archive {
+        Some((dir, interval)) => world = run_archived(world, generations, dir, *interval, settings.progress_interval, report)?,
+        None => {
+            settings.topology.wrap(&mut world);
+            let mut progress = ProgressReporter::new(generations, settings.progress_interval);
+            let mut cycles = settings.until_stable.map(|max_period| max_period.map_or_else(CycleDetector::any_period, CycleDetector::new));
+            if let Some(cycles) = &mut cycles { cycles.observe(&world); }
+            for generation in 1..=generations {
+                settings.topology.step(&mut world);
+                if let Some(progress) = progress.update(generation, &world) { report(&progress) }
+                if let Some(period) = cycles.as_mut().and_then(|cycles| cycles.observe(&world)) {
+                    stable = Some(period);
+                    generations = generation;
+                    break;
+                }
+            }
+        }
+    }
+    Ok(RunReport { world, generations, stable, initial_population, elapsed: start.elapsed() })
+}
+
+/// Step the world on a [`Runner`], saving a snapshot to the directory every interval
+/// and calling `report` with the progress. Returns the world after the last generation.
+pub fn run_archived(world: GameOfLife, generations: u64, dir: &Path, interval: Duration, progress_interval: Duration,
+                    mut report: impl FnMut(&Progress)) -> Result<GameOfLife, GolError> {
+    fs::create_dir_all(dir)?;
+    let mut progress = ProgressReporter::new(generations, progress_interval);
+    let runner = Runner::start(world, Some(generations));
+    let mut next_archive = Instant::now() + interval;
+    while !runner.is_finished() {
+        // Wake up regularly to notice the runner finishing
+        thread::sleep(next_archive.saturating_duration_since(Instant::now()).min(Duration::from_millis(50)));
+        let snapshot = runner.snapshot();
+        if let Some(progress) = progress.update(snapshot.generation(), snapshot.world()) { report(&progress) }
+        if Instant::now() < next_archive { continue }
+
+        let path = dir.join(format!("gen_{:09}.rle", snapshot.generation()));
+        fs::write(path, format_pattern(&snapshot.world().to_region()?, PatternFormat::Rle))?;
+        next_archive += interval;
+    }
+    Ok(runner.stop().to_world())
+}
+
+#[cfg(test)]
+mod runner_tests {
+    use super::*;
+    use crate::gol::Cell;
+
+    #[test]
+    fn snapshots_are_consistent() {
+        // Blinker, which alternates between horizontal on even generations and vertical on odd ones
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+
+        let runner = Runner::start(world, None);
+        for _ in 0..50 {
+            let snapshot = runner.snapshot();
+            let horizontal = snapshot.generation().is_multiple_of(2);
+            assert_eq!(3, snapshot.world().population());
+            assert_eq!(horizontal, snapshot.world().get_cell(1, 0) == Cell::Alive);
+            assert_eq!(!horizontal, snapshot.world().get_cell(0, 1) == Cell::Alive);
+        }
+        assert!(!runner.is_finished());
+        runner.stop();
+    }
+
+    #[test]
+    fn generation_limit() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        let runner = Runner::start(world, Some(5));
+        while !runner.is_finished() {
+            thread::yield_now();
+        }
+        let snapshot = runner.stop();
+        assert_eq!(5, snapshot.generation());
+        assert_eq!(0, snapshot.world().population());
+    }
+
+    #[test]
+    fn snapshot_is_unaffected_by_changes() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        let snapshot = world.clone();
+        world.set_cell(1, 0, Cell::Alive);
+        world.set_cell(0, 0, Cell::Dead);
+        assert_eq!(Cell::Alive, snapshot.get_cell(0, 0));
+        assert_eq!(Cell::Dead, snapshot.get_cell(1, 0));
+    }
+
+    #[test]
+    fn run_until_stable() {
+        // This tetromino becomes a beehive at generation 3, which is seen to repeat a generation later
+        let mut world = GameOfLife::new();
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (0, 1)] {
+            world.set_cell(x, y, Cell::Alive);
+        }
+        let settings = RunSettings { generations: 100, until_stable: Some(Some(2)), ..RunSettings::default() };
+        let report = run(world, &settings, |_| {}).unwrap();
+        assert_eq!((4, Some(1)), (report.generations, report.stable));
+        assert_eq!((4, 6), (report.initial_population, report.world.population()));
+        assert!(report.to_string().starts_with("generations: 4\nrule: B3/S23\ninitial population: 4\nfinal population: 6\n"));
+    }
+}
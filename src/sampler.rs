@@ -0,0 +1,135 @@
+//! Statistics sampled every so often through a long run, rather than every generation,
+//! so a run of millions of generations can be looked over afterwards without keeping a record of each one.
+
+use std::fmt::{Display, Formatter};
+
+use crate::analysis::state_hash;
+use crate::geometry::Rect;
+use crate::gol::GameOfLife;
+
+/// Statistic a [`Sampler`] can record.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    Population,
+    /// Bounding box of the live cells.
+    Bounds,
+    /// Hash of the live cells, as [`state_hash`], for spotting states that repeat.
+    Hash
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Metric::Population => write!(f, "population"),
+            Metric::Bounds => write!(f, "bounds"),
+            Metric::Hash => write!(f, "hash")
+        }
+    }
+}
+
+/// Statistics of the world at one generation, with [`None`] for each metric that wasn't sampled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub generation: u64,
+    pub population: Option<usize>,
+    /// Bounding box of the live cells if sampled, which is itself [`None`] for an empty world.
+    pub bounds: Option<Option<Rect>>,
+    pub hash: Option<u64>
+}
+
+/// Records the chosen metrics every given number of generations into a table of samples.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    every: u64,
+    metrics: Vec<Metric>,
+    samples: Vec<Sample>
+}
+
+impl Sampler {
+    /// Sample the metrics on every generation that is a multiple of `every`, including generation 0.
+    /// An interval of 0 is taken as 1.
+    pub fn new(every: u64, metrics: &[Metric]) -> Sampler {
+        Sampler { every: every.max(1), metrics: metrics.to_vec(), samples: vec![] }
+    }
+
+    /// Sample the world if the generation it is at is due, returning whether it was.
+    pub fn observe(&mut self, generation: u64, world: &GameOfLife) -> bool {
+        if !generation.is_multiple_of(self.every) { return false }
+        let sampled = |metric| self.metrics.contains(&metric);
+        self.samples.push(Sample {
+            generation,
+            population: sampled(Metric::Population).then(|| world.population()),
+            bounds: sampled(Metric::Bounds).then(|| world.live_bounds()),
+            hash: sampled(Metric::Hash).then(|| state_hash(world))
+        });
+        true
+    }
+
+    /// Forget every sample, such as to reuse the sampler for another run.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    // GETTERS
+    pub fn every(&self) -> u64 { self.every }
+    pub fn metrics(&self) -> &[Metric] { &self.metrics }
+    /// Samples taken, in the order they were taken.
+    pub fn samples(&self) -> &[Sample] { &self.samples }
+}
+
+/// Tab separated table of the samples, with a header line naming the metrics.
+/// Empty worlds have a bounds of `none`, and hashes are in hexadecimal.
+impl Display for Sampler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "generation")?;
+        for metric in &self.metrics {
+            write!(f, "\t{}", metric)?;
+        }
+        writeln!(f)?;
+
+        for sample in &self.samples {
+            write!(f, "{}", sample.generation)?;
+            for metric in &self.metrics {
+                match metric {
+                    Metric::Population => write!(f, "\t{}", sample.population.unwrap_or_default())?,
+                    Metric::Bounds => match sample.bounds.flatten() {
+                        Some(bounds) => write!(f, "\t{}", bounds)?,
+                        None => write!(f, "\tnone")?
+                    },
+                    Metric::Hash => write!(f, "\t{:016x}", sample.hash.unwrap_or_default())?
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod sampler_tests {
+    use super::*;
+    use crate::io::rle::read_rle;
+
+    #[test]
+    fn samples() {
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("bo$2bo$3o!").unwrap());
+        let mut sampler = Sampler::new(4, &[Metric::Population, Metric::Bounds]);
+        for generation in 0..=8 {
+            sampler.observe(generation, &world);
+            world.step();
+        }
+
+        // A glider moves one cell diagonally every four generations
+        let generations: Vec<u64> = sampler.samples().iter().map(|sample| sample.generation).collect();
+        assert_eq!(vec![0, 4, 8], generations);
+        assert_eq!(Some(Some(Rect::new(1, 1, 3, 3))), sampler.samples()[1].bounds);
+        assert!(sampler.samples().iter().all(|sample| sample.population == Some(5) && sample.hash.is_none()));
+        assert_eq!("generation\tpopulation\tbounds\n0\t5\t3x3 at 0 0\n4\t5\t3x3 at 1 1\n8\t5\t3x3 at 2 2\n", sampler.to_string());
+
+        let mut sampler = Sampler::new(0, &[Metric::Hash, Metric::Bounds]);
+        assert!(sampler.observe(7, &GameOfLife::new()));
+        assert_eq!(Some(None), sampler.samples()[0].bounds);
+        assert!(sampler.to_string().ends_with("\tnone\n"));
+    }
+}
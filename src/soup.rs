@@ -0,0 +1,99 @@
+//! Seeded random soups, the standard starting point for experiments with rules and patterns.
+//! The generator is implemented here rather than taken from a dependency,
+//! so a seed produces the same soup on every platform and version.
+
+use crate::geometry::Coord;
+use crate::gol::{Cell, Region, RegionError};
+
+/// Small, fast, seeded pseudo-random number generator (SplitMix64).
+/// Not suitable for anything security related.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    /// Create a generator that always produces the same sequence for the same seed.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Returns the next number in the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        // Top 53 bits fill the mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number in the range `0..bound`, or 0 if the bound is 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 { return 0 }
+        // Widening multiply avoids the bias of a plain modulo
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Returns true with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+}
+
+/// Create a region from x y with the given width and height,
+/// where each cell is independently alive with the given probability.
+pub fn random_soup(rng: &mut Rng, x: Coord, y: Coord, width: usize, height: usize, density: f64) -> Result<Region, RegionError> {
+    let mut soup = Region::new(x, y, width, height)?;
+    for cell_x in x..x.saturating_add_unsigned(width as u64) {
+        for cell_y in y..y.saturating_add_unsigned(height as u64) {
+            if rng.chance(density) {
+                soup.set_cell(cell_x, cell_y, Cell::Alive);
+            }
+        }
+    }
+    Ok(soup)
+}
+
+#[cfg(test)]
+mod soup_tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_reproducible() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let sequence: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        assert_eq!(sequence, (0..8).map(|_| b.next_u64()).collect::<Vec<u64>>());
+        assert_ne!(sequence, (0..8).map(|_| c.next_u64()).collect::<Vec<u64>>());
+
+        // Reference value of SplitMix64, so the sequence never silently changes
+        assert_eq!(0xe220a8397b1dcdaf, Rng::new(0).next_u64());
+    }
+
+    #[test]
+    fn rng_ranges() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!(rng.below(10) < 10);
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+        }
+        assert_eq!(0, rng.below(0));
+    }
+
+    #[test]
+    fn soup_density() {
+        let soup = random_soup(&mut Rng::new(1), -50, -50, 100, 100, 0.3).unwrap();
+        let alive = soup.live_cells().count();
+        assert!((2700..3300).contains(&alive), "{} live cells", alive);
+
+        assert!(random_soup(&mut Rng::new(1), 0, 0, 10, 10, 0.0).unwrap().live_bounds().is_none());
+    }
+}
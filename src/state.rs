@@ -0,0 +1,148 @@
+//! States cells can be in besides the main engine's alive and dead, and the sparse storage the multi-state worlds
+//! share. The main engine keeps its dense regions of two-state [`Cell`]s, as widening every cell would cost every
+//! pattern memory and speed, while the other worlds store only their cells that aren't in the default state,
+//! so each needs just its own [`CellState`] and next state function rather than its own copy of the storage and stepping.
+
+use std::collections::HashMap;
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+use crate::rule::{Neighbourhood, Rule};
+
+/// State of a cell in a world stored as [`SparseCells`]. The default state is the one every cell
+/// not stored is in, and must not count as alive, so the infinite background stays quiet.
+pub trait CellState: Copy + PartialEq + Default {
+    /// Whether the cell counts as alive, both to its neighbours and in the population.
+    fn is_alive(self) -> bool;
+}
+
+impl CellState for Cell {
+    fn is_alive(self) -> bool { self == Cell::Alive }
+}
+
+/// Infinite grid of cells, storing only those that aren't in the default state.
+#[derive(Debug, Clone, Default)]
+pub struct SparseCells<S: CellState> {
+    cells: HashMap<Position, S>
+}
+
+impl<S: CellState> SparseCells<S> {
+    /// Create a grid with every cell in the default state.
+    pub fn new() -> SparseCells<S> {
+        SparseCells { cells: HashMap::new() }
+    }
+
+    /// Create a grid with the live cells of a two-state world in the given state.
+    pub fn from_world(world: &GameOfLife, alive: S) -> SparseCells<S> {
+        SparseCells { cells: world.live_cells().into_iter().map(|cell| (cell, alive)).collect() }
+    }
+
+    /// Step every cell to the state given by its current state and a tally of its live neighbours, returning
+    /// how many cells came alive and stopped being alive. Each live cell adds itself to the tally of every cell
+    /// in the neighbourhood around it, with the weight it counts for there, so tallies can be plain counts
+    /// or break the neighbours down by state. Only cells stored or with a live neighbour can change,
+    /// so the next state function is only called for those, in no particular order.
+    pub fn step<T: Copy + Default>(
+        &mut self,
+        neighbourhood: &Neighbourhood,
+        tally: impl Fn(&mut T, S, u8),
+        mut next_state: impl FnMut(Position, S, T) -> S
+    ) -> StepStats {
+        let mut tallies: HashMap<Position, T> = HashMap::new();
+        for (&cell, &state) in self.cells.iter().filter(|(_, state)| state.is_alive()) {
+            for (x_off, y_off, weight) in neighbourhood.neighbours() {
+                tally(tallies.entry(cell + Position::new(x_off, y_off)).or_default(), state, weight);
+            }
+        }
+
+        let mut stats = StepStats::default();
+        let mut next = HashMap::with_capacity(self.cells.len());
+        let unstored = tallies.keys().filter(|cell| !self.cells.contains_key(cell));
+        for &cell in self.cells.keys().chain(unstored) {
+            let current = self.get(cell);
+            let state = next_state(cell, current, tallies.get(&cell).copied().unwrap_or_default());
+            match (current.is_alive(), state.is_alive()) {
+                (false, true) => stats.births += 1,
+                (true, false) => stats.deaths += 1,
+                _ => {}
+            }
+            if state.is_alive() { stats.population += 1 }
+            if state != S::default() { next.insert(cell, state); }
+        }
+        self.cells = next;
+        stats
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get(&self, position: Position) -> S {
+        self.cells.get(&position).copied().unwrap_or_default()
+    }
+
+    /// Set the state of the cell at the position, dropping it from storage if it is the default.
+    pub fn set(&mut self, position: Position, state: S) {
+        if state == S::default() { self.cells.remove(&position); } else { self.cells.insert(position, state); }
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.values().filter(|state| state.is_alive()).count()
+    }
+
+    /// Positions of the live cells, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.iter().filter(|(_, state)| state.is_alive()).map(|(&position, _)| position)
+    }
+
+    /// Every cell that isn't in the default state, with its state, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, S)> + '_ {
+        self.cells.iter().map(|(&position, &state)| (position, state))
+    }
+
+    /// Number of cells that aren't in the default state.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether every cell is in the default state.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the bounding box of every cell that isn't in the default state, or [`None`] if they all are.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::around(self.cells.keys().copied())
+    }
+
+    /// Copy the live cells into a two-state world under the given rule.
+    pub fn to_world(&self, rule: Rule) -> GameOfLife {
+        let mut world = GameOfLife::with_rule(rule);
+        for position in self.live_cells() {
+            world.set_cell(position.x, position.y, Cell::Alive);
+        }
+        world
+    }
+}
+
+#[cfg(test)]
+mod state_tests {
+    use super::*;
+
+    #[test]
+    fn step_two_states() {
+        // A blinker turns with two-state cells counting their neighbours, leaving only the live cells stored
+        let mut cells = SparseCells::new();
+        for x in 0..3 {
+            cells.set(Position::new(x, 0), Cell::Alive);
+        }
+        let life = Rule::LIFE;
+        let stats = cells.step(
+            life.neighbourhood(),
+            |neighbours: &mut u8, _, weight| *neighbours += weight,
+            |_, current, neighbours| life.next_state(current, neighbours)
+        );
+        assert_eq!(StepStats { births: 2, deaths: 2, population: 3 }, stats);
+        assert_eq!((3, Cell::Alive), (cells.len(), cells.get(Position::new(1, -1))));
+        cells.set(Position::new(1, -1), Cell::Dead);
+        assert_eq!(Some(Rect::new(1, 0, 1, 2)), cells.bounds());
+    }
+}
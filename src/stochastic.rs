@@ -0,0 +1,138 @@
+//! Worlds under stochastic rules, where births and survivals only happen with some chance.
+//! Every chance is rolled from the world's seed, the generation and the cell's position, rather than drawn in turn
+//! from one generator, so a seed gives the same run whatever order the cells are visited in.
+
+use crate::geometry::{Position, Rect};
+use crate::gol::{Cell, GameOfLife, StepStats};
+use crate::rule::StochasticRule;
+use crate::soup::Rng;
+use crate::state::SparseCells;
+
+/// Infinite world of cells following a stochastic rule.
+#[derive(Debug, Clone)]
+pub struct StochasticWorld {
+    cells: SparseCells<Cell>,
+    rule: StochasticRule,
+    seed: u64,
+    generation: u64
+}
+
+impl StochasticWorld {
+    /// Create a new empty world using the given rule, rolling its chances from the seed.
+    pub fn new(rule: StochasticRule, seed: u64) -> StochasticWorld {
+        StochasticWorld { cells: SparseCells::new(), rule, seed, generation: 0 }
+    }
+
+    /// Create a world with the live cells of a two-state world, under the given rule and seed.
+    pub fn from_world(world: &GameOfLife, rule: StochasticRule, seed: u64) -> StochasticWorld {
+        StochasticWorld { cells: SparseCells::from_world(world, Cell::Alive), ..StochasticWorld::new(rule, seed) }
+    }
+
+    /// Step the world to the next state, returning how many cells were born and died.
+    pub fn step(&mut self) -> StepStats {
+        let (rule, seed, generation) = (&self.rule, self.seed, self.generation);
+        let stats = self.cells.step(
+            rule.neighbourhood(),
+            |neighbours: &mut u8, _, weight| *neighbours += weight,
+            |position, current, neighbours| {
+                let chance = rule.chance(current, neighbours);
+                let alive = chance >= 1.0 || (chance > 0.0 && roll(seed, generation, position) < chance);
+                if alive { Cell::Alive } else { Cell::Dead }
+            }
+        );
+        self.generation += 1;
+        stats
+    }
+
+    /// Returns the state of the cell at the position.
+    pub fn get_cell(&self, position: Position) -> Cell {
+        self.cells.get(position)
+    }
+
+    /// Set the state of the cell at the position.
+    pub fn set_cell(&mut self, position: Position, state: Cell) {
+        self.cells.set(position, state);
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.population()
+    }
+
+    /// Positions of the live cells, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.live_cells()
+    }
+
+    /// Returns the bounding box of the live cells, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.cells.bounds()
+    }
+
+    /// Copy the live cells into a two-state world under every condition of the rule that can hold.
+    pub fn to_world(&self) -> GameOfLife {
+        self.cells.to_world(self.rule.rule())
+    }
+
+    // GETTERS
+    pub fn rule(&self) -> &StochasticRule { &self.rule }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn generation(&self) -> u64 { self.generation }
+}
+
+/// Number in the range `0.0..1.0` for the cell in the generation, the same every time for the same seed.
+fn roll(seed: u64, generation: u64, position: Position) -> f64 {
+    let key = [generation, position.x as u64, position.y as u64]
+        .into_iter()
+        .fold(seed, |key, part| Rng::new(key ^ part).next_u64());
+    Rng::new(key).next_f64()
+}
+
+#[cfg(test)]
+mod stochastic_tests {
+    use super::*;
+    use crate::rule::Rule;
+    use crate::soup::random_soup;
+
+    fn soup_world(rule: &str, seed: u64) -> StochasticWorld {
+        let mut world = GameOfLife::new();
+        world.set_region(&random_soup(&mut Rng::new(1), 0, 0, 24, 24, 0.4).unwrap());
+        StochasticWorld::from_world(&world, rule.parse().unwrap(), seed)
+    }
+
+    #[test]
+    fn reproducible() {
+        // The same seed gives the same run, and another seed a different one
+        let mut runs = [soup_world("B3:0.5/S2,3", 7), soup_world("B3:0.5/S2,3", 7), soup_world("B3:0.5/S2,3", 8)];
+        for _ in 0..20 {
+            runs.iter_mut().for_each(|world| { world.step(); });
+        }
+        let cells = runs.map(|world| {
+            let mut cells: Vec<Position> = world.live_cells().collect();
+            cells.sort_unstable();
+            cells
+        });
+        assert_eq!(cells[0], cells[1]);
+        assert_ne!(cells[0], cells[2]);
+    }
+
+    #[test]
+    fn certain_chances() {
+        // With every chance 0 or 1 it steps just like the main engine
+        let mut world = GameOfLife::new();
+        world.set_region(&random_soup(&mut Rng::new(1), 0, 0, 24, 24, 0.4).unwrap());
+        let mut stochastic = StochasticWorld::from_world(&world, StochasticRule::new(Rule::LIFE).unwrap(), 3);
+        for _ in 0..20 {
+            assert_eq!(world.step(), stochastic.step());
+        }
+        assert_eq!(20, stochastic.generation());
+
+        // Births that never happen leave only survivors, and half chances only ever lose some of them
+        let mut block = StochasticWorld::new("B3:0/S2:0.5,3:0.5".parse().unwrap(), 3);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            block.set_cell(Position::new(x, y), Cell::Alive);
+        }
+        let stats = block.step();
+        assert_eq!((0, 4), (stats.births, stats.deaths + stats.population));
+    }
+}
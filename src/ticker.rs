@@ -0,0 +1,125 @@
+//! Pacing repeated work, such as stepping generations or drawing frames, at a steady rate.
+//! Each kind of work gets its own ticker, so a frontend can step 1000 generations a second
+//! while drawing only 30 frames, rather than stepping as fast as it happens to draw.
+
+use std::time::{Duration, Instant};
+
+/// Schedule of ticks at a fixed number per second.
+#[derive(Debug, Copy, Clone)]
+pub struct Ticker {
+    interval: Duration,
+    next: Instant
+}
+
+impl Ticker {
+    /// Tick the given number of times per second, with the first tick due straight away.
+    pub fn new(per_second: u32) -> Ticker {
+        Ticker::every(Ticker::interval_for(per_second))
+    }
+
+    /// Tick once every interval, with the first tick due straight away.
+    pub fn every(interval: Duration) -> Ticker {
+        Ticker { interval, next: Instant::now() }
+    }
+
+    /// Change the number of ticks per second, keeping when the next tick is due.
+    pub fn set_rate(&mut self, per_second: u32) {
+        self.interval = Ticker::interval_for(per_second);
+    }
+
+    /// Make the next tick due at the given time, such as when resuming after a pause,
+    /// so the ticks missed while paused aren't caught up on.
+    pub fn restart(&mut self, at: Instant) {
+        self.next = at;
+    }
+
+    /// Take a tick if one is due at the given time, for work that only needs doing once however late it is,
+    /// such as drawing a frame. Any other ticks missed are skipped, and the next is due an interval from now.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if now < self.next { return false }
+        self.next += self.interval;
+        if self.next <= now { self.next = now + self.interval }
+        true
+    }
+
+    /// Take the next tick if it is due, for work that should happen once for every tick, such as stepping.
+    /// Call it until it returns false, starting from the same time, to catch up on every tick missed.
+    /// Once more than `budget` has passed since `started`, the remaining missed ticks are skipped
+    /// rather than falling ever further behind when the work can't keep up.
+    pub fn catch_up(&mut self, started: Instant, budget: Duration) -> bool {
+        let now = Instant::now();
+        if now < self.next { return false }
+        if now.duration_since(started) > budget {
+            self.next = now + self.interval;
+            return false;
+        }
+        self.next += self.interval;
+        true
+    }
+
+    /// Time from the given time until the next tick is due, or zero if it already is.
+    pub fn until_next(&self, now: Instant) -> Duration {
+        self.next.saturating_duration_since(now)
+    }
+
+    fn interval_for(per_second: u32) -> Duration {
+        Duration::from_secs(1) / per_second.max(1)
+    }
+
+    // GETTERS
+    pub fn interval(&self) -> Duration { self.interval }
+    pub fn next(&self) -> Instant { self.next }
+}
+
+#[cfg(test)]
+mod ticker_tests {
+    use super::*;
+
+    #[test]
+    fn tick() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new(10);
+        ticker.restart(start);
+        assert_eq!(Duration::from_millis(100), ticker.interval());
+
+        assert!(ticker.tick(start));
+        assert!(!ticker.tick(start + Duration::from_millis(50)));
+        assert_eq!(Duration::from_millis(50), ticker.until_next(start + Duration::from_millis(50)));
+        assert!(ticker.tick(start + Duration::from_millis(120)));
+        assert_eq!(start + Duration::from_millis(200), ticker.next());
+
+        // Ticks missed while busy are skipped
+        assert!(ticker.tick(start + Duration::from_millis(950)));
+        assert!(!ticker.tick(start + Duration::from_millis(960)));
+        assert_eq!(start + Duration::from_millis(1050), ticker.next());
+
+        ticker.set_rate(0);
+        assert_eq!(Duration::from_secs(1), ticker.interval());
+    }
+
+    #[test]
+    fn catch_up() {
+        // Ten ticks are overdue, and the eleventh is half an interval from now
+        let mut ticker = Ticker::new(1);
+        let started = Instant::now();
+        ticker.restart(started - Duration::from_millis(9500));
+        let mut ticks = 0;
+        while ticker.catch_up(started, Duration::from_secs(60)) {
+            ticks += 1;
+        }
+        assert_eq!(10, ticks);
+        assert!(ticker.next() > started);
+
+        // Too slow to catch up, so the rest are skipped once over budget
+        let mut ticker = Ticker::new(1000);
+        let started = Instant::now();
+        ticker.restart(started - Duration::from_secs(10));
+        let mut ticks = 0;
+        while ticker.catch_up(started, Duration::from_millis(5)) {
+            std::thread::sleep(Duration::from_millis(1));
+            ticks += 1;
+        }
+        assert!(ticks < 100);
+        assert!(ticker.next() > started);
+    }
+}
@@ -0,0 +1,164 @@
+//! Shape of the space a world is stepped in: the endless plane the engine simulates,
+//! or a torus that wraps around from each edge to the opposite one.
+//! A torus is stepped on the plane, by copying the cells just inside each edge to just outside the opposite one
+//! before every step, as far as the rules' neighbours reach, and putting everything outside back to the background after it.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::GameOfLife;
+
+/// Space a world is stepped in.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Topology {
+    /// The endless plane.
+    #[default]
+    Plane,
+    /// Torus of the given width and height in cells, centred on 0 0 like soups are.
+    Torus { width: usize, height: usize }
+}
+
+impl Topology {
+    /// Returns the cells of a torus, or [`None`] for the plane.
+    pub fn area(&self) -> Option<Rect> {
+        match *self {
+            Topology::Plane => None,
+            Topology::Torus { width, height } => Some(Rect::new(-(width as Coord / 2), -(height as Coord / 2), width, height))
+        }
+    }
+
+    /// Move every live cell outside a torus to where it wraps around to inside it, such as for a pattern bigger
+    /// than the torus. Cells wrapping onto the same place are alive if the last of them is.
+    pub fn wrap(&self, world: &mut GameOfLife) {
+        let Some(area) = self.area() else { return };
+        for position in world.live_cells() {
+            if area.contains(position) { continue }
+            let state = world.get_cell(position.x, position.y);
+            world.set_cell(position.x, position.y, world.background());
+            let wrapped = wrap_position(area, position);
+            world.set_cell(wrapped.x, wrapped.y, state);
+        }
+    }
+
+    /// Step the world once in this space.
+    pub fn step(&self, world: &mut GameOfLife) {
+        let Some(area) = self.area() else {
+            world.step();
+            return
+        };
+
+        let reach = world.region_buffer();
+        for position in ring(area, reach) {
+            let wrapped = wrap_position(area, position);
+            world.set_cell(position.x, position.y, world.get_cell(wrapped.x, wrapped.y));
+        }
+        world.step();
+        // Cells outside can only have changed within reach of those copied in
+        for position in ring(area, 2 * reach) {
+            world.set_cell(position.x, position.y, world.background());
+        }
+    }
+}
+
+impl Display for Topology {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Topology::Plane => write!(f, "plane"),
+            Topology::Torus { width, height } => write!(f, "torus:{}x{}", width, height)
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = TopologyError;
+
+    fn from_str(s: &str) -> Result<Topology, TopologyError> {
+        let text = s.trim().to_ascii_lowercase();
+        if text == "plane" { return Ok(Topology::Plane) }
+        let invalid = || TopologyError(s.to_string());
+        let (width, height) = text.strip_prefix("torus:").and_then(|size| size.split_once('x')).ok_or_else(invalid)?;
+        let (width, height) = (width.parse().map_err(|_| invalid())?, height.parse().map_err(|_| invalid())?);
+        if width == 0 || height == 0 { return Err(invalid()) }
+        Ok(Topology::Torus { width, height })
+    }
+}
+
+/// Error from parsing a topology that isn't `plane` or a torus with a size.
+#[derive(Debug, PartialEq)]
+pub struct TopologyError(pub String);
+
+impl Display for TopologyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid topology '{}', expected plane or torus:WxH such as torus:64x64", self.0)
+    }
+}
+
+impl core::error::Error for TopologyError {}
+
+/// Returns the position inside the area the given one wraps around to.
+fn wrap_position(area: Rect, position: Position) -> Position {
+    Position::new(
+        area.x + (position.x - area.x).rem_euclid(area.width as Coord),
+        area.y + (position.y - area.y).rem_euclid(area.height as Coord)
+    )
+}
+
+/// Every position outside the area but within `reach` of it, in rows from the -y edge.
+fn ring(area: Rect, reach: usize) -> Vec<Position> {
+    let (outer, max) = (area.expand(reach), area.max());
+    let outer_max = outer.max();
+    let mut positions = Vec::new();
+    for y in outer.y..=outer_max.y {
+        match (area.y..=max.y).contains(&y) {
+            true => positions.extend((outer.x..area.x).chain(max.x + 1..=outer_max.x).map(|x| Position::new(x, y))),
+            false => positions.extend((outer.x..=outer_max.x).map(|x| Position::new(x, y)))
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod topology_tests {
+    use super::*;
+    use crate::gol::Cell;
+    use crate::io::rle::read_rle;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Topology::Plane), "plane".parse());
+        assert_eq!(Ok(Topology::Torus { width: 64, height: 32 }), " Torus:64x32".parse());
+        assert_eq!("torus:64x32", Topology::Torus { width: 64, height: 32 }.to_string());
+        for invalid in ["sphere", "torus", "torus:64", "torus:0x5", "torus:-4x4"] {
+            assert_eq!(Err(TopologyError(invalid.to_string())), invalid.parse::<Topology>());
+        }
+    }
+
+    #[test]
+    fn glider_wraps_around() {
+        // A glider moves a cell diagonally every 4 generations, so is back where it started after 32 on an 8 by 8 torus
+        let torus = Topology::Torus { width: 8, height: 8 };
+        let mut world = GameOfLife::new();
+        world.set_region(&read_rle("bo$2bo$3o!").unwrap());
+        let mut start = world.live_cells();
+        start.sort_unstable();
+        for generation in 1..=32 {
+            torus.step(&mut world);
+            assert_eq!(5, world.population(), "generation {}", generation);
+            assert!(world.live_cells().into_iter().all(|position| torus.area().unwrap().contains(position)));
+        }
+        let mut end = world.live_cells();
+        end.sort_unstable();
+        assert_eq!(start, end);
+
+        // Patterns outside are moved in, and the plane leaves them be
+        let mut world = GameOfLife::new();
+        world.set_cell(10, -5, Cell::Alive);
+        Topology::Plane.wrap(&mut world);
+        assert_eq!(Cell::Alive, world.get_cell(10, -5));
+        torus.wrap(&mut world);
+        assert_eq!(vec![Position::new(2, 3)], world.live_cells());
+    }
+}
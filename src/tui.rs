@@ -0,0 +1,1105 @@
+//! Full-screen interactive frontend for running a world in the terminal.
+//! Only the parts of the screen that change between frames are redrawn,
+//! so large mostly still patterns don't flood the terminal with output.
+
+pub mod command;
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+#[cfg(feature = "clipboard")]
+use crate::clipboard;
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife};
+use crate::history::History;
+use crate::io::read_pattern;
+use crate::journal::{Edit, Journal};
+use crate::render::CellAges;
+use crate::render::terminal::{self as text, Styled, TerminalViewport};
+use crate::ticker::Ticker;
+use crate::tui::command::Command;
+use crate::undo::UndoStack;
+
+/// Slowest speed, in generations per second.
+const MIN_SPEED: u32 = 1;
+/// Fastest speed, in generations per second.
+const MAX_SPEED: u32 = 1024;
+/// Most frames drawn per second, however fast the simulation runs.
+const MAX_FPS: u32 = 60;
+/// Longest to wait for input while paused before drawing again.
+const IDLE_POLL: Duration = Duration::from_millis(160);
+/// Characters moved by each press of an arrow key.
+const PAN_STEP: Coord = 4;
+/// Generations kept for stepping backwards.
+const HISTORY_LENGTH: usize = 1000;
+/// Period the measured steps per second are averaged over.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// State of the interactive frontend, separate from the terminal so it can be driven by tests.
+pub struct App {
+    world: GameOfLife,
+    generation: u64,
+    history: History,
+    paused: bool,
+    /// Generations per second while running.
+    speed: u32,
+    /// Views shown side by side, each panned and zoomed on its own.
+    viewports: Vec<TerminalViewport>,
+    /// Index of the view that keys act on.
+    active: usize,
+    /// Cell being edited, while in cursor mode.
+    cursor: Option<Position>,
+    /// Corner marked for selecting, with the cursor as the opposite corner.
+    anchor: Option<Position>,
+    /// Ages of the live cells while colouring by age, and [`None`] otherwise.
+    ages: Option<CellAges>,
+    /// Edits made since the world was loaded, for replaying the session.
+    journal: Journal,
+    /// Edits made since the world last stepped, which can be undone.
+    undo: UndoStack,
+    /// State being painted onto cells while the left mouse button is held down.
+    painting: Option<Cell>,
+    /// Command being typed after `:`, while the command line is open.
+    command: Option<String>,
+    /// Result of the last command, shown until the next key press.
+    message: Option<String>,
+    /// Bumped whenever the world or how its cells are coloured changes, so the grid is only redrawn when it could look different.
+    revision: u64,
+    rate: StepRate,
+    running: bool
+}
+
+impl App {
+    /// Create a paused app showing the given world, centred on its live cells.
+    pub fn new(world: GameOfLife) -> App {
+        let viewports = vec![TerminalViewport::centred_on(&world)];
+        App {
+            world,
+            generation: 0,
+            history: History::new(HISTORY_LENGTH),
+            paused: true,
+            speed: 8,
+            viewports,
+            active: 0,
+            cursor: None,
+            anchor: None,
+            ages: None,
+            journal: Journal::new(),
+            undo: UndoStack::new(),
+            painting: None,
+            command: None,
+            message: None,
+            revision: 0,
+            rate: StepRate::default(),
+            running: true
+        }
+    }
+
+    /// Respond to a key press.
+    pub fn handle_key(&mut self, key: KeyCode) {
+        self.message = None;
+        // While typing a command every key goes to the command line
+        if let Some(command) = &mut self.command {
+            match key {
+                KeyCode::Char(character) => command.push(character),
+                // Deleting past the start closes the command line
+                KeyCode::Backspace if command.is_empty() => self.command = None,
+                KeyCode::Backspace => { command.pop(); }
+                KeyCode::Enter => {
+                    let line = self.command.take().expect("Command line is open");
+                    if line.trim().is_empty() { return }
+                    self.message = match self.run_command(&line) {
+                        Ok(message) => message,
+                        Err(error) => Some(error.to_string())
+                    };
+                }
+                KeyCode::Esc => self.command = None,
+                _ => {}
+            }
+            return
+        }
+        // In cursor mode the arrow keys move the cursor instead of the view
+        if let Some(cursor) = self.cursor {
+            match key {
+                KeyCode::Left => self.cursor = Some(cursor.saturating_add(Position::new(-1, 0))),
+                KeyCode::Right => self.cursor = Some(cursor.saturating_add(Position::new(1, 0))),
+                KeyCode::Up => self.cursor = Some(cursor.saturating_add(Position::new(0, -1))),
+                KeyCode::Down => self.cursor = Some(cursor.saturating_add(Position::new(0, 1))),
+                KeyCode::Enter => {
+                    let state = if self.world.get_cell(cursor.x, cursor.y) == Cell::Alive { Cell::Dead } else { Cell::Alive };
+                    self.edit(Edit::SetCell(cursor, state));
+                }
+                KeyCode::Char('s') => self.anchor = if self.anchor.is_some() { None } else { Some(cursor) },
+                KeyCode::Char('y') => self.message = Some(self.copy()),
+                KeyCode::Char('p') => self.message = self.paste(),
+                KeyCode::Char('e') | KeyCode::Esc => {
+                    self.cursor = None;
+                    self.anchor = None;
+                }
+                _ => return self.handle_view_key(key)
+            }
+            return
+        }
+        self.handle_view_key(key)
+    }
+
+    /// Respond to a key press that isn't specific to cursor mode.
+    fn handle_view_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('.') => {
+                self.paused = true;
+                self.step();
+            }
+            KeyCode::Char(',') => {
+                self.paused = true;
+                self.step_back();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            KeyCode::Char('-') => self.speed = (self.speed / 2).max(MIN_SPEED),
+            KeyCode::Left => self.viewport_mut().pan(-PAN_STEP, 0),
+            KeyCode::Right => self.viewport_mut().pan(PAN_STEP, 0),
+            KeyCode::Up => self.viewport_mut().pan(0, -PAN_STEP),
+            KeyCode::Down => self.viewport_mut().pan(0, PAN_STEP),
+            KeyCode::Char('z') => self.viewport_mut().zoom_in(),
+            KeyCode::Char('x') => self.viewport_mut().zoom_out(),
+            KeyCode::Char('c') => *self.viewport_mut() = TerminalViewport::centred_on(&self.world),
+            KeyCode::Char('e') => self.cursor = Some(Position::new(self.viewport().x(), self.viewport().y())),
+            KeyCode::Char('v') => self.split(),
+            KeyCode::Char('V') => self.close_view(),
+            KeyCode::Tab => self.active = (self.active + 1) % self.viewports.len(),
+            KeyCode::Char('a') => self.toggle_ages(),
+            KeyCode::Char('u') => { self.undo(); }
+            KeyCode::Char('U') => { self.redo(); }
+            KeyCode::Char(':') => self.command = Some(String::new()),
+            KeyCode::Char('q') | KeyCode::Esc => self.running = false,
+            _ => {}
+        }
+    }
+
+    /// Advance the world by one generation.
+    pub fn step(&mut self) {
+        self.history.record(self.generation, &self.world);
+        self.undo.clear();
+        self.world.step();
+        self.generation += 1;
+        if let Some(ages) = &mut self.ages { ages.update(&self.world) }
+        self.revision += 1;
+        self.rate.record(Instant::now());
+    }
+
+    /// Go back to the state before the last step, if it is still in the history.
+    /// Returns false if there is nothing left to step back to.
+    pub fn step_back(&mut self) -> bool {
+        let Some((generation, world)) = self.history.step_back() else { return false };
+        self.world = world;
+        self.generation = generation;
+        self.journal.forget_after(generation);
+        self.undo.clear();
+        // Ages can't be wound back, so they start again from the restored generation
+        if let Some(ages) = &mut self.ages {
+            ages.clear();
+            ages.update(&self.world);
+        }
+        self.revision += 1;
+        true
+    }
+
+    /// Respond to the mouse on a screen whose grid is `columns` by `rows` characters, with the status lines below it.
+    /// Clicking toggles the cell under the pointer and makes its view the active one,
+    /// dragging paints cells the same state, and scrolling zooms the view under the pointer.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, columns: usize, rows: usize) {
+        let (column, row) = (mouse.column as usize, mouse.row as usize);
+        if mouse.kind == MouseEventKind::Up(MouseButton::Left) {
+            self.painting = None;
+            self.undo.finish_group();
+        }
+        // Nothing happens over the status lines or the borders between views
+        if row >= rows { return }
+        let Some((index, pane)) = pane_spans(columns, self.viewports.len()).into_iter().enumerate().find(|(_, pane)| pane.contains(&column)) else { return };
+        let viewport = &mut self.viewports[index];
+        let cell = viewport.cell_at(column - pane.start, row, pane.len(), rows);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.active = index;
+                let state = if self.world.get_cell(cell.x, cell.y) == Cell::Alive { Cell::Dead } else { Cell::Alive };
+                self.painting = Some(state);
+                // Everything painted until the button is let go is undone together
+                self.undo.start_group();
+                self.paint(cell, state);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(state) = self.painting { self.paint(cell, state) }
+            }
+            MouseEventKind::ScrollUp => viewport.zoom_in(),
+            MouseEventKind::ScrollDown => viewport.zoom_out(),
+            _ => {}
+        }
+    }
+
+    /// Set a cell edited with the mouse, moving the cursor there while editing so the two don't disagree.
+    fn paint(&mut self, cell: Position, state: Cell) {
+        if self.cursor.is_some() { self.cursor = Some(cell) }
+        if self.world.get_cell(cell.x, cell.y) == state { return }
+        self.edit(Edit::SetCell(cell, state));
+    }
+
+    /// Copy the selection to the system clipboard as RLE and stop selecting, returning a message about how it went.
+    #[cfg(feature = "clipboard")]
+    fn copy(&mut self) -> String {
+        let Some(selection) = self.selection() else { return "nothing selected, s marks a corner".to_string() };
+        self.anchor = None;
+        match clipboard::copy_selection(&self.world, selection.x, selection.y, selection.width, selection.height) {
+            Ok(()) => format!("copied {}x{} cells", selection.width, selection.height),
+            Err(error) => error.to_string()
+        }
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn copy(&mut self) -> String { "copying needs the clipboard feature".to_string() }
+
+    /// Paste the RLE pattern on the system clipboard with its -x -y corner at the cursor, returning any error message.
+    #[cfg(feature = "clipboard")]
+    fn paste(&mut self) -> Option<String> {
+        let cursor = self.cursor?;
+        match clipboard::paste_at(cursor.x, cursor.y) {
+            Ok(pattern) => {
+                self.edit(Edit::Paste(pattern));
+                None
+            }
+            Err(error) => Some(error.to_string())
+        }
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn paste(&mut self) -> Option<String> { Some("pasting needs the clipboard feature".to_string()) }
+
+    /// Make a change to the world, recording it in the journal.
+    fn edit(&mut self, edit: Edit) {
+        self.undo.apply(edit.clone(), &mut self.world);
+        self.journal.record(self.generation, edit);
+        self.revision += 1;
+    }
+
+    /// Take back the last edit, or the last stroke of edits painted with the mouse, since the world last stepped.
+    /// The edits reversing it are journalled like any other. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edits) = self.undo.undo(&mut self.world) else { return false };
+        self.journal_all(edits);
+        true
+    }
+
+    /// Make the last edits undone again. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edits) = self.undo.redo(&mut self.world) else { return false };
+        self.journal_all(edits);
+        true
+    }
+
+    fn journal_all(&mut self, edits: Vec<Edit>) {
+        for edit in edits {
+            self.journal.record(self.generation, edit);
+        }
+        self.revision += 1;
+    }
+
+    /// Parse and run a command typed after `:`, returning a message about what it did, if there is anything to say.
+    pub fn run_command(&mut self, line: &str) -> Result<Option<String>, Box<dyn Error>> {
+        match line.parse()? {
+            Command::Load(path) => {
+                self.load(&path)?;
+                Ok(Some(format!("loaded {} with {} live cells", path.display(), self.world.population())))
+            }
+            Command::Rule(rule) => {
+                self.edit(Edit::Rule(rule));
+                Ok(None)
+            }
+            Command::Paste(path) => {
+                let mut pattern = read_pattern(&path)?;
+                let corner = self.cursor.unwrap_or(Position::new(self.viewport().x(), self.viewport().y()));
+                pattern.set_position(corner.x, corner.y)?;
+                self.edit(Edit::Paste(pattern));
+                Ok(None)
+            }
+            Command::Goto(position) => {
+                self.viewport_mut().move_to(position);
+                if self.cursor.is_some() { self.cursor = Some(position) }
+                Ok(None)
+            }
+            Command::Step(generations) => {
+                self.paused = true;
+                for _ in 0..generations {
+                    self.step();
+                }
+                Ok(None)
+            }
+            Command::History(generations) => {
+                self.history.set_capacity(generations);
+                Ok(Some(match generations {
+                    0 => "stepping back turned off".to_string(),
+                    _ => format!("keeping {} generations to step back through", generations)
+                }))
+            }
+            Command::Quit => {
+                self.running = false;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Replace the world with a pattern file, keeping the rule and starting again from generation 0.
+    pub fn load(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let region = read_pattern(path)?;
+        let mut world = GameOfLife::with_rule(self.world.rule());
+        world.set_trim_policy(self.world.trim_policy());
+        world.set_region(&region);
+
+        self.world = world;
+        self.generation = 0;
+        self.history.clear();
+        self.journal = Journal::new();
+        self.undo.clear();
+        for viewport in &mut self.viewports {
+            *viewport = TerminalViewport::centred_on(&self.world);
+        }
+        self.cursor = None;
+        self.anchor = None;
+        if let Some(ages) = &mut self.ages {
+            ages.clear();
+            ages.update(&self.world);
+        }
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Split the active view in two, with the new copy of it becoming the active view.
+    pub fn split(&mut self) {
+        self.viewports.insert(self.active + 1, self.viewport().clone());
+        self.active += 1;
+    }
+
+    /// Close the active view, unless it is the only one.
+    pub fn close_view(&mut self) {
+        if self.viewports.len() == 1 { return }
+        self.viewports.remove(self.active);
+        self.active = self.active.min(self.viewports.len() - 1);
+    }
+
+    /// Re-centre the active view on the cursor if it has gone out of sight, on a screen whose grid is `columns` by `rows`.
+    pub fn keep_cursor_visible(&mut self, columns: usize, rows: usize) {
+        let Some(cursor) = self.cursor else { return };
+        let width = self.active_pane(columns).len();
+        self.viewport_mut().keep_visible(cursor, width, rows);
+    }
+
+    /// Returns the column and row on screen of the cursor in the active view, or [`None`] if not editing or it is out of sight.
+    pub fn cursor_char(&self, columns: usize, rows: usize) -> Option<(usize, usize)> {
+        let pane = self.active_pane(columns);
+        let (column, row) = self.viewport().char_at(self.cursor?, pane.len(), rows)?;
+        Some((pane.start + column, row))
+    }
+
+    /// Cells between the marked corner and the cursor, or [`None`] if not selecting.
+    pub fn selection(&self) -> Option<Rect> {
+        Some(Rect::from_corners(self.anchor?, self.cursor?))
+    }
+
+    /// Columns of the screen taken by the active view.
+    fn active_pane(&self, columns: usize) -> Range<usize> {
+        pane_spans(columns, self.viewports.len()).swap_remove(self.active)
+    }
+
+    fn viewport_mut(&mut self) -> &mut TerminalViewport { &mut self.viewports[self.active] }
+
+    /// Switch colouring cells by age on or off. Ages count from when it is switched on.
+    pub fn toggle_ages(&mut self) {
+        self.ages = match self.ages {
+            Some(_) => None,
+            None => {
+                let mut ages = CellAges::new();
+                ages.update(&self.world);
+                Some(ages)
+            }
+        };
+        self.revision += 1;
+    }
+
+    /// Lines of characters for the world on a screen of the given size, each with the 256 colour ANSI codes to draw it in,
+    /// or [`None`] for the terminal's default colours. The views are side by side with a border between each.
+    pub fn frame(&self, columns: usize, rows: usize) -> Vec<Vec<Styled>> {
+        let mut lines = vec![vec![]; rows];
+        for (viewport, pane) in self.viewports.iter().zip(pane_spans(columns, self.viewports.len())) {
+            for (line, view_line) in lines.iter_mut().zip(self.view_frame(viewport, pane.len(), rows)) {
+                if pane.start > 0 { line.push(('│', None, None)) }
+                line.extend(view_line);
+            }
+        }
+        lines
+    }
+
+    /// Lines of characters for one view, coloured as in [`App::frame`].
+    fn view_frame(&self, viewport: &TerminalViewport, columns: usize, rows: usize) -> Vec<Vec<Styled>> {
+        match &self.ages {
+            Some(ages) => {
+                let origin = viewport.origin(columns, rows);
+                text::styled_by_age(&self.world, ages, origin.x, origin.y, columns, rows, viewport.glyphs())
+            }
+            None => viewport.draw(&self.world, columns, rows)
+                .lines()
+                .map(|line| line.chars().map(|glyph| (glyph, None, None)).collect())
+                .collect()
+        }
+    }
+
+    /// Time between generations at the current speed.
+    pub fn step_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.speed
+    }
+
+    /// Line of text summarising the state of the world and the view, measuring the speed at the given time.
+    /// Shows the cell under the cursor while editing, and the cell at the centre of the view otherwise.
+    pub fn status_bar(&self, now: Instant) -> String {
+        let selecting = match self.selection() {
+            Some(selection) => format!(", selecting {}x{}", selection.width, selection.height),
+            None => String::new()
+        };
+        let position = match self.cursor {
+            Some(cursor) => format!(
+                "cursor {} ({}){}",
+                cursor,
+                if self.world.get_cell(cursor.x, cursor.y) == Cell::Alive { "alive" } else { "dead" },
+                selecting
+            ),
+            None => format!("centre {} {}", self.viewport().x(), self.viewport().y())
+        };
+        let view = match self.viewports.len() {
+            1 => String::new(),
+            count => format!("view {}/{} ", self.active + 1, count)
+        };
+        let (across, down) = self.viewport().glyphs().cells_per_char();
+        format!(
+            "gen {} (-{}) | pop {} | {:.1} steps/s of {}{} | {}{} | {}x{} cells/char | {}",
+            self.generation,
+            self.history.len(),
+            self.world.population(),
+            self.rate.per_second(now),
+            self.speed,
+            if self.paused { " (paused)" } else { "" },
+            view,
+            position,
+            across,
+            down,
+            self.world.rule()
+        )
+    }
+
+    /// Line of text listing the controls, or the command being typed or the result of the last one.
+    pub fn help_line(&self) -> String {
+        if let Some(command) = &self.command { return format!(":{}", command) }
+        if let Some(message) = &self.message { return message.clone() }
+        let toggle = if self.paused { "run" } else { "pause" };
+        match self.cursor {
+            Some(_) => format!("arrows: move | enter: toggle | s: select | y/p: copy/paste | e/esc: done | space: {} | q: quit", toggle),
+            None => format!(
+                "space: {} | ./,: step fwd/back | +/-: speed | arrows: pan | z/x: zoom | c: centre | e: edit | u/U: undo/redo | v/V/tab: split/close/switch view | a: age colours | :: command | mouse: draw, scroll: zoom | q: quit",
+                toggle
+            )
+        }
+    }
+
+    // GETTERS
+    pub fn world(&self) -> &GameOfLife { &self.world }
+    pub fn generation(&self) -> u64 { self.generation }
+    pub fn paused(&self) -> bool { self.paused }
+    pub fn speed(&self) -> u32 { self.speed }
+    pub fn viewport(&self) -> &TerminalViewport { &self.viewports[self.active] }
+    pub fn viewports(&self) -> &[TerminalViewport] { &self.viewports }
+    pub fn active(&self) -> usize { self.active }
+    pub fn cursor(&self) -> Option<Position> { self.cursor }
+    pub fn ages(&self) -> Option<&CellAges> { self.ages.as_ref() }
+    pub fn command(&self) -> Option<&str> { self.command.as_deref() }
+    pub fn journal(&self) -> &Journal { &self.journal }
+    pub fn revision(&self) -> u64 { self.revision }
+    pub fn running(&self) -> bool { self.running }
+}
+
+/// Measures how many generations are actually stepped per second, which falls behind the set speed
+/// when the world is too large to keep up.
+#[derive(Debug, Default)]
+struct StepRate {
+    /// When each step in the last [`RATE_WINDOW`] happened, oldest first.
+    steps: VecDeque<Instant>
+}
+
+impl StepRate {
+    fn record(&mut self, at: Instant) {
+        self.steps.push_back(at);
+        while let Some(&oldest) = self.steps.front() && at.saturating_duration_since(oldest) >= RATE_WINDOW {
+            self.steps.pop_front();
+        }
+    }
+
+    /// Steps per second over the window leading up to the given time.
+    fn per_second(&self, now: Instant) -> f64 {
+        let recent = self.steps.iter().filter(|&&step| now.saturating_duration_since(step) < RATE_WINDOW).count();
+        recent as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+/// What the grid was drawn for: the app's revision, the views, and the number of columns and rows.
+type GridKey = (u64, Vec<TerminalViewport>, usize, usize);
+
+/// Columns of a screen `columns` wide taken by each of `count` views side by side, leaving one between each for a border.
+/// Any columns that don't divide evenly go to the leftmost views.
+fn pane_spans(columns: usize, count: usize) -> Vec<Range<usize>> {
+    let usable = columns.saturating_sub(count - 1);
+    let mut start = 0;
+    (0..count).map(|index| {
+        let width = usable / count + usize::from(index < usable % count);
+        let pane = start..start + width;
+        start += width + 1;
+        pane
+    }).collect()
+}
+
+/// Lines currently on screen, used to work out which parts of the next frame need redrawing.
+#[derive(Default)]
+struct Screen {
+    lines: Vec<Vec<Styled>>
+}
+
+impl Screen {
+    /// Returns the row, starting column and characters of the smallest span of each line that differs from the screen,
+    /// and records the new lines as being on screen. A character counts as changed if only its colour has.
+    fn update(&mut self, frame: &[Vec<Styled>]) -> Vec<(usize, usize, Vec<Styled>)> {
+        let mut spans = vec![];
+        let mut lines = Vec::with_capacity(frame.len());
+        for (row, new) in frame.iter().enumerate() {
+            let old = self.lines.get(row).map(Vec::as_slice).unwrap_or_default();
+            // Pad the new line so any leftover text from a longer old line is cleared
+            let padded: Vec<Styled> = new.iter().copied().chain(std::iter::repeat((' ', None, None))).take(new.len().max(old.len())).collect();
+
+            let differs = |column: &usize| old.get(*column) != padded.get(*column);
+            if let Some(start) = (0..padded.len()).find(differs) {
+                let end = (0..padded.len()).rev().find(differs).expect("Start differs") + 1;
+                spans.push((row, start, padded[start..end].to_vec()));
+            }
+            lines.push(new.clone());
+        }
+        self.lines = lines;
+        spans
+    }
+
+    /// Forget what is on screen, so the next frame is drawn in full.
+    fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+/// Queue the characters, switching colours only where they change and going back to the default colours at the end.
+fn queue_styled(stdout: &mut impl Write, characters: &[Styled]) -> io::Result<()> {
+    let (mut foreground, mut background) = (None, None);
+    for &(character, wanted_foreground, wanted_background) in characters {
+        if wanted_foreground != foreground {
+            queue!(stdout, SetForegroundColor(wanted_foreground.map_or(Color::Reset, Color::AnsiValue)))?;
+            foreground = wanted_foreground;
+        }
+        if wanted_background != background {
+            queue!(stdout, SetBackgroundColor(wanted_background.map_or(Color::Reset, Color::AnsiValue)))?;
+            background = wanted_background;
+        }
+        queue!(stdout, Print(character))?;
+    }
+    if foreground.is_some() { queue!(stdout, SetForegroundColor(Color::Reset))? }
+    if background.is_some() { queue!(stdout, SetBackgroundColor(Color::Reset))? }
+    Ok(())
+}
+
+/// Restores the terminal when dropped, even if the frontend exits with an error or panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide, EnableMouseCapture)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), DisableMouseCapture, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Run the interactive frontend until the user quits, starting paused.
+/// Returns the journal of the edits made, as of the generation the world was left at.
+pub fn run(world: GameOfLife) -> io::Result<Journal> {
+    let mut app = App::new(world);
+    let _guard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+    let mut screen = Screen::default();
+    // Grid last drawn, with what it was drawn for, so a frame with only the status bar changing doesn't rebuild it
+    let mut grid: Option<(GridKey, Vec<Vec<Styled>>)> = None;
+
+    let mut highlighted: Option<(usize, usize)> = None;
+
+    // Steps and frames each keep their own pace, so fast speeds don't draw a frame for every generation
+    let mut steps = Ticker::new(app.speed);
+    let mut frames = Ticker::new(MAX_FPS);
+    let mut redraw = true;
+    while app.running {
+        let (columns, rows) = terminal::size()?;
+        let (columns, rows) = (columns as usize, (rows as usize).saturating_sub(2));
+        if redraw && frames.tick(Instant::now()) {
+            // Draw the world above the status bar and the controls
+            app.keep_cursor_visible(columns, rows);
+            let key = (app.revision, app.viewports.clone(), columns, rows);
+            if grid.as_ref().is_none_or(|(drawn_for, _)| *drawn_for != key) {
+                grid = Some((key, app.frame(columns, rows)));
+            }
+            let mut frame = grid.as_ref().expect("Grid just drawn").1.clone();
+            for line in [app.status_bar(Instant::now()), app.help_line()] {
+                frame.push(line.chars().take(columns).map(|character| (character, None, None)).collect());
+            }
+
+            for (row, column, span) in screen.update(&frame) {
+                queue!(stdout, MoveTo(column as u16, row as u16))?;
+                queue_styled(&mut stdout, &span)?;
+            }
+
+            // The diff only knows about text, so the highlight under the cursor is managed separately
+            let cursor = app.cursor_char(columns, rows);
+            let char_at = |(column, row): (usize, usize)| frame.get(row).and_then(|line| line.get(column)).copied().unwrap_or((' ', None, None));
+            if let Some(position) = highlighted && highlighted != cursor {
+                queue!(stdout, MoveTo(position.0 as u16, position.1 as u16))?;
+                queue_styled(&mut stdout, &[char_at(position)])?;
+            }
+            if let Some(position) = cursor {
+                queue!(stdout, MoveTo(position.0 as u16, position.1 as u16), SetAttribute(Attribute::Reverse))?;
+                queue_styled(&mut stdout, &[char_at(position)])?;
+                queue!(stdout, SetAttribute(Attribute::Reset))?;
+            }
+            highlighted = cursor;
+            stdout.flush()?;
+            redraw = false;
+        }
+
+        // Wait for input until the next generation is due, or the next frame if one is waiting to be drawn
+        let now = Instant::now();
+        let mut timeout = if app.paused { IDLE_POLL } else { steps.until_next(now) };
+        if redraw { timeout = timeout.min(frames.until_next(now)) }
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    let was_paused = app.paused;
+                    app.handle_key(key.code);
+                    if was_paused && !app.paused { steps.restart(Instant::now()) }
+                }
+                Event::Mouse(mouse) => app.handle_mouse(mouse, columns, rows),
+                Event::Resize(..) => {
+                    execute!(stdout, terminal::Clear(terminal::ClearType::All))?;
+                    screen.clear();
+                }
+                _ => {}
+            }
+        }
+        // Even without input, the measured speed in the status bar changes over time
+        redraw = true;
+
+        // Catch up on any generations due, without spending longer than a frame on them
+        if app.paused { continue }
+        steps.set_rate(app.speed);
+        let started = Instant::now();
+        while steps.catch_up(started, frames.interval()) {
+            app.step();
+        }
+    }
+    Ok(app.journal)
+}
+
+#[cfg(test)]
+mod tui_tests {
+    use super::*;
+    use crate::gol::Cell;
+    use crate::render;
+
+    #[test]
+    fn keys() {
+        let mut world = GameOfLife::new();
+        for x in 0..3 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut app = App::new(world);
+        assert!(app.paused());
+
+        app.handle_key(KeyCode::Char(' '));
+        assert!(!app.paused());
+
+        // Single stepping pauses
+        app.handle_key(KeyCode::Char('.'));
+        assert!(app.paused());
+        assert_eq!(1, app.generation());
+        assert_eq!(Cell::Alive, app.world().get_cell(1, -1));
+
+        // Stepping back restores the previous generation and pauses
+        app.handle_key(KeyCode::Char(' '));
+        app.handle_key(KeyCode::Char(','));
+        assert!(app.paused());
+        assert_eq!(0, app.generation());
+        assert_eq!(Cell::Alive, app.world().get_cell(2, 0));
+        assert!(!app.step_back());
+
+        // The history can be shortened, or turned off
+        assert_eq!("keeping 2 generations to step back through", app.run_command("history 2").unwrap().unwrap());
+        for _ in 0..3 {
+            app.step();
+        }
+        assert!(app.step_back() && app.step_back() && !app.step_back());
+        assert_eq!(1, app.generation());
+        app.run_command("h 0").unwrap();
+        app.step();
+        assert!(!app.step_back());
+
+        app.handle_key(KeyCode::Char('+'));
+        assert_eq!(16, app.speed());
+        for _ in 0..20 {
+            app.handle_key(KeyCode::Char('-'));
+        }
+        assert_eq!(MIN_SPEED, app.speed());
+        assert_eq!(Duration::from_secs(1), app.step_interval());
+
+        let centre = (app.viewport().x(), app.viewport().y());
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Char('x'));
+        app.handle_key(KeyCode::Up);
+        assert_eq!((centre.0 + PAN_STEP, centre.1 - PAN_STEP * 2), (app.viewport().x(), app.viewport().y()));
+        app.handle_key(KeyCode::Char('c'));
+        assert_eq!((1, 0), (app.viewport().x(), app.viewport().y()));
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(!app.running());
+    }
+
+    #[test]
+    fn cursor_editing() {
+        let mut app = App::new(GameOfLife::new());
+        app.handle_key(KeyCode::Char('e'));
+        assert_eq!(Some(Position::new(0, 0)), app.cursor());
+
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(Some(Position::new(1, 1)), app.cursor());
+        assert_eq!(2, app.world().population());
+        assert_eq!(Cell::Alive, app.world().get_cell(1, 1));
+
+        // Toggling again kills the cell, and the view doesn't move while editing
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(Cell::Dead, app.world().get_cell(1, 1));
+        assert_eq!((0, 0), (app.viewport().x(), app.viewport().y()));
+
+        // Other keys still work, and escape leaves cursor mode rather than quitting
+        app.handle_key(KeyCode::Char(' '));
+        assert!(!app.paused());
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(None, app.cursor());
+        assert!(app.running());
+    }
+
+    #[test]
+    fn select_copy_paste() {
+        let mut world = GameOfLife::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            world.set_cell(x, y, Cell::Alive);
+        }
+        let mut app = App::new(world);
+        app.handle_key(KeyCode::Char('e'));
+        app.run_command("goto 0 0").unwrap();
+        app.handle_key(KeyCode::Char('y'));
+        assert_ne!(None, app.message);
+
+        // The selection runs from the marked corner to wherever the cursor goes
+        app.handle_key(KeyCode::Char('s'));
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(Some(Rect::new(0, 0, 3, 3)), app.selection());
+        assert!(app.status_bar(Instant::now()).contains("(alive), selecting 3x3 |"));
+
+        app.handle_key(KeyCode::Char('y'));
+        let copied = app.message.clone().unwrap();
+        if cfg!(not(feature = "clipboard")) || copied.starts_with("clipboard unavailable") {
+            // Without a system clipboard nothing is copied or pasted, and the world is left alone
+            app.handle_key(KeyCode::Char('p'));
+            assert!(app.message.is_some());
+            assert_eq!(5, app.world().population());
+            return;
+        }
+        assert_eq!("copied 3x3 cells", copied);
+        assert_eq!(None, app.selection());
+
+        app.run_command("goto 10 10").unwrap();
+        app.handle_key(KeyCode::Char('p'));
+        assert_eq!(None, app.message);
+        assert_eq!(10, app.world().population());
+        assert_eq!(Cell::Alive, app.world().get_cell(11, 10));
+        assert_eq!(Cell::Alive, app.world().get_cell(12, 12));
+        assert!(app.undo());
+        assert_eq!(5, app.world().population());
+    }
+
+    #[test]
+    fn age_colours() {
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut app = App::new(world);
+        assert!(app.frame(3, 1).iter().flatten().all(|&(_, foreground, background)| foreground.is_none() && background.is_none()));
+
+        app.handle_key(KeyCode::Char('a'));
+        app.step();
+        app.step();
+        assert_eq!(Some(2), app.ages().unwrap().get_age(0, 0));
+        // The blinker's middle has lived through both steps, while its ends were just born again
+        let frame = app.frame(3, 2);
+        assert_eq!(vec![('▀', Some(render::age_ansi(0)), None), ('▀', Some(render::age_ansi(2)), None), ('▀', Some(render::age_ansi(0)), None)], frame[1]);
+
+        // Stepping back restarts the ages, and switching off drops them
+        app.step_back();
+        assert_eq!(Some(0), app.ages().unwrap().get_age(0, 0));
+        app.handle_key(KeyCode::Char('a'));
+        assert!(app.ages().is_none());
+    }
+
+    #[test]
+    fn status_bar() {
+        let mut world = GameOfLife::new();
+        for x in 0..3 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let mut app = App::new(world);
+        app.step();
+        app.step();
+        let now = Instant::now();
+        let status = app.status_bar(now);
+        assert!(status.starts_with("gen 2 (-2) | pop 3 | 2.0 steps/s of 8 (paused) | centre 1 0 |"));
+        assert!(status.ends_with("| B3/S23"));
+        // The measured speed only counts recent steps
+        assert!(app.status_bar(now + RATE_WINDOW).contains("| 0.0 steps/s"));
+
+        app.handle_key(KeyCode::Char('e'));
+        app.handle_key(KeyCode::Right);
+        assert!(app.status_bar(now).contains("| cursor 2 0 (alive) |"));
+        assert!(app.help_line().starts_with("arrows: move"));
+    }
+
+    #[test]
+    fn step_rate() {
+        let mut rate = StepRate::default();
+        let start = Instant::now();
+        for step in 0..20 {
+            rate.record(start + Duration::from_millis(step * 100));
+        }
+        // Only the steps in the last second are kept
+        assert_eq!(10, rate.steps.len());
+        assert_eq!(10.0, rate.per_second(start + Duration::from_millis(1900)));
+        assert_eq!(5.0, rate.per_second(start + Duration::from_millis(2400)));
+    }
+
+    #[test]
+    fn revision() {
+        let mut app = App::new(GameOfLife::new());
+        // Moving the view or changing speed leaves the world as it was
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Char('+'));
+        assert_eq!(0, app.revision());
+
+        app.handle_key(KeyCode::Char('.'));
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Char('e'));
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(3, app.revision());
+    }
+
+    #[test]
+    fn mouse() {
+        let mouse = |kind, column, row| MouseEvent { kind, column, row, modifiers: event::KeyModifiers::NONE };
+        let mut app = App::new(GameOfLife::new());
+        app.handle_key(KeyCode::Char('z'));
+        // One cell per character on a 4 by 2 grid shows -2 -1 to 1 0
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), 2, 1), 4, 2);
+        assert_eq!(Cell::Alive, app.world().get_cell(0, 0));
+
+        // Dragging paints the state the click set, even over cells that already have it
+        app.handle_mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 3, 1), 4, 2);
+        app.handle_mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 3, 1), 4, 2);
+        app.handle_mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 3, 0), 4, 2);
+        app.handle_mouse(mouse(MouseEventKind::Up(MouseButton::Left), 3, 0), 4, 2);
+        assert_eq!(3, app.world().population());
+        assert_eq!(Cell::Alive, app.world().get_cell(1, -1));
+        assert_eq!(3, app.revision());
+
+        // Clicking a live cell starts erasing, and the status lines below the grid can't be drawn on
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), 3, 1), 4, 2);
+        app.handle_mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 2, 1), 4, 2);
+        app.handle_mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 2, 2), 4, 2);
+        assert_eq!(1, app.world().population());
+        app.handle_mouse(mouse(MouseEventKind::Moved, 2, 1), 4, 2);
+        assert_eq!(1, app.world().population());
+
+        // Each stroke is undone in one go, and undoing is journalled so replays match
+        app.handle_mouse(mouse(MouseEventKind::Up(MouseButton::Left), 2, 1), 4, 2);
+        app.handle_key(KeyCode::Char('u'));
+        assert_eq!(3, app.world().population());
+        app.handle_key(KeyCode::Char('u'));
+        assert_eq!(0, app.world().population());
+        assert!(!app.undo());
+        app.handle_key(KeyCode::Char('U'));
+        assert_eq!(3, app.world().population());
+        assert_eq!(13, app.journal().len());
+        app.step();
+        assert!(!app.undo());
+
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, 0, 0), 4, 2);
+        assert_eq!(TerminalViewport::new(0, 0).glyphs(), app.viewport().glyphs());
+    }
+
+    #[test]
+    fn split_views() {
+        assert_eq!(vec![0..10], pane_spans(10, 1));
+        assert_eq!(vec![0..5, 6..10, 11..15], pane_spans(15, 3));
+
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        let mut app = App::new(world);
+        app.handle_key(KeyCode::Char('z'));
+        app.handle_key(KeyCode::Char('v'));
+        assert_eq!((2, 1), (app.viewports().len(), app.active()));
+
+        // Each view pans on its own, side by side with a border between them
+        app.handle_key(KeyCode::Right);
+        let frame = app.frame(7, 1);
+        let text: String = frame[0].iter().map(|&(character, _, _)| character).collect();
+        assert_eq!(" █ │   ", text);
+        assert!(app.status_bar(Instant::now()).contains("| view 2/2 centre 4 0 |"));
+
+        // Clicking in a view makes it active, and the cursor is placed within the active view
+        let click = MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column: 0, row: 0, modifiers: event::KeyModifiers::NONE };
+        app.handle_mouse(click, 7, 1);
+        assert_eq!(0, app.active());
+        assert_eq!(Cell::Alive, app.world().get_cell(-1, 0));
+        app.handle_key(KeyCode::Tab);
+        app.handle_key(KeyCode::Char('e'));
+        assert_eq!(Some((5, 0)), app.cursor_char(7, 1));
+        app.handle_key(KeyCode::Esc);
+
+        app.handle_key(KeyCode::Char('V'));
+        app.handle_key(KeyCode::Char('V'));
+        assert_eq!((1, 0), (app.viewports().len(), app.active()));
+        assert_eq!((0, 0), (app.viewport().x(), app.viewport().y()));
+    }
+
+    #[test]
+    fn journal() {
+        let mut app = App::new(GameOfLife::new());
+        app.handle_key(KeyCode::Char('e'));
+        app.handle_key(KeyCode::Enter);
+        app.step();
+        app.step();
+        app.handle_key(KeyCode::Enter);
+        app.run_command("rule B36/S23").unwrap();
+        assert_eq!("#J rust-gol edit journal\n0 set 0 0 alive\n2 set 0 0 alive\n2 rule B36/S23\n", app.journal().to_string());
+
+        // Stepping back forgets the edits made after the generation it goes back to
+        app.step_back();
+        assert_eq!(Some(0), app.journal().last_generation());
+        app.run_command("load missing.rle").unwrap_err();
+        assert_eq!(1, app.journal().len());
+    }
+
+    #[test]
+    fn command_console() {
+        let mut app = App::new(GameOfLife::new());
+        let type_line = |app: &mut App, line: &str| {
+            app.handle_key(KeyCode::Char(':'));
+            for character in line.chars() {
+                app.handle_key(KeyCode::Char(character));
+            }
+            app.handle_key(KeyCode::Enter);
+        };
+
+        // Keys go to the command line while it's open, rather than quitting or moving the view
+        app.handle_key(KeyCode::Char(':'));
+        app.handle_key(KeyCode::Char('q'));
+        app.handle_key(KeyCode::Char('x'));
+        app.handle_key(KeyCode::Backspace);
+        assert_eq!(Some("q"), app.command());
+        assert_eq!(":q", app.help_line());
+        assert!(app.running());
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(None, app.command());
+
+        type_line(&mut app, "goto 1000 -500");
+        assert_eq!((1000, -500), (app.viewport().x(), app.viewport().y()));
+        type_line(&mut app, "rule B36/S23");
+        assert_eq!("B36/S23", app.world().rule().to_string());
+        type_line(&mut app, "bogus");
+        assert!(app.help_line().starts_with("unknown command 'bogus'"));
+        // The message goes once another key is pressed
+        app.handle_key(KeyCode::Char('+'));
+        assert!(app.help_line().starts_with("space: run"));
+
+        let path = std::env::temp_dir().join(format!("rust-gol-tui-{}.rle", std::process::id()));
+        std::fs::write(&path, "x = 3, y = 1\n3o!").unwrap();
+        app.step();
+        type_line(&mut app, &format!("load {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((0, 3), (app.generation(), app.world().population()));
+        assert_eq!("B36/S23", app.world().rule().to_string());
+        assert!(app.help_line().ends_with("with 3 live cells"));
+
+        type_line(&mut app, "step 4");
+        assert_eq!(4, app.generation());
+        type_line(&mut app, "load missing.rle");
+        assert_eq!(4, app.generation());
+        type_line(&mut app, "quit");
+        assert!(!app.running());
+    }
+
+    #[test]
+    fn only_changes_redrawn() {
+        let plain = |text: &str| text.chars().map(|character| (character, None, None)).collect::<Vec<Styled>>();
+        let mut screen = Screen::default();
+        let spans = screen.update(&[plain("ab"), plain("cd")]);
+        assert_eq!(vec![(0, 0, plain("ab")), (1, 0, plain("cd"))], spans);
+
+        assert!(screen.update(&[plain("ab"), plain("cd")]).is_empty());
+
+        let spans = screen.update(&[plain("abcd"), plain("c")]);
+        assert_eq!(vec![(0, 2, plain("cd")), (1, 1, plain(" "))], spans);
+
+        screen.clear();
+        assert_eq!(2, screen.update(&[plain("abcd"), plain("c")]).len());
+
+        // A change of colour alone is redrawn
+        let spans = screen.update(&[vec![('a', None, None), ('b', Some(1), None), ('c', None, None), ('d', None, None)], plain("c")]);
+        assert_eq!(vec![(0, 1, vec![('b', Some(1), None)])], spans);
+        let spans = screen.update(&[vec![('a', None, None), ('b', Some(1), Some(2)), ('c', None, None), ('d', None, None)], plain("c")]);
+        assert_eq!(vec![(0, 1, vec![('b', Some(1), Some(2))])], spans);
+    }
+}
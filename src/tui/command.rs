@@ -0,0 +1,120 @@
+//! Commands typed after `:` in the interactive frontend, for driving the engine without a key for everything.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::geometry::Position;
+use crate::rule::{Rule, RuleError};
+
+/// A command parsed from a line such as `goto 1000 -500`, without the leading `:`.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Replace the world with a pattern file, starting again from generation 0.
+    Load(PathBuf),
+    /// Place a pattern file with its -x -y corner at the cursor, or the centre of the view when not editing.
+    Paste(PathBuf),
+    /// Switch the rule the world steps by.
+    Rule(Rule),
+    /// Centre the view on a cell, moving the cursor there too while editing.
+    Goto(Position),
+    /// Step the given number of generations at once.
+    Step(u64),
+    /// Keep the given number of generations to step back through, or none to turn stepping back off.
+    History(usize),
+    Quit
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Command, CommandError> {
+        let mut words = s.split_whitespace();
+        let Some(name) = words.next() else { return Err(CommandError::Empty) };
+        let arguments: Vec<&str> = words.collect();
+        let wrong_arguments = || CommandError::Usage(usage(name).expect("Known command"));
+
+        match (name, arguments.as_slice()) {
+            ("load" | "l" | "paste" | "p", []) => Err(wrong_arguments()),
+            // Paths can have spaces in them, so the rest of the line is the path
+            ("load" | "l", _) => Ok(Command::Load(PathBuf::from(s.trim_start()[name.len()..].trim()))),
+            ("paste" | "p", _) => Ok(Command::Paste(PathBuf::from(s.trim_start()[name.len()..].trim()))),
+            ("rule" | "r", [rule]) => Ok(Command::Rule(rule.parse().map_err(CommandError::Rule)?)),
+            ("goto" | "g", [x, y]) => match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Ok(Command::Goto(Position::new(x, y))),
+                _ => Err(wrong_arguments())
+            },
+            ("step" | "s", []) => Ok(Command::Step(1)),
+            ("step" | "s", [generations]) => generations.parse().map(Command::Step).map_err(|_| wrong_arguments()),
+            ("history" | "h", [generations]) => generations.parse().map(Command::History).map_err(|_| wrong_arguments()),
+            ("quit" | "q", []) => Ok(Command::Quit),
+            _ if usage(name).is_some() => Err(wrong_arguments()),
+            _ => Err(CommandError::Unknown(name.to_string()))
+        }
+    }
+}
+
+/// How to use the command with the given name, or [`None`] if there is no such command.
+fn usage(name: &str) -> Option<&'static str> {
+    match name {
+        "load" | "l" => Some("load <pattern file>"),
+        "paste" | "p" => Some("paste <pattern file>"),
+        "rule" | "r" => Some("rule <rulestring>"),
+        "goto" | "g" => Some("goto <x> <y>"),
+        "step" | "s" => Some("step [generations]"),
+        "history" | "h" => Some("history <generations>"),
+        "quit" | "q" => Some("quit"),
+        _ => None
+    }
+}
+
+/// Errors from parsing a command.
+#[derive(Debug, PartialEq)]
+pub enum CommandError {
+    /// Nothing was typed.
+    Empty,
+    /// No command has the given name.
+    Unknown(String),
+    /// The command was given the wrong arguments, with how it should be used.
+    Usage(&'static str),
+    Rule(RuleError)
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "no command given"),
+            CommandError::Unknown(name) => write!(f, "unknown command '{}', expected load, paste, rule, goto, step, history or quit", name),
+            CommandError::Usage(usage) => write!(f, "usage: :{}", usage),
+            CommandError::Rule(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[cfg(test)]
+mod command_tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Command::Load(PathBuf::from("my patterns/glider.rle"))), " load  my patterns/glider.rle ".parse());
+        assert_eq!(Ok(Command::Paste(PathBuf::from("glider.rle"))), "p glider.rle".parse());
+        assert_eq!(Ok(Command::Rule(Rule::new(&[3, 6], &[2, 3]))), "rule B36/S23".parse());
+        assert_eq!(Ok(Command::Goto(Position::new(1000, -500))), "g 1000 -500".parse());
+        assert_eq!(Ok(Command::Step(100)), "step 100".parse());
+        assert_eq!(Ok(Command::Step(1)), "s".parse());
+        assert_eq!(Ok(Command::History(0)), "history 0".parse());
+        assert_eq!(Err(CommandError::Usage("history <generations>")), "h".parse::<Command>());
+        assert_eq!(Ok(Command::Quit), "q".parse());
+
+        assert_eq!(Err(CommandError::Empty), "  ".parse::<Command>());
+        assert_eq!(Err(CommandError::Unknown("jump".to_string())), "jump 5".parse::<Command>());
+        assert_eq!(Err(CommandError::Usage("goto <x> <y>")), "goto 5".parse::<Command>());
+        assert_eq!(Err(CommandError::Usage("step [generations]")), "step -1".parse::<Command>());
+        assert_eq!(Err(CommandError::Usage("load <pattern file>")), "load".parse::<Command>());
+        assert!(matches!("rule B3/S2x".parse::<Command>(), Err(CommandError::Rule(_))));
+        assert_eq!("usage: :goto <x> <y>", CommandError::Usage("goto <x> <y>").to_string());
+    }
+}
@@ -0,0 +1,131 @@
+//! Undoing and redoing the edits made to a world, separately from stepping back through its generations.
+
+use crate::gol::GameOfLife;
+use crate::journal::Edit;
+
+/// Edits that can be undone and redone, in groups that each come from a single interaction,
+/// such as dragging the mouse to draw a line, so one undo takes back the whole line.
+#[derive(Clone, Default)]
+pub struct UndoStack {
+    /// Groups of edits made, oldest first, each edit with the edit that reverses it.
+    done: Vec<Vec<(Edit, Edit)>>,
+    /// Groups undone, most recently undone last, ready to be redone.
+    undone: Vec<Vec<(Edit, Edit)>>,
+    /// Whether edits join the last group rather than starting their own.
+    grouping: bool
+}
+
+impl UndoStack {
+    pub fn new() -> UndoStack {
+        UndoStack::default()
+    }
+
+    /// Start a group, so every edit until [`UndoStack::finish_group`] is undone and redone together.
+    pub fn start_group(&mut self) {
+        self.finish_group();
+        self.done.push(vec![]);
+        self.grouping = true;
+    }
+
+    /// Stop adding edits to the current group, so the next edit starts its own.
+    pub fn finish_group(&mut self) {
+        if self.grouping && self.done.last().is_some_and(Vec::is_empty) { self.done.pop(); }
+        self.grouping = false;
+    }
+
+    /// Make an edit to the world, remembering how to undo it. Anything undone can no longer be redone.
+    pub fn apply(&mut self, edit: Edit, world: &mut GameOfLife) {
+        let inverse = edit.inverse(world);
+        edit.apply(world);
+        self.undone.clear();
+        match self.done.last_mut() {
+            Some(group) if self.grouping => group.push((edit, inverse)),
+            _ => self.done.push(vec![(edit, inverse)])
+        }
+    }
+
+    /// Reverse the most recent group of edits, returning the edits made to reverse them,
+    /// or [`None`] if there is nothing to undo.
+    pub fn undo(&mut self, world: &mut GameOfLife) -> Option<Vec<Edit>> {
+        self.finish_group();
+        let group = self.done.pop()?;
+        let inverses: Vec<Edit> = group.iter().rev().map(|(_, inverse)| inverse.clone()).collect();
+        for inverse in &inverses {
+            inverse.apply(world);
+        }
+        self.undone.push(group);
+        Some(inverses)
+    }
+
+    /// Make the most recently undone group of edits again, returning them,
+    /// or [`None`] if there is nothing to redo.
+    pub fn redo(&mut self, world: &mut GameOfLife) -> Option<Vec<Edit>> {
+        self.finish_group();
+        let group = self.undone.pop()?;
+        let edits: Vec<Edit> = group.iter().map(|(edit, _)| edit.clone()).collect();
+        for edit in &edits {
+            edit.apply(world);
+        }
+        self.done.push(group);
+        Some(edits)
+    }
+
+    /// Forget every edit, such as when the world steps on and they no longer make sense to undo.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+        self.grouping = false;
+    }
+
+    pub fn can_undo(&self) -> bool { self.done.iter().any(|group| !group.is_empty()) }
+    pub fn can_redo(&self) -> bool { !self.undone.is_empty() }
+}
+
+#[cfg(test)]
+mod undo_tests {
+    use super::*;
+    use crate::geometry::Position;
+    use crate::gol::Cell;
+    use crate::io::rle::read_rle;
+    use crate::rule::Rule;
+
+    #[test]
+    fn undo_and_redo() {
+        let mut world = GameOfLife::new();
+        let mut undo = UndoStack::new();
+        undo.apply(Edit::SetCell(Position::new(0, 0), Cell::Alive), &mut world);
+
+        // Drawing a line is undone in one go
+        undo.start_group();
+        for x in 1..=3 {
+            undo.apply(Edit::SetCell(Position::new(x, 0), Cell::Alive), &mut world);
+        }
+        undo.finish_group();
+        assert_eq!(4, world.population());
+        assert_eq!(3, undo.undo(&mut world).unwrap().len());
+        assert_eq!(1, world.population());
+        assert!(undo.redo(&mut world).is_some());
+        assert_eq!(4, world.population());
+
+        // A paste is undone back to whatever it covered, and a rule change back to the old rule
+        undo.apply(Edit::Paste(read_rle("b2o$2o!").unwrap()), &mut world);
+        undo.apply(Edit::Rule("B36/S23".parse().unwrap()), &mut world);
+        undo.undo(&mut world);
+        assert_eq!(Rule::LIFE, world.rule());
+        assert_eq!(Cell::Dead, world.get_cell(0, 0));
+        undo.undo(&mut world);
+        assert_eq!((Cell::Alive, Cell::Alive, 4), (world.get_cell(0, 0), world.get_cell(3, 0), world.population()));
+
+        // New edits drop whatever was undone, and empty groups take no undo
+        undo.apply(Edit::SetCell(Position::new(0, 0), Cell::Dead), &mut world);
+        assert!(!undo.can_redo());
+        undo.start_group();
+        undo.finish_group();
+        undo.undo(&mut world);
+        assert_eq!(Cell::Alive, world.get_cell(0, 0));
+
+        undo.clear();
+        assert!(!undo.can_undo());
+        assert!(undo.undo(&mut world).is_none());
+    }
+}
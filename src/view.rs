@@ -0,0 +1,134 @@
+//! Read-only handles on a world as of a single generation, for analysis on other threads.
+//! A view shares its regions with the world it came from, and the world replaces rather than changes
+//! any region it steps or edits while shared, so views stay valid and unchanged however far the world runs on.
+//! A [`Viewport`] instead borrows a world to print a fixed area of it.
+
+use std::fmt::{Display, Formatter};
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::{Cell, GameOfLife, Region, RegionError};
+use crate::rule::Rule;
+
+/// Immutable state of a world at a generation.
+/// Taking a view only copies references to the world's regions, and cloning one is just as cheap,
+/// so threads can each hold their own while the world keeps stepping.
+#[derive(Clone)]
+pub struct WorldView {
+    generation: u64,
+    world: GameOfLife
+}
+
+impl WorldView {
+    /// Take a view of the world, tagged with the generation it is at.
+    pub fn new(world: &GameOfLife, generation: u64) -> WorldView {
+        WorldView { generation, world: world.clone() }
+    }
+
+    /// Get the state of the cell at the given x y coordinates.
+    pub fn get_cell(&self, x: Coord, y: Coord) -> Cell { self.world.get_cell(x, y) }
+
+    /// Number of live cells in the view.
+    pub fn population(&self) -> usize { self.world.population() }
+
+    /// Positions of every live cell in the view, in no particular order.
+    pub fn live_cells(&self) -> Vec<Position> { self.world.live_cells() }
+
+    /// Returns the smallest rectangle holding every live cell in the view, or [`None`] if there are no live cells.
+    pub fn live_bounds(&self) -> Option<Rect> { self.world.live_bounds() }
+
+    /// Copy the view into a new region covering the bounding box of its live cells.
+    pub fn to_region(&self) -> Result<Region, RegionError> { self.world.to_region() }
+
+    /// Copy the view into a world that can be stepped or edited independently.
+    pub fn to_world(&self) -> GameOfLife { self.world.clone() }
+
+    // GETTERS
+    pub fn generation(&self) -> u64 { self.generation }
+    pub fn rule(&self) -> Rule { self.world.rule() }
+    /// The world as of this view, for functions that read a world, such as renderers.
+    pub fn world(&self) -> &GameOfLife { &self.world }
+}
+
+/// A fixed area of a world, displayed as a grid of `O` for live cells and `.` for dead ones, a row to a line as in
+/// plaintext patterns. Every cell of the area is drawn the same way whether or not the world has a region over it.
+#[derive(Clone, Copy)]
+pub struct Viewport<'a> {
+    world: &'a GameOfLife,
+    area: Rect
+}
+
+impl<'a> Viewport<'a> {
+    pub fn new(world: &'a GameOfLife, area: Rect) -> Viewport<'a> {
+        Viewport { world, area }
+    }
+
+    /// Create a viewport over the live cells of the world, or over nothing if there are none.
+    pub fn around(world: &'a GameOfLife) -> Viewport<'a> {
+        Viewport::new(world, world.live_bounds().unwrap_or_default())
+    }
+
+    // GETTERS
+    pub fn world(&self) -> &'a GameOfLife { self.world }
+    pub fn area(&self) -> Rect { self.area }
+}
+
+impl Display for Viewport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = (self.area.min(), self.area.max());
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                write!(f, "{}", if self.world.get_cell(x, y) == Cell::Alive { 'O' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod view_tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn views_survive_stepping() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<WorldView>();
+
+        // Blinker, horizontal on even generations
+        let mut world = GameOfLife::new();
+        for x in -1..=1 {
+            world.set_cell(x, 0, Cell::Alive);
+        }
+        let view = WorldView::new(&world, 0);
+
+        // Count on another thread while this one keeps stepping and editing
+        let counter = {
+            let view = view.clone();
+            thread::spawn(move || (0..100).map(|_| view.live_cells().len()).collect::<Vec<_>>())
+        };
+        for _ in 0..101 {
+            world.step();
+        }
+        world.set_cell(10, 10, Cell::Alive);
+        assert!(counter.join().unwrap().iter().all(|&population| population == 3));
+
+        assert_eq!(0, view.generation());
+        assert_eq!(Cell::Alive, view.get_cell(1, 0));
+        assert_eq!(Some(Rect::new(-1, 0, 3, 1)), view.live_bounds());
+        assert_eq!(Cell::Alive, world.get_cell(0, 1));
+        assert_eq!(Cell::Dead, view.get_cell(10, 10));
+    }
+
+    #[test]
+    fn display_viewport() {
+        // Cells outside every region print as dead, like any other, and an empty world prints nothing
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(1, 1, Cell::Alive);
+        assert_eq!("O.\n.O\n", Viewport::around(&world).to_string());
+        assert_eq!("...\n.O.\n..O\n", Viewport::new(&world, Rect::new(-1, -1, 3, 3)).to_string());
+        assert_eq!("...\n", Viewport::new(&world, Rect::new(-500, 40, 3, 1)).to_string());
+        assert_eq!("", Viewport::around(&GameOfLife::new()).to_string());
+    }
+}
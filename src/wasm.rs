@@ -0,0 +1,110 @@
+//! Bindings for running the engine in a browser, built as a `cdylib` with the `wasm` feature and bound with `wasm-bindgen`.
+//! Coordinates are `i32`, as JavaScript numbers can't hold every 64-bit [`Coord`](crate::geometry::Coord),
+//! and live cells are handed over as a flat typed array of x y pairs rather than one object per cell.
+
+use wasm_bindgen::prelude::*;
+
+use crate::geometry::Coord;
+use crate::gol::{Cell, GameOfLife};
+use crate::io::rle::{read_rle, write_rle};
+
+/// World for JavaScript to step, edit and draw.
+#[wasm_bindgen]
+pub struct World {
+    world: GameOfLife,
+    generation: u64
+}
+
+#[wasm_bindgen]
+impl World {
+    /// Create an empty world using the given rulestring, such as `B3/S23`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(rule: &str) -> Result<World, JsError> {
+        Ok(World { world: GameOfLife::with_rule(rule.parse()?), generation: 0 })
+    }
+
+    /// Create a world from an RLE pattern, with its -x -y corner at 0 0 unless the pattern gives a position.
+    #[wasm_bindgen(js_name = fromRle)]
+    pub fn from_rle(text: &str, rule: &str) -> Result<World, JsError> {
+        let mut world = World::new(rule)?;
+        world.world.set_region(&read_rle(text)?);
+        Ok(world)
+    }
+
+    /// Step the given number of generations.
+    pub fn step(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.world.step();
+        }
+        self.generation += generations as u64;
+    }
+
+    #[wasm_bindgen(js_name = getCell)]
+    pub fn get_cell(&self, x: i32, y: i32) -> bool {
+        self.world.get_cell(x as Coord, y as Coord) == Cell::Alive
+    }
+
+    #[wasm_bindgen(js_name = setCell)]
+    pub fn set_cell(&mut self, x: i32, y: i32, alive: bool) {
+        self.world.set_cell(x as Coord, y as Coord, if alive { Cell::Alive } else { Cell::Dead });
+    }
+
+    /// Positions of every live cell as an `Int32Array` of x y pairs, in no particular order.
+    #[wasm_bindgen(js_name = liveCells)]
+    pub fn live_cells(&self) -> Vec<i32> {
+        live_cell_buffer(&self.world)
+    }
+
+    /// Inclusive min x, min y, max x and max y of the live cells, or an empty array if there are none.
+    #[wasm_bindgen(js_name = liveBounds)]
+    pub fn live_bounds(&self) -> Vec<i32> {
+        self.world.live_bounds()
+            .map_or(vec![], |bounds| {
+                let (min, max) = (bounds.min(), bounds.max());
+                vec![min.x as i32, min.y as i32, max.x as i32, max.y as i32]
+            })
+    }
+
+    /// Write the live cells as an RLE pattern.
+    #[wasm_bindgen(js_name = toRle)]
+    pub fn to_rle(&self) -> Result<String, JsError> {
+        Ok(write_rle(&self.world.to_region()?))
+    }
+
+    pub fn population(&self) -> usize { self.world.population() }
+
+    /// Number of generations stepped, as a JavaScript number rather than a `BigInt`.
+    pub fn generation(&self) -> f64 { self.generation as f64 }
+
+    pub fn rule(&self) -> String { self.world.rule().to_string() }
+}
+
+/// Live cells of the world flattened into x y pairs, skipping any outside the `i32` range.
+pub fn live_cell_buffer(world: &GameOfLife) -> Vec<i32> {
+    world.live_cells().into_iter()
+        .filter_map(|cell| Some([i32::try_from(cell.x).ok()?, i32::try_from(cell.y).ok()?]))
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod wasm_tests {
+    use super::*;
+
+    #[test]
+    fn world() {
+        let mut world = World::from_rle("3o!", "B3/S23").unwrap();
+        world.step(3);
+        assert_eq!((3, 3.0), (world.population(), world.generation()));
+        assert!(world.get_cell(1, 1) && !world.get_cell(0, 0));
+
+        let mut cells = world.live_cells().chunks(2).map(|pair| (pair[0], pair[1])).collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(vec![(1, -1), (1, 0), (1, 1)], cells);
+        assert_eq!(vec![1, -1, 1, 1], world.live_bounds());
+
+        world.set_cell(1, 0, false);
+        assert_eq!(2, world.population());
+        assert_eq!("B3/S23", world.rule());
+    }
+}
@@ -0,0 +1,303 @@
+//! Graphical frontend drawing cells as pixels in a window, for viewports far larger than a terminal can show.
+//! Opening the window needs the `window` feature, while the drawing itself is always available.
+
+#[cfg(feature = "window")]
+use std::time::{Duration, Instant};
+
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::GameOfLife;
+use crate::render::{self, CellAges, Palette};
+#[cfg(feature = "window")]
+use crate::ticker::Ticker;
+
+/// Closest zoom, as a power of two pixels per cell.
+const MAX_ZOOM: i32 = 5;
+/// Furthest zoom, as a power of two cells per pixel.
+const MIN_ZOOM: i32 = -6;
+
+/// Maps between world cells and window pixels.
+/// Positive zoom levels draw each cell as a square of `2^zoom` pixels,
+/// while negative ones draw `2^-zoom` cells per side into each pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    x: Coord,
+    y: Coord,
+    zoom: i32
+}
+
+impl Camera {
+    /// Create a camera centred on x y, with each cell drawn as a square of `2^zoom` pixels.
+    pub fn new(x: Coord, y: Coord, zoom: i32) -> Camera {
+        Camera { x, y, zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM) }
+    }
+
+    /// Create a camera centred on the live cells of the world, zoomed to fit them in the window if possible.
+    pub fn fit(world: &GameOfLife, width: usize, height: usize) -> Camera {
+        let Some(bounds) = world.live_bounds() else { return Camera::new(0, 0, 2) };
+        let centre = bounds.centre();
+
+        // Closest zoom that still shows the whole pattern
+        let zoom = (MIN_ZOOM..=MAX_ZOOM).rev()
+            .find(|&zoom| {
+                let camera = Camera::new(centre.x, centre.y, zoom);
+                camera.cells_across(width) >= bounds.width && camera.cells_across(height) >= bounds.height
+            })
+            .unwrap_or(MIN_ZOOM);
+        Camera::new(centre.x, centre.y, zoom)
+    }
+
+    /// Number of cells shown across the given number of pixels.
+    fn cells_across(&self, pixels: usize) -> usize {
+        if self.zoom >= 0 { pixels >> self.zoom } else { pixels << -self.zoom }
+    }
+
+    /// Returns the cell at the -x -y corner of a window of the given size.
+    pub fn origin(&self, width: usize, height: usize) -> Position {
+        Position::new(self.x.saturating_sub_unsigned((self.cells_across(width) / 2) as u64), self.y.saturating_sub_unsigned((self.cells_across(height) / 2) as u64))
+    }
+
+    /// Returns the cell under a pixel of a window of the given size.
+    pub fn cell_at(&self, pixel_x: usize, pixel_y: usize, width: usize, height: usize) -> Position {
+        let origin = self.origin(width, height);
+        Position::new(origin.x.saturating_add_unsigned((self.cells_across(pixel_x)) as u64), origin.y.saturating_add_unsigned((self.cells_across(pixel_y)) as u64))
+    }
+
+    /// Area of the world shown in a window of the given size.
+    pub fn visible_area(&self, width: usize, height: usize) -> Rect {
+        let origin = self.origin(width, height);
+        Rect::new(origin.x, origin.y, self.cells_across(width), self.cells_across(height))
+    }
+
+    /// Move the view by a number of pixels, so panning is the same speed on screen at any zoom.
+    pub fn pan(&mut self, pixels_x: Coord, pixels_y: Coord) {
+        // Always move at least a cell, so panning works when zoomed in close
+        let cells = |pixels: Coord| match pixels.signum() {
+            0 => 0,
+            sign => sign * self.cells_across(pixels.unsigned_abs() as usize).max(1) as Coord
+        };
+        let (cells_x, cells_y) = (cells(pixels_x), cells(pixels_y));
+        self.x = self.x.saturating_add(cells_x);
+        self.y = self.y.saturating_add(cells_y);
+    }
+
+    /// Show each cell twice as large, if not already at the closest zoom.
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 1).min(MAX_ZOOM);
+    }
+
+    /// Show twice as many cells across, if not already at the furthest zoom.
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom - 1).max(MIN_ZOOM);
+    }
+
+    // GETTERS
+    pub fn x(&self) -> Coord { self.x }
+    pub fn y(&self) -> Coord { self.y }
+    pub fn zoom(&self) -> i32 { self.zoom }
+}
+
+/// Draw the view of the world into a buffer of `0RGB` pixels in rows, as used by windowing libraries.
+/// Only live cells are visited, so the cost depends on the population rather than the window size.
+pub fn draw_frame(world: &GameOfLife, camera: &Camera, width: usize, height: usize, palette: &Palette) -> Vec<u32> {
+    let alive = palette.get_colour(1);
+    draw_cells(world.live_cells().into_iter().map(|position| (position, alive)), camera, width, height, palette.get_colour(0))
+}
+
+/// Draw like [`draw_frame`], colouring live cells by their age with [`render::age_colour`].
+/// Where several cells share a pixel the youngest wins, so growth stands out against ash when zoomed out.
+/// Cells alive in the world but not yet recorded in the ages are drawn as newborn.
+pub fn draw_frame_by_age(world: &GameOfLife, ages: &CellAges, camera: &Camera, width: usize, height: usize, palette: &Palette) -> Vec<u32> {
+    let mut cells: Vec<(Position, u32)> = world.live_cells().into_iter()
+        .map(|position| (position, ages.get_age(position.x, position.y).unwrap_or(0)))
+        .collect();
+    // Drawn oldest first, so younger cells are drawn over them
+    cells.sort_unstable_by_key(|&(_, age)| std::cmp::Reverse(age));
+    let cells = cells.into_iter().map(|(position, age)| (position, render::age_colour(age)));
+    draw_cells(cells, camera, width, height, palette.get_colour(0))
+}
+
+/// Fill a buffer with the background colour and draw each cell over it in order, in the colour given with it.
+fn draw_cells(cells: impl Iterator<Item = (Position, [u8; 3])>, camera: &Camera, width: usize, height: usize, background: [u8; 3]) -> Vec<u32> {
+    let pack = |[r, g, b]: [u8; 3]| (r as u32) << 16 | (g as u32) << 8 | b as u32;
+    let mut buffer = vec![pack(background); width * height];
+    let origin = camera.origin(width, height);
+
+    for (cell, colour) in cells {
+        let Some(offset) = cell.checked_sub(origin) else { continue };
+        if offset.x < 0 || offset.y < 0 { continue }
+        let (offset_x, offset_y) = (offset.x as usize, offset.y as usize);
+
+        // Cells as squares of pixels, or many cells sharing a pixel
+        let (pixel_x, pixel_y, size) = if camera.zoom >= 0 {
+            (offset_x.saturating_mul(1 << camera.zoom), offset_y.saturating_mul(1 << camera.zoom), 1 << camera.zoom)
+        }
+        else {
+            (offset_x >> -camera.zoom, offset_y >> -camera.zoom, 1)
+        };
+        if pixel_x >= width || pixel_y >= height { continue }
+
+        let colour = pack(colour);
+        for row in pixel_y..(pixel_y + size).min(height) {
+            buffer[row * width + pixel_x..row * width + (pixel_x + size).min(width)].fill(colour);
+        }
+    }
+    buffer
+}
+
+/// Open a window showing the world, running until it is closed.
+/// Space pauses, `.` steps once, `+`/`-` change speed, arrows pan, the scroll wheel or `z`/`x` zoom,
+/// clicking toggles a cell, and `a` switches to colouring cells by how long they have been alive.
+#[cfg(feature = "window")]
+pub fn run(world: GameOfLife, palette: Palette) -> Result<(), minifb::Error> {
+    use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+    use crate::gol::Cell;
+
+    const MAX_SPEED: u32 = 4096;
+    let (width, height) = (1000, 1000);
+    let mut window = Window::new("gol", width, height, WindowOptions { resize: true, ..WindowOptions::default() })?;
+    window.set_target_fps(60);
+
+    let mut world = world;
+    let mut camera = Camera::fit(&world, width, height);
+    let (mut paused, mut speed, mut generation) = (true, 16u32, 0u64);
+    let mut steps = Ticker::new(speed);
+    let mut was_clicking = false;
+    let mut ages: Option<CellAges> = None;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) && !window.is_key_down(Key::Q) {
+        let (width, height) = window.get_size();
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            match key {
+                Key::Space => {
+                    paused = !paused;
+                    steps.restart(Instant::now());
+                }
+                Key::Period => {
+                    paused = true;
+                    world.step();
+                    generation += 1;
+                    if let Some(ages) = &mut ages { ages.update(&world) }
+                }
+                Key::Equal | Key::NumPadPlus => speed = (speed * 2).min(MAX_SPEED),
+                Key::Minus | Key::NumPadMinus => speed = (speed / 2).max(1),
+                Key::Left => camera.pan(-(width as Coord) / 10, 0),
+                Key::Right => camera.pan(width as Coord / 10, 0),
+                Key::Up => camera.pan(0, -(height as Coord) / 10),
+                Key::Down => camera.pan(0, height as Coord / 10),
+                Key::Z => camera.zoom_in(),
+                Key::X => camera.zoom_out(),
+                Key::A => {
+                    ages = match ages {
+                        Some(_) => None,
+                        None => {
+                            let mut ages = CellAges::new();
+                            ages.update(&world);
+                            Some(ages)
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some((_, scroll)) = window.get_scroll_wheel() {
+            if scroll > 0.0 { camera.zoom_in() } else if scroll < 0.0 { camera.zoom_out() }
+        }
+
+        // Toggle on the press rather than every frame the button is held
+        let clicking = window.get_mouse_down(MouseButton::Left);
+        if clicking && !was_clicking && let Some((mouse_x, mouse_y)) = window.get_mouse_pos(MouseMode::Discard) {
+            let Position { x, y } = camera.cell_at(mouse_x as usize, mouse_y as usize, width, height);
+            let state = if world.get_cell(x, y) == Cell::Alive { Cell::Dead } else { Cell::Alive };
+            world.set_cell(x, y, state);
+        }
+        was_clicking = clicking;
+
+        // Catch up on due generations, but never spend more than a frame doing so
+        if !paused {
+            steps.set_rate(speed);
+            let started = Instant::now();
+            while steps.catch_up(started, Duration::from_millis(16)) {
+                world.step();
+                generation += 1;
+                if let Some(ages) = &mut ages { ages.update(&world) }
+            }
+        }
+
+        window.set_title(&format!(
+            "gol - gen {} | pop {} | {} gen/s{}",
+            generation,
+            world.population(),
+            speed,
+            if paused { " (paused)" } else { "" }
+        ));
+        let frame = match &ages {
+            Some(ages) => draw_frame_by_age(&world, ages, &camera, width, height, &palette),
+            None => draw_frame(&world, &camera, width, height, &palette)
+        };
+        window.update_with_buffer(&frame, width, height)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod window_tests {
+    use super::*;
+    use crate::gol::Cell;
+
+    #[test]
+    fn camera() {
+        let mut camera = Camera::new(0, 0, 2);
+        // 4 pixels per cell, so a 40 pixel window shows 10 cells from -5
+        assert_eq!(Position::new(-5, -5), camera.origin(40, 40));
+        assert_eq!(Position::new(-5, -5), camera.cell_at(3, 3, 40, 40));
+        assert_eq!(Position::new(-4, 0), camera.cell_at(4, 20, 40, 40));
+        assert_eq!(Rect::new(-5, -5, 10, 10), camera.visible_area(40, 40));
+
+        camera.zoom_out();
+        camera.zoom_out();
+        camera.zoom_out();
+        // 2 cells per pixel
+        assert_eq!(Position::new(-40, -40), camera.origin(40, 40));
+        assert_eq!(Position::new(-38, -40), camera.cell_at(1, 0, 40, 40));
+
+        camera.pan(10, -10);
+        assert_eq!((20, -20), (camera.x(), camera.y()));
+    }
+
+    #[test]
+    fn fit() {
+        let mut world = GameOfLife::new();
+        world.set_cell(-50, 0, Cell::Alive);
+        world.set_cell(49, 9, Cell::Alive);
+        let camera = Camera::fit(&world, 400, 400);
+        // 100 cells wide fits at 4 pixels per cell, but not 8
+        assert_eq!((-1, 4, 2), (camera.x(), camera.y(), camera.zoom()));
+    }
+
+    #[test]
+    fn frame() {
+        let mut world = GameOfLife::new();
+        world.set_cell(0, 0, Cell::Alive);
+        world.set_cell(-2, -2, Cell::Alive);
+        let palette = Palette::new(vec![[0, 0, 1], [255, 0, 0]]);
+
+        // 2 pixels per cell on a 4 by 4 window shows -1 -1 to 0 0
+        let frame = draw_frame(&world, &Camera::new(0, 0, 1), 4, 4, &palette);
+        let (dead, alive) = (0x000001, 0xff0000);
+        assert_eq!(vec![dead, dead, dead, dead, dead, dead, dead, dead, dead, dead, alive, alive, dead, dead, alive, alive], frame);
+
+        // 2 cells per pixel puts both cells in view
+        let frame = draw_frame(&world, &Camera::new(0, 0, -1), 2, 2, &palette);
+        assert_eq!(vec![alive, dead, dead, alive], frame);
+
+        // Coloured by age, where the younger of two cells sharing a pixel wins
+        let mut ages = CellAges::new();
+        ages.update(&world);
+        ages.update(&world);
+        world.set_cell(-1, -1, Cell::Alive);
+        let pack = |[r, g, b]: [u8; 3]| (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        let (young, old) = (pack(render::age_colour(0)), pack(render::age_colour(1)));
+        let frame = draw_frame_by_age(&world, &ages, &Camera::new(0, 0, -1), 2, 2, &palette);
+        assert_eq!(vec![young, dead, dead, old], frame);
+    }
+}
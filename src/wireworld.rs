@@ -0,0 +1,195 @@
+//! Wireworld, where electrons flow along wires of conductor cells, so logic gates and whole circuits can be built.
+//! Wires never move or change, so rather than regions that follow live cells around, cells are kept in fixed
+//! square chunks covering the wiring, using the coordinate math from [`chunk`](crate::chunk).
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::chunk::{chunk_to_world, world_to_chunk, world_to_local};
+use crate::error::GolError;
+use crate::geometry::{Coord, Position, Rect};
+use crate::gol::StepStats;
+use crate::io::rle::read_rle_states;
+use crate::rule::Neighbourhood;
+
+/// Width and height of each chunk of cells.
+const CHUNK_SIZE: usize = 16;
+
+/// State of a Wireworld cell, numbered as Golly numbers them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum WireCell {
+    #[default]
+    Empty,
+    /// Front of an electron, which becomes its tail.
+    Head,
+    /// Back of an electron, which turns back into conductor.
+    Tail,
+    /// Wire that becomes an electron head when one or two of its neighbours are heads.
+    Conductor
+}
+
+impl WireCell {
+    /// Calculate the next state of a cell given how many of its 8 neighbours are electron heads.
+    pub fn next_state(self, heads: u8) -> WireCell {
+        match self {
+            WireCell::Empty => WireCell::Empty,
+            WireCell::Head => WireCell::Tail,
+            WireCell::Tail => WireCell::Conductor,
+            WireCell::Conductor if heads == 1 || heads == 2 => WireCell::Head,
+            WireCell::Conductor => WireCell::Conductor
+        }
+    }
+
+    /// The cell for a state number, or [`None`] past the last state.
+    pub fn from_state(state: u8) -> Option<WireCell> {
+        [WireCell::Empty, WireCell::Head, WireCell::Tail, WireCell::Conductor].get(state as usize).copied()
+    }
+
+    /// Number of the state, from 0 for empty to 3 for conductor.
+    pub fn state(self) -> u8 { self as u8 }
+}
+
+/// Error from reading a Wireworld pattern.
+#[derive(Debug, PartialEq)]
+pub enum WireworldError {
+    /// A cell is in a state past the 4 Wireworld has.
+    InvalidState(u8)
+}
+
+impl Display for WireworldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WireworldError::InvalidState(state) => write!(f, "wireworld has no state {}", state)
+        }
+    }
+}
+
+impl std::error::Error for WireworldError {}
+
+/// Infinite world of Wireworld cells.
+#[derive(Debug, Clone, Default)]
+pub struct WireworldWorld {
+    /// Cells of every chunk with anything in it, in rows, keyed by the chunk's coordinates.
+    chunks: HashMap<(Coord, Coord), Vec<WireCell>>
+}
+
+impl WireworldWorld {
+    /// Create a new empty world.
+    pub fn new() -> WireworldWorld {
+        WireworldWorld::default()
+    }
+
+    /// Read a circuit from a multi-state RLE pattern, as Golly writes for its WireWorld rule.
+    pub fn from_rle(text: &str) -> Result<WireworldWorld, GolError> {
+        let mut world = WireworldWorld::new();
+        for (position, state) in read_rle_states(text)? {
+            world.set_cell(position, WireCell::from_state(state).ok_or(WireworldError::InvalidState(state))?);
+        }
+        Ok(world)
+    }
+
+    /// Step the world to the next state. Electron heads count as the live cells, so births are heads
+    /// moving onto conductor and deaths are heads turning into tails.
+    pub fn step(&mut self) -> StepStats {
+        let mut heads: HashMap<Position, u8> = HashMap::new();
+        for (position, _) in self.cells().filter(|&(_, cell)| cell == WireCell::Head) {
+            for (x_off, y_off, _) in Neighbourhood::Moore.neighbours() {
+                *heads.entry(position + Position::new(x_off, y_off)).or_insert(0) += 1;
+            }
+        }
+
+        let mut stats = StepStats::default();
+        for (&(chunk_x, chunk_y), cells) in &mut self.chunks {
+            for (index, cell) in cells.iter_mut().enumerate() {
+                let next = match cell {
+                    WireCell::Conductor => {
+                        let position = chunk_to_world(chunk_x, chunk_y, index % CHUNK_SIZE, index / CHUNK_SIZE, CHUNK_SIZE);
+                        cell.next_state(heads.get(&position.into()).copied().unwrap_or(0))
+                    }
+                    _ => cell.next_state(0)
+                };
+                match (*cell, next) {
+                    (WireCell::Conductor, WireCell::Head) => stats.births += 1,
+                    (WireCell::Head, _) => stats.deaths += 1,
+                    _ => {}
+                }
+                if next == WireCell::Head { stats.population += 1 }
+                *cell = next;
+            }
+        }
+        stats
+    }
+
+    /// Returns the cell at the position.
+    pub fn get_cell(&self, position: Position) -> WireCell {
+        let chunk = world_to_chunk(position.x, position.y, CHUNK_SIZE);
+        let (x, y) = world_to_local(position.x, position.y, CHUNK_SIZE);
+        self.chunks.get(&chunk).map_or(WireCell::Empty, |cells| cells[y * CHUNK_SIZE + x])
+    }
+
+    /// Set the cell at the position, dropping its chunk once nothing is left in it.
+    pub fn set_cell(&mut self, position: Position, cell: WireCell) {
+        let chunk = world_to_chunk(position.x, position.y, CHUNK_SIZE);
+        let (x, y) = world_to_local(position.x, position.y, CHUNK_SIZE);
+        if cell == WireCell::Empty && !self.chunks.contains_key(&chunk) { return }
+
+        let cells = self.chunks.entry(chunk).or_insert_with(|| vec![WireCell::Empty; CHUNK_SIZE * CHUNK_SIZE]);
+        cells[y * CHUNK_SIZE + x] = cell;
+        if cells.iter().all(|&cell| cell == WireCell::Empty) { self.chunks.remove(&chunk); }
+    }
+
+    /// Every cell that isn't empty, with its position, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, WireCell)> + '_ {
+        self.chunks.iter().flat_map(|(&(chunk_x, chunk_y), cells)| {
+            cells.iter().enumerate().filter(|&(_, &cell)| cell != WireCell::Empty).map(move |(index, &cell)| {
+                (chunk_to_world(chunk_x, chunk_y, index % CHUNK_SIZE, index / CHUNK_SIZE, CHUNK_SIZE).into(), cell)
+            })
+        })
+    }
+
+    /// Number of electron heads.
+    pub fn population(&self) -> usize {
+        self.cells().filter(|&(_, cell)| cell == WireCell::Head).count()
+    }
+
+    /// Returns the bounding box of every cell that isn't empty, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::around(self.cells().map(|(position, _)| position))
+    }
+}
+
+#[cfg(test)]
+mod wireworld_tests {
+    use super::*;
+
+    #[test]
+    fn next_state() {
+        assert_eq!(WireCell::Head, WireCell::Conductor.next_state(2));
+        assert_eq!(WireCell::Conductor, WireCell::Conductor.next_state(3));
+        assert_eq!(WireCell::Tail, WireCell::Head.next_state(0));
+        assert_eq!(WireCell::Conductor, WireCell::Tail.next_state(1));
+        assert_eq!(WireCell::Empty, WireCell::Empty.next_state(1));
+        assert_eq!((Some(WireCell::Conductor), None), (WireCell::from_state(3), WireCell::from_state(4)));
+    }
+
+    #[test]
+    fn electron_along_wire() {
+        // An electron runs along a wire crossing a chunk edge, one cell a generation, and is lost off the end
+        let mut world = WireworldWorld::from_rle("#CXRLE Pos=10,-1\nx = 10, y = 1, rule = WireWorld\nBA8C!").unwrap();
+        assert_eq!((1, Some(Rect::new(10, -1, 10, 1))), (world.population(), world.bounds()));
+        assert_eq!(StepStats { births: 1, deaths: 1, population: 1 }, world.step());
+        assert_eq!(WireCell::Head, world.get_cell(Position::new(12, -1)));
+        assert_eq!(WireCell::Tail, world.get_cell(Position::new(11, -1)));
+        assert_eq!(WireCell::Conductor, world.get_cell(Position::new(10, -1)));
+        for _ in 0..8 {
+            world.step();
+        }
+        assert_eq!(0, world.population());
+        assert_eq!(10, world.cells().count());
+
+        world.set_cell(Position::new(-1, -1), WireCell::Conductor);
+        world.set_cell(Position::new(-1, -1), WireCell::Empty);
+        assert_eq!(10, world.cells().count());
+        assert!(matches!(WireworldWorld::from_rle("D!"), Err(GolError::Wireworld(WireworldError::InvalidState(4)))));
+    }
+}